@@ -0,0 +1,4 @@
+// migrations are embedded with sqlx::migrate!, so the binary has to be rebuilt when they change
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
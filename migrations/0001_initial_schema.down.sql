@@ -0,0 +1,15 @@
+DROP TABLE IF EXISTS sessions;
+DROP TABLE IF EXISTS users;
+DROP TABLE IF EXISTS dispensed_drugs;
+DROP TABLE IF EXISTS prescription_fills;
+DROP TABLE IF EXISTS prescribed_drugs;
+DROP TABLE IF EXISTS drugs;
+DROP TABLE IF EXISTS prescriptions;
+DROP TABLE IF EXISTS patients;
+DROP TABLE IF EXISTS pharmacists;
+DROP TABLE IF EXISTS doctors;
+
+DROP TYPE IF EXISTS user_role;
+DROP TYPE IF EXISTS drug_content_type;
+DROP TYPE IF EXISTS prescription_status;
+DROP TYPE IF EXISTS prescription_type;
@@ -0,0 +1,175 @@
+-- Schema previously created by create_tables. Every statement is guarded, so databases
+-- created before migrations were introduced are adopted without losing data.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'prescription_type') THEN
+    CREATE TYPE prescription_type AS ENUM ('regular', 'for_antibiotics', 'for_chronic_disease_drugs', 'for_immunological_drugs');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'prescription_status') THEN
+    CREATE TYPE prescription_status AS ENUM ('pending', 'active', 'partially_filled', 'filled', 'expired', 'cancelled');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'drug_content_type') THEN
+    CREATE TYPE drug_content_type AS ENUM ('solid_pills', 'liquid_pills', 'bottle_of_liquid');
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'user_role') THEN
+    CREATE TYPE user_role AS ENUM ('doctor', 'pharmacist');
+    END IF;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS doctors (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    pesel_number VARCHAR(11) UNIQUE NOT NULL,
+    pwz_number VARCHAR(7) UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pharmacists (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    pesel_number VARCHAR(11) UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS patients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    pesel_number VARCHAR(11) UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS prescriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    patient_id UUID NOT NULL REFERENCES patients(id),
+    doctor_id UUID NOT NULL REFERENCES doctors(id),
+    prescription_type prescription_type NOT NULL,
+    code VARCHAR(8) NOT NULL,
+    start_date TIMESTAMPTZ NOT NULL,
+    end_date TIMESTAMPTZ NOT NULL,
+    cancellation_reason TEXT,
+    cancelled_at TIMESTAMPTZ,
+    status prescription_status DEFAULT 'active' NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS drugs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    content_type drug_content_type NOT NULL,
+    pills_count INT,
+    mg_per_pill INT,
+    ml_per_pill INT,
+    volume_ml INT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS prescribed_drugs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    prescription_id UUID NOT NULL REFERENCES prescriptions(id),
+    drug_id UUID NOT NULL REFERENCES drugs(id),
+    quantity INT NOT NULL,
+    portion_quantity INT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS prescription_fills (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    prescription_id UUID NOT NULL REFERENCES prescriptions(id),
+    pharmacist_id UUID NOT NULL REFERENCES pharmacists(id),
+    portion_number INT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS dispensed_drugs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    prescription_fill_id UUID NOT NULL REFERENCES prescription_fills(id),
+    prescribed_drug_id UUID NOT NULL REFERENCES prescribed_drugs(id),
+    quantity INT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    username VARCHAR(100) UNIQUE NOT NULL,
+    password_hash VARCHAR(255) NOT NULL,
+    email VARCHAR(255) UNIQUE NOT NULL,
+    phone_number VARCHAR(15) NOT NULL,
+    role user_role NOT NULL,
+    doctor_id UUID REFERENCES doctors(id),
+    pharmacist_id UUID REFERENCES pharmacists(id),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id),
+    doctor_id UUID REFERENCES doctors(id),
+    pharmacist_id UUID REFERENCES pharmacists(id),
+    ip_address VARCHAR(255) NOT NULL,
+    user_agent VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    invalidated_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS prescriptions_status_end_date_idx ON prescriptions (status, end_date);
+
+-- Changes that create_tables used to apply to already existing tables
+
+ALTER TABLE prescription_fills DROP CONSTRAINT IF EXISTS prescription_fills_prescription_id_key;
+
+ALTER TABLE prescriptions
+    ADD COLUMN IF NOT EXISTS cancellation_reason TEXT,
+    ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS status prescription_status DEFAULT 'active' NOT NULL;
+ALTER TABLE prescribed_drugs ADD COLUMN IF NOT EXISTS portion_quantity INT;
+ALTER TABLE prescription_fills ADD COLUMN IF NOT EXISTS portion_number INT;
+
+UPDATE prescriptions SET status = CASE
+    WHEN cancelled_at IS NOT NULL THEN 'cancelled'::prescription_status
+    WHEN NOT EXISTS (
+        SELECT 1 FROM prescribed_drugs
+        WHERE prescribed_drugs.prescription_id = prescriptions.id
+        AND prescribed_drugs.quantity > COALESCE((
+            SELECT SUM(dispensed_drugs.quantity) FROM dispensed_drugs
+            WHERE dispensed_drugs.prescribed_drug_id = prescribed_drugs.id
+        ), 0)
+    ) THEN 'filled'::prescription_status
+    ELSE 'partially_filled'::prescription_status
+END
+WHERE status = 'active' AND (
+    cancelled_at IS NOT NULL
+    OR EXISTS (SELECT 1 FROM prescription_fills WHERE prescription_fills.prescription_id = prescriptions.id)
+);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'users_doctor_id_fkey') THEN
+    ALTER TABLE users ADD CONSTRAINT users_doctor_id_fkey FOREIGN KEY (doctor_id) REFERENCES doctors(id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'users_pharmacist_id_fkey') THEN
+    ALTER TABLE users ADD CONSTRAINT users_pharmacist_id_fkey FOREIGN KEY (pharmacist_id) REFERENCES pharmacists(id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'sessions_user_id_fkey') THEN
+    ALTER TABLE sessions ADD CONSTRAINT sessions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'sessions_doctor_id_fkey') THEN
+    ALTER TABLE sessions ADD CONSTRAINT sessions_doctor_id_fkey FOREIGN KEY (doctor_id) REFERENCES doctors(id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'sessions_pharmacist_id_fkey') THEN
+    ALTER TABLE sessions ADD CONSTRAINT sessions_pharmacist_id_fkey FOREIGN KEY (pharmacist_id) REFERENCES pharmacists(id);
+    END IF;
+END
+$$;
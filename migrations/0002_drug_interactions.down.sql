@@ -0,0 +1,7 @@
+ALTER TABLE prescriptions DROP COLUMN IF EXISTS interaction_override_justification;
+
+DROP TABLE IF EXISTS drug_interactions;
+
+DROP TYPE IF EXISTS drug_interaction_severity;
+
+ALTER TABLE drugs DROP COLUMN IF EXISTS active_substances;
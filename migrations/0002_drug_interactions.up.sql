@@ -0,0 +1,20 @@
+ALTER TABLE drugs ADD COLUMN active_substances TEXT[] DEFAULT '{}' NOT NULL;
+
+CREATE TYPE drug_interaction_severity AS ENUM ('minor', 'moderate', 'major', 'contraindicated');
+
+-- Pairs are stored once, with substance_a ordered before substance_b
+CREATE TABLE drug_interactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    substance_a VARCHAR(100) NOT NULL,
+    substance_b VARCHAR(100) NOT NULL,
+    severity drug_interaction_severity NOT NULL,
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CHECK (substance_a COLLATE "C" < substance_b COLLATE "C"),
+    UNIQUE (substance_a, substance_b)
+);
+
+CREATE INDEX drug_interactions_substance_b_idx ON drug_interactions (substance_b);
+
+ALTER TABLE prescriptions ADD COLUMN interaction_override_justification TEXT;
@@ -0,0 +1,13 @@
+CREATE TYPE allergy_severity AS ENUM ('mild', 'moderate', 'severe', 'life_threatening');
+
+CREATE TABLE patient_allergies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    patient_id UUID NOT NULL REFERENCES patients(id) ON DELETE CASCADE,
+    substance VARCHAR(100) NOT NULL,
+    reaction TEXT NOT NULL,
+    severity allergy_severity NOT NULL,
+    recorded_by_doctor_id UUID NOT NULL REFERENCES doctors(id),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (patient_id, substance)
+);
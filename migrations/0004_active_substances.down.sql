@@ -0,0 +1,15 @@
+ALTER TABLE drugs ADD COLUMN IF NOT EXISTS active_substances TEXT[] DEFAULT '{}' NOT NULL;
+
+UPDATE drugs SET active_substances = ARRAY(
+    SELECT active_substances.name
+    FROM drug_active_substances
+    JOIN active_substances ON active_substances.id = drug_active_substances.active_substance_id
+    WHERE drug_active_substances.drug_id = drugs.id
+    ORDER BY active_substances.name
+);
+
+DROP TABLE IF EXISTS drug_active_substances;
+
+DROP TABLE IF EXISTS active_substances;
+
+DROP TYPE IF EXISTS strength_unit;
@@ -0,0 +1,31 @@
+CREATE TYPE strength_unit AS ENUM ('mg', 'mcg', 'g', 'iu', 'ml', 'mg_per_ml', 'percent');
+
+CREATE TABLE active_substances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- amount and unit are NULL for compositions migrated from drugs.active_substances, which had no strengths
+CREATE TABLE drug_active_substances (
+    drug_id UUID NOT NULL REFERENCES drugs(id) ON DELETE CASCADE,
+    active_substance_id UUID NOT NULL REFERENCES active_substances(id),
+    amount DOUBLE PRECISION CHECK (amount > 0),
+    unit strength_unit,
+    PRIMARY KEY (drug_id, active_substance_id),
+    CHECK ((amount IS NULL) = (unit IS NULL))
+);
+
+CREATE INDEX drug_active_substances_active_substance_id_idx ON drug_active_substances (active_substance_id);
+
+INSERT INTO active_substances (name)
+SELECT DISTINCT unnest(active_substances) FROM drugs;
+
+INSERT INTO drug_active_substances (drug_id, active_substance_id)
+SELECT drugs.id, active_substances.id
+FROM drugs
+CROSS JOIN LATERAL unnest(drugs.active_substances) AS substance(name)
+JOIN active_substances ON active_substances.name = substance.name;
+
+ALTER TABLE drugs DROP COLUMN active_substances;
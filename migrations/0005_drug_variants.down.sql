@@ -0,0 +1,14 @@
+ALTER TABLE prescribed_drugs DROP CONSTRAINT prescribed_drugs_variant_id_fkey;
+ALTER TABLE prescribed_drugs DROP COLUMN variant_id;
+
+ALTER TABLE drugs ADD COLUMN pills_count INT, ADD COLUMN volume_ml INT;
+
+UPDATE drugs SET pills_count = first_variant.pills_count, volume_ml = first_variant.volume_ml
+FROM (
+    SELECT DISTINCT ON (drug_id) drug_id, pills_count, volume_ml
+    FROM drug_variants
+    ORDER BY drug_id, created_at
+) AS first_variant
+WHERE first_variant.drug_id = drugs.id;
+
+DROP TABLE drug_variants;
@@ -0,0 +1,28 @@
+-- pills_count and volume_ml describe a package size, so they move from drugs to their variants.
+-- Every existing drug becomes a product with a single variant
+CREATE TABLE drug_variants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    drug_id UUID NOT NULL REFERENCES drugs(id) ON DELETE CASCADE,
+    pills_count INT CHECK (pills_count > 0),
+    volume_ml INT CHECK (volume_ml > 0),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (drug_id, id)
+);
+
+INSERT INTO drug_variants (drug_id, pills_count, volume_ml, created_at, updated_at)
+SELECT id, pills_count, volume_ml, created_at, updated_at FROM drugs;
+
+ALTER TABLE drugs DROP COLUMN pills_count, DROP COLUMN volume_ml;
+
+-- A prescribed drug references either a variant of the drug (quantity counts its packages) or the
+-- drug itself (quantity counts pills or ml). Existing prescriptions were written for packages
+ALTER TABLE prescribed_drugs ADD COLUMN variant_id UUID;
+
+UPDATE prescribed_drugs SET variant_id = drug_variants.id
+FROM drug_variants
+WHERE drug_variants.drug_id = prescribed_drugs.drug_id;
+
+ALTER TABLE prescribed_drugs
+    ADD CONSTRAINT prescribed_drugs_variant_id_fkey
+    FOREIGN KEY (drug_id, variant_id) REFERENCES drug_variants(drug_id, id);
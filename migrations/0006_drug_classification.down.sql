@@ -0,0 +1,4 @@
+ALTER TABLE drugs DROP COLUMN atc_code, DROP COLUMN category_id;
+
+DROP TABLE drug_categories;
+DROP TABLE atc_groups;
@@ -0,0 +1,27 @@
+-- Groups of all 5 ATC levels, each one except the anatomical main group belongs to the group of the
+-- previous level
+CREATE TABLE atc_groups (
+    code VARCHAR(7) PRIMARY KEY,
+    name VARCHAR(200) NOT NULL,
+    parent_code VARCHAR(7) REFERENCES atc_groups(code),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CHECK ((parent_code IS NULL) = (LENGTH(code) = 1))
+);
+
+CREATE TABLE drug_categories (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    parent_id UUID REFERENCES drug_categories(id),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX drug_categories_parent_id_idx ON drug_categories (parent_id);
+
+ALTER TABLE drugs
+    ADD COLUMN atc_code VARCHAR(7) REFERENCES atc_groups(code),
+    ADD COLUMN category_id UUID REFERENCES drug_categories(id);
+
+CREATE INDEX drugs_atc_code_idx ON drugs (atc_code varchar_pattern_ops);
+CREATE INDEX drugs_category_id_idx ON drugs (category_id);
@@ -0,0 +1,5 @@
+ALTER TABLE dispensed_drugs DROP COLUMN substitute_drug_id;
+
+ALTER TABLE drugs DROP COLUMN equivalence_group_id;
+
+DROP TABLE drug_equivalence_groups;
@@ -0,0 +1,17 @@
+-- Drugs with the same active substances, strength and form, which can be dispensed instead of
+-- each other
+CREATE TABLE drug_equivalence_groups (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+ALTER TABLE drugs
+    ADD COLUMN equivalence_group_id UUID REFERENCES drug_equivalence_groups(id);
+
+CREATE INDEX drugs_equivalence_group_id_idx ON drugs (equivalence_group_id);
+
+-- Drug that was actually dispensed instead of the prescribed one
+ALTER TABLE dispensed_drugs
+    ADD COLUMN substitute_drug_id UUID REFERENCES drugs(id);
@@ -0,0 +1,13 @@
+ALTER TABLE prescribed_drugs
+    DROP CONSTRAINT prescribed_drugs_dosage_check,
+    DROP COLUMN dose_amount,
+    DROP COLUMN dose_unit,
+    DROP COLUMN dosage_frequency,
+    DROP COLUMN dosage_frequency_period,
+    DROP COLUMN dosage_duration_days,
+    DROP COLUMN administration_route,
+    DROP COLUMN dosage_note;
+
+DROP TYPE administration_route;
+DROP TYPE dosage_period;
+DROP TYPE dose_unit;
@@ -0,0 +1,23 @@
+CREATE TYPE dose_unit AS ENUM ('tablet', 'capsule', 'drop', 'ml', 'puff', 'sachet', 'application', 'unit');
+CREATE TYPE dosage_period AS ENUM ('day', 'week', 'month');
+CREATE TYPE administration_route AS ENUM (
+    'oral', 'sublingual', 'inhalation', 'nasal', 'ophthalmic', 'otic', 'topical', 'transdermal',
+    'rectal', 'vaginal', 'subcutaneous', 'intramuscular', 'intravenous'
+);
+
+-- Dosage instructions (signa) are optional, but when given, only duration and note can be omitted
+ALTER TABLE prescribed_drugs
+    ADD COLUMN dose_amount DOUBLE PRECISION CHECK (dose_amount > 0),
+    ADD COLUMN dose_unit dose_unit,
+    ADD COLUMN dosage_frequency INT CHECK (dosage_frequency > 0),
+    ADD COLUMN dosage_frequency_period dosage_period,
+    ADD COLUMN dosage_duration_days INT CHECK (dosage_duration_days > 0),
+    ADD COLUMN administration_route administration_route,
+    ADD COLUMN dosage_note VARCHAR(200),
+    ADD CONSTRAINT prescribed_drugs_dosage_check CHECK (
+        (dose_amount IS NULL AND dose_unit IS NULL AND dosage_frequency IS NULL
+            AND dosage_frequency_period IS NULL AND dosage_duration_days IS NULL
+            AND administration_route IS NULL AND dosage_note IS NULL)
+        OR (dose_amount IS NOT NULL AND dose_unit IS NOT NULL AND dosage_frequency IS NOT NULL
+            AND dosage_frequency_period IS NOT NULL AND administration_route IS NOT NULL)
+    );
@@ -0,0 +1,14 @@
+DROP TABLE controlled_substance_dispenses;
+
+-- Values can't be removed from an enum, so the type is recreated. This fails if any prescription
+-- for controlled substances exists
+ALTER TYPE prescription_type RENAME TO prescription_type_old;
+CREATE TYPE prescription_type AS ENUM ('regular', 'for_antibiotics', 'for_chronic_disease_drugs', 'for_immunological_drugs');
+ALTER TABLE prescriptions
+    ALTER COLUMN prescription_type TYPE prescription_type
+    USING prescription_type::TEXT::prescription_type;
+DROP TYPE prescription_type_old;
+
+ALTER TABLE drugs DROP COLUMN controlled_substance_list;
+
+DROP TYPE controlled_substance_list;
@@ -0,0 +1,26 @@
+CREATE TYPE controlled_substance_list AS ENUM ('narcotic', 'psychotropic');
+
+ALTER TABLE drugs ADD COLUMN controlled_substance_list controlled_substance_list;
+
+-- The new value can't be used in this transaction, which is fine as no rows are written here
+ALTER TYPE prescription_type ADD VALUE 'for_controlled_substances';
+
+-- Register of every dispense of a controlled substance. Rows are only inserted, together with the
+-- fill they belong to
+CREATE TABLE controlled_substance_dispenses (
+    id UUID PRIMARY KEY,
+    prescription_id UUID NOT NULL REFERENCES prescriptions(id),
+    prescription_fill_id UUID NOT NULL REFERENCES prescription_fills(id),
+    prescribed_drug_id UUID NOT NULL REFERENCES prescribed_drugs(id),
+    drug_id UUID NOT NULL REFERENCES drugs(id),
+    variant_id UUID REFERENCES drug_variants(id),
+    quantity INT NOT NULL CHECK (quantity > 0),
+    patient_id UUID NOT NULL REFERENCES patients(id),
+    doctor_id UUID NOT NULL REFERENCES doctors(id),
+    pharmacist_id UUID NOT NULL REFERENCES pharmacists(id),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX controlled_substance_dispenses_created_at_idx
+    ON controlled_substance_dispenses (created_at);
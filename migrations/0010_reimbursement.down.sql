@@ -0,0 +1,7 @@
+ALTER TABLE dispensed_drugs DROP COLUMN payable_amount, DROP COLUMN reimbursed_amount;
+
+ALTER TABLE prescribed_drugs DROP COLUMN reimbursement_level;
+
+DROP TABLE drug_reimbursements;
+
+DROP TYPE reimbursement_level;
@@ -0,0 +1,28 @@
+CREATE TYPE reimbursement_level AS ENUM (
+    'full',
+    'fifty_percent',
+    'thirty_percent',
+    'flat_fee',
+    'free_for_seniors',
+    'free_for_children'
+);
+
+-- Reimbursement scheme of a drug package. Amounts are in grosze
+CREATE TABLE drug_reimbursements (
+    id UUID PRIMARY KEY,
+    drug_id UUID NOT NULL,
+    variant_id UUID NOT NULL UNIQUE,
+    levels reimbursement_level[] NOT NULL CHECK (cardinality(levels) > 0),
+    reimbursement_limit BIGINT NOT NULL CHECK (reimbursement_limit > 0),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT drug_reimbursements_variant_id_fkey
+        FOREIGN KEY (drug_id, variant_id) REFERENCES drug_variants(drug_id, id) ON DELETE CASCADE
+);
+
+ALTER TABLE prescribed_drugs ADD COLUMN reimbursement_level reimbursement_level;
+
+-- Amounts are computed when the drug is dispensed, so they don't change with later schemes
+ALTER TABLE dispensed_drugs
+    ADD COLUMN payable_amount BIGINT CHECK (payable_amount >= 0),
+    ADD COLUMN reimbursed_amount BIGINT CHECK (reimbursed_amount >= 0);
@@ -0,0 +1,5 @@
+ALTER TABLE dispensed_drugs DROP COLUMN price;
+
+DROP TABLE drug_prices;
+
+DROP TABLE drug_price_lists;
@@ -0,0 +1,29 @@
+CREATE TABLE drug_price_lists (
+    id UUID PRIMARY KEY,
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ CHECK (valid_to > valid_from),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- Price of a drug package from a price list. Prices are in grosze, and valid_to of a price is
+-- set when a newer price list replaces it
+CREATE TABLE drug_prices (
+    id UUID PRIMARY KEY,
+    price_list_id UUID NOT NULL REFERENCES drug_price_lists(id) ON DELETE CASCADE,
+    drug_id UUID NOT NULL,
+    variant_id UUID NOT NULL,
+    retail_price BIGINT NOT NULL CHECK (retail_price > 0),
+    official_price BIGINT NOT NULL CHECK (official_price > 0),
+    valid_from TIMESTAMPTZ NOT NULL,
+    valid_to TIMESTAMPTZ CHECK (valid_to > valid_from),
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CONSTRAINT drug_prices_variant_id_fkey
+        FOREIGN KEY (drug_id, variant_id) REFERENCES drug_variants(drug_id, id) ON DELETE CASCADE
+);
+
+CREATE INDEX drug_prices_variant_id_valid_from_idx ON drug_prices (variant_id, valid_from);
+
+-- Price of a package that applied when the drug was dispensed
+ALTER TABLE dispensed_drugs ADD COLUMN price BIGINT CHECK (price > 0);
@@ -0,0 +1 @@
+ALTER TABLE drugs DROP COLUMN external_id;
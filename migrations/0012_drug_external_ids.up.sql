@@ -0,0 +1,3 @@
+-- Identifier of the drug in the registry the catalogue is imported from, used to update drugs
+-- imported before
+ALTER TABLE drugs ADD COLUMN external_id VARCHAR(100) CONSTRAINT drugs_external_id_key UNIQUE;
@@ -0,0 +1 @@
+ALTER TABLE drug_variants DROP COLUMN gtin;
@@ -0,0 +1,2 @@
+-- GTIN-13 (EAN) barcode printed on the package, used by pharmacies to scan dispensed packages
+ALTER TABLE drug_variants ADD COLUMN gtin VARCHAR(13) CONSTRAINT drug_variants_gtin_key UNIQUE;
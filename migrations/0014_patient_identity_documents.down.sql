@@ -0,0 +1,25 @@
+-- Patients without a PESEL number can't be kept once it's required again, and deleting them
+-- would destroy their clinical records, so they have to be migrated by hand before reverting
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM patients WHERE pesel_number IS NULL) THEN
+        RAISE EXCEPTION 'Cannot revert: % patient(s) have no PESEL number',
+            (SELECT count(*) FROM patients WHERE pesel_number IS NULL);
+    END IF;
+END $$;
+
+ALTER TABLE patients
+    DROP CONSTRAINT patients_identifier_check,
+    DROP CONSTRAINT patients_identity_document_check,
+    DROP CONSTRAINT patients_identity_document_key,
+    DROP COLUMN sex,
+    DROP COLUMN birth_date,
+    DROP COLUMN parent_pesel_number,
+    DROP COLUMN document_issuing_country,
+    DROP COLUMN document_number,
+    DROP COLUMN document_type,
+    ALTER COLUMN pesel_number SET NOT NULL;
+
+DROP TYPE identity_document_type;
+
+DROP TYPE sex;
@@ -0,0 +1,46 @@
+CREATE TYPE sex AS ENUM ('female', 'male');
+
+CREATE TYPE identity_document_type AS ENUM ('passport', 'eu_id_card');
+
+-- Patients without a PESEL number are identified by an identity document (foreigners) or by
+-- the PESEL number of a parent (newborns), so their birth date and sex are stored as given
+ALTER TABLE patients
+    ALTER COLUMN pesel_number DROP NOT NULL,
+    ADD COLUMN document_type identity_document_type,
+    ADD COLUMN document_number VARCHAR(20),
+    ADD COLUMN document_issuing_country CHAR(2),
+    ADD COLUMN parent_pesel_number VARCHAR(11),
+    ADD COLUMN birth_date DATE,
+    ADD COLUMN sex sex;
+
+-- Month of birth is encoded with an offset of 80 for 1800s, 20 for 2000s, 40 for 2100s and 60
+-- for 2200s, and the 10th digit is odd for men
+UPDATE patients SET
+    birth_date = make_date(
+        CASE
+            WHEN substring(pesel_number, 3, 2)::INT > 80 THEN 1800
+            WHEN substring(pesel_number, 3, 2)::INT > 60 THEN 2200
+            WHEN substring(pesel_number, 3, 2)::INT > 40 THEN 2100
+            WHEN substring(pesel_number, 3, 2)::INT > 20 THEN 2000
+            ELSE 1900
+        END + substring(pesel_number, 1, 2)::INT,
+        substring(pesel_number, 3, 2)::INT % 20,
+        substring(pesel_number, 5, 2)::INT
+    ),
+    sex = CASE WHEN substring(pesel_number, 10, 1)::INT % 2 = 1 THEN 'male' ELSE 'female' END::sex;
+
+ALTER TABLE patients
+    ALTER COLUMN birth_date SET NOT NULL,
+    ALTER COLUMN sex SET NOT NULL,
+    ADD CONSTRAINT patients_identity_document_key
+        UNIQUE (document_type, document_issuing_country, document_number),
+    ADD CONSTRAINT patients_identity_document_check CHECK (
+        (document_type IS NULL) = (document_number IS NULL)
+        AND (document_number IS NULL) = (document_issuing_country IS NULL)
+    ),
+    ADD CONSTRAINT patients_identifier_check
+        CHECK (num_nonnulls(pesel_number, document_number, parent_pesel_number) = 1);
+
+CREATE INDEX patients_document_number_idx ON patients (document_number);
+
+CREATE INDEX patients_parent_pesel_number_idx ON patients (parent_pesel_number);
@@ -0,0 +1,29 @@
+ALTER TABLE patient_allergies ADD COLUMN substance VARCHAR(100);
+
+UPDATE patient_allergies SET
+    substance = (SELECT name FROM active_substances WHERE id = patient_allergies.active_substance_id);
+
+ALTER TABLE patient_allergies
+    ALTER COLUMN substance SET NOT NULL,
+    DROP COLUMN active_substance_id,
+    ADD UNIQUE (patient_id, substance);
+
+ALTER TABLE drug_interactions
+    ADD COLUMN substance_a VARCHAR(100),
+    ADD COLUMN substance_b VARCHAR(100);
+
+UPDATE drug_interactions SET
+    substance_a = LEAST(a.name COLLATE "C", b.name COLLATE "C"),
+    substance_b = GREATEST(a.name COLLATE "C", b.name COLLATE "C")
+FROM active_substances a, active_substances b
+WHERE a.id = drug_interactions.substance_a_id AND b.id = drug_interactions.substance_b_id;
+
+ALTER TABLE drug_interactions
+    ALTER COLUMN substance_a SET NOT NULL,
+    ALTER COLUMN substance_b SET NOT NULL,
+    DROP COLUMN substance_a_id,
+    DROP COLUMN substance_b_id,
+    ADD CHECK (substance_a COLLATE "C" < substance_b COLLATE "C"),
+    ADD UNIQUE (substance_a, substance_b);
+
+CREATE INDEX drug_interactions_substance_b_idx ON drug_interactions (substance_b);
@@ -0,0 +1,40 @@
+-- Interactions and allergies reference the catalogue instead of repeating substance names, so a
+-- substance has one spelling across drugs, interactions and allergies
+INSERT INTO active_substances (name)
+SELECT substance_a FROM drug_interactions
+UNION SELECT substance_b FROM drug_interactions
+UNION SELECT substance FROM patient_allergies
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE drug_interactions
+    ADD COLUMN substance_a_id UUID REFERENCES active_substances(id),
+    ADD COLUMN substance_b_id UUID REFERENCES active_substances(id);
+
+UPDATE drug_interactions SET
+    substance_a_id = (SELECT id FROM active_substances WHERE name = drug_interactions.substance_a),
+    substance_b_id = (SELECT id FROM active_substances WHERE name = drug_interactions.substance_b);
+
+ALTER TABLE drug_interactions
+    ALTER COLUMN substance_a_id SET NOT NULL,
+    ALTER COLUMN substance_b_id SET NOT NULL,
+    DROP COLUMN substance_a,
+    DROP COLUMN substance_b,
+    ADD CHECK (substance_a_id <> substance_b_id);
+
+-- Pairs are still stored once, whichever order their substances' ids are in
+CREATE UNIQUE INDEX drug_interactions_substances_key ON drug_interactions (
+    LEAST(substance_a_id, substance_b_id),
+    GREATEST(substance_a_id, substance_b_id)
+);
+CREATE INDEX drug_interactions_substance_a_id_idx ON drug_interactions (substance_a_id);
+CREATE INDEX drug_interactions_substance_b_id_idx ON drug_interactions (substance_b_id);
+
+ALTER TABLE patient_allergies ADD COLUMN active_substance_id UUID REFERENCES active_substances(id);
+
+UPDATE patient_allergies SET
+    active_substance_id = (SELECT id FROM active_substances WHERE name = patient_allergies.substance);
+
+ALTER TABLE patient_allergies
+    ALTER COLUMN active_substance_id SET NOT NULL,
+    DROP COLUMN substance,
+    ADD UNIQUE (patient_id, active_substance_id);
@@ -2,7 +2,7 @@ use okapi::openapi3::Responses;
 use rocket::{
     get,
     http::Status,
-    post,
+    patch, post,
     response::{status::Created, Responder},
     serde::json::Json,
     Request,
@@ -15,13 +15,19 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    application::api::utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    application::api::{
+        guards::authorization::DoctorSession,
+        utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    },
     domain::doctors::{
         entities::Doctor,
         repository::{
             CreateDoctorRepositoryError, GetDoctorByIdRepositoryError, GetDoctorsRepositoryError,
+            UpdateDoctorRepositoryError,
+        },
+        service::{
+            CreateDoctorError, GetDoctorByIdError, GetDoctorsWithPaginationError, UpdateDoctorError,
         },
-        service::{CreateDoctorError, GetDoctorByIdError, GetDoctorsWithPaginationError},
     },
     Ctx,
 };
@@ -178,17 +184,96 @@ pub async fn get_doctors_with_pagination(
     Ok(Json(doctors))
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateDoctorDto {
+    #[schemars(example = "example_name")]
+    name: Option<String>,
+    #[schemars(example = "example_pesel_number")]
+    pesel_number: Option<String>,
+    #[schemars(example = "example_pwz_number")]
+    pwz_number: Option<String>,
+}
+
+impl<'r> Responder<'r, 'static> for UpdateDoctorError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::NotAccountOwner => (
+                "Doctor can only update their own account".into(),
+                Status::Forbidden,
+            ),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    UpdateDoctorRepositoryError::NotFound(_) => Status::NotFound,
+                    UpdateDoctorRepositoryError::DuplicatedPeselNumber => Status::Conflict,
+                    UpdateDoctorRepositoryError::DuplicatedPwzNumber => Status::Conflict,
+                    UpdateDoctorRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for UpdateDoctorError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "403",
+                "Returned when the session is not a session of the updated doctor",
+            ),
+            ("404", "Returned when the doctor with given id doesn't exist"),
+            (
+                "422",
+                "Returned when the doctor_id is not a valid UUID, or the name, the pesel_number or the pwz_number are incorrect",
+            ),
+            (
+                "409",
+                "Returned when another doctor with given pwz_number or pesel_number exists in the database",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Doctors")]
+#[patch("/doctors/<doctor_id>", format = "application/json", data = "<dto>")]
+pub async fn update_doctor(
+    ctx: &Ctx,
+    session: DoctorSession,
+    doctor_id: Uuid,
+    dto: Json<UpdateDoctorDto>,
+) -> Result<Json<Doctor>, UpdateDoctorError> {
+    let updated_doctor = ctx
+        .doctors_service
+        .update_doctor(
+            doctor_id,
+            session.0.doctor_id.unwrap(),
+            dto.0.name,
+            dto.0.pesel_number,
+            dto.0.pwz_number,
+        )
+        .await?;
+
+    Ok(Json(updated_doctor))
+}
+
 #[cfg(test)]
 mod tests {
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::asynchronous::Client,
         routes,
         serde::json,
     };
+    use uuid::Uuid;
 
     use crate::{
-        application::api::utils::fake_api_context::create_fake_api_context,
+        application::api::utils::fake_api_context::{
+            create_fake_api_context, create_session_token,
+        },
         domain::doctors::entities::Doctor,
     };
 
@@ -198,7 +283,8 @@ mod tests {
         let routes = routes![
             super::create_doctor,
             super::get_doctor_by_id,
-            super::get_doctors_with_pagination
+            super::get_doctors_with_pagination,
+            super::update_doctor
         ];
 
         let rocket = rocket::build().manage(context).mount("/", routes);
@@ -383,4 +469,129 @@ mod tests {
             Status::UnprocessableEntity
         );
     }
+
+    #[tokio::test]
+    async fn updates_doctor() {
+        let client = create_api_client().await;
+
+        let create_doctor_response = client
+            .post("/doctors")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250", "pwz_number":"5425740"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_doctor: Doctor =
+            json::from_str(&create_doctor_response.into_string().await.unwrap()).unwrap();
+
+        let token = create_session_token(&client, Some(created_doctor.id), None).await;
+
+        let response = client
+            .patch(format!("/doctors/{}", created_doctor.id))
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let updated_doctor: Doctor =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(updated_doctor.name, "John Smith");
+        assert_eq!(updated_doctor.pesel_number, "96021807250");
+        assert_eq!(updated_doctor.pwz_number, "5425740");
+        assert!(updated_doctor.updated_at > created_doctor.updated_at);
+    }
+
+    #[tokio::test]
+    async fn update_doctor_returns_error_if_doctor_doesnt_exist_or_body_is_incorrect() {
+        let client = create_api_client().await;
+
+        let create_doctor_response = client
+            .post("/doctors")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250", "pwz_number":"5425740"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_doctor: Doctor =
+            json::from_str(&create_doctor_response.into_string().await.unwrap()).unwrap();
+        client
+            .post("/doctors")
+            .body(r#"{"name":"John Doey", "pesel_number":"99031301347", "pwz_number":"8463856"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        let token = create_session_token(&client, Some(created_doctor.id), None).await;
+
+        let response = client
+            .patch(format!("/doctors/{}", created_doctor.id))
+            .body(r#"{"pesel_number":"96021807251"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .patch(format!("/doctors/{}", created_doctor.id))
+            .body(r#"{"pwz_number":"8463856"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let token = create_session_token(&client, Some(Uuid::nil()), None).await;
+        let response = client
+            .patch("/doctors/00000000-0000-0000-0000-000000000000")
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn update_doctor_returns_forbidden_if_session_isnt_of_updated_doctor() {
+        let client = create_api_client().await;
+
+        let create_doctor_response = client
+            .post("/doctors")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250", "pwz_number":"5425740"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_doctor: Doctor =
+            json::from_str(&create_doctor_response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .patch(format!("/doctors/{}", created_doctor.id))
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        for token in [
+            create_session_token(&client, Some(Uuid::new_v4()), None).await,
+            create_session_token(&client, None, Some(Uuid::new_v4())).await,
+        ] {
+            let response = client
+                .patch(format!("/doctors/{}", created_doctor.id))
+                .body(r#"{"name":"John Smith"}"#)
+                .header(ContentType::JSON)
+                .header(Header::new("Authorization", format!("Bearer {}", token)))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Forbidden);
+        }
+    }
 }
@@ -1,8 +1,9 @@
+use chrono::{DateTime, Utc};
 use okapi::openapi3::Responses;
 use rocket::{
     get,
     http::Status,
-    post,
+    patch, post,
     response::{status::Created, Responder},
     serde::json::Json,
     Request,
@@ -13,11 +14,41 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    application::api::utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    application::api::{
+        guards::authorization::DoctorSession,
+        utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    },
     domain::drugs::{
-        entities::{Drug, DrugContentType},
-        repository::{GetDrugByIdRepositoryError, GetDrugsRepositoryError},
-        service::{CreateDrugError, GetDrugByIdError, GetDrugsWithPaginationError},
+        entities::{
+            ActiveSubstance, AtcGroup, ControlledSubstanceList, Drug, DrugActiveSubstance,
+            DrugCategory, DrugContentType, DrugEquivalenceGroup, DrugImportFormat,
+            DrugImportReport, DrugInteraction, DrugInteractionSeverity, DrugPriceList,
+            DrugPriceListItem, DrugReimbursement, DrugVariant, ReimbursementLevel, StrengthUnit,
+        },
+        repository::{
+            CreateAtcGroupRepositoryError, CreateDrugCategoryRepositoryError,
+            CreateDrugEquivalenceGroupRepositoryError, CreateDrugInteractionRepositoryError,
+            CreateDrugPriceListRepositoryError, CreateDrugReimbursementRepositoryError,
+            CreateDrugRepositoryError, CreateDrugVariantRepositoryError,
+            GetActiveSubstancesRepositoryError, GetAtcGroupsRepositoryError,
+            GetDrugByGtinRepositoryError, GetDrugByIdRepositoryError,
+            GetDrugCategoriesRepositoryError, GetDrugEquivalenceGroupsRepositoryError,
+            GetDrugInteractionsRepositoryError, GetDrugsRepositoryError, UpdateDrugRepositoryError,
+        },
+        service::{
+            CreateAtcGroupError, CreateDrugCategoryError, CreateDrugEquivalenceGroupError,
+            CreateDrugError, CreateDrugInteractionError, CreateDrugPriceListError,
+            CreateDrugReimbursementError, CreateDrugVariantError,
+            GetActiveSubstancesWithPaginationError, GetAtcGroupsWithPaginationError,
+            GetDrugByGtinError, GetDrugByIdError, GetDrugCategoriesWithPaginationError,
+            GetDrugEquivalenceGroupsWithPaginationError, GetDrugInteractionsWithPaginationError,
+            GetDrugsWithPaginationError, ImportDrugsError, UpdateDrugError,
+        },
+        use_cases::{
+            create_drug_price_list::CreateDrugPriceListDomainError,
+            create_drug_reimbursement::CreateDrugReimbursementDomainError,
+            create_drug_variant::CreateDrugVariantDomainError,
+        },
     },
     Ctx,
 };
@@ -40,6 +71,51 @@ fn example_ml_per_pill() -> Option<i32> {
 fn example_volume_ml() -> Option<i32> {
     None
 }
+fn example_gtin() -> Option<&'static str> {
+    Some("5909990733828")
+}
+fn example_active_substances() -> Option<Vec<DrugActiveSubstanceDto>> {
+    Some(vec![DrugActiveSubstanceDto {
+        name: "paracetamol".into(),
+        amount: Some(300.0),
+        unit: Some(StrengthUnit::Mg),
+    }])
+}
+fn example_substance_a() -> &'static str {
+    "warfarin"
+}
+fn example_substance_b() -> &'static str {
+    "ibuprofen"
+}
+fn example_severity() -> DrugInteractionSeverity {
+    DrugInteractionSeverity::Major
+}
+fn example_description() -> &'static str {
+    "Increased risk of bleeding"
+}
+fn example_atc_code() -> Option<&'static str> {
+    Some("N02BE01")
+}
+fn example_atc_group_code() -> &'static str {
+    "N02BE"
+}
+fn example_atc_group_name() -> &'static str {
+    "Anilides"
+}
+fn example_category_name() -> &'static str {
+    "Painkillers"
+}
+fn example_equivalence_group_name() -> &'static str {
+    "Paracetamol 300 mg tablets"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DrugActiveSubstanceDto {
+    name: String,
+    #[schemars(description = "Amount of the substance per pill, or per whole bottle of liquid")]
+    amount: Option<f64>,
+    unit: Option<StrengthUnit>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreateDrugDto {
@@ -47,21 +123,169 @@ pub struct CreateDrugDto {
     name: String,
     #[schemars(example = "example_drug_content_type")]
     content_type: DrugContentType,
-    #[schemars(example = "example_pills_count")]
+    #[schemars(
+        example = "example_pills_count",
+        description = "Pills count of the first package size, more can be added as variants"
+    )]
     pills_count: Option<i32>,
     #[schemars(example = "example_mg_per_pill")]
     mg_per_pill: Option<i32>,
     #[schemars(example = "example_ml_per_pill")]
     ml_per_pill: Option<i32>,
+    #[schemars(
+        example = "example_volume_ml",
+        description = "Volume of the first package size, more can be added as variants"
+    )]
+    volume_ml: Option<i32>,
+    #[schemars(
+        example = "example_gtin",
+        description = "GTIN-13 (EAN) barcode of the first package size"
+    )]
+    gtin: Option<String>,
+    #[schemars(example = "example_active_substances")]
+    active_substances: Option<Vec<DrugActiveSubstanceDto>>,
+    #[schemars(
+        example = "example_atc_code",
+        description = "ATC code of the chemical substance level, its group has to exist"
+    )]
+    atc_code: Option<String>,
+    category_id: Option<Uuid>,
+    #[schemars(
+        description = "List of controlled substances the drug is on, such drugs can only be prescribed on prescription for controlled substances"
+    )]
+    controlled_substance_list: Option<ControlledSubstanceList>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateDrugDto {
+    #[schemars(example = "example_drug_name")]
+    name: Option<String>,
+    #[schemars(
+        example = "example_mg_per_pill",
+        description = "Ignored unless the content type of the drug is SOLID_PILLS"
+    )]
+    mg_per_pill: Option<i32>,
+    #[schemars(
+        example = "example_ml_per_pill",
+        description = "Ignored unless the content type of the drug is LIQUID_PILLS"
+    )]
+    ml_per_pill: Option<i32>,
+    #[schemars(
+        example = "example_atc_code",
+        description = "ATC code of the chemical substance level, its group has to exist"
+    )]
+    atc_code: Option<String>,
+    category_id: Option<Uuid>,
+    controlled_substance_list: Option<ControlledSubstanceList>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugVariantDto {
+    #[schemars(example = "example_pills_count")]
+    pills_count: Option<i32>,
     #[schemars(example = "example_volume_ml")]
     volume_ml: Option<i32>,
+    #[schemars(
+        example = "example_gtin",
+        description = "GTIN-13 (EAN) barcode of the package"
+    )]
+    gtin: Option<String>,
+}
+
+fn example_reimbursement_levels() -> Vec<ReimbursementLevel> {
+    vec![
+        ReimbursementLevel::FiftyPercent,
+        ReimbursementLevel::FreeForSeniors,
+    ]
+}
+fn example_reimbursement_limit() -> i64 {
+    1250
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugReimbursementDto {
+    variant_id: Uuid,
+    #[schemars(example = "example_reimbursement_levels")]
+    levels: Vec<ReimbursementLevel>,
+    #[schemars(
+        example = "example_reimbursement_limit",
+        description = "Price of the package (in grosze) the reimbursement is computed from"
+    )]
+    reimbursement_limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugPriceListDto {
+    #[schemars(description = "By default the price list is valid from now")]
+    valid_from: Option<DateTime<Utc>>,
+    #[schemars(
+        description = "By default the price list is valid until the next price list of the same drug packages starts"
+    )]
+    valid_to: Option<DateTime<Utc>>,
+    prices: Vec<DrugPriceListItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImportDrugsDto {
+    format: DrugImportFormat,
+    #[schemars(
+        description = "Registry export with columns (CSV header) or <drug> child elements (XML): external_id, name, content_type, pills_count, mg_per_pill, ml_per_pill, volume_ml, atc_code, gtin"
+    )]
+    content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugInteractionDto {
+    #[schemars(example = "example_substance_a")]
+    substance_a: String,
+    #[schemars(example = "example_substance_b")]
+    substance_b: String,
+    #[schemars(example = "example_severity")]
+    severity: DrugInteractionSeverity,
+    #[schemars(example = "example_description")]
+    description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateAtcGroupDto {
+    #[schemars(example = "example_atc_group_code")]
+    code: String,
+    #[schemars(example = "example_atc_group_name")]
+    name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugCategoryDto {
+    #[schemars(example = "example_category_name")]
+    name: String,
+    parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDrugEquivalenceGroupDto {
+    #[schemars(example = "example_equivalence_group_name")]
+    name: String,
+    #[schemars(
+        description = "Drugs with the same active substances, strengths and form, that don't belong to any other group"
+    )]
+    drug_ids: Vec<Uuid>,
 }
 
 impl<'r> Responder<'r, 'static> for CreateDrugError {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let (message, status) = match self {
             Self::DomainError(message) => (message, Status::UnprocessableEntity),
-            Self::RepositoryError(err) => (err.to_string(), Status::InternalServerError),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugRepositoryError::AtcGroupNotFound(_) => Status::NotFound,
+                    CreateDrugRepositoryError::CategoryNotFound(_) => Status::NotFound,
+                    CreateDrugRepositoryError::DuplicatedExternalId(_) => Status::Conflict,
+                    CreateDrugRepositoryError::DuplicatedGtin(_) => Status::Conflict,
+                    CreateDrugRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
         };
 
         ApiError::build_rocket_response(req, message, status)
@@ -71,9 +295,17 @@ impl<'r> Responder<'r, 'static> for CreateDrugError {
 impl OpenApiResponderInner for CreateDrugError {
     fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
         get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the ATC group of the atc_code or the category with the given id was not found",
+            ),
+            (
+                "409",
+                "Returned when a drug package with the given gtin already exists",
+            ),
             (
                 "422",
-                "Returned when the quantity parameters dont match the content type (for instance when missing volume_ml from BOTTLE_OF_LIQUID content_type)",
+                "Returned when the quantity parameters dont match the content type (for instance when missing volume_ml from BOTTLE_OF_LIQUID content_type), when an active substance is invalid, listed twice, or has an amount without a unit, when the atc_code is not a valid code of a chemical substance, or when the gtin is not a valid GTIN-13",
             ),
         ])
     }
@@ -94,6 +326,20 @@ pub async fn create_drug(
             dto.0.mg_per_pill,
             dto.0.ml_per_pill,
             dto.0.volume_ml,
+            dto.0.gtin,
+            dto.0
+                .active_substances
+                .unwrap_or_default()
+                .into_iter()
+                .map(|active_substance| DrugActiveSubstance {
+                    name: active_substance.name,
+                    amount: active_substance.amount,
+                    unit: active_substance.unit,
+                })
+                .collect(),
+            dto.0.atc_code,
+            dto.0.category_id,
+            dto.0.controlled_substance_list,
         )
         .await?;
 
@@ -138,6 +384,297 @@ pub async fn get_drug_by_id(ctx: &Ctx, drug_id: Uuid) -> Result<Json<Drug>, GetD
     Ok(Json(drug))
 }
 
+impl<'r> Responder<'r, 'static> for GetDrugByGtinError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetDrugByGtinRepositoryError::NotFound(_) => Status::NotFound,
+                    GetDrugByGtinRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetDrugByGtinError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when no drug has a package with the given gtin",
+            ),
+            (
+                "422",
+                "Returned when the gtin is not a valid GTIN-13 (13 digits with a correct check digit)",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get("/drugs/by-gtin/<gtin>")]
+pub async fn get_drug_by_gtin(ctx: &Ctx, gtin: String) -> Result<Json<Drug>, GetDrugByGtinError> {
+    let drug = ctx.drugs_service.get_drug_by_gtin(gtin).await?;
+
+    Ok(Json(drug))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugVariantError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugVariantDomainError::InvalidPillsCount => Status::UnprocessableEntity,
+                    CreateDrugVariantDomainError::InvalidVolume => Status::UnprocessableEntity,
+                    CreateDrugVariantDomainError::DuplicatedVariant => Status::Conflict,
+                    CreateDrugVariantDomainError::InvalidGtin(_) => Status::UnprocessableEntity,
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugVariantRepositoryError::DrugNotFound(_) => Status::NotFound,
+                    CreateDrugVariantRepositoryError::DuplicatedGtin(_) => Status::Conflict,
+                    CreateDrugVariantRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugVariantError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the drug with the given id was not found",
+            ),
+            (
+                "409",
+                "Returned when the drug already has a variant of this size, or a drug package with the given gtin already exists",
+            ),
+            (
+                "422",
+                "Returned when the drug_id is not a valid UUID, the size doesn't match the drug's content type (pills_count for pills, volume_ml for BOTTLE_OF_LIQUID), or the gtin is not a valid GTIN-13",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/<drug_id>/variants", format = "json", data = "<dto>")]
+pub async fn create_drug_variant(
+    ctx: &Ctx,
+    drug_id: Uuid,
+    dto: Json<CreateDrugVariantDto>,
+) -> Result<Created<Json<DrugVariant>>, CreateDrugVariantError> {
+    let created_variant = ctx
+        .drugs_service
+        .create_drug_variant(drug_id, dto.0.pills_count, dto.0.volume_ml, dto.0.gtin)
+        .await?;
+
+    let location = format!("/drugs/{}", drug_id);
+    Ok(Created::new(location).body(Json(created_variant)))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugReimbursementError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugReimbursementDomainError::VariantNotFound(_) => Status::NotFound,
+                    CreateDrugReimbursementDomainError::DuplicatedReimbursement(_) => {
+                        Status::Conflict
+                    }
+                    CreateDrugReimbursementDomainError::NoLevels => Status::UnprocessableEntity,
+                    CreateDrugReimbursementDomainError::InvalidReimbursementLimit => {
+                        Status::UnprocessableEntity
+                    }
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugReimbursementRepositoryError::DrugNotFound(_) => Status::NotFound,
+                    CreateDrugReimbursementRepositoryError::DrugVariantNotFound(_) => {
+                        Status::NotFound
+                    }
+                    CreateDrugReimbursementRepositoryError::DuplicatedReimbursement(_) => {
+                        Status::Conflict
+                    }
+                    CreateDrugReimbursementRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugReimbursementError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the drug or its variant with the given id was not found",
+            ),
+            (
+                "409",
+                "Returned when the variant already has a reimbursement scheme",
+            ),
+            (
+                "422",
+                "Returned when the drug_id is not a valid UUID, no reimbursement level is given or the reimbursement_limit is not greater than 0",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/<drug_id>/reimbursements", format = "json", data = "<dto>")]
+pub async fn create_drug_reimbursement(
+    ctx: &Ctx,
+    drug_id: Uuid,
+    dto: Json<CreateDrugReimbursementDto>,
+) -> Result<Created<Json<DrugReimbursement>>, CreateDrugReimbursementError> {
+    let created_reimbursement = ctx
+        .drugs_service
+        .create_drug_reimbursement(
+            drug_id,
+            dto.0.variant_id,
+            dto.0.levels,
+            dto.0.reimbursement_limit,
+        )
+        .await?;
+
+    let location = format!("/drugs/{}", drug_id);
+    Ok(Created::new(location).body(Json(created_reimbursement)))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugPriceListError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugPriceListDomainError::DrugNotFound(_)
+                    | CreateDrugPriceListDomainError::VariantNotFound(_, _) => Status::NotFound,
+                    CreateDrugPriceListDomainError::OverlappingPrice(_) => Status::Conflict,
+                    CreateDrugPriceListDomainError::NoPrices
+                    | CreateDrugPriceListDomainError::InvalidValidityPeriod
+                    | CreateDrugPriceListDomainError::DuplicatedVariant(_)
+                    | CreateDrugPriceListDomainError::InvalidPrice(_) => {
+                        Status::UnprocessableEntity
+                    }
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugPriceListRepositoryError::DrugVariantNotFound(_) => Status::NotFound,
+                    CreateDrugPriceListRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugPriceListError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when one of the drugs or their variants was not found",
+            ),
+            (
+                "409",
+                "Returned when one of the variants already has a price in the validity period of the price list, other than the current one",
+            ),
+            (
+                "422",
+                "Returned when no prices are given, a price is not greater than 0, a variant is listed twice or valid_to is not after valid_from",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/price-lists", format = "json", data = "<dto>")]
+pub async fn create_drug_price_list(
+    ctx: &Ctx,
+    dto: Json<CreateDrugPriceListDto>,
+) -> Result<Created<Json<DrugPriceList>>, CreateDrugPriceListError> {
+    let created_price_list = ctx
+        .drugs_service
+        .create_drug_price_list(dto.0.valid_from, dto.0.valid_to, dto.0.prices)
+        .await?;
+
+    let location = format!("/drugs/price-lists/{}", created_price_list.id);
+    Ok(Created::new(location).body(Json(created_price_list)))
+}
+
+impl<'r> Responder<'r, 'static> for ImportDrugsError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => (err.to_string(), Status::UnprocessableEntity),
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for ImportDrugsError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a doctor's session"),
+            (
+                "422",
+                "Returned when the file can't be parsed or doesn't contain any drugs",
+            ),
+        ])
+    }
+}
+
+// Invalid drugs are listed as rejected in the report, together with the created and updated ones
+#[openapi(tag = "Admin")]
+#[post("/admin/drugs/import", format = "json", data = "<dto>")]
+pub async fn import_drugs(
+    ctx: &Ctx,
+    _session: DoctorSession,
+    dto: Json<ImportDrugsDto>,
+) -> Result<Json<DrugImportReport>, ImportDrugsError> {
+    let report = ctx
+        .drugs_service
+        .import_drugs(dto.0.content, dto.0.format)
+        .await?;
+
+    Ok(Json(report))
+}
+
 impl<'r> Responder<'r, 'static> for GetDrugsWithPaginationError {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let (message, status) = match self {
@@ -167,49 +704,719 @@ impl OpenApiResponderInner for GetDrugsWithPaginationError {
 }
 
 #[openapi(tag = "Drugs")]
-#[get("/drugs?<page>&<page_size>", format = "application/json")]
+#[get(
+    "/drugs?<page>&<page_size>&<substance>&<atc_code>&<category_id>",
+    format = "application/json"
+)]
 pub async fn get_drugs_with_pagination(
     ctx: &Ctx,
     page: Option<i64>,
     page_size: Option<i64>,
+    substance: Option<String>,
+    atc_code: Option<String>,
+    category_id: Option<Uuid>,
 ) -> Result<Json<Vec<Drug>>, GetDrugsWithPaginationError> {
     let drugs = ctx
         .drugs_service
-        .get_drugs_with_pagination(page, page_size)
+        .get_drugs_with_pagination(page, page_size, substance, atc_code, category_id)
         .await?;
 
     Ok(Json(drugs))
 }
-#[cfg(test)]
-mod tests {
+
+impl<'r> Responder<'r, 'static> for UpdateDrugError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    UpdateDrugRepositoryError::NotFound(_) => Status::NotFound,
+                    UpdateDrugRepositoryError::AtcGroupNotFound(_) => Status::NotFound,
+                    UpdateDrugRepositoryError::CategoryNotFound(_) => Status::NotFound,
+                    UpdateDrugRepositoryError::DuplicatedExternalId(_) => Status::Conflict,
+                    UpdateDrugRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for UpdateDrugError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a doctor's session"),
+            (
+                "404",
+                "Returned when the drug, the ATC group of the atc_code or the category with the given id was not found",
+            ),
+            (
+                "422",
+                "Returned when the drug_id is not a valid UUID, when mg_per_pill or ml_per_pill matching the content type of the drug is not greater than 0, or when the atc_code is not a valid code of a chemical substance",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[patch("/drugs/<drug_id>", format = "json", data = "<dto>")]
+pub async fn update_drug(
+    ctx: &Ctx,
+    _session: DoctorSession,
+    drug_id: Uuid,
+    dto: Json<UpdateDrugDto>,
+) -> Result<Json<Drug>, UpdateDrugError> {
+    let updated_drug = ctx
+        .drugs_service
+        .update_drug(
+            drug_id,
+            dto.0.name,
+            dto.0.mg_per_pill,
+            dto.0.ml_per_pill,
+            dto.0.atc_code,
+            dto.0.category_id,
+            dto.0.controlled_substance_list,
+        )
+        .await?;
+
+    Ok(Json(updated_drug))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugInteractionError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugInteractionRepositoryError::DuplicatedInteraction => Status::Conflict,
+                    CreateDrugInteractionRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugInteractionError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "409",
+                "Returned when the interaction between the given substances already exists",
+            ),
+            (
+                "422",
+                "Returned when the substances are invalid or the same, or when the description is empty",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/interactions", format = "json", data = "<dto>")]
+pub async fn create_drug_interaction(
+    ctx: &Ctx,
+    dto: Json<CreateDrugInteractionDto>,
+) -> Result<Created<Json<DrugInteraction>>, CreateDrugInteractionError> {
+    let created_drug_interaction = ctx
+        .drugs_service
+        .create_drug_interaction(
+            dto.0.substance_a,
+            dto.0.substance_b,
+            dto.0.severity,
+            dto.0.description,
+        )
+        .await?;
+
+    let location = format!("/drugs/interactions/{}", created_drug_interaction.id);
+    Ok(Created::new(location).body(Json(created_drug_interaction)))
+}
+
+impl<'r> Responder<'r, 'static> for GetDrugInteractionsWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetDrugInteractionsRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetDrugInteractionsRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetDrugInteractionsWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![(
+            "422",
+            "Returned when the the page < 0 or page_size < 1",
+        )])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get("/drugs/interactions?<page>&<page_size>", format = "application/json")]
+pub async fn get_drug_interactions_with_pagination(
+    ctx: &Ctx,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<Json<Vec<DrugInteraction>>, GetDrugInteractionsWithPaginationError> {
+    let drug_interactions = ctx
+        .drugs_service
+        .get_drug_interactions_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(drug_interactions))
+}
+
+impl<'r> Responder<'r, 'static> for GetActiveSubstancesWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetActiveSubstancesRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetActiveSubstancesRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetActiveSubstancesWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![(
+            "422",
+            "Returned when the the page < 0 or page_size < 1",
+        )])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get("/drugs/substances?<page>&<page_size>", format = "application/json")]
+pub async fn get_active_substances_with_pagination(
+    ctx: &Ctx,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<Json<Vec<ActiveSubstance>>, GetActiveSubstancesWithPaginationError> {
+    let active_substances = ctx
+        .drugs_service
+        .get_active_substances_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(active_substances))
+}
+
+impl<'r> Responder<'r, 'static> for CreateAtcGroupError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateAtcGroupRepositoryError::DuplicatedCode => Status::Conflict,
+                    CreateAtcGroupRepositoryError::ParentNotFound(_) => Status::NotFound,
+                    CreateAtcGroupRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateAtcGroupError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the group of the previous ATC level was not found",
+            ),
+            (
+                "409",
+                "Returned when the ATC group with the given code already exists",
+            ),
+            (
+                "422",
+                "Returned when the code is not a valid ATC code or the name is empty or too long",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/atc-groups", format = "json", data = "<dto>")]
+pub async fn create_atc_group(
+    ctx: &Ctx,
+    dto: Json<CreateAtcGroupDto>,
+) -> Result<Created<Json<AtcGroup>>, CreateAtcGroupError> {
+    let created_atc_group = ctx
+        .drugs_service
+        .create_atc_group(dto.0.code, dto.0.name)
+        .await?;
+
+    let location = format!("/drugs/atc-groups/{}", created_atc_group.code);
+    Ok(Created::new(location).body(Json(created_atc_group)))
+}
+
+impl<'r> Responder<'r, 'static> for GetAtcGroupsWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetAtcGroupsRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetAtcGroupsRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetAtcGroupsWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![(
+            "422",
+            "Returned when the the page < 0 or page_size < 1",
+        )])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get("/drugs/atc-groups?<page>&<page_size>", format = "application/json")]
+pub async fn get_atc_groups_with_pagination(
+    ctx: &Ctx,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<Json<Vec<AtcGroup>>, GetAtcGroupsWithPaginationError> {
+    let atc_groups = ctx
+        .drugs_service
+        .get_atc_groups_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(atc_groups))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugCategoryError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugCategoryRepositoryError::ParentNotFound(_) => Status::NotFound,
+                    CreateDrugCategoryRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugCategoryError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the parent category with the given id was not found",
+            ),
+            ("422", "Returned when the name is empty or too long"),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/categories", format = "json", data = "<dto>")]
+pub async fn create_drug_category(
+    ctx: &Ctx,
+    dto: Json<CreateDrugCategoryDto>,
+) -> Result<Created<Json<DrugCategory>>, CreateDrugCategoryError> {
+    let created_drug_category = ctx
+        .drugs_service
+        .create_drug_category(dto.0.name, dto.0.parent_id)
+        .await?;
+
+    let location = format!("/drugs/categories/{}", created_drug_category.id);
+    Ok(Created::new(location).body(Json(created_drug_category)))
+}
+
+impl<'r> Responder<'r, 'static> for GetDrugCategoriesWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetDrugCategoriesRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetDrugCategoriesRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetDrugCategoriesWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![(
+            "422",
+            "Returned when the the page < 0 or page_size < 1",
+        )])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get("/drugs/categories?<page>&<page_size>", format = "application/json")]
+pub async fn get_drug_categories_with_pagination(
+    ctx: &Ctx,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<Json<Vec<DrugCategory>>, GetDrugCategoriesWithPaginationError> {
+    let drug_categories = ctx
+        .drugs_service
+        .get_drug_categories_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(drug_categories))
+}
+
+impl<'r> Responder<'r, 'static> for CreateDrugEquivalenceGroupError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(_) => Status::NotFound,
+                    CreateDrugEquivalenceGroupRepositoryError::DrugAlreadyInGroup(_) => {
+                        Status::Conflict
+                    }
+                    CreateDrugEquivalenceGroupRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreateDrugEquivalenceGroupError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("404", "Returned when drug with one of the given ids was not found"),
+            (
+                "409",
+                "Returned when one of the drugs already belongs to an equivalence group",
+            ),
+            (
+                "422",
+                "Returned when the name is invalid, less than 2 drugs are given or the drugs aren't equivalent",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[post("/drugs/equivalence-groups", format = "json", data = "<dto>")]
+pub async fn create_drug_equivalence_group(
+    ctx: &Ctx,
+    dto: Json<CreateDrugEquivalenceGroupDto>,
+) -> Result<Created<Json<DrugEquivalenceGroup>>, CreateDrugEquivalenceGroupError> {
+    let created_drug_equivalence_group = ctx
+        .drugs_service
+        .create_drug_equivalence_group(dto.0.name, dto.0.drug_ids)
+        .await?;
+
+    let location = format!(
+        "/drugs/equivalence-groups/{}",
+        created_drug_equivalence_group.id
+    );
+    Ok(Created::new(location).body(Json(created_drug_equivalence_group)))
+}
+
+impl<'r> Responder<'r, 'static> for GetDrugEquivalenceGroupsWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetDrugEquivalenceGroupsRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetDrugEquivalenceGroupsRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetDrugEquivalenceGroupsWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![(
+            "422",
+            "Returned when the the page < 0 or page_size < 1",
+        )])
+    }
+}
+
+#[openapi(tag = "Drugs")]
+#[get(
+    "/drugs/equivalence-groups?<page>&<page_size>",
+    format = "application/json"
+)]
+pub async fn get_drug_equivalence_groups_with_pagination(
+    ctx: &Ctx,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<Json<Vec<DrugEquivalenceGroup>>, GetDrugEquivalenceGroupsWithPaginationError> {
+    let drug_equivalence_groups = ctx
+        .drugs_service
+        .get_drug_equivalence_groups_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(drug_equivalence_groups))
+}
+
+#[cfg(test)]
+mod tests {
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::asynchronous::Client,
         routes,
         serde::json,
     };
+    use uuid::Uuid;
+
+    use crate::{
+        application::api::utils::fake_api_context::{
+            create_fake_api_context, create_session_token,
+        },
+        domain::drugs::entities::{
+            ActiveSubstance, AtcGroup, Drug, DrugActiveSubstance, DrugCategory, DrugContentType,
+            DrugEquivalenceGroup, DrugImportReport, DrugImportRowStatus, DrugInteraction,
+            DrugPriceList, DrugReimbursement, DrugVariant, ReimbursementLevel, StrengthUnit,
+        },
+    };
+
+    async fn create_api_client() -> Client {
+        let context = create_fake_api_context();
+
+        let routes = routes![
+            super::create_drug,
+            super::get_drug_by_id,
+            super::get_drug_by_gtin,
+            super::get_drugs_with_pagination,
+            super::update_drug,
+            super::create_drug_variant,
+            super::create_drug_reimbursement,
+            super::create_drug_price_list,
+            super::import_drugs,
+            super::create_drug_interaction,
+            super::get_drug_interactions_with_pagination,
+            super::get_active_substances_with_pagination,
+            super::create_atc_group,
+            super::get_atc_groups_with_pagination,
+            super::create_drug_category,
+            super::get_drug_categories_with_pagination,
+            super::create_drug_equivalence_group,
+            super::get_drug_equivalence_groups_with_pagination,
+        ];
+
+        let rocket = rocket::build().manage(context).mount("/", routes);
+
+        Client::tracked(rocket).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn creates_and_gets_drug_by_id() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": 30, "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(created_drug_response.status(), Status::Created);
+
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_drug.name, "Drug 1");
+        assert_eq!(created_drug.variants[0].pills_count, Some(30));
+        assert_eq!(created_drug.mg_per_pill, Some(300));
+        assert_eq!(created_drug.content_type, DrugContentType::SolidPills);
+
+        let response = client
+            .get(format!("/drugs/{}", created_drug.id))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn gets_drug_by_gtin() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": 30, "mg_per_pill": 300, "content_type": "SOLID_PILLS", "gtin": "5909990733828"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(created_drug_response.status(), Status::Created);
+
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_drug.variants[0].gtin, Some("5909990733828".into()));
+
+        let response = client.get("/drugs/by-gtin/5909990733828").dispatch().await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drug.id, created_drug.id);
+
+        let duplicated_gtin_response = client
+            .post(format!("/drugs/{}/variants", created_drug.id))
+            .header(ContentType::JSON)
+            .body(r#"{"pills_count": 60, "gtin": "5909990733828"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(duplicated_gtin_response.status(), Status::Conflict);
+        assert_eq!(
+            client
+                .get("/drugs/by-gtin/5909990733835")
+                .dispatch()
+                .await
+                .status(),
+            Status::NotFound
+        );
+        assert_eq!(
+            client
+                .get("/drugs/by-gtin/5909990733829")
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
+    }
+
+    #[tokio::test]
+    async fn create_drug_returns_unprocessable_entity_with_invalid_data() {
+        let client = create_api_client().await;
+        assert_eq!(client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": "30", "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await.status(), Status::UnprocessableEntity);
 
-    use crate::{
-        application::api::utils::fake_api_context::create_fake_api_context,
-        domain::drugs::entities::{Drug, DrugContentType},
-    };
+        assert_eq!(client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": 30, "ml_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await.status(), Status::UnprocessableEntity);
 
-    async fn create_api_client() -> Client {
-        let context = create_fake_api_context();
+        assert_eq!(client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 2", "pills_count": 30, "volume_ml": 300, "content_type": "LIQUID_PILLS"}"#)
+            .dispatch()
+            .await.status(), Status::UnprocessableEntity);
+    }
 
-        let routes = routes![
-            super::create_drug,
-            super::get_drug_by_id,
-            super::get_drugs_with_pagination,
-        ];
+    #[tokio::test]
+    async fn updates_drug() {
+        let client = create_api_client().await;
 
-        let rocket = rocket::build().manage(context).mount("/", routes);
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": 30, "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
 
-        Client::tracked(rocket).await.unwrap()
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+
+        let response = client
+            .patch(format!("/drugs/{}", created_drug.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(r#"{"name": "Drug 1 Forte", "mg_per_pill": 600}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let updated_drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(updated_drug.name, "Drug 1 Forte");
+        assert_eq!(updated_drug.mg_per_pill, Some(600));
+        assert_eq!(updated_drug.variants[0].pills_count, Some(30));
+        assert!(updated_drug.updated_at > created_drug.updated_at);
     }
 
     #[tokio::test]
-    async fn creates_and_gets_drug_by_id() {
+    async fn update_drug_returns_error_if_drug_doesnt_exist_or_body_is_incorrect() {
         let client = create_api_client().await;
 
         let created_drug_response = client
@@ -218,48 +1425,380 @@ mod tests {
             .body(r#"{"name": "Drug 1", "pills_count": 30, "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
             .dispatch()
             .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
 
-        assert_eq!(created_drug_response.status(), Status::Created);
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+
+        assert_eq!(
+            client
+                .patch(format!("/drugs/{}", created_drug.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token)
+                ))
+                .body(r#"{"mg_per_pill": 0}"#)
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
+        assert_eq!(
+            client
+                .patch(format!("/drugs/{}", created_drug.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token)
+                ))
+                .body(r#"{"atc_code": "N02BE01"}"#)
+                .dispatch()
+                .await
+                .status(),
+            Status::NotFound
+        );
+        assert_eq!(
+            client
+                .patch("/drugs/00000000-0000-0000-0000-000000000000")
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token)
+                ))
+                .body(r#"{"name": "Drug 2"}"#)
+                .dispatch()
+                .await
+                .status(),
+            Status::NotFound
+        );
+    }
 
+    #[tokio::test]
+    async fn update_drug_returns_forbidden_if_session_isnt_doctors_session() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Drug 1", "pills_count": 30, "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
         let created_drug: Drug =
             json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
 
-        assert_eq!(created_drug.name, "Drug 1");
-        assert_eq!(created_drug.pills_count, Some(30));
-        assert_eq!(created_drug.mg_per_pill, Some(300));
-        assert_eq!(created_drug.content_type, DrugContentType::SolidPills);
+        let pharmacist_token = create_session_token(&client, None, Some(Uuid::new_v4())).await;
+
+        assert_eq!(
+            client
+                .patch(format!("/drugs/{}", created_drug.id))
+                .header(ContentType::JSON)
+                .body(r#"{"name": "Drug 1 Forte"}"#)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+        assert_eq!(
+            client
+                .patch(format!("/drugs/{}", created_drug.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(r#"{"name": "Drug 1 Forte"}"#)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+    }
+
+    #[tokio::test]
+    async fn creates_drug_variant() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Apap", "pills_count": 10, "mg_per_pill": 500, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .post(format!("/drugs/{}/variants", created_drug.id))
+            .header(ContentType::JSON)
+            .body(r#"{"pills_count": 20}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_variant: DrugVariant =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_variant.drug_id, created_drug.id);
+        assert_eq!(created_variant.pills_count, Some(20));
 
         let response = client
             .get(format!("/drugs/{}", created_drug.id))
             .dispatch()
             .await;
+        let drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
 
-        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(drug.variants.len(), 2);
+        assert_eq!(drug.variants[1], created_variant);
     }
 
     #[tokio::test]
-    async fn create_drug_returns_unprocessable_entity_with_invalid_data() {
+    async fn create_drug_variant_returns_error_if_variant_is_invalid() {
         let client = create_api_client().await;
-        assert_eq!(client
+
+        let created_drug_response = client
             .post("/drugs")
             .header(ContentType::JSON)
-            .body(r#"{"name": "Drug 1", "pills_count": "30", "mg_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .body(r#"{"name": "Apap", "pills_count": 10, "mg_per_pill": 500, "content_type": "SOLID_PILLS"}"#)
             .dispatch()
-            .await.status(), Status::UnprocessableEntity);
+            .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
 
-        assert_eq!(client
+        let response = client
+            .post(format!("/drugs/{}/variants", created_drug.id))
+            .header(ContentType::JSON)
+            .body(r#"{"pills_count": 10}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .post(format!("/drugs/{}/variants", created_drug.id))
+            .header(ContentType::JSON)
+            .body(r#"{"volume_ml": 100}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .post("/drugs/00000000-0000-0000-0000-000000000000/variants")
+            .header(ContentType::JSON)
+            .body(r#"{"pills_count": 20}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn creates_drug_reimbursement() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Apap", "pills_count": 10, "mg_per_pill": 500, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
+        let body = format!(
+            r#"{{"variant_id": "{}", "levels": ["FIFTY_PERCENT", "FLAT_FEE"], "reimbursement_limit": 1999}}"#,
+            created_drug.variants[0].id
+        );
+
+        let response = client
+            .post(format!("/drugs/{}/reimbursements", created_drug.id))
+            .header(ContentType::JSON)
+            .body(&body)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_reimbursement: DrugReimbursement =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(
+            created_reimbursement.levels,
+            vec![
+                ReimbursementLevel::FiftyPercent,
+                ReimbursementLevel::FlatFee
+            ]
+        );
+
+        let response = client
+            .post(format!("/drugs/{}/reimbursements", created_drug.id))
+            .header(ContentType::JSON)
+            .body(&body)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .post("/drugs/00000000-0000-0000-0000-000000000000/reimbursements")
+            .header(ContentType::JSON)
+            .body(&body)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn creates_drug_price_list() {
+        let client = create_api_client().await;
+
+        let created_drug_response = client
             .post("/drugs")
             .header(ContentType::JSON)
-            .body(r#"{"name": "Drug 1", "pills_count": 30, "ml_per_pill": 300, "content_type": "SOLID_PILLS"}"#)
+            .body(r#"{"name": "Apap", "pills_count": 10, "mg_per_pill": 500, "content_type": "SOLID_PILLS"}"#)
+            .dispatch()
+            .await;
+        let created_drug: Drug =
+            json::from_str(&created_drug_response.into_string().await.unwrap()).unwrap();
+        let create_body = |drug_id, valid_from, valid_to| {
+            format!(
+                r#"{{"valid_from": "{}", "valid_to": "{}", "prices": [{{"drug_id": "{}", "variant_id": "{}", "retail_price": 1599, "official_price": 1320}}]}}"#,
+                valid_from, valid_to, drug_id, created_drug.variants[0].id
+            )
+        };
+
+        let response = client
+            .post("/drugs/price-lists")
+            .header(ContentType::JSON)
+            .body(create_body(
+                created_drug.id,
+                "2024-01-01T00:00:00Z",
+                "2024-07-01T00:00:00Z",
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_price_list: DrugPriceList =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_price_list.prices.len(), 1);
+        assert_eq!(created_price_list.prices[0].retail_price, 1599);
+        assert_eq!(created_price_list.prices[0].official_price, 1320);
+
+        let response = client
+            .post("/drugs/price-lists")
+            .header(ContentType::JSON)
+            .body(create_body(
+                created_drug.id,
+                "2024-03-01T00:00:00Z",
+                "2024-09-01T00:00:00Z",
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .post("/drugs/price-lists")
+            .header(ContentType::JSON)
+            .body(create_body(
+                Uuid::nil(),
+                "2024-07-01T00:00:00Z",
+                "2024-09-01T00:00:00Z",
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn imports_drugs_and_returns_report() {
+        let client = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+        let body = r#"{
+            "format": "XML",
+            "content": "<drugs><drug><external_id>100001</external_id><name>Apap</name><content_type>SOLID_PILLS</content_type><pills_count>10</pills_count><mg_per_pill>500</mg_per_pill></drug><drug><external_id>100002</external_id><name>Syrop</name><content_type>BOTTLE_OF_LIQUID</content_type></drug></drugs>"
+        }"#;
+
+        let response = client
+            .post("/admin/drugs/import")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(body)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let report: DrugImportReport =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(report.created_count, 1);
+        assert_eq!(report.rejected_count, 1);
+        assert_eq!(report.rows[1].status, DrugImportRowStatus::Rejected);
+        assert_eq!(
+            report.rows[1].reason,
+            Some("Volume in ml must be provided for bottle of liquid".into())
+        );
+
+        let response = client
+            .get(format!("/drugs/{}", report.rows[0].drug_id.unwrap()))
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drug.external_id, Some("100001".into()));
+
+        let response = client
+            .post("/admin/drugs/import")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(r#"{"format": "CSV", "content": "external_id,name\n"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[tokio::test]
+    async fn import_drugs_returns_forbidden_if_session_isnt_doctors_session() {
+        let client = create_api_client().await;
+        let pharmacist_token = create_session_token(&client, None, Some(Uuid::new_v4())).await;
+        let body = r#"{"format": "CSV", "content": "external_id,name\n100001,Apap\n"}"#;
+
+        let response = client
+            .post("/admin/drugs/import")
+            .header(ContentType::JSON)
+            .body(body)
             .dispatch()
-            .await.status(), Status::UnprocessableEntity);
+            .await;
 
-        assert_eq!(client
-            .post("/drugs")
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .post("/admin/drugs/import")
             .header(ContentType::JSON)
-            .body(r#"{"name": "Drug 2", "pills_count": 30, "volume_ml": 300, "content_type": "LIQUID_PILLS"}"#)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(body)
             .dispatch()
-            .await.status(), Status::UnprocessableEntity);
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
     }
 
     #[tokio::test]
@@ -350,4 +1889,398 @@ mod tests {
             Status::UnprocessableEntity
         );
     }
+
+    #[tokio::test]
+    async fn creates_drug_with_active_substances() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Gripex", "pills_count": 20, "mg_per_pill": 355, "content_type": "SOLID_PILLS", "active_substances": [{"name": "Pseudoephedrine", "amount": 30, "unit": "MG"}, {"name": "paracetamol", "amount": 325, "unit": "MG"}]}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(
+            created_drug.active_substances,
+            vec![
+                DrugActiveSubstance {
+                    name: "paracetamol".into(),
+                    amount: Some(325.0),
+                    unit: Some(StrengthUnit::Mg),
+                },
+                DrugActiveSubstance {
+                    name: "pseudoephedrine".into(),
+                    amount: Some(30.0),
+                    unit: Some(StrengthUnit::Mg),
+                },
+            ]
+        );
+
+        let response = client
+            .get("/drugs?substance=Pseudoephedrine")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let drugs: Vec<Drug> = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drugs, vec![created_drug]);
+
+        let response = client
+            .get("/drugs?substance=ibuprofen")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let drugs: Vec<Drug> = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drugs.len(), 0);
+
+        let response = client
+            .get("/drugs/substances")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let active_substances: Vec<ActiveSubstance> =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn create_drug_returns_unprocessable_entity_if_active_substance_strength_is_incomplete() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Apap", "pills_count": 10, "mg_per_pill": 500, "content_type": "SOLID_PILLS", "active_substances": [{"name": "paracetamol", "amount": 500}]}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[tokio::test]
+    async fn creates_and_gets_drug_interactions() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/drugs/interactions")
+            .header(ContentType::JSON)
+            .body(r#"{"substance_a": "warfarin", "substance_b": "ibuprofen", "severity": "MAJOR", "description": "Increased risk of bleeding"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let response = client
+            .post("/drugs/interactions")
+            .header(ContentType::JSON)
+            .body(r#"{"substance_a": "ibuprofen", "substance_b": "warfarin", "severity": "MINOR", "description": "Increased risk of bleeding"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .post("/drugs/interactions")
+            .header(ContentType::JSON)
+            .body(r#"{"substance_a": "warfarin", "substance_b": "warfarin", "severity": "MINOR", "description": "Increased risk of bleeding"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .get("/drugs/interactions")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let interactions: Vec<DrugInteraction> =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0].substance_a, "ibuprofen");
+        assert_eq!(interactions[0].substance_b, "warfarin");
+    }
+
+    #[tokio::test]
+    async fn creates_atc_groups_and_classified_drug() {
+        let client = create_api_client().await;
+
+        for (code, name) in [
+            ("N", "Nervous system"),
+            ("N02", "Analgesics"),
+            ("N02B", "Other analgesics and antipyretics"),
+            ("N02BE", "Anilides"),
+            ("N02BE01", "Paracetamol"),
+        ] {
+            let response = client
+                .post("/drugs/atc-groups")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{"code": "{}", "name": "{}"}}"#, code, name))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Created);
+        }
+
+        let response = client
+            .post("/drugs/atc-groups")
+            .header(ContentType::JSON)
+            .body(r#"{"code": "N02", "name": "Analgesics"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .post("/drugs/atc-groups")
+            .header(ContentType::JSON)
+            .body(r#"{"code": "A01", "name": "Stomatological preparations"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .post("/drugs/atc-groups")
+            .header(ContentType::JSON)
+            .body(r#"{"code": "N2", "name": "Analgesics"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .get("/drugs/atc-groups")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let atc_groups: Vec<AtcGroup> =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(atc_groups.len(), 5);
+
+        let response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Apap", "pills_count": 30, "mg_per_pill": 500, "content_type": "SOLID_PILLS", "atc_code": "N02BE01"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_drug.atc_code, Some("N02BE01".into()));
+        assert_eq!(created_drug.atc_path.len(), 5);
+
+        let response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Ibuprom", "pills_count": 30, "mg_per_pill": 200, "content_type": "SOLID_PILLS", "atc_code": "M01AE01"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .get("/drugs?atc_code=N02")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let drugs: Vec<Drug> = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0].name, "Apap");
+    }
+
+    #[tokio::test]
+    async fn creates_drug_categories_and_filters_drugs_by_category() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/drugs/categories")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Painkillers"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let parent_category: DrugCategory =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .post("/drugs/categories")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"name": "NSAIDs", "parent_id": "{}"}}"#,
+                parent_category.id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let child_category: DrugCategory =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .post("/drugs/categories")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Antibiotics", "parent_id": "00000000-0000-0000-0000-000000000000"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .post("/drugs/categories")
+            .header(ContentType::JSON)
+            .body(r#"{"name": ""}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"name": "Ibuprom", "pills_count": 30, "mg_per_pill": 200, "content_type": "SOLID_PILLS", "category_id": "{}"}}"#,
+                child_category.id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let response = client
+            .get(format!("/drugs?category_id={}", parent_category.id))
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let drugs: Vec<Drug> = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0].category_id, Some(child_category.id));
+
+        let response = client
+            .get("/drugs/categories")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        let drug_categories: Vec<DrugCategory> =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drug_categories.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn creates_and_gets_drug_equivalence_groups() {
+        let client = create_api_client().await;
+
+        let mut drugs = vec![];
+        for (name, mg_per_pill) in [("Apap", 500), ("Panadol", 500), ("Apap Extra", 1000)] {
+            let response = client
+                .post("/drugs")
+                .header(ContentType::JSON)
+                .body(format!(
+                    r#"{{"name": "{}", "pills_count": 10, "mg_per_pill": {}, "content_type": "SOLID_PILLS", "active_substances": [{{"name": "paracetamol", "amount": {}, "unit": "MG"}}]}}"#,
+                    name, mg_per_pill, mg_per_pill
+                ))
+                .dispatch()
+                .await;
+            let drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+            drugs.push(drug);
+        }
+
+        let response = client
+            .post("/drugs/equivalence-groups")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"name": "Paracetamol 500 mg tablets", "drug_ids": ["{}", "{}"]}}"#,
+                drugs[0].id, drugs[1].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_group: DrugEquivalenceGroup =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_group.drug_ids, vec![drugs[0].id, drugs[1].id]);
+
+        let response = client
+            .post("/drugs/equivalence-groups")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"name": "Paracetamol", "drug_ids": ["{}", "{}"]}}"#,
+                drugs[2].id, drugs[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .post("/drugs/equivalence-groups")
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{"name": "Paracetamol", "drug_ids": ["{}", "00000000-0000-0000-0000-000000000000"]}}"#,
+                drugs[2].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client
+            .get(format!("/drugs/{}", drugs[1].id))
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let drug: Drug = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(drug.equivalence_group_id, Some(created_group.id));
+
+        let response = client
+            .get("/drugs/equivalence-groups")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let groups: Vec<DrugEquivalenceGroup> =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(groups, vec![created_group]);
+    }
 }
@@ -1,9 +1,13 @@
+use chrono::NaiveDate;
 use okapi::openapi3::Responses;
 use rocket::{
-    get,
+    delete, get,
     http::Status,
-    post,
-    response::{status::Created, Responder},
+    patch, post, put,
+    response::{
+        status::{Created, NoContent},
+        Responder,
+    },
     serde::json::Json,
     Request,
 };
@@ -15,13 +19,27 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    application::api::utils::{error::ApiError, openapi_responses::get_openapi_responses},
-    domain::patients::{
-        entities::Patient,
-        repository::{
-            CreatePatientRepositoryError, GetPatientByIdRepositoryError, GetPatientsRepositoryError,
+    application::api::{
+        guards::authorization::DoctorSession,
+        utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    },
+    domain::{
+        patients::{
+            entities::{AllergySeverity, IdentityDocument, Patient, PatientAllergy},
+            repository::{
+                CreatePatientAllergyRepositoryError, CreatePatientRepositoryError,
+                DeletePatientAllergyRepositoryError, GetPatientAllergiesRepositoryError,
+                GetPatientByIdRepositoryError, GetPatientsRepositoryError,
+                UpdatePatientAllergyRepositoryError, UpdatePatientRepositoryError,
+            },
+            service::{
+                CreatePatientAllergyError, CreatePatientError, DeletePatientAllergyError,
+                GetPatientAllergiesError, GetPatientByIdError, GetPatientsWithPaginationError,
+                UpdatePatientAllergyError, UpdatePatientError,
+            },
+            use_cases::create_patient_allergy::PatientAllergyDomainError,
         },
-        service::{CreatePatientError, GetPatientByIdError, GetPatientsWithPaginationError},
+        utils::validators::validate_pesel_number::Sex,
     },
     Ctx,
 };
@@ -32,13 +50,30 @@ fn example_name() -> &'static str {
 fn example_pesel_number() -> &'static str {
     "96021807250"
 }
+fn example_substance() -> &'static str {
+    "amoxicillin"
+}
+fn example_reaction() -> &'static str {
+    "Hives and swelling of the face"
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreatePatientDto {
     #[schemars(example = "example_name")]
     name: String,
-    #[schemars(example = "example_pesel_number")]
-    pesel_number: String,
+    #[schemars(
+        example = "example_pesel_number",
+        description = "Exactly one of pesel_number, identity_document and parent_pesel_number must be given"
+    )]
+    pesel_number: Option<String>,
+    #[schemars(description = "Passport or EU ID card of a patient without a PESEL number")]
+    identity_document: Option<IdentityDocument>,
+    #[schemars(description = "PESEL number of a parent of a newborn without a PESEL number")]
+    parent_pesel_number: Option<String>,
+    #[schemars(description = "Required for a patient without a PESEL number")]
+    birth_date: Option<NaiveDate>,
+    #[schemars(description = "Required for a patient without a PESEL number")]
+    sex: Option<Sex>,
 }
 
 impl<'r> Responder<'r, 'static> for CreatePatientError {
@@ -48,7 +83,8 @@ impl<'r> Responder<'r, 'static> for CreatePatientError {
             Self::RepositoryError(err) => {
                 let message = err.to_string();
                 let status = match err {
-                    CreatePatientRepositoryError::DuplicatedPeselNumber => Status::Conflict,
+                    CreatePatientRepositoryError::DuplicatedPeselNumber
+                    | CreatePatientRepositoryError::DuplicatedIdentityDocument => Status::Conflict,
                     CreatePatientRepositoryError::DatabaseError(_) => Status::InternalServerError,
                 };
                 (message, status)
@@ -64,11 +100,11 @@ impl OpenApiResponderInner for CreatePatientError {
         get_openapi_responses(vec![
             (
                 "422",
-                "Returned when the name or the pesel_number are incorrect",
+                "Returned when the name or the identifiers of the patient are incorrect",
             ),
             (
                 "409",
-                "Returned when patient with given pesel_number exist in the database",
+                "Returned when patient with given pesel_number or identity_document exist in the database",
             ),
         ])
     }
@@ -82,7 +118,14 @@ pub async fn create_patient(
 ) -> Result<Created<Json<Patient>>, CreatePatientError> {
     let created_patient = ctx
         .patients_service
-        .create_patient(dto.0.name, dto.0.pesel_number)
+        .create_patient(
+            dto.0.name,
+            dto.0.pesel_number,
+            dto.0.identity_document,
+            dto.0.parent_pesel_number,
+            dto.0.birth_date,
+            dto.0.sex,
+        )
         .await?;
 
     let location = format!("/patients/{}", created_patient.id);
@@ -175,19 +218,360 @@ pub async fn get_patients_with_pagination(
     Ok(Json(patients))
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatePatientDto {
+    #[schemars(example = "example_name")]
+    name: Option<String>,
+    #[schemars(
+        example = "example_pesel_number",
+        description = "When any of pesel_number, identity_document and parent_pesel_number is given, they replace all identifiers of the patient"
+    )]
+    pesel_number: Option<String>,
+    #[schemars(description = "Passport or EU ID card of a patient without a PESEL number")]
+    identity_document: Option<IdentityDocument>,
+    #[schemars(description = "PESEL number of a parent of a newborn without a PESEL number")]
+    parent_pesel_number: Option<String>,
+    #[schemars(description = "Ignored for a patient with a PESEL number")]
+    birth_date: Option<NaiveDate>,
+    #[schemars(description = "Ignored for a patient with a PESEL number")]
+    sex: Option<Sex>,
+}
+
+impl<'r> Responder<'r, 'static> for UpdatePatientError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    UpdatePatientRepositoryError::NotFound(_) => Status::NotFound,
+                    UpdatePatientRepositoryError::DuplicatedPeselNumber
+                    | UpdatePatientRepositoryError::DuplicatedIdentityDocument => Status::Conflict,
+                    UpdatePatientRepositoryError::DatabaseError(_) => Status::InternalServerError,
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for UpdatePatientError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a doctor's session"),
+            (
+                "404",
+                "Returned when the patient with given id doesn't exist",
+            ),
+            (
+                "422",
+                "Returned when the patient_id is not a valid UUID, or the name or the identifiers of the patient are incorrect",
+            ),
+            (
+                "409",
+                "Returned when another patient with given pesel_number or identity_document exists in the database",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Patients")]
+#[patch("/patients/<patient_id>", format = "application/json", data = "<dto>")]
+pub async fn update_patient(
+    ctx: &Ctx,
+    _session: DoctorSession,
+    patient_id: Uuid,
+    dto: Json<UpdatePatientDto>,
+) -> Result<Json<Patient>, UpdatePatientError> {
+    let updated_patient = ctx
+        .patients_service
+        .update_patient(
+            patient_id,
+            dto.0.name,
+            dto.0.pesel_number,
+            dto.0.identity_document,
+            dto.0.parent_pesel_number,
+            dto.0.birth_date,
+            dto.0.sex,
+        )
+        .await?;
+
+    Ok(Json(updated_patient))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatePatientAllergyDto {
+    #[schemars(example = "example_substance")]
+    substance: String,
+    #[schemars(example = "example_reaction")]
+    reaction: String,
+    severity: AllergySeverity,
+}
+
+impl<'r> Responder<'r, 'static> for CreatePatientAllergyError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CreatePatientAllergyRepositoryError::PatientNotFound(_) => Status::NotFound,
+                    CreatePatientAllergyRepositoryError::DoctorNotFound(_) => Status::NotFound,
+                    CreatePatientAllergyRepositoryError::DuplicatedAllergy => Status::Conflict,
+                    CreatePatientAllergyRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CreatePatientAllergyError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a doctor's session"),
+            ("404", "Returned when the patient with given id doesn't exist"),
+            (
+                "409",
+                "Returned when the patient already has an allergy to given substance registered",
+            ),
+            (
+                "422",
+                "Returned when the patient_id is not a valid UUID, or the substance or reaction are incorrect",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Patients")]
+#[post(
+    "/patients/<patient_id>/allergies",
+    format = "application/json",
+    data = "<dto>"
+)]
+pub async fn create_patient_allergy(
+    ctx: &Ctx,
+    session: DoctorSession,
+    patient_id: Uuid,
+    dto: Json<CreatePatientAllergyDto>,
+) -> Result<Created<Json<PatientAllergy>>, CreatePatientAllergyError> {
+    let created_allergy = ctx
+        .patients_service
+        .create_patient_allergy(
+            patient_id,
+            dto.0.substance,
+            dto.0.reaction,
+            dto.0.severity,
+            session.0.doctor_id.unwrap(),
+        )
+        .await?;
+
+    let location = format!("/patients/{}/allergies", patient_id);
+    Ok(Created::new(location).body(Json(created_allergy)))
+}
+
+impl<'r> Responder<'r, 'static> for GetPatientAllergiesError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetPatientAllergiesRepositoryError::PatientNotFound(_) => Status::NotFound,
+                    GetPatientAllergiesRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetPatientAllergiesError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "404",
+                "Returned when the patient with given id doesn't exist",
+            ),
+            ("422", "Returned when the patient_id is not a valid UUID"),
+        ])
+    }
+}
+
+#[openapi(tag = "Patients")]
+#[get("/patients/<patient_id>/allergies", format = "application/json")]
+pub async fn get_patient_allergies(
+    ctx: &Ctx,
+    patient_id: Uuid,
+) -> Result<Json<Vec<PatientAllergy>>, GetPatientAllergiesError> {
+    let allergies = ctx
+        .patients_service
+        .get_patient_allergies(patient_id)
+        .await?;
+
+    Ok(Json(allergies))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatePatientAllergyDto {
+    #[schemars(example = "example_reaction")]
+    reaction: String,
+    severity: AllergySeverity,
+}
+
+impl<'r> Responder<'r, 'static> for UpdatePatientAllergyError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => (err.to_string(), Status::UnprocessableEntity),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    UpdatePatientAllergyRepositoryError::NotFound(_) => Status::NotFound,
+                    UpdatePatientAllergyRepositoryError::DoctorNotFound(_) => Status::NotFound,
+                    UpdatePatientAllergyRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for UpdatePatientAllergyError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a doctor's session"),
+            (
+                "404",
+                "Returned when the allergy with given id isn't registered for the patient",
+            ),
+            (
+                "422",
+                "Returned when the patient_id or allergy_id is not a valid UUID, or the reaction is empty",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Patients")]
+#[put(
+    "/patients/<patient_id>/allergies/<allergy_id>",
+    format = "application/json",
+    data = "<dto>"
+)]
+pub async fn update_patient_allergy(
+    ctx: &Ctx,
+    session: DoctorSession,
+    patient_id: Uuid,
+    allergy_id: Uuid,
+    dto: Json<UpdatePatientAllergyDto>,
+) -> Result<Json<PatientAllergy>, UpdatePatientAllergyError> {
+    let updated_allergy = ctx
+        .patients_service
+        .update_patient_allergy(
+            patient_id,
+            allergy_id,
+            dto.0.reaction,
+            dto.0.severity,
+            session.0.doctor_id.unwrap(),
+        )
+        .await?;
+
+    Ok(Json(updated_allergy))
+}
+
+impl<'r> Responder<'r, 'static> for DeletePatientAllergyError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    PatientAllergyDomainError::NotRecordingDoctor => Status::Forbidden,
+                    _ => Status::UnprocessableEntity,
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    DeletePatientAllergyRepositoryError::NotFound(_) => Status::NotFound,
+                    DeletePatientAllergyRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for DeletePatientAllergyError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "403",
+                "Returned when the session is not a doctor's session, or the doctor didn't record the allergy",
+            ),
+            (
+                "404",
+                "Returned when the allergy with given id isn't registered for the patient",
+            ),
+            (
+                "422",
+                "Returned when the patient_id or allergy_id is not a valid UUID",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Patients")]
+#[delete("/patients/<patient_id>/allergies/<allergy_id>")]
+pub async fn delete_patient_allergy(
+    ctx: &Ctx,
+    session: DoctorSession,
+    patient_id: Uuid,
+    allergy_id: Uuid,
+) -> Result<NoContent, DeletePatientAllergyError> {
+    ctx.patients_service
+        .delete_patient_allergy(patient_id, allergy_id, session.0.doctor_id.unwrap())
+        .await?;
+
+    Ok(NoContent)
+}
+
 #[cfg(test)]
 mod tests {
-
+    use chrono::Utc;
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::asynchronous::Client,
         routes,
         serde::json,
     };
+    use uuid::Uuid;
 
     use crate::{
-        application::api::utils::fake_api_context::create_fake_api_context,
-        domain::patients::entities::Patient,
+        application::api::utils::fake_api_context::{
+            create_fake_api_context, create_session_token,
+        },
+        domain::{
+            patients::entities::{AllergySeverity, Patient, PatientAllergy},
+            utils::validators::validate_pesel_number::Sex,
+        },
     };
 
     async fn create_api_client() -> Client {
@@ -196,7 +580,12 @@ mod tests {
         let routes = routes![
             super::create_patient,
             super::get_patient_by_id,
-            super::get_patients_with_pagination
+            super::get_patients_with_pagination,
+            super::update_patient,
+            super::create_patient_allergy,
+            super::get_patient_allergies,
+            super::update_patient_allergy,
+            super::delete_patient_allergy
         ];
 
         let rocket = rocket::build().manage(context).mount("/", routes);
@@ -204,6 +593,17 @@ mod tests {
         Client::tracked(rocket).await.unwrap()
     }
 
+    async fn create_patient(client: &Client) -> Patient {
+        let response = client
+            .post("/patients")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        json::from_str(&response.into_string().await.unwrap()).unwrap()
+    }
+
     #[tokio::test]
     async fn creates_patient_and_reads_by_id() {
         let client = create_api_client().await;
@@ -221,7 +621,7 @@ mod tests {
             json::from_str(&create_patient_response.into_string().await.unwrap()).unwrap();
 
         assert_eq!(created_patient.name, "John Doex");
-        assert_eq!(created_patient.pesel_number, "96021807250");
+        assert_eq!(created_patient.pesel_number, Some("96021807250".into()));
 
         let get_patient_by_id_response = client
             .get(format!("/patients/{}", created_patient.id))
@@ -235,7 +635,108 @@ mod tests {
             json::from_str(&get_patient_by_id_response.into_string().await.unwrap()).unwrap();
 
         assert_eq!(patient.name, "John Doex");
-        assert_eq!(patient.pesel_number, "96021807250");
+        assert_eq!(patient.pesel_number, Some("96021807250".into()));
+    }
+
+    #[tokio::test]
+    async fn returns_birth_date_and_sex_of_patient_born_after_2000() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/patients")
+            .body(r#"{"name":"Jane Doex", "pesel_number":"02270803626"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let patient: Patient = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(patient.birth_date.to_string(), "2002-07-08");
+        assert_eq!(patient.sex, Sex::Female);
+    }
+
+    #[tokio::test]
+    async fn creates_patients_without_pesel_number() {
+        let client = create_api_client().await;
+
+        let response = client
+            .post("/patients")
+            .body(
+                r#"{
+                    "name": "Hans Muller",
+                    "identity_document": {
+                        "document_type": "EU_ID_CARD",
+                        "number": "L01X00T47",
+                        "issuing_country": "DE"
+                    },
+                    "birth_date": "1988-04-12",
+                    "sex": "MALE"
+                }"#,
+            )
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let patient: Patient = json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(patient.pesel_number, None);
+        assert_eq!(patient.birth_date.to_string(), "1988-04-12");
+
+        let duplicated_identity_document_response = client
+            .post("/patients")
+            .body(
+                r#"{
+                    "name": "Anna Muller",
+                    "identity_document": {
+                        "document_type": "EU_ID_CARD",
+                        "number": "L01X00T47",
+                        "issuing_country": "DE"
+                    },
+                    "birth_date": "1990-01-01",
+                    "sex": "FEMALE"
+                }"#,
+            )
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            duplicated_identity_document_response.status(),
+            Status::Conflict
+        );
+
+        let newborn_response = client
+            .post("/patients")
+            .body(format!(
+                r#"{{
+                    "name": "Baby Muller",
+                    "parent_pesel_number": "96021807250",
+                    "birth_date": "{}",
+                    "sex": "FEMALE"
+                }}"#,
+                Utc::now().date_naive()
+            ))
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(newborn_response.status(), Status::Created);
+
+        let newborn_without_birth_date_response = client
+            .post("/patients")
+            .body(r#"{"name": "Baby Muller", "parent_pesel_number": "96021807250"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            newborn_without_birth_date_response.status(),
+            Status::UnprocessableEntity
+        );
     }
 
     #[tokio::test]
@@ -283,6 +784,91 @@ mod tests {
         assert_eq!(response.status(), Status::Conflict);
     }
 
+    #[tokio::test]
+    async fn updates_patient() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+
+        let response = client
+            .patch(format!("/patients/{}", patient.id))
+            .body(r#"{"identity_document":{"document_type":"PASSPORT","number":"C01X00T47","issuing_country":"US"}}"#)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let updated_patient: Patient =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(updated_patient.name, "John Doex");
+        assert_eq!(updated_patient.pesel_number, None);
+        assert_eq!(
+            updated_patient.identity_document.unwrap().number,
+            "C01X00T47"
+        );
+        assert_eq!(updated_patient.birth_date, patient.birth_date);
+        assert_eq!(updated_patient.sex, patient.sex);
+        assert!(updated_patient.updated_at > patient.updated_at);
+    }
+
+    #[tokio::test]
+    async fn update_patient_returns_error_if_patient_doesnt_exist_or_body_is_incorrect() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+        client
+            .post("/patients")
+            .body(r#"{"name":"John Doey", "pesel_number":"99031301347"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        let response = client
+            .patch(format!("/patients/{}", patient.id))
+            .body(r#"{"pesel_number":"99031301347", "parent_pesel_number":"96021817257"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .patch(format!("/patients/{}", patient.id))
+            .body(r#"{"pesel_number":"99031301347"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let response = client
+            .patch("/patients/00000000-0000-0000-0000-000000000000")
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
     #[tokio::test]
     async fn get_patient_by_id_returns_unprocessable_entity_if_id_param_is_invalid() {
         let client = create_api_client().await;
@@ -372,4 +958,206 @@ mod tests {
             Status::UnprocessableEntity
         );
     }
+
+    #[tokio::test]
+    async fn creates_updates_and_deletes_patient_allergy() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let doctor_id = Uuid::new_v4();
+        let doctor_token = create_session_token(&client, Some(doctor_id), None).await;
+
+        let create_allergy_response = client
+            .post(format!("/patients/{}/allergies", patient.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(r#"{"substance":"Amoxicillin", "reaction":"Hives", "severity":"MODERATE"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(create_allergy_response.status(), Status::Created);
+
+        let created_allergy: PatientAllergy =
+            json::from_str(&create_allergy_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(created_allergy.substance, "amoxicillin");
+        assert_eq!(created_allergy.severity, AllergySeverity::Moderate);
+        assert_eq!(created_allergy.recorded_by_doctor_id, doctor_id);
+
+        let update_allergy_response = client
+            .put(format!(
+                "/patients/{}/allergies/{}",
+                patient.id, created_allergy.id
+            ))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(r#"{"reaction":"Anaphylaxis", "severity":"LIFE_THREATENING"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(update_allergy_response.status(), Status::Ok);
+
+        let get_allergies_response = client
+            .get(format!("/patients/{}/allergies", patient.id))
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(get_allergies_response.status(), Status::Ok);
+
+        let allergies: Vec<PatientAllergy> =
+            json::from_str(&get_allergies_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(allergies.len(), 1);
+        assert_eq!(allergies[0].reaction, "Anaphylaxis");
+        assert_eq!(allergies[0].severity, AllergySeverity::LifeThreatening);
+
+        let delete_allergy_response = client
+            .delete(format!(
+                "/patients/{}/allergies/{}",
+                patient.id, created_allergy.id
+            ))
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(delete_allergy_response.status(), Status::NoContent);
+
+        let delete_again_response = client
+            .delete(format!(
+                "/patients/{}/allergies/{}",
+                patient.id, created_allergy.id
+            ))
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(delete_again_response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn delete_patient_allergy_returns_forbidden_if_doctor_didnt_record_it() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+        let other_doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+
+        let create_allergy_response = client
+            .post(format!("/patients/{}/allergies", patient.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(r#"{"substance":"Amoxicillin", "reaction":"Hives", "severity":"MODERATE"}"#)
+            .dispatch()
+            .await;
+        let created_allergy: PatientAllergy =
+            json::from_str(&create_allergy_response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .delete(format!(
+                "/patients/{}/allergies/{}",
+                patient.id, created_allergy.id
+            ))
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", other_doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let get_allergies_response = client
+            .get(format!("/patients/{}/allergies", patient.id))
+            .dispatch()
+            .await;
+        let allergies: Vec<PatientAllergy> =
+            json::from_str(&get_allergies_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(allergies, vec![created_allergy]);
+    }
+
+    #[tokio::test]
+    async fn create_patient_allergy_returns_forbidden_without_doctor_session() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let pharmacist_token = create_session_token(&client, None, Some(Uuid::new_v4())).await;
+
+        let response = client
+            .post(format!("/patients/{}/allergies", patient.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(r#"{"substance":"Amoxicillin", "reaction":"Hives", "severity":"MILD"}"#)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[tokio::test]
+    async fn create_patient_allergy_returns_conflict_if_allergy_is_duplicated() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+
+        for expected_status in [Status::Created, Status::Conflict] {
+            let response = client
+                .post(format!("/patients/{}/allergies", patient.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token),
+                ))
+                .body(r#"{"substance":"Amoxicillin", "reaction":"Hives", "severity":"MILD"}"#)
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), expected_status);
+        }
+    }
+
+    #[tokio::test]
+    async fn update_patient_returns_forbidden_if_session_isnt_doctors_session() {
+        let client = create_api_client().await;
+        let patient = create_patient(&client).await;
+        let pharmacist_token = create_session_token(&client, None, Some(Uuid::new_v4())).await;
+
+        let response = client
+            .patch(format!("/patients/{}", patient.id))
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .patch(format!("/patients/{}", patient.id))
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
 }
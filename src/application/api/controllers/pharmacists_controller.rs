@@ -2,7 +2,7 @@ use okapi::openapi3::Responses;
 use rocket::{
     get,
     http::Status,
-    post,
+    patch, post,
     response::{status::Created, Responder},
     serde::json::Json,
     Request,
@@ -15,15 +15,19 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    application::api::utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    application::api::{
+        guards::authorization::PharmacistSession,
+        utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    },
     domain::pharmacists::{
         entities::Pharmacist,
         repository::{
             CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
-            GetPharmacistsRepositoryError,
+            GetPharmacistsRepositoryError, UpdatePharmacistRepositoryError,
         },
         service::{
             CreatePharmacistError, GetPharmacistByIdError, GetPharmacistsWithPaginationError,
+            UpdatePharmacistError,
         },
     },
     Ctx,
@@ -185,18 +189,101 @@ pub async fn get_pharmacists_with_pagination(
     Ok(Json(pharmacists))
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdatePharmacistDto {
+    #[schemars(example = "example_name")]
+    name: Option<String>,
+    #[schemars(example = "example_pesel_number")]
+    pesel_number: Option<String>,
+}
+
+impl<'r> Responder<'r, 'static> for UpdatePharmacistError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::NotAccountOwner => (
+                "Pharmacist can only update their own account".into(),
+                Status::Forbidden,
+            ),
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    UpdatePharmacistRepositoryError::NotFound(_) => Status::NotFound,
+                    UpdatePharmacistRepositoryError::DuplicatedPeselNumber => Status::Conflict,
+                    UpdatePharmacistRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for UpdatePharmacistError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "403",
+                "Returned when the session is not a session of the updated pharmacist",
+            ),
+            (
+                "404",
+                "Returned when the pharmacist with given id doesn't exist",
+            ),
+            (
+                "422",
+                "Returned when the pharmacist_id is not a valid UUID, or the name or the pesel_number are incorrect",
+            ),
+            (
+                "409",
+                "Returned when another pharmacist with given pesel_number exists in the database",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Pharmacists")]
+#[patch(
+    "/pharmacists/<pharmacist_id>",
+    format = "application/json",
+    data = "<dto>"
+)]
+pub async fn update_pharmacist(
+    ctx: &Ctx,
+    session: PharmacistSession,
+    pharmacist_id: Uuid,
+    dto: Json<UpdatePharmacistDto>,
+) -> Result<Json<Pharmacist>, UpdatePharmacistError> {
+    let updated_pharmacist = ctx
+        .pharmacists_service
+        .update_pharmacist(
+            pharmacist_id,
+            session.0.pharmacist_id.unwrap(),
+            dto.0.name,
+            dto.0.pesel_number,
+        )
+        .await?;
+
+    Ok(Json(updated_pharmacist))
+}
+
 #[cfg(test)]
 mod tests {
-
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::asynchronous::Client,
         routes,
         serde::json,
     };
+    use uuid::Uuid;
 
     use crate::{
-        application::api::utils::fake_api_context::create_fake_api_context,
+        application::api::utils::fake_api_context::{
+            create_fake_api_context, create_session_token,
+        },
         domain::pharmacists::entities::Pharmacist,
     };
 
@@ -206,7 +293,8 @@ mod tests {
         let routes = routes![
             super::create_pharmacist,
             super::get_pharmacist_by_id,
-            super::get_pharmacists_with_pagination
+            super::get_pharmacists_with_pagination,
+            super::update_pharmacist
         ];
 
         let rocket = rocket::build().manage(context).mount("/", routes);
@@ -383,4 +471,128 @@ mod tests {
             Status::UnprocessableEntity
         );
     }
+
+    #[tokio::test]
+    async fn updates_pharmacist() {
+        let client = create_api_client().await;
+
+        let create_pharmacist_response = client
+            .post("/pharmacists")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_pharmacist: Pharmacist =
+            json::from_str(&create_pharmacist_response.into_string().await.unwrap()).unwrap();
+
+        let token = create_session_token(&client, None, Some(created_pharmacist.id)).await;
+
+        let response = client
+            .patch(format!("/pharmacists/{}", created_pharmacist.id))
+            .body(r#"{"pesel_number":"99031301347"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let updated_pharmacist: Pharmacist =
+            json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(updated_pharmacist.name, "John Doex");
+        assert_eq!(updated_pharmacist.pesel_number, "99031301347");
+        assert!(updated_pharmacist.updated_at > created_pharmacist.updated_at);
+    }
+
+    #[tokio::test]
+    async fn update_pharmacist_returns_error_if_pharmacist_doesnt_exist_or_body_is_incorrect() {
+        let client = create_api_client().await;
+
+        let create_pharmacist_response = client
+            .post("/pharmacists")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_pharmacist: Pharmacist =
+            json::from_str(&create_pharmacist_response.into_string().await.unwrap()).unwrap();
+        client
+            .post("/pharmacists")
+            .body(r#"{"name":"John Doey", "pesel_number":"99031301347"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        let token = create_session_token(&client, None, Some(created_pharmacist.id)).await;
+
+        let response = client
+            .patch(format!("/pharmacists/{}", created_pharmacist.id))
+            .body(r#"{"name":"john doex"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client
+            .patch(format!("/pharmacists/{}", created_pharmacist.id))
+            .body(r#"{"pesel_number":"99031301347"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+
+        let token = create_session_token(&client, None, Some(Uuid::nil())).await;
+        let response = client
+            .patch("/pharmacists/00000000-0000-0000-0000-000000000000")
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn update_pharmacist_returns_forbidden_if_session_isnt_of_updated_pharmacist() {
+        let client = create_api_client().await;
+
+        let create_pharmacist_response = client
+            .post("/pharmacists")
+            .body(r#"{"name":"John Doex", "pesel_number":"96021807250"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let created_pharmacist: Pharmacist =
+            json::from_str(&create_pharmacist_response.into_string().await.unwrap()).unwrap();
+
+        let response = client
+            .patch(format!("/pharmacists/{}", created_pharmacist.id))
+            .body(r#"{"name":"John Smith"}"#)
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        for token in [
+            create_session_token(&client, None, Some(Uuid::new_v4())).await,
+            create_session_token(&client, Some(Uuid::new_v4()), None).await,
+        ] {
+            let response = client
+                .patch(format!("/pharmacists/{}", created_pharmacist.id))
+                .body(r#"{"name":"John Smith"}"#)
+                .header(ContentType::JSON)
+                .header(Header::new("Authorization", format!("Bearer {}", token)))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Forbidden);
+        }
+    }
 }
@@ -14,49 +14,129 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    application::api::utils::{error::ApiError, openapi_responses::get_openapi_responses},
-    domain::prescriptions::{
-        entities::{Prescription, PrescriptionType},
-        repository::{
-            CreatePrescriptionRepositoryError, FillPrescriptionRepositoryError,
-            GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
-        },
-        service::{
-            CreatePrescriptionError, FillPrescriptionError, GetPrescriptionByIdError,
-            GetPrescriptionsWithPaginationError,
+    application::api::{
+        guards::authorization::{DoctorSession, PharmacistSession},
+        utils::{error::ApiError, openapi_responses::get_openapi_responses},
+    },
+    domain::{
+        drugs::entities::ReimbursementLevel,
+        prescriptions::{
+            entities::{
+                AdministrationRoute, ControlledSubstanceDispense, CreatedPrescription, Dosage,
+                DosagePeriod, DoseUnit, NewPrescribedDrug, Prescription, PrescriptionFillRequest,
+                PrescriptionStatus, PrescriptionType,
+            },
+            repository::{
+                CancelPrescriptionRepositoryError, CreatePrescriptionRepositoryError,
+                FillPrescriptionRepositoryError, GetControlledSubstanceDispensesRepositoryError,
+                GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
+                LookupPrescriptionsRepositoryError,
+            },
+            service::{
+                CancelPrescriptionError, CreatePrescriptionError, FillPrescriptionError,
+                GetControlledSubstanceDispensesWithPaginationError, GetPrescriptionByIdError,
+                GetPrescriptionsWithPaginationError, LookupPrescriptionsError,
+            },
+            use_cases::{
+                cancel_prescription::CancelPrescriptionDomainError,
+                check_allergies::CheckAllergiesDomainError,
+                check_drug_interactions::CheckDrugInteractionsDomainError,
+                create_prescription::{
+                    validate_prescribed_amount, CreateNewPrescriptionDomainError,
+                },
+                lookup_prescriptions::LookupPrescriptionsDomainError,
+            },
         },
     },
     Ctx,
 };
 
-fn example_prescribed_drug() -> Vec<(Uuid, u32)> {
-    vec![(Uuid::new_v4(), 2)]
+fn example_prescribed_drug() -> Vec<PrescribedDrugDto> {
+    vec![PrescribedDrugDto {
+        drug_id: Uuid::new_v4(),
+        variant_id: Some(Uuid::new_v4()),
+        quantity: Some(2),
+        total_amount: None,
+        portion_quantity: None,
+        dosage: Some(Dosage {
+            dose_amount: 1.0,
+            dose_unit: DoseUnit::Tablet,
+            frequency: 2,
+            frequency_period: DosagePeriod::Day,
+            duration_days: Some(7),
+            route: AdministrationRoute::Oral,
+            note: Some("After meal".into()),
+        }),
+        reimbursement_level: Some(ReimbursementLevel::FiftyPercent),
+    }]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PrescribedDrugDto {
+    drug_id: Uuid,
+    #[schemars(
+        description = "Package size of the drug, required with quantity and not allowed with total_amount"
+    )]
+    variant_id: Option<Uuid>,
+    #[schemars(description = "Number of packages of the variant given by variant_id")]
+    quantity: Option<u32>,
+    #[schemars(
+        description = "Total number of pills (or ml of liquid) of the drug, packed by the pharmacist from any of its variants. Given instead of quantity and variant_id"
+    )]
+    total_amount: Option<u32>,
+    #[schemars(
+        description = "Number of packages (or pills or ml when prescribed as total_amount) released per 30-day portion. Allowed only on prescriptions for chronic disease drugs, by default the prescribed amount is split into 12 portions"
+    )]
+    portion_quantity: Option<u32>,
+    dosage: Option<Dosage>,
+    #[schemars(
+        description = "Reimbursement level chosen from the levels available for the package, requires variant_id. Without it the patient pays the full price"
+    )]
+    reimbursement_level: Option<ReimbursementLevel>,
 }
 
-type PrescribedDrugDto = (Uuid, u32);
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreatePrescriptionDto {
-    doctor_id: Uuid,
     patient_id: Uuid,
     prescription_type: Option<PrescriptionType>,
     start_date: Option<DateTime<Utc>>,
+    #[schemars(example = "example_prescribed_drug")]
+    prescribed_drugs: Vec<PrescribedDrugDto>,
     #[schemars(
-        example = "example_prescribed_drug",
-        description = "List of tuples with drug_id and quantity"
+        description = "Required to prescribe drugs contraindicated with each other or with the patient's other current prescriptions"
     )]
-    prescribed_drugs: Vec<PrescribedDrugDto>,
+    interaction_override_justification: Option<String>,
 }
 
 impl<'r> Responder<'r, 'static> for CreatePrescriptionError {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let (message, status) = match self {
             Self::DomainError(message) => (message, Status::UnprocessableEntity),
+            Self::PrescriptionTypeError(err) => (err.to_string(), Status::UnprocessableEntity),
+            Self::DrugInteractionsError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CheckDrugInteractionsDomainError::ContraindicatedDrugs(_) => Status::Conflict,
+                    CheckDrugInteractionsDomainError::EmptyOverrideJustification => {
+                        Status::UnprocessableEntity
+                    }
+                };
+                (message, status)
+            }
+            Self::AllergiesError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CheckAllergiesDomainError::AllergenicDrugs(_) => Status::Conflict,
+                };
+                (message, status)
+            }
             Self::RepositoryError(err) => {
                 let message = err.to_string();
                 let status = match err {
                     CreatePrescriptionRepositoryError::DoctorNotFound(_) => Status::NotFound,
                     CreatePrescriptionRepositoryError::PatientNotFound(_) => Status::NotFound,
                     CreatePrescriptionRepositoryError::DrugNotFound(_) => Status::NotFound,
+                    CreatePrescriptionRepositoryError::DrugVariantNotFound(_) => Status::NotFound,
                     CreatePrescriptionRepositoryError::DatabaseError(_) => {
                         Status::InternalServerError
                     }
@@ -73,13 +153,18 @@ impl OpenApiResponderInner for CreatePrescriptionError {
     fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
         get_openapi_responses(
             vec![
+                ("403", "Returned when the session is not a doctor's session"),
                 (
                     "422",
-                    "Returned when the body parameters are invalid, or the doctor_id, patient_id or drug_id is not a valid UUID",
+                    "Returned when the body parameters are invalid, the patient_id or drug_id is not a valid UUID, a prescribed drug can't be prescribed on the prescription of given type (only antibiotics on prescription for antibiotics, only immunological drugs on prescription for immunological drugs, only controlled substances on prescription for controlled substances, and no antibiotics or controlled substances on other prescriptions), prescription for controlled substances has more than one drug or a drug without variant_id, a drug has quantity without variant_id or isn't given exactly one of quantity and total_amount, a reimbursement level isn't available for the package or for the patient's age, or the prescribed quantity exceeds the limits (120 days of therapy, 360 on prescription for chronic disease drugs, 90 on prescription for controlled substances, and 2 packages of an antibiotic)",
                 ),
                 (
                     "404",
-                    "Returned when doctor, patient or drug with given id doesn't exist",
+                    "Returned when patient or drug with given id doesn't exist, or the variant_id isn't a variant of the drug",
+                ),
+                (
+                    "409",
+                    "Returned when the prescribed drugs are contraindicated with each other or with the patient's current prescriptions, and no interaction_override_justification was given, or when the patient is allergic to an active substance of a prescribed drug",
                 ),
             ]
         )
@@ -90,20 +175,44 @@ impl OpenApiResponderInner for CreatePrescriptionError {
 #[post("/prescriptions", format = "application/json", data = "<dto>")]
 pub async fn create_prescription(
     ctx: &Ctx,
+    session: DoctorSession,
     dto: Json<CreatePrescriptionDto>,
-) -> Result<Created<Json<Prescription>>, CreatePrescriptionError> {
+) -> Result<Created<Json<CreatedPrescription>>, CreatePrescriptionError> {
+    let prescribed_drugs = dto
+        .0
+        .prescribed_drugs
+        .into_iter()
+        .map(|prescribed_drug| {
+            Ok(NewPrescribedDrug {
+                drug_id: prescribed_drug.drug_id,
+                variant_id: prescribed_drug.variant_id,
+                quantity: validate_prescribed_amount(
+                    prescribed_drug.drug_id,
+                    prescribed_drug.variant_id,
+                    prescribed_drug.quantity,
+                    prescribed_drug.total_amount,
+                )?,
+                portion_quantity: prescribed_drug.portion_quantity,
+                dosage: prescribed_drug.dosage,
+                reimbursement_level: prescribed_drug.reimbursement_level,
+            })
+        })
+        .collect::<Result<Vec<_>, CreateNewPrescriptionDomainError>>()
+        .map_err(|err| CreatePrescriptionError::DomainError(err.to_string()))?;
+
     let created_prescription = ctx
         .prescriptions_service
         .create_prescription(
-            dto.0.doctor_id,
+            session.0.doctor_id.unwrap(),
             dto.0.patient_id,
             dto.0.start_date,
             dto.0.prescription_type,
-            dto.0.prescribed_drugs,
+            prescribed_drugs,
+            dto.0.interaction_override_justification,
         )
         .await?;
 
-    let location = format!("/prescriptions/{}", created_prescription.id);
+    let location = format!("/prescriptions/{}", created_prescription.prescription.id);
     Ok(Created::new(location).body(Json(created_prescription)))
 }
 
@@ -155,20 +264,58 @@ pub async fn get_prescription_by_id(
     Ok(Json(prescription))
 }
 
+fn example_dispensed_drug() -> Option<Vec<(Uuid, u32)>> {
+    Some(vec![(Uuid::new_v4(), 1)])
+}
+
+fn example_substitute() -> Option<Vec<(Uuid, Uuid)>> {
+    Some(vec![(Uuid::new_v4(), Uuid::new_v4())])
+}
+
+fn example_scanned_gtins() -> Option<Vec<&'static str>> {
+    Some(vec!["5909990733828", "5909990733828"])
+}
+
+type DispensedDrugDto = (Uuid, u32);
+type SubstituteDto = (Uuid, Uuid);
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FillPrescriptionDto {
-    pharmacist_id: Uuid,
     prescription_code: String,
+    #[schemars(
+        example = "example_dispensed_drug",
+        description = "List of tuples with prescribed_drug_id and dispensed quantity. When omitted, all remaining drugs are dispensed"
+    )]
+    dispensed_drugs: Option<Vec<DispensedDrugDto>>,
+    #[schemars(
+        example = "example_substitute",
+        description = "List of tuples with prescribed_drug_id and id of the drug from the same equivalence group that is dispensed instead of the prescribed one"
+    )]
+    substitutes: Option<Vec<SubstituteDto>>,
+    #[schemars(
+        example = "example_scanned_gtins",
+        description = "GTINs of the scanned packages, one per dispensed package, used instead of dispensed_drugs. Packages must be of the prescribed drugs (or their substitutes) and of the prescribed size"
+    )]
+    scanned_gtins: Option<Vec<String>>,
+    #[schemars(
+        description = "Pharmacist's confirmation required to dispense drugs from prescription for controlled substances"
+    )]
+    controlled_substances_confirmed: Option<bool>,
 }
 
 impl<'r> Responder<'r, 'static> for FillPrescriptionError {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let (message, status) = match self {
+            Self::LookupError(err) => return err.respond_to(req),
             Self::RepositoryError(err) => {
                 let message = err.to_string();
                 let status = match err {
                     FillPrescriptionRepositoryError::PharmacistNotFound(_) => Status::NotFound,
                     FillPrescriptionRepositoryError::PrescriptionNotFound(_) => Status::NotFound,
+                    FillPrescriptionRepositoryError::PrescriptionCancelled(_) => Status::Conflict,
+                    FillPrescriptionRepositoryError::RemainingQuantityExceeded(_) => {
+                        Status::Conflict
+                    }
+                    FillPrescriptionRepositoryError::PortionQuantityExceeded(_) => Status::Conflict,
                     FillPrescriptionRepositoryError::DatabaseError(_) => {
                         Status::InternalServerError
                     }
@@ -185,13 +332,18 @@ impl<'r> Responder<'r, 'static> for FillPrescriptionError {
 impl OpenApiResponderInner for FillPrescriptionError {
     fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
         get_openapi_responses(vec![
+            ("403", "Returned when the session is not a pharmacist's session"),
             (
                 "404",
-                "Returned when the the prescription or pharmacist with given id doesn't exist",
+                "Returned when the the prescription with given id doesn't exist, or no active prescription matches given PESEL number and code",
+            ),
+            (
+                "409",
+                "Returned when the prescription was cancelled or its drugs were dispensed by another fill in the meantime",
             ),
             (
                 "422",
-                "Returned when the the prescription_id or pharmacist_id is not a valid UUID, prescriptions is already filled, or the prescription cant be filled today (e.g. today is before start_date or after end_date)",
+                "Returned when the the prescription_id is not a valid UUID, prescription is already fully dispensed, dispensed quantity exceeds remaining quantity or current portion, substitute isn't in the equivalence group of the prescribed drug, dispensing controlled substances isn't confirmed, a scanned package isn't of a prescribed drug or of the prescribed size, or the prescription cant be filled today (e.g. today is before start_date or after end_date)",
             ),
         ])
     }
@@ -205,6 +357,7 @@ impl OpenApiResponderInner for FillPrescriptionError {
 )]
 pub async fn fill_prescription(
     ctx: &Ctx,
+    session: PharmacistSession,
     prescription_id: Uuid,
     dto: Json<FillPrescriptionDto>,
 ) -> Result<Created<Json<Prescription>>, FillPrescriptionError> {
@@ -212,8 +365,142 @@ pub async fn fill_prescription(
         .prescriptions_service
         .fill_prescription(
             prescription_id,
-            dto.0.pharmacist_id,
-            dto.0.prescription_code,
+            PrescriptionFillRequest {
+                pharmacist_id: session.0.pharmacist_id.unwrap(),
+                prescription_code: dto.0.prescription_code,
+                dispensed_drugs: dto.0.dispensed_drugs,
+                substitutes: dto.0.substitutes,
+                scanned_gtins: dto.0.scanned_gtins,
+                controlled_substances_confirmed: dto
+                    .0
+                    .controlled_substances_confirmed
+                    .unwrap_or(false),
+            },
+        )
+        .await?;
+
+    let location = format!("/prescriptions/{}", prescription.id);
+    Ok(Created::new(location).body(Json(prescription)))
+}
+
+impl<'r> Responder<'r, 'static> for LookupPrescriptionsError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    LookupPrescriptionsDomainError::NotFound => Status::NotFound,
+                    _ => Status::UnprocessableEntity,
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    LookupPrescriptionsRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for LookupPrescriptionsError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            ("403", "Returned when the session is not a pharmacist's session"),
+            (
+                "422",
+                "Returned when not exactly one of the PESEL number and the identity document number is given, it's invalid, or the code doesn't consist of 4 or 8 digits",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Prescriptions")]
+#[get(
+    "/prescriptions/lookup?<pesel>&<document_number>&<code>",
+    format = "application/json"
+)]
+pub async fn lookup_prescriptions(
+    ctx: &Ctx,
+    _session: PharmacistSession,
+    pesel: Option<String>,
+    document_number: Option<String>,
+    code: String,
+) -> Result<Json<Vec<Prescription>>, LookupPrescriptionsError> {
+    let prescriptions = ctx
+        .prescriptions_service
+        .lookup_prescriptions(pesel, document_number, code)
+        .await?;
+
+    Ok(Json(prescriptions))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FillPrescriptionByCodeDto {
+    #[schemars(
+        description = "PESEL number of the patient, or of a parent of a newborn without a PESEL number"
+    )]
+    pesel_number: Option<String>,
+    #[schemars(
+        description = "Identity document number of a patient without a PESEL number, given instead of pesel_number"
+    )]
+    document_number: Option<String>,
+    #[schemars(description = "Full 8-digit prescription code or its first 4 digits")]
+    prescription_code: String,
+    #[schemars(
+        example = "example_dispensed_drug",
+        description = "List of tuples with prescribed_drug_id and dispensed quantity. When omitted, all remaining drugs are dispensed"
+    )]
+    dispensed_drugs: Option<Vec<DispensedDrugDto>>,
+    #[schemars(
+        example = "example_substitute",
+        description = "List of tuples with prescribed_drug_id and id of the drug from the same equivalence group that is dispensed instead of the prescribed one"
+    )]
+    substitutes: Option<Vec<SubstituteDto>>,
+    #[schemars(
+        example = "example_scanned_gtins",
+        description = "GTINs of the scanned packages, one per dispensed package, used instead of dispensed_drugs. Packages must be of the prescribed drugs (or their substitutes) and of the prescribed size"
+    )]
+    scanned_gtins: Option<Vec<String>>,
+    #[schemars(
+        description = "Pharmacist's confirmation required to dispense drugs from prescription for controlled substances"
+    )]
+    controlled_substances_confirmed: Option<bool>,
+}
+
+#[openapi(tag = "Prescriptions")]
+#[post(
+    "/prescriptions/lookup/fill",
+    format = "application/json",
+    data = "<dto>"
+)]
+pub async fn fill_prescription_by_code(
+    ctx: &Ctx,
+    session: PharmacistSession,
+    dto: Json<FillPrescriptionByCodeDto>,
+) -> Result<Created<Json<Prescription>>, FillPrescriptionError> {
+    let prescription = ctx
+        .prescriptions_service
+        .fill_prescription_by_code(
+            dto.0.pesel_number,
+            dto.0.document_number,
+            PrescriptionFillRequest {
+                pharmacist_id: session.0.pharmacist_id.unwrap(),
+                prescription_code: dto.0.prescription_code,
+                dispensed_drugs: dto.0.dispensed_drugs,
+                substitutes: dto.0.substitutes,
+                scanned_gtins: dto.0.scanned_gtins,
+                controlled_substances_confirmed: dto
+                    .0
+                    .controlled_substances_confirmed
+                    .unwrap_or(false),
+            },
         )
         .await?;
 
@@ -221,6 +508,77 @@ pub async fn fill_prescription(
     Ok(Created::new(location).body(Json(prescription)))
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CancelPrescriptionDto {
+    reason: String,
+}
+
+impl<'r> Responder<'r, 'static> for CancelPrescriptionError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::DomainError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CancelPrescriptionDomainError::NotIssuingDoctor => Status::Forbidden,
+                    _ => Status::UnprocessableEntity,
+                };
+                (message, status)
+            }
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    CancelPrescriptionRepositoryError::NotFound(_) => Status::NotFound,
+                    CancelPrescriptionRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for CancelPrescriptionError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "403",
+                "Returned when the session is not a doctor's session, or the doctor didn't issue the prescription",
+            ),
+            (
+                "404",
+                "Returned when the the prescription with given id doesn't exist",
+            ),
+            (
+                "422",
+                "Returned when the the prescription_id is not a valid UUID, the reason is empty, or the prescription is already cancelled, fully dispensed or expired",
+            ),
+        ])
+    }
+}
+
+#[openapi(tag = "Prescriptions")]
+#[post(
+    "/prescriptions/<prescription_id>/cancel",
+    format = "application/json",
+    data = "<dto>"
+)]
+pub async fn cancel_prescription(
+    ctx: &Ctx,
+    session: DoctorSession,
+    prescription_id: Uuid,
+    dto: Json<CancelPrescriptionDto>,
+) -> Result<Json<Prescription>, CancelPrescriptionError> {
+    let prescription = ctx
+        .prescriptions_service
+        .cancel_prescription(prescription_id, session.0.doctor_id.unwrap(), dto.0.reason)
+        .await?;
+
+    Ok(Json(prescription))
+}
+
 impl<'r> Responder<'r, 'static> for GetPrescriptionsWithPaginationError {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let (message, status) = match self {
@@ -252,33 +610,94 @@ impl OpenApiResponderInner for GetPrescriptionsWithPaginationError {
 }
 
 #[openapi(tag = "Prescriptions")]
-#[get("/prescriptions?<page>&<page_size>", format = "application/json")]
+#[get(
+    "/prescriptions?<page>&<page_size>&<status>",
+    format = "application/json"
+)]
 pub async fn get_prescriptions_with_pagination(
     ctx: &Ctx,
     page: Option<i64>,
     page_size: Option<i64>,
+    status: Option<PrescriptionStatus>,
 ) -> Result<Json<Vec<Prescription>>, GetPrescriptionsWithPaginationError> {
     let prescriptions = ctx
         .prescriptions_service
-        .get_prescriptions_with_pagination(page, page_size)
+        .get_prescriptions_with_pagination(page, page_size, status)
         .await?;
 
     Ok(Json(prescriptions))
 }
 
+impl<'r> Responder<'r, 'static> for GetControlledSubstanceDispensesWithPaginationError {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let (message, status) = match self {
+            Self::RepositoryError(err) => {
+                let message = err.to_string();
+                let status = match err {
+                    GetControlledSubstanceDispensesRepositoryError::InvalidPaginationParams(_) => {
+                        Status::UnprocessableEntity
+                    }
+                    GetControlledSubstanceDispensesRepositoryError::DatabaseError(_) => {
+                        Status::InternalServerError
+                    }
+                };
+                (message, status)
+            }
+        };
+
+        ApiError::build_rocket_response(req, message, status)
+    }
+}
+
+impl OpenApiResponderInner for GetControlledSubstanceDispensesWithPaginationError {
+    fn responses(_: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        get_openapi_responses(vec![
+            (
+                "403",
+                "Returned when the session is not a pharmacist's session",
+            ),
+            ("422", "Returned when the the page < 0 or page_size < 1"),
+        ])
+    }
+}
+
+#[openapi(tag = "Prescriptions")]
+#[get(
+    "/prescriptions/controlled-substance-dispenses?<page>&<page_size>",
+    format = "application/json"
+)]
+pub async fn get_controlled_substance_dispenses_with_pagination(
+    ctx: &Ctx,
+    _session: PharmacistSession,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<
+    Json<Vec<ControlledSubstanceDispense>>,
+    GetControlledSubstanceDispensesWithPaginationError,
+> {
+    let controlled_substance_dispenses = ctx
+        .prescriptions_service
+        .get_controlled_substance_dispenses_with_pagination(page, page_size)
+        .await?;
+
+    Ok(Json(controlled_substance_dispenses))
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::asynchronous::Client,
         routes,
         serde::json,
     };
+    use uuid::Uuid;
 
     use crate::{
         application::{
+            api::utils::fake_api_context::create_session_token,
             authentication::{
                 repository::AuthenticationRepositoryFake, service::AuthenticationService,
             },
@@ -289,19 +708,28 @@ mod tests {
                 entities::Doctor, repository::DoctorsRepositoryFake, service::DoctorsService,
             },
             drugs::{
-                entities::{Drug, DrugContentType},
+                entities::{
+                    ControlledSubstanceList, Drug, DrugActiveSubstance, DrugContentType,
+                    DrugInteractionSeverity, StrengthUnit,
+                },
                 repository::DrugsRepositoryFake,
                 service::DrugsService,
             },
             patients::{
-                entities::Patient, repository::PatientsRepositoryFake, service::PatientsService,
+                entities::{AllergySeverity, Patient},
+                repository::PatientsRepositoryFake,
+                service::PatientsService,
             },
             pharmacists::{
                 entities::Pharmacist, repository::PharmacistsRepositoryFake,
                 service::PharmacistsService,
             },
             prescriptions::{
-                entities::Prescription, repository::PrescriptionsRepositoryFake,
+                entities::{
+                    AdministrationRoute, ControlledSubstanceDispense, CreatedPrescription, Dosage,
+                    DosagePeriod, DoseUnit, Prescription, PrescriptionStatus,
+                },
+                repository::PrescriptionsRepositoryFake,
                 service::PrescriptionsService,
             },
         },
@@ -330,7 +758,14 @@ mod tests {
 
         let patients_service = PatientsService::new(Box::new(PatientsRepositoryFake::new()));
         let created_patient = patients_service
-            .create_patient("John Patient".into(), "92022900002".into())
+            .create_patient(
+                "John Patient".into(),
+                Some("92022900002".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
@@ -343,6 +778,11 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -354,48 +794,178 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
         let created_drug_2 = drugs_service
             .create_drug(
-                "Gripex".into(),
+                "Viagra".into(),
                 DrugContentType::SolidPills,
                 Some(20),
                 Some(300),
                 None,
                 None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "sildenafil".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
         let created_drug_3 = drugs_service
             .create_drug(
-                "Gripex".into(),
+                "Nitromint".into(),
                 DrugContentType::SolidPills,
                 Some(20),
                 Some(300),
                 None,
                 None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "nitroglycerin".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
-
-        let prescriptions_service =
-            PrescriptionsService::new(Box::new(PrescriptionsRepositoryFake::new(
+        let created_drug_4 = drugs_service
+            .create_drug(
+                "Augmentin".into(),
+                DrugContentType::SolidPills,
+                Some(14),
+                Some(875),
                 None,
-                Some(vec![created_doctor.clone()]),
-                Some(vec![created_patient.clone()]),
-                Some(vec![created_pharmacist.clone()]),
-                Some(vec![
-                    created_drug_0.clone(),
-                    created_drug_1.clone(),
-                    created_drug_2.clone(),
-                    created_drug_3.clone(),
-                ]),
-            )));
-
-        let authentication_repository = Box::new(AuthenticationRepositoryFake::new());
-        let authentication_service =
+                None,
+                None,
+                vec![
+                    DrugActiveSubstance {
+                        name: "amoxicillin".into(),
+                        amount: None,
+                        unit: None,
+                    },
+                    DrugActiveSubstance {
+                        name: "clavulanic acid".into(),
+                        amount: None,
+                        unit: None,
+                    },
+                ],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let mut equivalent_drugs = vec![];
+        for name in ["Apap", "Panadol"] {
+            let drug = drugs_service
+                .create_drug(
+                    name.into(),
+                    DrugContentType::SolidPills,
+                    Some(10),
+                    Some(500),
+                    None,
+                    None,
+                    None,
+                    vec![DrugActiveSubstance {
+                        name: "paracetamol".into(),
+                        amount: Some(500.0),
+                        unit: Some(StrengthUnit::Mg),
+                    }],
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            equivalent_drugs.push(drug);
+        }
+        drugs_service
+            .create_drug_equivalence_group(
+                "Paracetamol 500 mg tablets".into(),
+                equivalent_drugs.iter().map(|drug| drug.id).collect(),
+            )
+            .await
+            .unwrap();
+        let created_drug_5 = drugs_service
+            .get_drug_by_id(equivalent_drugs[0].id)
+            .await
+            .unwrap();
+        let created_drug_6 = drugs_service
+            .get_drug_by_id(equivalent_drugs[1].id)
+            .await
+            .unwrap();
+        let created_drug_7 = drugs_service
+            .create_drug(
+                "Morphini sulfas".into(),
+                DrugContentType::SolidPills,
+                Some(60),
+                Some(30),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                Some(ControlledSubstanceList::Narcotic),
+            )
+            .await
+            .unwrap();
+        let created_patient_allergy = patients_service
+            .create_patient_allergy(
+                created_patient.id,
+                "amoxicillin".into(),
+                "Anaphylaxis".into(),
+                AllergySeverity::LifeThreatening,
+                created_doctor.id,
+            )
+            .await
+            .unwrap();
+        let created_drug_interaction = drugs_service
+            .create_drug_interaction(
+                "sildenafil".into(),
+                "nitroglycerin".into(),
+                DrugInteractionSeverity::Contraindicated,
+                "Severe hypotension".into(),
+            )
+            .await
+            .unwrap();
+
+        let prescriptions_service =
+            PrescriptionsService::new(Box::new(PrescriptionsRepositoryFake::new(
+                None,
+                Some(vec![created_doctor.clone()]),
+                Some(vec![created_patient.clone()]),
+                Some(vec![created_pharmacist.clone()]),
+                Some(vec![
+                    created_drug_0.clone(),
+                    created_drug_1.clone(),
+                    created_drug_2.clone(),
+                    created_drug_3.clone(),
+                    created_drug_4.clone(),
+                    created_drug_5.clone(),
+                    created_drug_6.clone(),
+                    created_drug_7.clone(),
+                ]),
+                Some(vec![created_drug_interaction]),
+                Some(vec![created_patient_allergy]),
+            )));
+
+        let authentication_repository = Box::new(AuthenticationRepositoryFake::new());
+        let authentication_service =
             Arc::new(AuthenticationService::new(authentication_repository));
 
         let sessions_repository = Box::new(SessionsRepositoryFake::new());
@@ -420,6 +990,10 @@ mod tests {
                     created_drug_1,
                     created_drug_2,
                     created_drug_3,
+                    created_drug_4,
+                    created_drug_5,
+                    created_drug_6,
+                    created_drug_7,
                 ],
             },
         )
@@ -432,7 +1006,11 @@ mod tests {
             super::create_prescription,
             super::get_prescription_by_id,
             super::get_prescriptions_with_pagination,
-            super::fill_prescription
+            super::fill_prescription,
+            super::cancel_prescription,
+            super::lookup_prescriptions,
+            super::fill_prescription_by_code,
+            super::get_controlled_substance_dispenses_with_pagination
         ];
 
         let rocket = rocket::build().manage(context).mount("/", routes);
@@ -445,18 +1023,26 @@ mod tests {
     #[tokio::test]
     async fn creates_and_fills_prescription() {
         let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
 
         let create_prescription_response = client
             .post("/prescriptions")
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
             .body(format!(
                 r#"{{
-                    "doctor_id": "{}",
                     "patient_id": "{}",
                     "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                    "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
                 }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
             ))
             .dispatch()
             .await;
@@ -468,17 +1054,19 @@ mod tests {
         )
         .unwrap();
 
-        assert!(created_prescription.fill.is_none());
+        assert!(created_prescription.fills.is_empty());
 
         let fill_prescription_response = client
             .post(format!("/prescriptions/{}/fill", created_prescription.id))
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
             .body(format!(
                 r#"{{
-                    "pharmacist_id": "{}",
                     "prescription_code": "{}"
                 }}"#,
-                seeds.pharmacist.id,
                 created_prescription.code
             ))
             .dispatch()
@@ -500,23 +1088,199 @@ mod tests {
         let prescription_by_id: Prescription =
             json::from_str(&get_prescription_by_id_response.into_string().await.unwrap()).unwrap();
 
-        assert!(prescription_by_id.fill.is_some());
+        assert_eq!(prescription_by_id.fills.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_with_equivalent_substitute() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[5].id
+            ))
+            .dispatch()
+            .await;
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+        let prescribed_drug_id = created_prescription.prescribed_drugs[0].id;
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(format!(
+                    r#"{{
+                        "prescription_code": "{}",
+                        "substitutes": [ ["{}", "{}"] ]
+                    }}"#,
+                    created_prescription.code, prescribed_drug_id, seeds.drugs[0].id
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
+
+        let fill_prescription_response = client
+            .post(format!("/prescriptions/{}/fill", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "prescription_code": "{}",
+                    "substitutes": [ ["{}", "{}"] ]
+                }}"#,
+                created_prescription.code, prescribed_drug_id, seeds.drugs[6].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(fill_prescription_response.status(), Status::Created);
+
+        let filled_prescription = json::from_str::<Prescription>(
+            &fill_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            filled_prescription.prescribed_drugs[0].drug_id,
+            seeds.drugs[5].id
+        );
+        assert_eq!(
+            filled_prescription.fills[0].dispensed_drugs[0].substitute_drug_id,
+            Some(seeds.drugs[6].id)
+        );
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_partially() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+            ))
+            .dispatch()
+            .await;
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+        let prescribed_drug_id = created_prescription.prescribed_drugs[1].id;
+
+        let fill_prescription_response = client
+            .post(format!("/prescriptions/{}/fill", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "prescription_code": "{}",
+                    "dispensed_drugs": [ ["{}", 1] ]
+                }}"#,
+                created_prescription.code, prescribed_drug_id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(fill_prescription_response.status(), Status::Created);
+
+        let partially_filled_prescription = json::from_str::<Prescription>(
+            &fill_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            partially_filled_prescription.prescribed_drugs[1].remaining_quantity,
+            1
+        );
+        assert!(!partially_filled_prescription.is_fully_dispensed());
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(format!(
+                    r#"{{
+                        "prescription_code": "{}",
+                        "dispensed_drugs": [ ["{}", 2] ]
+                    }}"#,
+                    created_prescription.code, prescribed_drug_id
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
     }
 
     #[tokio::test]
     async fn doesnt_fill_if_already_filled() {
         let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
         let create_seed_prescription_response = client
             .post("/prescriptions")
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
             .body(format!(
                 r#"{{
-                    "doctor_id": "{}",
                     "patient_id": "{}",
                     "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                    "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
                 }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
             ))
             .dispatch()
             .await;
@@ -532,12 +1296,15 @@ mod tests {
             client
                 .post(format!("/prescriptions/{}/fill", seed_prescription.id))
                 .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
                 .body(format!(
                     r#"{{
-                        "pharmacist_id": "{}",
                         "prescription_code": "{}"
                     }}"#,
-                    seeds.pharmacist.id, seed_prescription.code,
+                    seed_prescription.code,
                 ))
                 .dispatch()
                 .await
@@ -549,12 +1316,15 @@ mod tests {
             client
                 .post(format!("/prescriptions/{}/fill", seed_prescription.id))
                 .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
                 .body(format!(
                     r#"{{
-                        "pharmacist_id": "{}",
                         "prescription_code": "{}"
                     }}"#,
-                    seeds.pharmacist.id, seed_prescription.code,
+                    seed_prescription.code,
                 ))
                 .dispatch()
                 .await
@@ -564,75 +1334,526 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn returns_error_if_prescription_does_not_exist() {
-        let (client, _) = create_api_client().await;
+    async fn doesnt_create_or_fill_prescription_without_matching_session() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+        let create_prescription_body = format!(
+            r#"{{
+                "patient_id": "{}",
+                "prescribed_drugs": [
+                    {{ "drug_id": "{}", "total_amount": 1 }}
+                ]
+            }}"#,
+            seeds.patient.id, seeds.drugs[0].id
+        );
 
-        let get_prescription_by_id_response = client
-            .get("/prescriptions/00000000-0000-0000-0000-000000000000")
+        assert_eq!(
+            client
+                .post("/prescriptions")
+                .header(ContentType::JSON)
+                .body(&create_prescription_body)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+
+        assert_eq!(
+            client
+                .post("/prescriptions")
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(&create_prescription_body)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+
+        let create_prescription_response = client
+            .post("/prescriptions")
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(&create_prescription_body)
             .dispatch()
             .await;
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
 
-        assert_eq!(get_prescription_by_id_response.status(), Status::NotFound);
+        assert_eq!(created_prescription.doctor.id, seeds.doctor.id);
+
+        let fill_prescription_body = format!(
+            r#"{{ "prescription_code": "{}" }}"#,
+            created_prescription.code
+        );
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .body(&fill_prescription_body)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token)
+                ))
+                .body(&fill_prescription_body)
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
     }
 
     #[tokio::test]
-    async fn gets_pharmacists_with_pagination() {
+    async fn fills_chronic_disease_drugs_prescription_in_portions() {
         let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
 
-        client
+        let create_prescription_response = client
             .post("/prescriptions")
             .header(ContentType::JSON)
-            .body(format!(
-                r#"{{
-                "doctor_id": "{}",
-                "patient_id": "{}",
-                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
-            }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
             ))
-            .dispatch()
-            .await;
-        client
-            .post("/prescriptions")
-            .header(ContentType::JSON)
             .body(format!(
                 r#"{{
-                "doctor_id": "{}",
-                "patient_id": "{}",
-                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
-            }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+                    "patient_id": "{}",
+                    "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 6, "portion_quantity": 2 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
             ))
             .dispatch()
             .await;
-        client
-            .post("/prescriptions")
+
+        assert_eq!(create_prescription_response.status(), Status::Created);
+
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            created_prescription.prescribed_drugs[0].portion_quantity,
+            Some(2)
+        );
+
+        let fill_prescription_response = client
+            .post(format!("/prescriptions/{}/fill", created_prescription.id))
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
             .body(format!(
                 r#"{{
-                "doctor_id": "{}",
-                "patient_id": "{}",
-                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
-            }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+                    "prescription_code": "{}"
+                }}"#,
+                created_prescription.code
             ))
             .dispatch()
             .await;
+
+        assert_eq!(fill_prescription_response.status(), Status::Created);
+
+        let filled_prescription = json::from_str::<Prescription>(
+            &fill_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            filled_prescription.prescribed_drugs[0].remaining_quantity,
+            4
+        );
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(format!(
+                    r#"{{
+                        "prescription_code": "{}"
+                    }}"#,
+                    created_prescription.code
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
+    }
+
+    #[tokio::test]
+    async fn cancels_prescription() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        let cancel_prescription_body = r#"{ "reason": "Issued by mistake" }"#;
+
+        let unauthorized_response = client
+            .post(format!("/prescriptions/{}/cancel", created_prescription.id))
+            .header(ContentType::JSON)
+            .body(cancel_prescription_body)
+            .dispatch()
+            .await;
+
+        assert_eq!(unauthorized_response.status(), Status::Forbidden);
+
+        let other_doctor_token = create_session_token(&client, Some(Uuid::new_v4()), None).await;
+        let other_doctor_response = client
+            .post(format!("/prescriptions/{}/cancel", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", other_doctor_token),
+            ))
+            .body(cancel_prescription_body)
+            .dispatch()
+            .await;
+
+        assert_eq!(other_doctor_response.status(), Status::Forbidden);
+
+        let cancel_prescription_response = client
+            .post(format!("/prescriptions/{}/cancel", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(cancel_prescription_body)
+            .dispatch()
+            .await;
+
+        assert_eq!(cancel_prescription_response.status(), Status::Ok);
+
+        let cancelled_prescription = json::from_str::<Prescription>(
+            &cancel_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            cancelled_prescription.cancellation.unwrap().reason,
+            "Issued by mistake"
+        );
+        assert_eq!(cancelled_prescription.status, PrescriptionStatus::Cancelled);
+
+        let cancelled_prescriptions_response = client
+            .get("/prescriptions?status=CANCELLED")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let cancelled_prescriptions: Vec<Prescription> = json::from_str(
+            &cancelled_prescriptions_response
+                .into_string()
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(cancelled_prescriptions.len(), 1);
+        assert_eq!(cancelled_prescriptions[0].id, created_prescription.id);
+
+        let active_prescriptions_response = client
+            .get("/prescriptions?status=ACTIVE")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+        let active_prescriptions: Vec<Prescription> =
+            json::from_str(&active_prescriptions_response.into_string().await.unwrap()).unwrap();
+
+        assert!(active_prescriptions.is_empty());
+
+        let fill_prescription_response = client
+            .post(format!("/prescriptions/{}/fill", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "prescription_code": "{}"
+                }}"#,
+                created_prescription.code
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            fill_prescription_response.status(),
+            Status::UnprocessableEntity
+        );
+    }
+
+    #[tokio::test]
+    async fn looks_up_and_fills_prescription_by_code() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+        let short_code = &created_prescription.code[..4];
+
+        let lookup_url = format!(
+            "/prescriptions/lookup?pesel={}&code={}",
+            seeds.patient.pesel_number.as_ref().unwrap(),
+            short_code
+        );
+
+        let unauthorized_response = client.get(&lookup_url).dispatch().await;
+
+        assert_eq!(unauthorized_response.status(), Status::Forbidden);
+
+        let lookup_response = client
+            .get(&lookup_url)
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(lookup_response.status(), Status::Ok);
+
+        let prescriptions: Vec<Prescription> =
+            json::from_str(&lookup_response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(prescriptions.len(), 1);
+        assert_eq!(prescriptions[0].id, created_prescription.id);
+
+        let invalid_lookup_response = client
+            .get(format!(
+                "/prescriptions/lookup?pesel={}&code=123",
+                seeds.patient.pesel_number.as_ref().unwrap()
+            ))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            invalid_lookup_response.status(),
+            Status::UnprocessableEntity
+        );
+
+        let fill_prescription_body = format!(
+            r#"{{
+                "pesel_number": "{}",
+                "prescription_code": "{}"
+            }}"#,
+            seeds.patient.pesel_number.as_ref().unwrap(),
+            short_code
+        );
+
+        let fill_prescription_response = client
+            .post("/prescriptions/lookup/fill")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(&fill_prescription_body)
+            .dispatch()
+            .await;
+
+        assert_eq!(fill_prescription_response.status(), Status::Created);
+
+        let filled_prescription = json::from_str::<Prescription>(
+            &fill_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            filled_prescription.fills[0].pharmacist_id,
+            seeds.pharmacist.id
+        );
+        assert_eq!(filled_prescription.status, PrescriptionStatus::Filled);
+
+        let refill_prescription_response = client
+            .post("/prescriptions/lookup/fill")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(&fill_prescription_body)
+            .dispatch()
+            .await;
+
+        assert_eq!(refill_prescription_response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_prescription_does_not_exist() {
+        let (client, _) = create_api_client().await;
+
+        let get_prescription_by_id_response = client
+            .get("/prescriptions/00000000-0000-0000-0000-000000000000")
+            .header(ContentType::JSON)
+            .dispatch()
+            .await;
+
+        assert_eq!(get_prescription_by_id_response.status(), Status::NotFound);
+    }
+
+    #[tokio::test]
+    async fn gets_pharmacists_with_pagination() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+
         client
             .post("/prescriptions")
             .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
             .body(format!(
                 r#"{{
-                "doctor_id": "{}",
                 "patient_id": "{}",
                 "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
-                "prescribed_drugs": [ ["{}",  1], ["{}",  2] ]
+                "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
             }}"#,
-                seeds.doctor.id, seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+            ))
+            .dispatch()
+            .await;
+        client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                "patient_id": "{}",
+                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
+                "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
+            }}"#,
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+            ))
+            .dispatch()
+            .await;
+        client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                "patient_id": "{}",
+                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
+                "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
+            }}"#,
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
+            ))
+            .dispatch()
+            .await;
+        client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                "patient_id": "{}",
+                "prescription_type": "FOR_CHRONIC_DISEASE_DRUGS",
+                "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }},
+                        {{ "drug_id": "{}", "total_amount": 2 }}
+                    ]
+            }}"#,
+                seeds.patient.id, seeds.drugs[0].id, seeds.drugs[1].id
             ))
             .dispatch()
             .await;
@@ -720,4 +1941,340 @@ mod tests {
             Status::UnprocessableEntity
         );
     }
+
+    #[tokio::test]
+    async fn creates_contraindicated_prescription_only_with_override_justification() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let prescribed_drugs = format!(
+            r#"[
+                {{ "drug_id": "{}", "total_amount": 1 }},
+                {{ "drug_id": "{}", "total_amount": 1 }}
+            ]"#,
+            seeds.drugs[2].id, seeds.drugs[3].id
+        );
+
+        for (interaction_override_justification, expected_status) in [
+            ("null", Status::Conflict),
+            (r#""  ""#, Status::UnprocessableEntity),
+            (
+                r#""Nitroglycerin is taken only in emergencies""#,
+                Status::Created,
+            ),
+        ] {
+            let response = client
+                .post("/prescriptions")
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token),
+                ))
+                .body(format!(
+                    r#"{{
+                        "patient_id": "{}",
+                        "prescribed_drugs": {},
+                        "interaction_override_justification": {}
+                    }}"#,
+                    seeds.patient.id, prescribed_drugs, interaction_override_justification
+                ))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), expected_status);
+
+            if expected_status == Status::Created {
+                let created_prescription =
+                    json::from_str::<CreatedPrescription>(&response.into_string().await.unwrap())
+                        .unwrap();
+
+                assert_eq!(created_prescription.interaction_warnings.len(), 1);
+                assert_eq!(
+                    created_prescription.interaction_warnings[0].severity,
+                    DrugInteractionSeverity::Contraindicated
+                );
+                assert_eq!(
+                    created_prescription
+                        .prescription
+                        .interaction_override_justification,
+                    Some("Nitroglycerin is taken only in emergencies".into())
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_conflict_if_patient_is_allergic_to_prescribed_drug() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+
+        let response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [{{ "drug_id": "{}", "total_amount": 1 }}]
+                }}"#,
+                seeds.patient.id, seeds.drugs[4].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Conflict);
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_unprocessable_entity_if_quantity_is_given_without_variant()
+    {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+
+        let response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [{{ "drug_id": "{}", "quantity": 1 }}]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_unprocessable_entity_if_drug_isnt_eligible_for_prescription_type(
+    ) {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+
+        let response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescription_type": "FOR_ANTIBIOTICS",
+                    "prescribed_drugs": [{{ "drug_id": "{}", "total_amount": 1 }}]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[tokio::test]
+    async fn creates_prescription_with_dosage_instructions() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+
+        let response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [{{
+                        "drug_id": "{}",
+                        "total_amount": 1,
+                        "dosage": {{
+                            "dose_amount": 1,
+                            "dose_unit": "TABLET",
+                            "frequency": 2,
+                            "frequency_period": "DAY",
+                            "duration_days": 7,
+                            "route": "ORAL"
+                        }}
+                    }}]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Created);
+
+        let created_prescription =
+            json::from_str::<Prescription>(&response.into_string().await.unwrap()).unwrap();
+
+        assert_eq!(
+            created_prescription.prescribed_drugs[0].dosage,
+            Some(Dosage {
+                dose_amount: 1.0,
+                dose_unit: DoseUnit::Tablet,
+                frequency: 2,
+                frequency_period: DosagePeriod::Day,
+                duration_days: Some(7),
+                route: AdministrationRoute::Oral,
+                note: None,
+            })
+        );
+
+        let response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [{{
+                        "drug_id": "{}",
+                        "total_amount": 1,
+                        "dosage": {{
+                            "dose_amount": 1,
+                            "dose_unit": "TABLET",
+                            "frequency": 0,
+                            "frequency_period": "DAY",
+                            "route": "ORAL"
+                        }}
+                    }}]
+                }}"#,
+                seeds.patient.id, seeds.drugs[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_for_controlled_substances_only_with_pharmacists_confirmation() {
+        let (client, seeds) = create_api_client().await;
+        let doctor_token = create_session_token(&client, Some(seeds.doctor.id), None).await;
+        let pharmacist_token = create_session_token(&client, None, Some(seeds.pharmacist.id)).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescription_type": "FOR_CONTROLLED_SUBSTANCES",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "variant_id": "{}", "quantity": 1 }}
+                    ]
+                }}"#,
+                seeds.patient.id, seeds.drugs[7].id, seeds.drugs[7].variants[0].id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(create_prescription_response.status(), Status::Created);
+
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(format!(
+                    r#"{{ "prescription_code": "{}" }}"#,
+                    created_prescription.code
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::UnprocessableEntity
+        );
+
+        assert_eq!(
+            client
+                .post(format!("/prescriptions/{}/fill", created_prescription.id))
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", pharmacist_token)
+                ))
+                .body(format!(
+                    r#"{{
+                        "prescription_code": "{}",
+                        "controlled_substances_confirmed": true
+                    }}"#,
+                    created_prescription.code
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::Created
+        );
+
+        assert_eq!(
+            client
+                .get("/prescriptions/controlled-substance-dispenses")
+                .header(ContentType::JSON)
+                .header(Header::new(
+                    "Authorization",
+                    format!("Bearer {}", doctor_token)
+                ))
+                .dispatch()
+                .await
+                .status(),
+            Status::Forbidden
+        );
+
+        let get_controlled_substance_dispenses_response = client
+            .get("/prescriptions/controlled-substance-dispenses")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            get_controlled_substance_dispenses_response.status(),
+            Status::Ok
+        );
+
+        let controlled_substance_dispenses: Vec<ControlledSubstanceDispense> = json::from_str(
+            &get_controlled_substance_dispenses_response
+                .into_string()
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(controlled_substance_dispenses.len(), 1);
+        assert_eq!(
+            controlled_substance_dispenses[0].prescription_id,
+            created_prescription.id
+        );
+        assert_eq!(controlled_substance_dispenses[0].drug_id, seeds.drugs[7].id);
+    }
 }
@@ -1,5 +1,12 @@
+#[cfg(test)]
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 
+#[cfg(test)]
+use rocket::local::asynchronous::Client;
+#[cfg(test)]
+use uuid::Uuid;
+
 use crate::{
     application::{
         authentication::{
@@ -31,7 +38,7 @@ pub fn create_fake_api_context() -> Context {
     let drugs_service = Arc::new(DrugsService::new(drugs_repository));
 
     let prescriptions_repository = Box::new(PrescriptionsRepositoryFake::new(
-        None, None, None, None, None,
+        None, None, None, None, None, None, None,
     ));
     let prescriptions_service = Arc::new(PrescriptionsService::new(prescriptions_repository));
 
@@ -51,3 +58,27 @@ pub fn create_fake_api_context() -> Context {
         sessions_service,
     }
 }
+
+#[cfg(test)]
+pub(crate) async fn create_session_token(
+    client: &Client,
+    doctor_id: Option<Uuid>,
+    pharmacist_id: Option<Uuid>,
+) -> String {
+    let session = client
+        .rocket()
+        .state::<Context>()
+        .unwrap()
+        .sessions_service
+        .create_session(
+            Uuid::new_v4(),
+            doctor_id,
+            pharmacist_id,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            "Mozilla/5.0".into(),
+        )
+        .await
+        .unwrap();
+
+    session.id.to_string()
+}
@@ -11,6 +11,14 @@ pub struct NewDoctor {
     pub pesel_number: String,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct DoctorUpdate {
+    pub id: Uuid,
+    pub name: String,
+    pub pwz_number: String,
+    pub pesel_number: String,
+}
+
 fn example_name() -> &'static str {
     "John Doe"
 }
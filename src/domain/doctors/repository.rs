@@ -5,7 +5,7 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::domain::{
-    doctors::entities::{Doctor, NewDoctor},
+    doctors::entities::{Doctor, DoctorUpdate, NewDoctor},
     utils::pagination::get_pagination_params,
 };
 
@@ -35,6 +35,18 @@ pub enum GetDoctorByIdRepositoryError {
     DatabaseError(String),
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum UpdateDoctorRepositoryError {
+    #[error("Doctor with this id not found ({0})")]
+    NotFound(Uuid),
+    #[error("PWZ number already exists")]
+    DuplicatedPwzNumber,
+    #[error("PESEL number already exists")]
+    DuplicatedPeselNumber,
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
 #[async_trait]
 pub trait DoctorsRepository: Send + Sync + 'static {
     async fn create_doctor(&self, doctor: NewDoctor)
@@ -48,6 +60,10 @@ pub trait DoctorsRepository: Send + Sync + 'static {
         &self,
         doctor_id: Uuid,
     ) -> Result<Doctor, GetDoctorByIdRepositoryError>;
+    async fn update_doctor(
+        &self,
+        doctor_update: DoctorUpdate,
+    ) -> Result<Doctor, UpdateDoctorRepositoryError>;
 }
 
 pub struct DoctorsRepositoryFake {
@@ -128,6 +144,37 @@ impl DoctorsRepository for DoctorsRepositoryFake {
             None => Err(GetDoctorByIdRepositoryError::NotFound(doctor_id)),
         }
     }
+
+    async fn update_doctor(
+        &self,
+        doctor_update: DoctorUpdate,
+    ) -> Result<Doctor, UpdateDoctorRepositoryError> {
+        let mut doctors = self.doctors.write().unwrap();
+        let index = doctors
+            .iter()
+            .position(|doctor| doctor.id == doctor_update.id)
+            .ok_or(UpdateDoctorRepositoryError::NotFound(doctor_update.id))?;
+        for doctor in doctors
+            .iter()
+            .filter(|doctor| doctor.id != doctor_update.id)
+        {
+            if doctor.pwz_number == doctor_update.pwz_number {
+                return Err(UpdateDoctorRepositoryError::DuplicatedPwzNumber);
+            }
+            if doctor.pesel_number == doctor_update.pesel_number {
+                return Err(UpdateDoctorRepositoryError::DuplicatedPeselNumber);
+            }
+        }
+
+        let doctor = &mut doctors[index];
+
+        doctor.name = doctor_update.name;
+        doctor.pwz_number = doctor_update.pwz_number;
+        doctor.pesel_number = doctor_update.pesel_number;
+        doctor.updated_at = Utc::now();
+
+        Ok(doctor.clone())
+    }
 }
 
 #[cfg(test)]
@@ -139,10 +186,10 @@ mod tests {
     use super::DoctorsRepositoryFake;
     use crate::domain::{
         doctors::{
-            entities::NewDoctor,
+            entities::{DoctorUpdate, NewDoctor},
             repository::{
                 CreateDoctorRepositoryError, DoctorsRepository, GetDoctorByIdRepositoryError,
-                GetDoctorsRepositoryError,
+                GetDoctorsRepositoryError, UpdateDoctorRepositoryError,
             },
         },
         utils::pagination::PaginationError,
@@ -279,4 +326,79 @@ mod tests {
             Err(CreateDoctorRepositoryError::DuplicatedPeselNumber)
         );
     }
+
+    #[tokio::test]
+    async fn updates_doctor() {
+        let repository = setup_repository();
+
+        let new_doctor =
+            NewDoctor::new("John Doe".into(), "5425740".into(), "96021817257".into()).unwrap();
+        let doctor = repository.create_doctor(new_doctor).await.unwrap();
+
+        let updated_doctor = repository
+            .update_doctor(DoctorUpdate {
+                id: doctor.id,
+                name: "John Smith".into(),
+                pwz_number: "8463856".into(),
+                pesel_number: doctor.pesel_number.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_doctor.name, "John Smith");
+        assert_eq!(updated_doctor.pwz_number, "8463856");
+        assert_eq!(updated_doctor.created_at, doctor.created_at);
+        assert!(updated_doctor.updated_at > doctor.updated_at);
+        assert_eq!(
+            repository.get_doctor_by_id(doctor.id).await.unwrap(),
+            updated_doctor
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_update_doctor_if_it_doesnt_exist_or_numbers_are_duplicated() {
+        let repository = setup_repository();
+
+        let doctor_0 =
+            NewDoctor::new("John Doe".into(), "5425740".into(), "96021817257".into()).unwrap();
+        let doctor_1 =
+            NewDoctor::new("John Doe".into(), "8463856".into(), "99031301347".into()).unwrap();
+        repository.create_doctor(doctor_0.clone()).await.unwrap();
+        repository.create_doctor(doctor_1.clone()).await.unwrap();
+
+        let doctor_update = DoctorUpdate {
+            id: doctor_1.id,
+            name: doctor_1.name,
+            pwz_number: doctor_0.pwz_number.clone(),
+            pesel_number: doctor_1.pesel_number.clone(),
+        };
+
+        assert_eq!(
+            repository.update_doctor(doctor_update.clone()).await,
+            Err(UpdateDoctorRepositoryError::DuplicatedPwzNumber)
+        );
+        assert_eq!(
+            repository
+                .update_doctor(DoctorUpdate {
+                    pwz_number: "3123456".into(),
+                    pesel_number: doctor_0.pesel_number,
+                    ..doctor_update.clone()
+                })
+                .await,
+            Err(UpdateDoctorRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let doctor_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_doctor(DoctorUpdate {
+                    id: doctor_id,
+                    pwz_number: "3123456".into(),
+                    ..doctor_update
+                })
+                .await,
+            Err(UpdateDoctorRepositoryError::NotFound(doctor_id))
+        );
+    }
 }
@@ -1,10 +1,10 @@
 use uuid::Uuid;
 
 use super::{
-    entities::{Doctor, NewDoctor},
+    entities::{Doctor, DoctorUpdate, NewDoctor},
     repository::{
         CreateDoctorRepositoryError, DoctorsRepository, GetDoctorByIdRepositoryError,
-        GetDoctorsRepositoryError,
+        GetDoctorsRepositoryError, UpdateDoctorRepositoryError,
     },
 };
 
@@ -24,6 +24,13 @@ pub enum GetDoctorsWithPaginationError {
     RepositoryError(GetDoctorsRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum UpdateDoctorError {
+    DomainError(String),
+    NotAccountOwner,
+    RepositoryError(UpdateDoctorRepositoryError),
+}
+
 pub struct DoctorsService {
     repository: Box<dyn DoctorsRepository>,
 }
@@ -74,14 +81,56 @@ impl DoctorsService {
 
         Ok(doctors)
     }
+
+    // Doctors can only update their own account
+    pub async fn update_doctor(
+        &self,
+        doctor_id: Uuid,
+        updating_doctor_id: Uuid,
+        name: Option<String>,
+        pesel_number: Option<String>,
+        pwz_number: Option<String>,
+    ) -> Result<Doctor, UpdateDoctorError> {
+        if updating_doctor_id != doctor_id {
+            return Err(UpdateDoctorError::NotAccountOwner);
+        }
+
+        let doctor =
+            self.repository
+                .get_doctor_by_id(doctor_id)
+                .await
+                .map_err(|err| match err {
+                    GetDoctorByIdRepositoryError::NotFound(id) => {
+                        UpdateDoctorError::RepositoryError(UpdateDoctorRepositoryError::NotFound(
+                            id,
+                        ))
+                    }
+                    GetDoctorByIdRepositoryError::DatabaseError(message) => {
+                        UpdateDoctorError::RepositoryError(
+                            UpdateDoctorRepositoryError::DatabaseError(message),
+                        )
+                    }
+                })?;
+
+        let doctor_update = DoctorUpdate::new(&doctor, name, pwz_number, pesel_number)
+            .map_err(|err| UpdateDoctorError::DomainError(err.to_string()))?;
+
+        let updated_doctor = self
+            .repository
+            .update_doctor(doctor_update)
+            .await
+            .map_err(UpdateDoctorError::RepositoryError)?;
+
+        Ok(updated_doctor)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use uuid::Uuid;
 
-    use super::{CreateDoctorError, DoctorsService, GetDoctorByIdError};
-    use crate::domain::doctors::repository::DoctorsRepositoryFake;
+    use super::{CreateDoctorError, DoctorsService, GetDoctorByIdError, UpdateDoctorError};
+    use crate::domain::doctors::repository::{DoctorsRepositoryFake, UpdateDoctorRepositoryError};
 
     fn setup_service() -> DoctorsService {
         DoctorsService::new(Box::new(DoctorsRepositoryFake::new()))
@@ -239,4 +288,98 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn updates_doctor() {
+        let service = setup_service();
+
+        let created_doctor = service
+            .create_doctor("John Doex".into(), "96021807250".into(), "5425740".into())
+            .await
+            .unwrap();
+
+        let updated_doctor = service
+            .update_doctor(
+                created_doctor.id,
+                created_doctor.id,
+                None,
+                None,
+                Some("8463856".into()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_doctor.name, "John Doex");
+        assert_eq!(updated_doctor.pesel_number, "96021807250");
+        assert_eq!(updated_doctor.pwz_number, "8463856");
+
+        let doctor_from_repository = service.get_doctor_by_id(created_doctor.id).await.unwrap();
+
+        assert_eq!(doctor_from_repository, updated_doctor);
+    }
+
+    #[tokio::test]
+    async fn update_doctor_returns_error_if_doctor_doesnt_exist_or_body_is_incorrect() {
+        let service = setup_service();
+        let doctor_id = Uuid::new_v4();
+
+        let created_doctor = service
+            .create_doctor("John Doex".into(), "96021807250".into(), "5425740".into())
+            .await
+            .unwrap();
+        service
+            .create_doctor("John Doey".into(), "99031301347".into(), "8463856".into())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .update_doctor(
+                    created_doctor.id,
+                    created_doctor.id,
+                    None,
+                    Some("96021807251".into()),
+                    None
+                )
+                .await,
+            Err(UpdateDoctorError::DomainError(_))
+        ));
+
+        assert!(matches!(
+            service
+                .update_doctor(
+                    created_doctor.id,
+                    created_doctor.id,
+                    None,
+                    Some("99031301347".into()),
+                    None
+                )
+                .await,
+            Err(UpdateDoctorError::RepositoryError(
+                UpdateDoctorRepositoryError::DuplicatedPeselNumber
+            ))
+        ));
+
+        assert!(matches!(
+            service
+                .update_doctor(doctor_id, doctor_id, Some("John Doez".into()), None, None)
+                .await,
+            Err(UpdateDoctorError::RepositoryError(
+                UpdateDoctorRepositoryError::NotFound(_)
+            ))
+        ));
+
+        assert!(matches!(
+            service
+                .update_doctor(
+                    created_doctor.id,
+                    Uuid::new_v4(),
+                    Some("John Doez".into()),
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdateDoctorError::NotAccountOwner)
+        ));
+    }
 }
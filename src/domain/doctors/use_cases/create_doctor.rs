@@ -3,7 +3,7 @@ use uuid::Uuid;
 use crate::domain::{
     doctors::entities::NewDoctor,
     utils::validators::{
-        validate_name::validate_name, validate_pesel_number::validate_pesel_number,
+        validate_name::validate_name, validate_pesel_number::Pesel,
         validate_pwz_number::validate_pwz_number,
     },
 };
@@ -11,7 +11,7 @@ use crate::domain::{
 impl NewDoctor {
     pub fn new(name: String, pwz_number: String, pesel_number: String) -> anyhow::Result<Self> {
         validate_name(&name)?;
-        validate_pesel_number(&pesel_number)?;
+        Pesel::new(&pesel_number)?;
         validate_pwz_number(&pwz_number)?;
 
         Ok(NewDoctor {
@@ -37,6 +37,11 @@ mod tests {
         assert_eq!(sut.pesel_number, "96021817257");
     }
 
+    #[test]
+    fn creates_doctor_born_after_2000() {
+        assert!(NewDoctor::new("John Doe".into(), "5425740".into(), "02270803626".into()).is_ok());
+    }
+
     #[test]
     fn doesnt_create_doctor_if_pesel_number_is_invalid() {
         assert!(NewDoctor::new("John Doe".into(), "4123456".into(), "92223300009".into()).is_err());
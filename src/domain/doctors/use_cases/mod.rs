@@ -1 +1,2 @@
 pub mod create_doctor;
+pub mod update_doctor;
@@ -0,0 +1,84 @@
+use crate::domain::{
+    doctors::entities::{Doctor, DoctorUpdate},
+    utils::validators::{
+        validate_name::validate_name, validate_pesel_number::Pesel,
+        validate_pwz_number::validate_pwz_number,
+    },
+};
+
+impl DoctorUpdate {
+    // Fields which are not given keep their current values, the result is validated the same way
+    // as a new doctor
+    pub fn new(
+        doctor: &Doctor,
+        name: Option<String>,
+        pwz_number: Option<String>,
+        pesel_number: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let name = name.unwrap_or(doctor.name.clone());
+        let pwz_number = pwz_number.unwrap_or(doctor.pwz_number.clone());
+        let pesel_number = pesel_number.unwrap_or(doctor.pesel_number.clone());
+        validate_name(&name)?;
+        Pesel::new(&pesel_number)?;
+        validate_pwz_number(&pwz_number)?;
+
+        Ok(DoctorUpdate {
+            id: doctor.id,
+            name,
+            pwz_number,
+            pesel_number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::doctors::entities::{Doctor, DoctorUpdate};
+
+    fn create_doctor() -> Doctor {
+        Doctor {
+            id: Uuid::new_v4(),
+            name: "John Doe".into(),
+            pwz_number: "5425740".into(),
+            pesel_number: "96021817257".into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn updates_only_given_fields_of_doctor() {
+        let doctor = create_doctor();
+
+        let sut = DoctorUpdate::new(&doctor, Some("John Smith".into()), None, None).unwrap();
+
+        assert_eq!(sut.id, doctor.id);
+        assert_eq!(sut.name, "John Smith");
+        assert_eq!(sut.pwz_number, "5425740");
+        assert_eq!(sut.pesel_number, "96021817257");
+
+        let sut = DoctorUpdate::new(
+            &doctor,
+            None,
+            Some("8463856".into()),
+            Some("99031301347".into()),
+        )
+        .unwrap();
+
+        assert_eq!(sut.name, "John Doe");
+        assert_eq!(sut.pwz_number, "8463856");
+        assert_eq!(sut.pesel_number, "99031301347");
+    }
+
+    #[test]
+    fn doesnt_update_doctor_with_invalid_fields() {
+        let doctor = create_doctor();
+
+        assert!(DoctorUpdate::new(&doctor, Some("John".into()), None, None).is_err());
+        assert!(DoctorUpdate::new(&doctor, None, Some("1234567".into()), None).is_err());
+        assert!(DoctorUpdate::new(&doctor, None, None, Some("92223300009".into())).is_err());
+    }
+}
@@ -12,15 +12,201 @@ pub enum DrugContentType {
     LiquidPills,
 }
 
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "strength_unit", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StrengthUnit {
+    Mg,
+    Mcg,
+    G,
+    Iu,
+    Ml,
+    MgPerMl,
+    Percent,
+}
+
+// Lists of narcotic drugs and psychotropic substances under control, drugs from them can only be
+// prescribed on prescription for controlled substances
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "controlled_substance_list", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ControlledSubstanceList {
+    Narcotic,
+    Psychotropic,
+}
+
+// Share of the package price covered by the public payer. Flat fee leaves the patient a fixed
+// amount to pay, and seniors and children get their drugs for free
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "reimbursement_level", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReimbursementLevel {
+    Full,
+    FiftyPercent,
+    ThirtyPercent,
+    FlatFee,
+    FreeForSeniors,
+    FreeForChildren,
+}
+
+impl sqlx::postgres::PgHasArrayType for ReimbursementLevel {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_reimbursement_level")
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugActiveSubstance {
+    pub name: String,
+    pub amount: Option<f64>,
+    pub unit: Option<StrengthUnit>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ActiveSubstance {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewAtcGroup {
+    pub code: String,
+    pub name: String,
+    pub parent_code: Option<String>,
+}
+
+// Node of the ATC (Anatomical Therapeutic Chemical) classification tree
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct AtcGroup {
+    #[schemars(example = "example_atc_code")]
+    pub code: String,
+    pub name: String,
+    pub parent_code: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewAtcGroup> for AtcGroup {
+    fn eq(&self, other: &NewAtcGroup) -> bool {
+        self.code == other.code && self.name == other.name && self.parent_code == other.parent_code
+    }
+}
+
+impl PartialEq<AtcGroup> for NewAtcGroup {
+    fn eq(&self, other: &AtcGroup) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugCategory> for DrugCategory {
+    fn eq(&self, other: &NewDrugCategory) -> bool {
+        self.id == other.id && self.name == other.name && self.parent_id == other.parent_id
+    }
+}
+
+impl PartialEq<DrugCategory> for NewDrugCategory {
+    fn eq(&self, other: &DrugCategory) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugEquivalenceGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub drug_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugEquivalenceGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub drug_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugEquivalenceGroup> for DrugEquivalenceGroup {
+    fn eq(&self, other: &NewDrugEquivalenceGroup) -> bool {
+        self.id == other.id && self.name == other.name && self.drug_ids == other.drug_ids
+    }
+}
+
+impl PartialEq<DrugEquivalenceGroup> for NewDrugEquivalenceGroup {
+    fn eq(&self, other: &DrugEquivalenceGroup) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugVariant {
+    pub id: Uuid,
+    pub drug_id: Uuid,
+    pub pills_count: Option<i32>,
+    pub volume_ml: Option<i32>,
+    pub gtin: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugReimbursement {
+    pub id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Uuid,
+    pub levels: Vec<ReimbursementLevel>,
+    pub reimbursement_limit: i64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugPrice {
+    pub id: Uuid,
+    pub price_list_id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Uuid,
+    pub retail_price: i64,
+    pub official_price: i64,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugPriceList {
+    pub id: Uuid,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: Option<DateTime<Utc>>,
+    pub prices: Vec<NewDrugPrice>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NewDrug {
     pub id: Uuid,
     pub name: String,
     pub content_type: DrugContentType,
-    pub pills_count: Option<i32>,
     pub mg_per_pill: Option<i32>,
     pub ml_per_pill: Option<i32>,
-    pub volume_ml: Option<i32>,
+    pub active_substances: Vec<DrugActiveSubstance>,
+    pub variants: Vec<NewDrugVariant>,
+    pub atc_code: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub controlled_substance_list: Option<ControlledSubstanceList>,
+    pub external_id: Option<String>,
 }
 
 fn example_drug_name() -> &'static str {
@@ -41,6 +227,232 @@ fn example_ml_per_pill() -> Option<i32> {
 fn example_volume_ml() -> Option<i32> {
     None
 }
+fn example_gtin() -> Option<&'static str> {
+    Some("5909990733828")
+}
+fn example_atc_code() -> &'static str {
+    "N02BE01"
+}
+fn example_active_substances() -> Vec<DrugActiveSubstance> {
+    vec![DrugActiveSubstance {
+        name: "paracetamol".into(),
+        amount: Some(300.0),
+        unit: Some(StrengthUnit::Mg),
+    }]
+}
+
+// Package size of a drug, e.g. 10, 20 or 50 pills of the same product
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugVariant {
+    pub id: Uuid,
+    pub drug_id: Uuid,
+    #[schemars(example = "example_pills_count")]
+    pub pills_count: Option<i32>,
+    #[schemars(example = "example_volume_ml")]
+    pub volume_ml: Option<i32>,
+    #[schemars(
+        example = "example_gtin",
+        description = "GTIN-13 (EAN) barcode printed on the package"
+    )]
+    pub gtin: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugVariant> for DrugVariant {
+    fn eq(&self, other: &NewDrugVariant) -> bool {
+        self.id == other.id
+            && self.drug_id == other.drug_id
+            && self.pills_count == other.pills_count
+            && self.volume_ml == other.volume_ml
+            && self.gtin == other.gtin
+    }
+}
+
+impl PartialEq<DrugVariant> for NewDrugVariant {
+    fn eq(&self, other: &DrugVariant) -> bool {
+        other.eq(self)
+    }
+}
+
+fn example_reimbursement_levels() -> Vec<ReimbursementLevel> {
+    vec![
+        ReimbursementLevel::FiftyPercent,
+        ReimbursementLevel::FreeForSeniors,
+    ]
+}
+fn example_reimbursement_limit() -> i64 {
+    1250
+}
+
+// Reimbursement scheme of a package (variant) of the drug, listing the levels a doctor can choose
+// from when prescribing it
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugReimbursement {
+    pub id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Uuid,
+    #[schemars(example = "example_reimbursement_levels")]
+    pub levels: Vec<ReimbursementLevel>,
+    #[schemars(
+        example = "example_reimbursement_limit",
+        description = "Price of the package (in grosze) the reimbursement is computed from"
+    )]
+    pub reimbursement_limit: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugReimbursement> for DrugReimbursement {
+    fn eq(&self, other: &NewDrugReimbursement) -> bool {
+        self.id == other.id
+            && self.drug_id == other.drug_id
+            && self.variant_id == other.variant_id
+            && self.levels == other.levels
+            && self.reimbursement_limit == other.reimbursement_limit
+    }
+}
+
+impl PartialEq<DrugReimbursement> for NewDrugReimbursement {
+    fn eq(&self, other: &DrugReimbursement) -> bool {
+        other.eq(self)
+    }
+}
+
+fn example_retail_price() -> i64 {
+    1599
+}
+fn example_official_price() -> i64 {
+    1320
+}
+
+// Price of a package (variant) of the drug in a price list
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugPriceListItem {
+    pub drug_id: Uuid,
+    pub variant_id: Uuid,
+    #[schemars(
+        example = "example_retail_price",
+        description = "Price of the package (in grosze) paid when it isn't reimbursed"
+    )]
+    pub retail_price: i64,
+    #[schemars(
+        example = "example_official_price",
+        description = "Price of the package (in grosze) set for reimbursed drugs"
+    )]
+    pub official_price: i64,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugPrice {
+    pub id: Uuid,
+    pub price_list_id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Uuid,
+    #[schemars(example = "example_retail_price")]
+    pub retail_price: i64,
+    #[schemars(example = "example_official_price")]
+    pub official_price: i64,
+    pub valid_from: DateTime<Utc>,
+    #[schemars(description = "End of the validity period (exclusive), None when still valid")]
+    pub valid_to: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugPrice> for DrugPrice {
+    fn eq(&self, other: &NewDrugPrice) -> bool {
+        self.id == other.id
+            && self.price_list_id == other.price_list_id
+            && self.drug_id == other.drug_id
+            && self.variant_id == other.variant_id
+            && self.retail_price == other.retail_price
+            && self.official_price == other.official_price
+            && self.valid_from == other.valid_from
+            && self.valid_to == other.valid_to
+    }
+}
+
+impl PartialEq<DrugPrice> for NewDrugPrice {
+    fn eq(&self, other: &DrugPrice) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugPriceList {
+    pub id: Uuid,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: Option<DateTime<Utc>>,
+    pub prices: Vec<DrugPrice>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugPriceList> for DrugPriceList {
+    fn eq(&self, other: &NewDrugPriceList) -> bool {
+        self.id == other.id
+            && self.valid_from == other.valid_from
+            && self.valid_to == other.valid_to
+            && self.prices == other.prices
+    }
+}
+
+impl PartialEq<DrugPriceList> for NewDrugPriceList {
+    fn eq(&self, other: &DrugPriceList) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DrugImportFormat {
+    Csv,
+    Xml,
+}
+
+// Drug from a registry export, with all values kept as text until they're validated, so that an
+// invalid row is rejected on its own instead of the whole file
+#[derive(Debug, PartialEq, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DrugImportRow {
+    pub external_id: Option<String>,
+    pub name: Option<String>,
+    pub content_type: Option<String>,
+    pub pills_count: Option<String>,
+    pub mg_per_pill: Option<String>,
+    pub ml_per_pill: Option<String>,
+    pub volume_ml: Option<String>,
+    pub atc_code: Option<String>,
+    pub gtin: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DrugImportRowStatus {
+    Created,
+    Updated,
+    Rejected,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugImportRowResult {
+    #[schemars(description = "Position of the drug in the imported file, starting from 1")]
+    pub row_number: usize,
+    pub external_id: Option<String>,
+    pub status: DrugImportRowStatus,
+    pub drug_id: Option<Uuid>,
+    #[schemars(description = "Reason why the row was rejected")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugImportReport {
+    pub created_count: usize,
+    pub updated_count: usize,
+    pub rejected_count: usize,
+    pub rows: Vec<DrugImportRowResult>,
+}
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct Drug {
@@ -49,14 +461,32 @@ pub struct Drug {
     pub name: String,
     #[schemars(example = "example_drug_content_type")]
     pub content_type: DrugContentType,
-    #[schemars(example = "example_pills_count")]
-    pub pills_count: Option<i32>,
     #[schemars(example = "example_mg_per_pill")]
     pub mg_per_pill: Option<i32>,
     #[schemars(example = "example_ml_per_pill")]
     pub ml_per_pill: Option<i32>,
-    #[schemars(example = "example_volume_ml")]
-    pub volume_ml: Option<i32>,
+    #[schemars(example = "example_active_substances")]
+    pub active_substances: Vec<DrugActiveSubstance>,
+    pub variants: Vec<DrugVariant>,
+    #[schemars(example = "example_atc_code")]
+    pub atc_code: Option<String>,
+    #[schemars(
+        description = "ATC groups of the drug, from the anatomical main group down to the chemical substance"
+    )]
+    pub atc_path: Vec<AtcGroup>,
+    pub category_id: Option<Uuid>,
+    #[schemars(
+        description = "Group of drugs with the same active substances, strength and form, which can substitute this drug when filling a prescription"
+    )]
+    pub equivalence_group_id: Option<Uuid>,
+    pub controlled_substance_list: Option<ControlledSubstanceList>,
+    pub reimbursements: Vec<DrugReimbursement>,
+    #[schemars(
+        description = "Prices of the drug packages from all price lists, from the oldest one"
+    )]
+    pub prices: Vec<DrugPrice>,
+    #[schemars(description = "Identifier of the drug in the registry it was imported from")]
+    pub external_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -66,10 +496,14 @@ impl PartialEq<NewDrug> for Drug {
         self.id == other.id
             && self.name == other.name
             && self.content_type == other.content_type
-            && self.pills_count == other.pills_count
             && self.mg_per_pill == other.mg_per_pill
             && self.ml_per_pill == other.ml_per_pill
-            && self.volume_ml == other.volume_ml
+            && self.active_substances == other.active_substances
+            && self.variants == other.variants
+            && self.atc_code == other.atc_code
+            && self.category_id == other.category_id
+            && self.controlled_substance_list == other.controlled_substance_list
+            && self.external_id == other.external_id
     }
 }
 
@@ -78,3 +512,49 @@ impl PartialEq<Drug> for NewDrug {
         other.eq(self)
     }
 }
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "drug_interaction_severity", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DrugInteractionSeverity {
+    Minor,
+    Moderate,
+    Major,
+    Contraindicated,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewDrugInteraction {
+    pub id: Uuid,
+    pub substance_a: String,
+    pub substance_b: String,
+    pub severity: DrugInteractionSeverity,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DrugInteraction {
+    pub id: Uuid,
+    pub substance_a: String,
+    pub substance_b: String,
+    pub severity: DrugInteractionSeverity,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewDrugInteraction> for DrugInteraction {
+    fn eq(&self, other: &NewDrugInteraction) -> bool {
+        self.id == other.id
+            && self.substance_a == other.substance_a
+            && self.substance_b == other.substance_b
+            && self.severity == other.severity
+            && self.description == other.description
+    }
+}
+
+impl PartialEq<DrugInteraction> for NewDrugInteraction {
+    fn eq(&self, other: &DrugInteraction) -> bool {
+        other.eq(self)
+    }
+}
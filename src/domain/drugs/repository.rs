@@ -5,12 +5,25 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::domain::{
-    drugs::entities::{Drug, NewDrug},
+    drugs::entities::{
+        ActiveSubstance, AtcGroup, Drug, DrugCategory, DrugEquivalenceGroup, DrugInteraction,
+        DrugPrice, DrugPriceList, DrugReimbursement, DrugVariant, NewAtcGroup, NewDrug,
+        NewDrugCategory, NewDrugEquivalenceGroup, NewDrugInteraction, NewDrugPriceList,
+        NewDrugReimbursement, NewDrugVariant,
+    },
     utils::pagination::get_pagination_params,
 };
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum CreateDrugRepositoryError {
+    #[error("ATC group with code {0} not found")]
+    AtcGroupNotFound(String),
+    #[error("Drug category with id {0} not found")]
+    CategoryNotFound(Uuid),
+    #[error("Drug with external id {0} already exists")]
+    DuplicatedExternalId(String),
+    #[error("Drug package with GTIN {0} already exists")]
+    DuplicatedGtin(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -31,19 +44,230 @@ pub enum GetDrugByIdRepositoryError {
     DatabaseError(String),
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugByExternalIdRepositoryError {
+    #[error("Drug with this external id not found ({0})")]
+    NotFound(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugByGtinRepositoryError {
+    #[error("Drug package with this GTIN not found ({0})")]
+    NotFound(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum UpdateDrugRepositoryError {
+    #[error("Drug with this id not found ({0})")]
+    NotFound(Uuid),
+    #[error("ATC group with code {0} not found")]
+    AtcGroupNotFound(String),
+    #[error("Drug category with id {0} not found")]
+    CategoryNotFound(Uuid),
+    #[error("Drug with external id {0} already exists")]
+    DuplicatedExternalId(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugVariantRepositoryError {
+    #[error("Drug with this id not found ({0})")]
+    DrugNotFound(Uuid),
+    #[error("Drug package with GTIN {0} already exists")]
+    DuplicatedGtin(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugReimbursementRepositoryError {
+    #[error("Drug with this id not found ({0})")]
+    DrugNotFound(Uuid),
+    #[error("Drug variant with this id not found ({0})")]
+    DrugVariantNotFound(Uuid),
+    #[error("Drug variant with id {0} already has a reimbursement scheme")]
+    DuplicatedReimbursement(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugPriceListRepositoryError {
+    #[error("Drug variant with this id not found ({0})")]
+    DrugVariantNotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugInteractionRepositoryError {
+    #[error("Interaction between these substances already exists")]
+    DuplicatedInteraction,
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugInteractionsRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetActiveSubstancesRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateAtcGroupRepositoryError {
+    #[error("ATC group with this code already exists")]
+    DuplicatedCode,
+    #[error("Parent ATC group with code {0} not found")]
+    ParentNotFound(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetAtcGroupsRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugCategoryRepositoryError {
+    #[error("Parent category with id {0} not found")]
+    ParentNotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugCategoriesRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugEquivalenceGroupRepositoryError {
+    #[error("Drug with id {0} not found")]
+    DrugNotFound(Uuid),
+    #[error("Drug with id {0} already belongs to an equivalence group")]
+    DrugAlreadyInGroup(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugEquivalenceGroupsRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
 #[async_trait]
 pub trait DrugsRepository: Send + Sync + 'static {
+    // Adds the drug's active substances to the catalogue if they aren't there yet
     async fn create_drug(&self, drug: NewDrug) -> Result<Drug, CreateDrugRepositoryError>;
+    // atc_code filters by code prefix, category_id includes drugs from all subcategories
     async fn get_drugs(
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        substance: Option<String>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
     ) -> Result<Vec<Drug>, GetDrugsRepositoryError>;
     async fn get_drug_by_id(&self, drug_id: Uuid) -> Result<Drug, GetDrugByIdRepositoryError>;
+    async fn get_drug_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> Result<Drug, GetDrugByExternalIdRepositoryError>;
+    // Drug with a variant of the given GTIN, the GTIN is expected to be already validated
+    async fn get_drug_by_gtin(&self, gtin: &str) -> Result<Drug, GetDrugByGtinRepositoryError>;
+    // Updates only the drug's own columns, its active substances, variants, reimbursements and
+    // prices are left as they are
+    async fn update_drug(&self, drug: Drug) -> Result<Drug, UpdateDrugRepositoryError>;
+    async fn create_drug_variant(
+        &self,
+        variant: NewDrugVariant,
+    ) -> Result<DrugVariant, CreateDrugVariantRepositoryError>;
+    async fn create_drug_reimbursement(
+        &self,
+        reimbursement: NewDrugReimbursement,
+    ) -> Result<DrugReimbursement, CreateDrugReimbursementRepositoryError>;
+    // Prices of the listed variants which are still valid when the price list starts are ended
+    // on that date
+    async fn create_drug_price_list(
+        &self,
+        price_list: NewDrugPriceList,
+    ) -> Result<DrugPriceList, CreateDrugPriceListRepositoryError>;
+    async fn create_drug_interaction(
+        &self,
+        drug_interaction: NewDrugInteraction,
+    ) -> Result<DrugInteraction, CreateDrugInteractionRepositoryError>;
+    async fn get_drug_interactions(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugInteraction>, GetDrugInteractionsRepositoryError>;
+    async fn get_active_substances(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ActiveSubstance>, GetActiveSubstancesRepositoryError>;
+    async fn create_atc_group(
+        &self,
+        atc_group: NewAtcGroup,
+    ) -> Result<AtcGroup, CreateAtcGroupRepositoryError>;
+    async fn get_atc_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<AtcGroup>, GetAtcGroupsRepositoryError>;
+    async fn create_drug_category(
+        &self,
+        drug_category: NewDrugCategory,
+    ) -> Result<DrugCategory, CreateDrugCategoryRepositoryError>;
+    async fn get_drug_categories(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugCategory>, GetDrugCategoriesRepositoryError>;
+    // Assigns the group to its drugs, none of which can be in another group already
+    async fn create_drug_equivalence_group(
+        &self,
+        drug_equivalence_group: NewDrugEquivalenceGroup,
+    ) -> Result<DrugEquivalenceGroup, CreateDrugEquivalenceGroupRepositoryError>;
+    async fn get_drug_equivalence_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugEquivalenceGroup>, GetDrugEquivalenceGroupsRepositoryError>;
 }
 
 pub struct DrugsRepositoryFake {
     drugs: RwLock<Vec<Drug>>,
+    drug_interactions: RwLock<Vec<DrugInteraction>>,
+    active_substances: RwLock<Vec<ActiveSubstance>>,
+    atc_groups: RwLock<Vec<AtcGroup>>,
+    drug_categories: RwLock<Vec<DrugCategory>>,
+    drug_equivalence_groups: RwLock<Vec<DrugEquivalenceGroup>>,
 }
 
 impl DrugsRepositoryFake {
@@ -51,21 +275,152 @@ impl DrugsRepositoryFake {
     pub fn new() -> Self {
         Self {
             drugs: RwLock::new(Vec::new()),
+            drug_interactions: RwLock::new(Vec::new()),
+            active_substances: RwLock::new(Vec::new()),
+            atc_groups: RwLock::new(Vec::new()),
+            drug_categories: RwLock::new(Vec::new()),
+            drug_equivalence_groups: RwLock::new(Vec::new()),
         }
     }
+
+    fn get_atc_path(&self, atc_code: &str) -> Vec<AtcGroup> {
+        let mut atc_path: Vec<AtcGroup> = self
+            .atc_groups
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|atc_group| atc_code.starts_with(&atc_group.code))
+            .cloned()
+            .collect();
+        atc_path.sort_by_key(|atc_group| atc_group.code.len());
+
+        atc_path
+    }
+
+    fn get_category_subtree(&self, category_id: Uuid) -> Vec<Uuid> {
+        let drug_categories = self.drug_categories.read().unwrap();
+        let mut category_ids = vec![category_id];
+        let mut i = 0;
+        while i < category_ids.len() {
+            let parent_id = category_ids[i];
+            category_ids.extend(
+                drug_categories
+                    .iter()
+                    .filter(|category| category.parent_id == Some(parent_id))
+                    .map(|category| category.id),
+            );
+            i += 1;
+        }
+
+        category_ids
+    }
+
+    fn has_variant_with_gtin(&self, gtin: &str) -> bool {
+        self.drugs.read().unwrap().iter().any(|drug| {
+            drug.variants
+                .iter()
+                .any(|variant| variant.gtin.as_deref() == Some(gtin))
+        })
+    }
 }
 
 #[async_trait]
 impl DrugsRepository for DrugsRepositoryFake {
     async fn create_drug(&self, new_drug: NewDrug) -> Result<Drug, CreateDrugRepositoryError> {
+        if let Some(external_id) = &new_drug.external_id {
+            if self
+                .drugs
+                .read()
+                .unwrap()
+                .iter()
+                .any(|drug| drug.external_id.as_ref() == Some(external_id))
+            {
+                return Err(CreateDrugRepositoryError::DuplicatedExternalId(
+                    external_id.clone(),
+                ));
+            }
+        }
+        for gtin in new_drug
+            .variants
+            .iter()
+            .filter_map(|variant| variant.gtin.as_ref())
+        {
+            if self.has_variant_with_gtin(gtin) {
+                return Err(CreateDrugRepositoryError::DuplicatedGtin(gtin.clone()));
+            }
+        }
+        let atc_path = match &new_drug.atc_code {
+            Some(atc_code) => {
+                if !self
+                    .atc_groups
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .any(|atc_group| &atc_group.code == atc_code)
+                {
+                    return Err(CreateDrugRepositoryError::AtcGroupNotFound(
+                        atc_code.clone(),
+                    ));
+                }
+                self.get_atc_path(atc_code)
+            }
+            None => vec![],
+        };
+        if let Some(category_id) = new_drug.category_id {
+            if !self
+                .drug_categories
+                .read()
+                .unwrap()
+                .iter()
+                .any(|category| category.id == category_id)
+            {
+                return Err(CreateDrugRepositoryError::CategoryNotFound(category_id));
+            }
+        }
+
+        let mut active_substances = self.active_substances.write().unwrap();
+        for drug_active_substance in &new_drug.active_substances {
+            if !active_substances
+                .iter()
+                .any(|active_substance| active_substance.name == drug_active_substance.name)
+            {
+                active_substances.push(ActiveSubstance {
+                    id: Uuid::new_v4(),
+                    name: drug_active_substance.name.clone(),
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                });
+            }
+        }
+
         let drug = Drug {
             id: new_drug.id,
             name: new_drug.name,
             content_type: new_drug.content_type,
             mg_per_pill: new_drug.mg_per_pill,
             ml_per_pill: new_drug.ml_per_pill,
-            pills_count: new_drug.pills_count,
-            volume_ml: new_drug.volume_ml,
+            active_substances: new_drug.active_substances,
+            variants: new_drug
+                .variants
+                .into_iter()
+                .map(|new_variant| DrugVariant {
+                    id: new_variant.id,
+                    drug_id: new_variant.drug_id,
+                    pills_count: new_variant.pills_count,
+                    volume_ml: new_variant.volume_ml,
+                    gtin: new_variant.gtin,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                })
+                .collect(),
+            atc_code: new_drug.atc_code,
+            atc_path,
+            category_id: new_drug.category_id,
+            equivalence_group_id: None,
+            controlled_substance_list: new_drug.controlled_substance_list,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: new_drug.external_id,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -79,21 +434,43 @@ impl DrugsRepository for DrugsRepositoryFake {
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        substance: Option<String>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
     ) -> Result<Vec<Drug>, GetDrugsRepositoryError> {
         let (page_size, offset) = get_pagination_params(page, page_size)
             .map_err(|err| GetDrugsRepositoryError::InvalidPaginationParams(err.to_string()))?;
-        let a = offset;
-        let b = offset + page_size;
-
-        let mut drugs: Vec<Drug> = vec![];
-        for i in a..b {
-            match self.drugs.read().unwrap().get(i as usize) {
-                Some(drug) => drugs.push(drug.clone()),
-                None => {}
-            }
-        }
+        let category_ids = category_id.map(|category_id| self.get_category_subtree(category_id));
 
-        Ok(drugs)
+        Ok(self
+            .drugs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|drug| match &substance {
+                Some(substance) => drug
+                    .active_substances
+                    .iter()
+                    .any(|active_substance| &active_substance.name == substance),
+                None => true,
+            })
+            .filter(|drug| match &atc_code {
+                Some(atc_code) => drug
+                    .atc_code
+                    .as_ref()
+                    .is_some_and(|drug_atc_code| drug_atc_code.starts_with(atc_code)),
+                None => true,
+            })
+            .filter(|drug| match &category_ids {
+                Some(category_ids) => drug
+                    .category_id
+                    .is_some_and(|category_id| category_ids.contains(&category_id)),
+                None => true,
+            })
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .cloned()
+            .collect())
     }
 
     async fn get_drug_by_id(&self, drug_id: Uuid) -> Result<Drug, GetDrugByIdRepositoryError> {
@@ -108,6 +485,482 @@ impl DrugsRepository for DrugsRepositoryFake {
             None => Err(GetDrugByIdRepositoryError::NotFound(drug_id)),
         }
     }
+
+    async fn get_drug_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> Result<Drug, GetDrugByExternalIdRepositoryError> {
+        match self
+            .drugs
+            .read()
+            .unwrap()
+            .iter()
+            .find(|drug| drug.external_id.as_deref() == Some(external_id))
+        {
+            Some(drug) => Ok(drug.clone()),
+            None => Err(GetDrugByExternalIdRepositoryError::NotFound(
+                external_id.into(),
+            )),
+        }
+    }
+
+    async fn get_drug_by_gtin(&self, gtin: &str) -> Result<Drug, GetDrugByGtinRepositoryError> {
+        match self.drugs.read().unwrap().iter().find(|drug| {
+            drug.variants
+                .iter()
+                .any(|variant| variant.gtin.as_deref() == Some(gtin))
+        }) {
+            Some(drug) => Ok(drug.clone()),
+            None => Err(GetDrugByGtinRepositoryError::NotFound(gtin.into())),
+        }
+    }
+
+    async fn update_drug(&self, drug: Drug) -> Result<Drug, UpdateDrugRepositoryError> {
+        let atc_path = match &drug.atc_code {
+            Some(atc_code) => {
+                if !self
+                    .atc_groups
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .any(|atc_group| &atc_group.code == atc_code)
+                {
+                    return Err(UpdateDrugRepositoryError::AtcGroupNotFound(
+                        atc_code.clone(),
+                    ));
+                }
+                self.get_atc_path(atc_code)
+            }
+            None => vec![],
+        };
+        if let Some(category_id) = drug.category_id {
+            if !self
+                .drug_categories
+                .read()
+                .unwrap()
+                .iter()
+                .any(|category| category.id == category_id)
+            {
+                return Err(UpdateDrugRepositoryError::CategoryNotFound(category_id));
+            }
+        }
+
+        let mut drugs = self.drugs.write().unwrap();
+        if let Some(external_id) = &drug.external_id {
+            if drugs.iter().any(|other_drug| {
+                other_drug.id != drug.id && other_drug.external_id.as_ref() == Some(external_id)
+            }) {
+                return Err(UpdateDrugRepositoryError::DuplicatedExternalId(
+                    external_id.clone(),
+                ));
+            }
+        }
+        let drug_from_repository = drugs
+            .iter_mut()
+            .find(|drug_from_repository| drug_from_repository.id == drug.id)
+            .ok_or(UpdateDrugRepositoryError::NotFound(drug.id))?;
+
+        drug_from_repository.name = drug.name;
+        drug_from_repository.content_type = drug.content_type;
+        drug_from_repository.mg_per_pill = drug.mg_per_pill;
+        drug_from_repository.ml_per_pill = drug.ml_per_pill;
+        drug_from_repository.atc_code = drug.atc_code;
+        drug_from_repository.atc_path = atc_path;
+        drug_from_repository.category_id = drug.category_id;
+        drug_from_repository.controlled_substance_list = drug.controlled_substance_list;
+        drug_from_repository.external_id = drug.external_id;
+        drug_from_repository.updated_at = Utc::now();
+
+        Ok(drug_from_repository.clone())
+    }
+
+    async fn create_drug_variant(
+        &self,
+        new_variant: NewDrugVariant,
+    ) -> Result<DrugVariant, CreateDrugVariantRepositoryError> {
+        if let Some(gtin) = &new_variant.gtin {
+            if self.has_variant_with_gtin(gtin) {
+                return Err(CreateDrugVariantRepositoryError::DuplicatedGtin(
+                    gtin.clone(),
+                ));
+            }
+        }
+        let mut drugs = self.drugs.write().unwrap();
+        let drug = drugs
+            .iter_mut()
+            .find(|drug| drug.id == new_variant.drug_id)
+            .ok_or(CreateDrugVariantRepositoryError::DrugNotFound(
+                new_variant.drug_id,
+            ))?;
+
+        let variant = DrugVariant {
+            id: new_variant.id,
+            drug_id: new_variant.drug_id,
+            pills_count: new_variant.pills_count,
+            volume_ml: new_variant.volume_ml,
+            gtin: new_variant.gtin,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        drug.variants.push(variant.clone());
+
+        Ok(variant)
+    }
+
+    async fn create_drug_reimbursement(
+        &self,
+        new_reimbursement: NewDrugReimbursement,
+    ) -> Result<DrugReimbursement, CreateDrugReimbursementRepositoryError> {
+        let mut drugs = self.drugs.write().unwrap();
+        let drug = drugs
+            .iter_mut()
+            .find(|drug| {
+                drug.id == new_reimbursement.drug_id
+                    && drug
+                        .variants
+                        .iter()
+                        .any(|variant| variant.id == new_reimbursement.variant_id)
+            })
+            .ok_or(CreateDrugReimbursementRepositoryError::DrugVariantNotFound(
+                new_reimbursement.variant_id,
+            ))?;
+        if drug
+            .reimbursements
+            .iter()
+            .any(|reimbursement| reimbursement.variant_id == new_reimbursement.variant_id)
+        {
+            Err(
+                CreateDrugReimbursementRepositoryError::DuplicatedReimbursement(
+                    new_reimbursement.variant_id,
+                ),
+            )?;
+        }
+
+        let reimbursement = DrugReimbursement {
+            id: new_reimbursement.id,
+            drug_id: new_reimbursement.drug_id,
+            variant_id: new_reimbursement.variant_id,
+            levels: new_reimbursement.levels,
+            reimbursement_limit: new_reimbursement.reimbursement_limit,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        drug.reimbursements.push(reimbursement.clone());
+
+        Ok(reimbursement)
+    }
+
+    async fn create_drug_price_list(
+        &self,
+        new_price_list: NewDrugPriceList,
+    ) -> Result<DrugPriceList, CreateDrugPriceListRepositoryError> {
+        let mut drugs = self.drugs.write().unwrap();
+        for new_price in &new_price_list.prices {
+            if !drugs.iter().any(|drug| {
+                drug.id == new_price.drug_id
+                    && drug
+                        .variants
+                        .iter()
+                        .any(|variant| variant.id == new_price.variant_id)
+            }) {
+                Err(CreateDrugPriceListRepositoryError::DrugVariantNotFound(
+                    new_price.variant_id,
+                ))?;
+            }
+        }
+
+        let mut prices = vec![];
+        for new_price in new_price_list.prices {
+            let drug = drugs
+                .iter_mut()
+                .find(|drug| drug.id == new_price.drug_id)
+                .unwrap();
+            for price in drug.prices.iter_mut() {
+                if price.variant_id == new_price.variant_id
+                    && price.valid_to.is_none()
+                    && price.valid_from < new_price.valid_from
+                {
+                    price.valid_to = Some(new_price.valid_from);
+                    price.updated_at = Utc::now();
+                }
+            }
+
+            let price = DrugPrice {
+                id: new_price.id,
+                price_list_id: new_price.price_list_id,
+                drug_id: new_price.drug_id,
+                variant_id: new_price.variant_id,
+                retail_price: new_price.retail_price,
+                official_price: new_price.official_price,
+                valid_from: new_price.valid_from,
+                valid_to: new_price.valid_to,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            drug.prices.push(price.clone());
+            drug.prices.sort_by_key(|price| price.valid_from);
+            prices.push(price);
+        }
+
+        Ok(DrugPriceList {
+            id: new_price_list.id,
+            valid_from: new_price_list.valid_from,
+            valid_to: new_price_list.valid_to,
+            prices,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    async fn create_drug_interaction(
+        &self,
+        new_drug_interaction: NewDrugInteraction,
+    ) -> Result<DrugInteraction, CreateDrugInteractionRepositoryError> {
+        let is_duplicated = self
+            .drug_interactions
+            .read()
+            .unwrap()
+            .iter()
+            .any(|interaction| {
+                interaction.substance_a == new_drug_interaction.substance_a
+                    && interaction.substance_b == new_drug_interaction.substance_b
+            });
+        if is_duplicated {
+            return Err(CreateDrugInteractionRepositoryError::DuplicatedInteraction);
+        }
+
+        let mut active_substances = self.active_substances.write().unwrap();
+        for substance in [
+            &new_drug_interaction.substance_a,
+            &new_drug_interaction.substance_b,
+        ] {
+            if !active_substances
+                .iter()
+                .any(|active_substance| &active_substance.name == substance)
+            {
+                active_substances.push(ActiveSubstance {
+                    id: Uuid::new_v4(),
+                    name: substance.clone(),
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                });
+            }
+        }
+
+        let drug_interaction = DrugInteraction {
+            id: new_drug_interaction.id,
+            substance_a: new_drug_interaction.substance_a,
+            substance_b: new_drug_interaction.substance_b,
+            severity: new_drug_interaction.severity,
+            description: new_drug_interaction.description,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.drug_interactions
+            .write()
+            .unwrap()
+            .push(drug_interaction.clone());
+
+        Ok(drug_interaction)
+    }
+
+    async fn get_drug_interactions(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugInteraction>, GetDrugInteractionsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugInteractionsRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        Ok(self
+            .drug_interactions
+            .read()
+            .unwrap()
+            .iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_active_substances(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ActiveSubstance>, GetActiveSubstancesRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetActiveSubstancesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let mut active_substances = self.active_substances.read().unwrap().clone();
+        active_substances.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(active_substances
+            .into_iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn create_atc_group(
+        &self,
+        new_atc_group: NewAtcGroup,
+    ) -> Result<AtcGroup, CreateAtcGroupRepositoryError> {
+        let mut atc_groups = self.atc_groups.write().unwrap();
+        if atc_groups
+            .iter()
+            .any(|atc_group| atc_group.code == new_atc_group.code)
+        {
+            return Err(CreateAtcGroupRepositoryError::DuplicatedCode);
+        }
+        if let Some(parent_code) = &new_atc_group.parent_code {
+            if !atc_groups
+                .iter()
+                .any(|atc_group| &atc_group.code == parent_code)
+            {
+                return Err(CreateAtcGroupRepositoryError::ParentNotFound(
+                    parent_code.clone(),
+                ));
+            }
+        }
+
+        let atc_group = AtcGroup {
+            code: new_atc_group.code,
+            name: new_atc_group.name,
+            parent_code: new_atc_group.parent_code,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        atc_groups.push(atc_group.clone());
+
+        Ok(atc_group)
+    }
+
+    async fn get_atc_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<AtcGroup>, GetAtcGroupsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size)
+            .map_err(|err| GetAtcGroupsRepositoryError::InvalidPaginationParams(err.to_string()))?;
+
+        let mut atc_groups = self.atc_groups.read().unwrap().clone();
+        atc_groups.sort_by(|a, b| a.code.cmp(&b.code));
+
+        Ok(atc_groups
+            .into_iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn create_drug_category(
+        &self,
+        new_drug_category: NewDrugCategory,
+    ) -> Result<DrugCategory, CreateDrugCategoryRepositoryError> {
+        let mut drug_categories = self.drug_categories.write().unwrap();
+        if let Some(parent_id) = new_drug_category.parent_id {
+            if !drug_categories
+                .iter()
+                .any(|category| category.id == parent_id)
+            {
+                return Err(CreateDrugCategoryRepositoryError::ParentNotFound(parent_id));
+            }
+        }
+
+        let drug_category = DrugCategory {
+            id: new_drug_category.id,
+            name: new_drug_category.name,
+            parent_id: new_drug_category.parent_id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        drug_categories.push(drug_category.clone());
+
+        Ok(drug_category)
+    }
+
+    async fn get_drug_categories(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugCategory>, GetDrugCategoriesRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugCategoriesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        Ok(self
+            .drug_categories
+            .read()
+            .unwrap()
+            .iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_drug_equivalence_group(
+        &self,
+        new_drug_equivalence_group: NewDrugEquivalenceGroup,
+    ) -> Result<DrugEquivalenceGroup, CreateDrugEquivalenceGroupRepositoryError> {
+        let mut drugs = self.drugs.write().unwrap();
+        for &drug_id in &new_drug_equivalence_group.drug_ids {
+            let drug = drugs.iter().find(|drug| drug.id == drug_id).ok_or(
+                CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(drug_id),
+            )?;
+            if drug.equivalence_group_id.is_some() {
+                return Err(CreateDrugEquivalenceGroupRepositoryError::DrugAlreadyInGroup(drug_id));
+            }
+        }
+        for drug in drugs
+            .iter_mut()
+            .filter(|drug| new_drug_equivalence_group.drug_ids.contains(&drug.id))
+        {
+            drug.equivalence_group_id = Some(new_drug_equivalence_group.id);
+        }
+
+        let drug_equivalence_group = DrugEquivalenceGroup {
+            id: new_drug_equivalence_group.id,
+            name: new_drug_equivalence_group.name,
+            drug_ids: new_drug_equivalence_group.drug_ids,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.drug_equivalence_groups
+            .write()
+            .unwrap()
+            .push(drug_equivalence_group.clone());
+
+        Ok(drug_equivalence_group)
+    }
+
+    async fn get_drug_equivalence_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugEquivalenceGroup>, GetDrugEquivalenceGroupsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugEquivalenceGroupsRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        Ok(self
+            .drug_equivalence_groups
+            .read()
+            .unwrap()
+            .iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .cloned()
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -115,9 +968,15 @@ mod tests {
     use uuid::Uuid;
 
     use super::{
-        DrugsRepository, DrugsRepositoryFake, GetDrugByIdRepositoryError, GetDrugsRepositoryError,
+        CreateAtcGroupRepositoryError, CreateDrugCategoryRepositoryError,
+        CreateDrugEquivalenceGroupRepositoryError, CreateDrugInteractionRepositoryError,
+        CreateDrugRepositoryError, CreateDrugVariantRepositoryError, DrugsRepository,
+        DrugsRepositoryFake, GetDrugByIdRepositoryError, GetDrugsRepositoryError,
+    };
+    use crate::domain::drugs::entities::{
+        DrugActiveSubstance, DrugContentType, DrugInteractionSeverity, NewAtcGroup, NewDrug,
+        NewDrugCategory, NewDrugEquivalenceGroup, NewDrugInteraction, StrengthUnit,
     };
-    use crate::domain::drugs::entities::{DrugContentType, NewDrug};
 
     fn setup_repository() -> DrugsRepositoryFake {
         DrugsRepositoryFake::new()
@@ -146,6 +1005,60 @@ mod tests {
         assert_eq!(drug, drug_from_repo);
     }
 
+    #[tokio::test]
+    async fn creates_drug_variant_and_reads_it_with_drug() {
+        let repository = setup_repository();
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let new_variant = created_drug.create_variant(Some(20), None, None).unwrap();
+
+        let created_variant = repository
+            .create_drug_variant(new_variant.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_variant, new_variant);
+
+        let drug_from_repo = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug_from_repo.variants.len(), 2);
+        assert_eq!(drug_from_repo.variants[0], drug.variants[0]);
+        assert_eq!(drug_from_repo.variants[1], new_variant);
+    }
+
+    #[tokio::test]
+    async fn doesnt_create_variant_of_nonexistent_drug() {
+        let repository = setup_repository();
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let mut new_variant = drug.variants[0].clone();
+        new_variant.pills_count = Some(20);
+
+        let result = repository.create_drug_variant(new_variant).await;
+
+        assert_eq!(
+            result,
+            Err(CreateDrugVariantRepositoryError::DrugNotFound(drug.id))
+        );
+    }
+
     #[tokio::test]
     async fn returns_error_if_drug_with_given_id_doesnt_exist() {
         let repository = setup_repository();
@@ -205,7 +1118,10 @@ mod tests {
         repository.create_drug(new_drug_2.clone()).await.unwrap();
         repository.create_drug(new_drug_3.clone()).await.unwrap();
 
-        let drugs = repository.get_drugs(None, Some(10)).await.unwrap();
+        let drugs = repository
+            .get_drugs(None, Some(10), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 4);
         assert_eq!(drugs[0], new_drug_0);
@@ -213,18 +1129,27 @@ mod tests {
         assert_eq!(drugs[2], new_drug_2);
         assert_eq!(drugs[3], new_drug_3);
 
-        let drugs = repository.get_drugs(None, Some(2)).await.unwrap();
+        let drugs = repository
+            .get_drugs(None, Some(2), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 2);
         assert_eq!(drugs[0], new_drug_0);
         assert_eq!(drugs[1], new_drug_1);
 
-        let drugs = repository.get_drugs(Some(1), Some(3)).await.unwrap();
+        let drugs = repository
+            .get_drugs(Some(1), Some(3), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 1);
         assert_eq!(drugs[0], new_drug_3);
 
-        let drugs = repository.get_drugs(Some(2), Some(3)).await.unwrap();
+        let drugs = repository
+            .get_drugs(Some(2), Some(3), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 0);
     }
@@ -233,14 +1158,443 @@ mod tests {
     async fn get_drugs_returns_error_if_pagination_params_are_incorrect() {
         let repository = setup_repository();
 
-        assert!(match repository.get_drugs(Some(-1), Some(10)).await {
+        assert!(match repository
+            .get_drugs(Some(-1), Some(10), None, None, None)
+            .await
+        {
             Err(GetDrugsRepositoryError::InvalidPaginationParams(_)) => true,
             _ => false,
         });
 
-        assert!(match repository.get_drugs(Some(0), Some(0)).await {
+        assert!(match repository
+            .get_drugs(Some(0), Some(0), None, None, None)
+            .await
+        {
             Err(GetDrugsRepositoryError::InvalidPaginationParams(_)) => true,
             _ => false,
         });
     }
+
+    #[tokio::test]
+    async fn creates_and_reads_drug_interactions() {
+        let repository = setup_repository();
+
+        let new_interaction_0 = NewDrugInteraction::new(
+            "warfarin".into(),
+            "ibuprofen".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        )
+        .unwrap();
+        let new_interaction_1 = NewDrugInteraction::new(
+            "sildenafil".into(),
+            "nitroglycerin".into(),
+            DrugInteractionSeverity::Contraindicated,
+            "Severe hypotension".into(),
+        )
+        .unwrap();
+
+        let created_interaction = repository
+            .create_drug_interaction(new_interaction_0.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_interaction, new_interaction_0);
+
+        repository
+            .create_drug_interaction(new_interaction_1.clone())
+            .await
+            .unwrap();
+
+        let interactions = repository.get_drug_interactions(None, None).await.unwrap();
+
+        assert_eq!(interactions.len(), 2);
+        assert_eq!(interactions[0], new_interaction_0);
+        assert_eq!(interactions[1], new_interaction_1);
+
+        let interactions = repository
+            .get_drug_interactions(Some(1), Some(1))
+            .await
+            .unwrap();
+
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0], new_interaction_1);
+    }
+
+    #[tokio::test]
+    async fn doesnt_create_duplicated_drug_interaction() {
+        let repository = setup_repository();
+
+        repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "warfarin".into(),
+                    "ibuprofen".into(),
+                    DrugInteractionSeverity::Major,
+                    "Increased risk of bleeding".into(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let result = repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "ibuprofen".into(),
+                    "warfarin".into(),
+                    DrugInteractionSeverity::Moderate,
+                    "Increased risk of bleeding".into(),
+                )
+                .unwrap(),
+            )
+            .await;
+
+        assert_eq!(
+            result,
+            Err(CreateDrugInteractionRepositoryError::DuplicatedInteraction)
+        );
+    }
+
+    #[tokio::test]
+    async fn adds_drug_interaction_substances_to_catalogue() {
+        let repository = setup_repository();
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![DrugActiveSubstance {
+            name: "paracetamol".into(),
+            amount: Some(500.0),
+            unit: Some(StrengthUnit::Mg),
+        }])
+        .unwrap();
+        repository.create_drug(apap).await.unwrap();
+
+        repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "warfarin".into(),
+                    "paracetamol".into(),
+                    DrugInteractionSeverity::Moderate,
+                    "Increased anticoagulant effect".into(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let active_substances = repository.get_active_substances(None, None).await.unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+        assert_eq!(active_substances[0].name, "paracetamol");
+        assert_eq!(active_substances[1].name, "warfarin");
+    }
+
+    #[tokio::test]
+    async fn filters_drugs_by_active_substance_and_reads_catalogue() {
+        let repository = setup_repository();
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![DrugActiveSubstance {
+            name: "paracetamol".into(),
+            amount: Some(500.0),
+            unit: Some(StrengthUnit::Mg),
+        }])
+        .unwrap();
+        let gripex = NewDrug::new(
+            "Gripex".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(355),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            DrugActiveSubstance {
+                name: "paracetamol".into(),
+                amount: Some(325.0),
+                unit: Some(StrengthUnit::Mg),
+            },
+            DrugActiveSubstance {
+                name: "pseudoephedrine".into(),
+                amount: Some(30.0),
+                unit: Some(StrengthUnit::Mg),
+            },
+        ])
+        .unwrap();
+        repository.create_drug(apap.clone()).await.unwrap();
+        repository.create_drug(gripex.clone()).await.unwrap();
+
+        let drugs = repository
+            .get_drugs(None, None, Some("paracetamol".into()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 2);
+
+        let drugs = repository
+            .get_drugs(None, None, Some("pseudoephedrine".into()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], gripex);
+
+        let active_substances = repository.get_active_substances(None, None).await.unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+        assert_eq!(active_substances[0].name, "paracetamol");
+        assert_eq!(active_substances[1].name, "pseudoephedrine");
+    }
+
+    #[tokio::test]
+    async fn creates_atc_groups_and_reads_them_in_drug_atc_path() {
+        let repository = setup_repository();
+
+        let atc_groups: Vec<NewAtcGroup> = [
+            ("N", "Nervous system"),
+            ("N02", "Analgesics"),
+            ("N02B", "Other analgesics and antipyretics"),
+            ("N02BE", "Anilides"),
+            ("N02BE01", "Paracetamol"),
+        ]
+        .into_iter()
+        .map(|(code, name)| NewAtcGroup::new(code.into(), name.into()).unwrap())
+        .collect();
+        for atc_group in &atc_groups {
+            repository
+                .create_atc_group(atc_group.clone())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            repository.create_atc_group(atc_groups[1].clone()).await,
+            Err(CreateAtcGroupRepositoryError::DuplicatedCode)
+        );
+        assert_eq!(
+            repository
+                .create_atc_group(
+                    NewAtcGroup::new("M01".into(), "Anti-inflammatory".into()).unwrap()
+                )
+                .await,
+            Err(CreateAtcGroupRepositoryError::ParentNotFound("M".into()))
+        );
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N02BE01".into()), None)
+        .unwrap();
+        let created_drug = repository.create_drug(drug).await.unwrap();
+
+        assert_eq!(created_drug.atc_path, atc_groups);
+
+        let drug_with_unknown_atc_code = NewDrug::new(
+            "Ibuprom".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(200),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("M01AE01".into()), None)
+        .unwrap();
+
+        assert_eq!(
+            repository.create_drug(drug_with_unknown_atc_code).await,
+            Err(CreateDrugRepositoryError::AtcGroupNotFound(
+                "M01AE01".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_drugs_by_atc_code_prefix_and_category_tree() {
+        let repository = setup_repository();
+
+        for (code, name) in [
+            ("N", "Nervous system"),
+            ("N02", "Analgesics"),
+            ("N02B", "Other analgesics and antipyretics"),
+            ("N02BE", "Anilides"),
+            ("N02BE01", "Paracetamol"),
+            ("N05", "Psycholeptics"),
+            ("N05C", "Hypnotics and sedatives"),
+            ("N05CF", "Benzodiazepine related drugs"),
+            ("N05CF02", "Zolpidem"),
+        ] {
+            repository
+                .create_atc_group(NewAtcGroup::new(code.into(), name.into()).unwrap())
+                .await
+                .unwrap();
+        }
+        let painkillers = NewDrugCategory::new("Painkillers".into(), None).unwrap();
+        let headache = NewDrugCategory::new("Headache".into(), Some(painkillers.id)).unwrap();
+        let sleep_aids = NewDrugCategory::new("Sleep aids".into(), None).unwrap();
+        for category in [&painkillers, &headache, &sleep_aids] {
+            repository
+                .create_drug_category(category.clone())
+                .await
+                .unwrap();
+        }
+
+        let nonexistent_category_id = Uuid::new_v4();
+        let migraine =
+            NewDrugCategory::new("Migraine".into(), Some(nonexistent_category_id)).unwrap();
+
+        assert_eq!(
+            repository.create_drug_category(migraine).await,
+            Err(CreateDrugCategoryRepositoryError::ParentNotFound(
+                nonexistent_category_id
+            ))
+        );
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N02BE01".into()), Some(headache.id))
+        .unwrap();
+        let stilnox = NewDrug::new(
+            "Stilnox".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(10),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N05CF02".into()), Some(sleep_aids.id))
+        .unwrap();
+        repository.create_drug(apap.clone()).await.unwrap();
+        repository.create_drug(stilnox.clone()).await.unwrap();
+
+        let drugs = repository
+            .get_drugs(None, None, None, Some("N".into()), None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 2);
+
+        let drugs = repository
+            .get_drugs(None, None, None, Some("N05C".into()), None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], stilnox);
+
+        let drugs = repository
+            .get_drugs(None, None, None, None, Some(painkillers.id))
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], apap);
+
+        let drug_categories = repository.get_drug_categories(None, None).await.unwrap();
+
+        assert_eq!(drug_categories.len(), 3);
+        assert_eq!(drug_categories[1], headache);
+    }
+
+    #[tokio::test]
+    async fn creates_drug_equivalence_group_and_assigns_it_to_drugs() {
+        let repository = setup_repository();
+
+        let mut drugs = vec![];
+        for name in ["Apap", "Panadol", "Codipar"] {
+            let drug = repository
+                .create_drug(
+                    NewDrug::new(
+                        name.into(),
+                        DrugContentType::SolidPills,
+                        Some(10),
+                        Some(500),
+                        None,
+                        None,
+                    )
+                    .unwrap()
+                    .with_active_substances(vec![DrugActiveSubstance {
+                        name: "paracetamol".into(),
+                        amount: Some(500.0),
+                        unit: Some(StrengthUnit::Mg),
+                    }])
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+            drugs.push(drug);
+        }
+
+        let group = NewDrugEquivalenceGroup::new("Paracetamol 500 mg tablets".into(), &drugs[0..2])
+            .unwrap();
+        let created_group = repository
+            .create_drug_equivalence_group(group.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_group, group);
+
+        let drug_from_repo = repository.get_drug_by_id(drugs[1].id).await.unwrap();
+
+        assert_eq!(drug_from_repo.equivalence_group_id, Some(group.id));
+
+        let mut other_group =
+            NewDrugEquivalenceGroup::new("Paracetamol 500 mg".into(), &drugs[1..3]).unwrap();
+
+        assert_eq!(
+            repository
+                .create_drug_equivalence_group(other_group.clone())
+                .await,
+            Err(CreateDrugEquivalenceGroupRepositoryError::DrugAlreadyInGroup(drugs[1].id))
+        );
+
+        let nonexistent_drug_id = Uuid::new_v4();
+        other_group.drug_ids = vec![drugs[2].id, nonexistent_drug_id];
+
+        assert_eq!(
+            repository.create_drug_equivalence_group(other_group).await,
+            Err(CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(
+                nonexistent_drug_id
+            ))
+        );
+
+        let groups = repository
+            .get_drug_equivalence_groups(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0], group);
+    }
 }
@@ -1,12 +1,36 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use super::{
-    entities::{Drug, DrugContentType, NewDrug},
+    entities::{
+        ActiveSubstance, AtcGroup, ControlledSubstanceList, Drug, DrugActiveSubstance,
+        DrugCategory, DrugContentType, DrugEquivalenceGroup, DrugImportFormat, DrugImportReport,
+        DrugImportRow, DrugImportRowResult, DrugImportRowStatus, DrugInteraction,
+        DrugInteractionSeverity, DrugPriceList, DrugPriceListItem, DrugReimbursement, DrugVariant,
+        NewAtcGroup, NewDrug, NewDrugCategory, NewDrugEquivalenceGroup, NewDrugInteraction,
+        NewDrugPriceList, ReimbursementLevel,
+    },
     repository::{
-        CreateDrugRepositoryError, DrugsRepository, GetDrugByIdRepositoryError,
-        GetDrugsRepositoryError,
+        CreateAtcGroupRepositoryError, CreateDrugCategoryRepositoryError,
+        CreateDrugEquivalenceGroupRepositoryError, CreateDrugInteractionRepositoryError,
+        CreateDrugPriceListRepositoryError, CreateDrugReimbursementRepositoryError,
+        CreateDrugRepositoryError, CreateDrugVariantRepositoryError, DrugsRepository,
+        GetActiveSubstancesRepositoryError, GetAtcGroupsRepositoryError,
+        GetDrugByExternalIdRepositoryError, GetDrugByGtinRepositoryError,
+        GetDrugByIdRepositoryError, GetDrugCategoriesRepositoryError,
+        GetDrugEquivalenceGroupsRepositoryError, GetDrugInteractionsRepositoryError,
+        GetDrugsRepositoryError, UpdateDrugRepositoryError,
+    },
+    use_cases::{
+        create_drug_price_list::CreateDrugPriceListDomainError,
+        create_drug_reimbursement::CreateDrugReimbursementDomainError,
+        create_drug_variant::CreateDrugVariantDomainError,
+        import_drugs::{parse_drug_import_file, ImportDrugRowDomainError, ImportDrugsDomainError},
     },
 };
+use crate::domain::utils::validators::validate_gtin::validate_gtin;
 
 pub struct DrugsService {
     repository: Box<dyn DrugsRepository>,
@@ -18,21 +42,106 @@ pub enum CreateDrugError {
     RepositoryError(CreateDrugRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum ImportDrugsError {
+    DomainError(ImportDrugsDomainError),
+}
+
 #[derive(Debug)]
 pub enum GetDrugByIdError {
     RepositoryError(GetDrugByIdRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum GetDrugByGtinError {
+    DomainError(String),
+    RepositoryError(GetDrugByGtinRepositoryError),
+}
+
 #[derive(Debug)]
 pub enum GetDrugsWithPaginationError {
     RepositoryError(GetDrugsRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum UpdateDrugError {
+    DomainError(String),
+    RepositoryError(UpdateDrugRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugVariantError {
+    DomainError(CreateDrugVariantDomainError),
+    RepositoryError(CreateDrugVariantRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugReimbursementError {
+    DomainError(CreateDrugReimbursementDomainError),
+    RepositoryError(CreateDrugReimbursementRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugPriceListError {
+    DomainError(CreateDrugPriceListDomainError),
+    RepositoryError(CreateDrugPriceListRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugInteractionError {
+    DomainError(String),
+    RepositoryError(CreateDrugInteractionRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetDrugInteractionsWithPaginationError {
+    RepositoryError(GetDrugInteractionsRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetActiveSubstancesWithPaginationError {
+    RepositoryError(GetActiveSubstancesRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateAtcGroupError {
+    DomainError(String),
+    RepositoryError(CreateAtcGroupRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetAtcGroupsWithPaginationError {
+    RepositoryError(GetAtcGroupsRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugCategoryError {
+    DomainError(String),
+    RepositoryError(CreateDrugCategoryRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetDrugCategoriesWithPaginationError {
+    RepositoryError(GetDrugCategoriesRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreateDrugEquivalenceGroupError {
+    DomainError(String),
+    RepositoryError(CreateDrugEquivalenceGroupRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetDrugEquivalenceGroupsWithPaginationError {
+    RepositoryError(GetDrugEquivalenceGroupsRepositoryError),
+}
+
 impl DrugsService {
     pub fn new(repository: Box<dyn DrugsRepository>) -> Self {
         Self { repository }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_drug(
         &self,
         name: String,
@@ -41,6 +150,11 @@ impl DrugsService {
         mg_per_pill: Option<i32>,
         ml_per_pill: Option<i32>,
         volume_ml: Option<i32>,
+        gtin: Option<String>,
+        active_substances: Vec<DrugActiveSubstance>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
+        controlled_substance_list: Option<ControlledSubstanceList>,
     ) -> Result<Drug, CreateDrugError> {
         let new_drug = NewDrug::new(
             name,
@@ -50,6 +164,10 @@ impl DrugsService {
             ml_per_pill,
             volume_ml,
         )
+        .and_then(|new_drug| new_drug.with_gtin(gtin))
+        .and_then(|new_drug| new_drug.with_active_substances(active_substances))
+        .and_then(|new_drug| new_drug.with_classification(atc_code, category_id))
+        .map(|new_drug| new_drug.with_controlled_substance_list(controlled_substance_list))
         .map_err(|err| CreateDrugError::DomainError(err.to_string()))?;
 
         let created_drug = self
@@ -61,6 +179,79 @@ impl DrugsService {
         Ok(created_drug)
     }
 
+    // Rows are imported one by one, a rejected row (also because of a database error) is reported
+    // without stopping the import of the others
+    pub async fn import_drugs(
+        &self,
+        content: String,
+        format: DrugImportFormat,
+    ) -> Result<DrugImportReport, ImportDrugsError> {
+        let rows =
+            parse_drug_import_file(&content, format).map_err(ImportDrugsError::DomainError)?;
+
+        let mut external_ids: HashSet<String> = HashSet::new();
+        let mut results: Vec<DrugImportRowResult> = vec![];
+        for (index, row) in rows.into_iter().enumerate() {
+            let external_id = row.get_external_id().map(String::from);
+            let result = match &external_id {
+                Some(external_id) if !external_ids.insert(external_id.clone()) => Err(
+                    ImportDrugRowDomainError::DuplicatedExternalId(external_id.clone()).to_string(),
+                ),
+                _ => self.import_drug(row).await,
+            };
+            let (status, drug_id, reason) = match result {
+                Ok((status, drug_id)) => (status, Some(drug_id), None),
+                Err(reason) => (DrugImportRowStatus::Rejected, None, Some(reason)),
+            };
+
+            results.push(DrugImportRowResult {
+                row_number: index + 1,
+                external_id,
+                status,
+                drug_id,
+                reason,
+            });
+        }
+
+        Ok(DrugImportReport::new(results))
+    }
+
+    async fn import_drug(&self, row: DrugImportRow) -> Result<(DrugImportRowStatus, Uuid), String> {
+        let new_drug = row.to_new_drug().map_err(|err| err.to_string())?;
+        let external_id = new_drug.external_id.clone().unwrap_or_default();
+
+        match self.repository.get_drug_by_external_id(&external_id).await {
+            Ok(drug) => {
+                let (drug, new_variant) = drug
+                    .update_from_import(new_drug)
+                    .map_err(|err| err.to_string())?;
+                let updated_drug = self
+                    .repository
+                    .update_drug(drug)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                if let Some(new_variant) = new_variant {
+                    self.repository
+                        .create_drug_variant(new_variant)
+                        .await
+                        .map_err(|err| err.to_string())?;
+                }
+
+                Ok((DrugImportRowStatus::Updated, updated_drug.id))
+            }
+            Err(GetDrugByExternalIdRepositoryError::NotFound(_)) => {
+                let created_drug = self
+                    .repository
+                    .create_drug(new_drug)
+                    .await
+                    .map_err(|err| err.to_string())?;
+
+                Ok((DrugImportRowStatus::Created, created_drug.id))
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
     pub async fn get_drug_by_id(&self, drug_id: Uuid) -> Result<Drug, GetDrugByIdError> {
         let doctor = self
             .repository
@@ -71,27 +262,372 @@ impl DrugsService {
         Ok(doctor)
     }
 
+    pub async fn get_drug_by_gtin(&self, gtin: String) -> Result<Drug, GetDrugByGtinError> {
+        let gtin = gtin.trim();
+        validate_gtin(gtin).map_err(|err| GetDrugByGtinError::DomainError(err.to_string()))?;
+
+        let drug = self
+            .repository
+            .get_drug_by_gtin(gtin)
+            .await
+            .map_err(GetDrugByGtinError::RepositoryError)?;
+
+        Ok(drug)
+    }
+
     pub async fn get_drugs_with_pagination(
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        substance: Option<String>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
     ) -> Result<Vec<Drug>, GetDrugsWithPaginationError> {
+        let substance = substance.map(|substance| substance.trim().to_lowercase());
+        let atc_code = atc_code.map(|atc_code| atc_code.trim().to_uppercase());
         let result = self
             .repository
-            .get_drugs(page, page_size)
+            .get_drugs(page, page_size, substance, atc_code, category_id)
             .await
             .map_err(|err| GetDrugsWithPaginationError::RepositoryError(err))?;
 
         Ok(result)
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_drug(
+        &self,
+        drug_id: Uuid,
+        name: Option<String>,
+        mg_per_pill: Option<i32>,
+        ml_per_pill: Option<i32>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
+        controlled_substance_list: Option<ControlledSubstanceList>,
+    ) -> Result<Drug, UpdateDrugError> {
+        let drug = self
+            .repository
+            .get_drug_by_id(drug_id)
+            .await
+            .map_err(|err| match err {
+                GetDrugByIdRepositoryError::NotFound(id) => {
+                    UpdateDrugError::RepositoryError(UpdateDrugRepositoryError::NotFound(id))
+                }
+                _ => UpdateDrugError::RepositoryError(UpdateDrugRepositoryError::DatabaseError(
+                    err.to_string(),
+                )),
+            })?;
+
+        let drug = drug
+            .update(
+                name,
+                mg_per_pill,
+                ml_per_pill,
+                atc_code,
+                category_id,
+                controlled_substance_list,
+            )
+            .map_err(|err| UpdateDrugError::DomainError(err.to_string()))?;
+
+        let updated_drug = self
+            .repository
+            .update_drug(drug)
+            .await
+            .map_err(UpdateDrugError::RepositoryError)?;
+
+        Ok(updated_drug)
+    }
+
+    pub async fn create_drug_variant(
+        &self,
+        drug_id: Uuid,
+        pills_count: Option<i32>,
+        volume_ml: Option<i32>,
+        gtin: Option<String>,
+    ) -> Result<DrugVariant, CreateDrugVariantError> {
+        let drug = self
+            .repository
+            .get_drug_by_id(drug_id)
+            .await
+            .map_err(|err| match err {
+                GetDrugByIdRepositoryError::NotFound(id) => {
+                    CreateDrugVariantError::RepositoryError(
+                        CreateDrugVariantRepositoryError::DrugNotFound(id),
+                    )
+                }
+                _ => CreateDrugVariantError::RepositoryError(
+                    CreateDrugVariantRepositoryError::DatabaseError(err.to_string()),
+                ),
+            })?;
+
+        let new_variant = drug
+            .create_variant(pills_count, volume_ml, gtin)
+            .map_err(CreateDrugVariantError::DomainError)?;
+
+        let created_variant = self
+            .repository
+            .create_drug_variant(new_variant)
+            .await
+            .map_err(CreateDrugVariantError::RepositoryError)?;
+
+        Ok(created_variant)
+    }
+
+    pub async fn create_drug_reimbursement(
+        &self,
+        drug_id: Uuid,
+        variant_id: Uuid,
+        levels: Vec<ReimbursementLevel>,
+        reimbursement_limit: i64,
+    ) -> Result<DrugReimbursement, CreateDrugReimbursementError> {
+        let drug = self
+            .repository
+            .get_drug_by_id(drug_id)
+            .await
+            .map_err(|err| match err {
+                GetDrugByIdRepositoryError::NotFound(id) => {
+                    CreateDrugReimbursementError::RepositoryError(
+                        CreateDrugReimbursementRepositoryError::DrugNotFound(id),
+                    )
+                }
+                _ => CreateDrugReimbursementError::RepositoryError(
+                    CreateDrugReimbursementRepositoryError::DatabaseError(err.to_string()),
+                ),
+            })?;
+
+        let new_reimbursement = drug
+            .create_reimbursement(variant_id, levels, reimbursement_limit)
+            .map_err(CreateDrugReimbursementError::DomainError)?;
+
+        let created_reimbursement = self
+            .repository
+            .create_drug_reimbursement(new_reimbursement)
+            .await
+            .map_err(CreateDrugReimbursementError::RepositoryError)?;
+
+        Ok(created_reimbursement)
+    }
+
+    pub async fn create_drug_price_list(
+        &self,
+        valid_from: Option<DateTime<Utc>>,
+        valid_to: Option<DateTime<Utc>>,
+        items: Vec<DrugPriceListItem>,
+    ) -> Result<DrugPriceList, CreateDrugPriceListError> {
+        let mut drugs: Vec<Drug> = vec![];
+        for item in &items {
+            if drugs.iter().any(|drug| drug.id == item.drug_id) {
+                continue;
+            }
+            match self.repository.get_drug_by_id(item.drug_id).await {
+                Ok(drug) => drugs.push(drug),
+                Err(GetDrugByIdRepositoryError::NotFound(_)) => {}
+                Err(err) => Err(CreateDrugPriceListError::RepositoryError(
+                    CreateDrugPriceListRepositoryError::DatabaseError(err.to_string()),
+                ))?,
+            }
+        }
+
+        let new_price_list = NewDrugPriceList::new(valid_from, valid_to, items, &drugs)
+            .map_err(CreateDrugPriceListError::DomainError)?;
+
+        let created_price_list = self
+            .repository
+            .create_drug_price_list(new_price_list)
+            .await
+            .map_err(CreateDrugPriceListError::RepositoryError)?;
+
+        Ok(created_price_list)
+    }
+
+    pub async fn create_drug_interaction(
+        &self,
+        substance_a: String,
+        substance_b: String,
+        severity: DrugInteractionSeverity,
+        description: String,
+    ) -> Result<DrugInteraction, CreateDrugInteractionError> {
+        let new_drug_interaction =
+            NewDrugInteraction::new(substance_a, substance_b, severity, description)
+                .map_err(|err| CreateDrugInteractionError::DomainError(err.to_string()))?;
+
+        let created_drug_interaction = self
+            .repository
+            .create_drug_interaction(new_drug_interaction)
+            .await
+            .map_err(CreateDrugInteractionError::RepositoryError)?;
+
+        Ok(created_drug_interaction)
+    }
+
+    pub async fn get_drug_interactions_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugInteraction>, GetDrugInteractionsWithPaginationError> {
+        let result = self
+            .repository
+            .get_drug_interactions(page, page_size)
+            .await
+            .map_err(GetDrugInteractionsWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
+
+    pub async fn get_active_substances_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ActiveSubstance>, GetActiveSubstancesWithPaginationError> {
+        let result = self
+            .repository
+            .get_active_substances(page, page_size)
+            .await
+            .map_err(GetActiveSubstancesWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
+
+    pub async fn create_atc_group(
+        &self,
+        code: String,
+        name: String,
+    ) -> Result<AtcGroup, CreateAtcGroupError> {
+        let new_atc_group = NewAtcGroup::new(code, name)
+            .map_err(|err| CreateAtcGroupError::DomainError(err.to_string()))?;
+
+        let created_atc_group = self
+            .repository
+            .create_atc_group(new_atc_group)
+            .await
+            .map_err(CreateAtcGroupError::RepositoryError)?;
+
+        Ok(created_atc_group)
+    }
+
+    pub async fn get_atc_groups_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<AtcGroup>, GetAtcGroupsWithPaginationError> {
+        let result = self
+            .repository
+            .get_atc_groups(page, page_size)
+            .await
+            .map_err(GetAtcGroupsWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
+
+    pub async fn create_drug_category(
+        &self,
+        name: String,
+        parent_id: Option<Uuid>,
+    ) -> Result<DrugCategory, CreateDrugCategoryError> {
+        let new_drug_category = NewDrugCategory::new(name, parent_id)
+            .map_err(|err| CreateDrugCategoryError::DomainError(err.to_string()))?;
+
+        let created_drug_category = self
+            .repository
+            .create_drug_category(new_drug_category)
+            .await
+            .map_err(CreateDrugCategoryError::RepositoryError)?;
+
+        Ok(created_drug_category)
+    }
+
+    pub async fn get_drug_categories_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugCategory>, GetDrugCategoriesWithPaginationError> {
+        let result = self
+            .repository
+            .get_drug_categories(page, page_size)
+            .await
+            .map_err(GetDrugCategoriesWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
+
+    pub async fn create_drug_equivalence_group(
+        &self,
+        name: String,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<DrugEquivalenceGroup, CreateDrugEquivalenceGroupError> {
+        let mut drugs = vec![];
+        for drug_id in drug_ids {
+            let drug = self
+                .repository
+                .get_drug_by_id(drug_id)
+                .await
+                .map_err(|err| match err {
+                    GetDrugByIdRepositoryError::NotFound(id) => {
+                        CreateDrugEquivalenceGroupError::RepositoryError(
+                            CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(id),
+                        )
+                    }
+                    _ => CreateDrugEquivalenceGroupError::RepositoryError(
+                        CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string()),
+                    ),
+                })?;
+            drugs.push(drug);
+        }
+
+        let new_drug_equivalence_group = NewDrugEquivalenceGroup::new(name, &drugs)
+            .map_err(|err| CreateDrugEquivalenceGroupError::DomainError(err.to_string()))?;
+
+        let created_drug_equivalence_group = self
+            .repository
+            .create_drug_equivalence_group(new_drug_equivalence_group)
+            .await
+            .map_err(CreateDrugEquivalenceGroupError::RepositoryError)?;
+
+        Ok(created_drug_equivalence_group)
+    }
+
+    pub async fn get_drug_equivalence_groups_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugEquivalenceGroup>, GetDrugEquivalenceGroupsWithPaginationError> {
+        let result = self
+            .repository
+            .get_drug_equivalence_groups(page, page_size)
+            .await
+            .map_err(GetDrugEquivalenceGroupsWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
     use uuid::Uuid;
 
-    use super::DrugsService;
-    use crate::domain::drugs::{entities::DrugContentType, repository::DrugsRepositoryFake};
+    use super::{
+        CreateDrugEquivalenceGroupError, CreateDrugPriceListError, CreateDrugReimbursementError,
+        CreateDrugVariantError, DrugsService, GetDrugByGtinError, ImportDrugsError,
+        UpdateDrugError,
+    };
+    use crate::domain::drugs::{
+        entities::{
+            DrugActiveSubstance, DrugContentType, DrugImportFormat, DrugImportRowStatus,
+            DrugPriceListItem, ReimbursementLevel, StrengthUnit,
+        },
+        repository::{
+            CreateDrugEquivalenceGroupRepositoryError, CreateDrugReimbursementRepositoryError,
+            CreateDrugVariantRepositoryError, DrugsRepositoryFake, GetDrugByGtinRepositoryError,
+            UpdateDrugRepositoryError,
+        },
+        use_cases::{
+            create_drug_price_list::CreateDrugPriceListDomainError,
+            create_drug_reimbursement::CreateDrugReimbursementDomainError,
+            create_drug_variant::CreateDrugVariantDomainError,
+            import_drugs::ImportDrugsDomainError,
+        },
+    };
 
     fn setup_service() -> DrugsService {
         DrugsService::new(Box::new(DrugsRepositoryFake::new()))
@@ -109,16 +645,21 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
 
         assert_eq!(created_drug.name, "Gripex");
         assert_eq!(created_drug.content_type, DrugContentType::SolidPills);
-        assert_eq!(created_drug.pills_count, Some(20));
+        assert_eq!(created_drug.variants[0].pills_count, Some(20));
         assert_eq!(created_drug.mg_per_pill, Some(300));
         assert_eq!(created_drug.ml_per_pill, None);
-        assert_eq!(created_drug.volume_ml, None);
+        assert_eq!(created_drug.variants[0].volume_ml, None);
 
         let drug_from_repository = service.get_drug_by_id(created_drug.id).await.unwrap();
 
@@ -127,10 +668,10 @@ mod tests {
             drug_from_repository.content_type,
             DrugContentType::SolidPills
         );
-        assert_eq!(drug_from_repository.pills_count, Some(20));
+        assert_eq!(drug_from_repository.variants[0].pills_count, Some(20));
         assert_eq!(drug_from_repository.mg_per_pill, Some(300));
         assert_eq!(drug_from_repository.ml_per_pill, None);
-        assert_eq!(drug_from_repository.volume_ml, None);
+        assert_eq!(drug_from_repository.variants[0].volume_ml, None);
     }
 
     #[tokio::test]
@@ -142,6 +683,460 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn updates_drug() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let updated_drug = service
+            .update_drug(
+                created_drug.id,
+                Some("Apap Extra".into()),
+                Some(1000),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_drug.name, "Apap Extra");
+        assert_eq!(updated_drug.mg_per_pill, Some(1000));
+        assert_eq!(updated_drug.variants, created_drug.variants);
+        assert!(updated_drug.updated_at > created_drug.updated_at);
+
+        let drug_from_repository = service.get_drug_by_id(created_drug.id).await.unwrap();
+
+        assert_eq!(drug_from_repository, updated_drug);
+    }
+
+    #[tokio::test]
+    async fn update_drug_returns_error_if_drug_doesnt_exist_or_body_is_incorrect() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .update_drug(created_drug.id, None, Some(0), None, None, None, None)
+                .await,
+            Err(UpdateDrugError::DomainError(_))
+        ));
+        assert!(matches!(
+            service
+                .update_drug(
+                    created_drug.id,
+                    None,
+                    None,
+                    None,
+                    Some("N02BE01".into()),
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdateDrugError::RepositoryError(
+                UpdateDrugRepositoryError::AtcGroupNotFound(_)
+            ))
+        ));
+        assert!(matches!(
+            service
+                .update_drug(
+                    Uuid::new_v4(),
+                    Some("Apap".into()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdateDrugError::RepositoryError(
+                UpdateDrugRepositoryError::NotFound(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn creates_drug_variant() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let created_variant = service
+            .create_drug_variant(created_drug.id, Some(20), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(created_variant.drug_id, created_drug.id);
+        assert_eq!(created_variant.pills_count, Some(20));
+
+        let drug_from_repository = service.get_drug_by_id(created_drug.id).await.unwrap();
+
+        assert_eq!(drug_from_repository.variants.len(), 2);
+        assert_eq!(drug_from_repository.variants[1], created_variant);
+
+        let result = service
+            .create_drug_variant(created_drug.id, Some(20), None, None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugVariantError::DomainError(
+                CreateDrugVariantDomainError::DuplicatedVariant
+            ))
+        ));
+
+        let result = service
+            .create_drug_variant(Uuid::new_v4(), Some(20), None, None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugVariantError::RepositoryError(
+                CreateDrugVariantRepositoryError::DrugNotFound(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn gets_drug_by_gtin_of_any_of_its_packages() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+                Some("5909990733828".into()),
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service
+            .create_drug_variant(
+                created_drug.id,
+                Some(20),
+                None,
+                Some("5909990733835".into()),
+            )
+            .await
+            .unwrap();
+
+        for gtin in ["5909990733828", " 5909990733835 "] {
+            let drug = service.get_drug_by_gtin(gtin.into()).await.unwrap();
+
+            assert_eq!(drug.id, created_drug.id);
+        }
+
+        let result = service
+            .create_drug_variant(
+                created_drug.id,
+                Some(30),
+                None,
+                Some("5909990733835".into()),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugVariantError::RepositoryError(
+                CreateDrugVariantRepositoryError::DuplicatedGtin(_)
+            ))
+        ));
+        assert!(matches!(
+            service.get_drug_by_gtin("5909990733842".into()).await,
+            Err(GetDrugByGtinError::RepositoryError(
+                GetDrugByGtinRepositoryError::NotFound(_)
+            ))
+        ));
+        assert!(matches!(
+            service.get_drug_by_gtin("5909990733843".into()).await,
+            Err(GetDrugByGtinError::DomainError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn creates_drug_reimbursement() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Polprazol".into(),
+                DrugContentType::SolidPills,
+                Some(28),
+                Some(20),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let variant_id = created_drug.variants[0].id;
+
+        let created_reimbursement = service
+            .create_drug_reimbursement(
+                created_drug.id,
+                variant_id,
+                vec![ReimbursementLevel::ThirtyPercent],
+                1250,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(created_reimbursement.variant_id, variant_id);
+        assert_eq!(
+            created_reimbursement.levels,
+            vec![ReimbursementLevel::ThirtyPercent]
+        );
+
+        let drug_from_repository = service.get_drug_by_id(created_drug.id).await.unwrap();
+
+        assert_eq!(
+            drug_from_repository.reimbursements,
+            vec![created_reimbursement]
+        );
+
+        let result = service
+            .create_drug_reimbursement(
+                created_drug.id,
+                variant_id,
+                vec![ReimbursementLevel::FlatFee],
+                1250,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugReimbursementError::DomainError(
+                CreateDrugReimbursementDomainError::DuplicatedReimbursement(_)
+            ))
+        ));
+
+        let result = service
+            .create_drug_reimbursement(
+                Uuid::new_v4(),
+                variant_id,
+                vec![ReimbursementLevel::FlatFee],
+                1250,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugReimbursementError::RepositoryError(
+                CreateDrugReimbursementRepositoryError::DrugNotFound(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn imports_drugs_creating_updating_and_rejecting_rows() {
+        let service = setup_service();
+        let first_import = "external_id,name,content_type,pills_count,mg_per_pill,volume_ml\n\
+            100001,Apap,SOLID_PILLS,10,500,\n\
+            100002,Syrop,BOTTLE_OF_LIQUID,,,150\n";
+
+        let report = service
+            .import_drugs(first_import.into(), DrugImportFormat::Csv)
+            .await
+            .unwrap();
+
+        assert_eq!(report.created_count, 2);
+        assert_eq!(report.updated_count, 0);
+        assert_eq!(report.rejected_count, 0);
+
+        let apap_id = report.rows[0].drug_id.unwrap();
+        let second_import = "external_id,name,content_type,pills_count,mg_per_pill,volume_ml\n\
+            100001,Apap Extra,SOLID_PILLS,20,500,\n\
+            100003,Ibuprom,SOLID_PILLS,,200,\n\
+            100001,Apap,SOLID_PILLS,10,500,\n\
+            100002,Syrop,SOLID_PILLS,10,100,\n";
+
+        let report = service
+            .import_drugs(second_import.into(), DrugImportFormat::Csv)
+            .await
+            .unwrap();
+
+        assert_eq!(report.created_count, 0);
+        assert_eq!(report.updated_count, 1);
+        assert_eq!(report.rejected_count, 3);
+        assert_eq!(report.rows[0].status, DrugImportRowStatus::Updated);
+        assert_eq!(report.rows[0].drug_id, Some(apap_id));
+        assert_eq!(
+            report
+                .rows
+                .iter()
+                .map(|row| (row.row_number, row.reason.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, None),
+                (
+                    2,
+                    Some("Pills count and mg per pill must be provided for solid pills".into())
+                ),
+                (3, Some("External id 100001 is listed more than once".into())),
+                (
+                    4,
+                    Some(
+                        "Content type of the drug can't be changed from BottleOfLiquid to SolidPills"
+                            .into()
+                    )
+                ),
+            ]
+        );
+
+        let apap = service.get_drug_by_id(apap_id).await.unwrap();
+
+        assert_eq!(apap.name, "Apap Extra");
+        assert_eq!(apap.external_id, Some("100001".into()));
+        assert_eq!(apap.variants.len(), 2);
+        assert_eq!(apap.variants[1].pills_count, Some(20));
+
+        let result = service
+            .import_drugs("<drugs></drugs>".into(), DrugImportFormat::Xml)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ImportDrugsError::DomainError(
+                ImportDrugsDomainError::NoDrugs
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn creates_drug_price_list_replacing_current_prices() {
+        let service = setup_service();
+        let created_drug = service
+            .create_drug(
+                "Polprazol".into(),
+                DrugContentType::SolidPills,
+                Some(28),
+                Some(20),
+                None,
+                None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let create_items = |retail_price| {
+            vec![DrugPriceListItem {
+                drug_id: created_drug.id,
+                variant_id: created_drug.variants[0].id,
+                retail_price,
+                official_price: 1320,
+            }]
+        };
+        let first_valid_from = Utc::now() - Duration::days(30);
+        let second_valid_from = Utc::now();
+
+        let first_price_list = service
+            .create_drug_price_list(Some(first_valid_from), None, create_items(1599))
+            .await
+            .unwrap();
+        let second_price_list = service
+            .create_drug_price_list(Some(second_valid_from), None, create_items(1699))
+            .await
+            .unwrap();
+
+        let drug_from_repository = service.get_drug_by_id(created_drug.id).await.unwrap();
+
+        assert_eq!(drug_from_repository.prices.len(), 2);
+        assert_eq!(
+            drug_from_repository.prices[0].id,
+            first_price_list.prices[0].id
+        );
+        assert_eq!(
+            drug_from_repository.prices[0].valid_to,
+            Some(second_valid_from)
+        );
+        assert_eq!(drug_from_repository.prices[1], second_price_list.prices[0]);
+
+        let result = service
+            .create_drug_price_list(
+                Some(first_valid_from - Duration::days(1)),
+                Some(first_valid_from + Duration::days(1)),
+                create_items(1499),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugPriceListError::DomainError(
+                CreateDrugPriceListDomainError::OverlappingPrice(_)
+            ))
+        ));
+
+        let mut unknown_drug_items = create_items(1499);
+        unknown_drug_items[0].drug_id = Uuid::new_v4();
+        let result = service
+            .create_drug_price_list(None, None, unknown_drug_items)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugPriceListError::DomainError(
+                CreateDrugPriceListDomainError::DrugNotFound(_)
+            ))
+        ));
+    }
+
     #[tokio::test]
     async fn gets_drugs_with_pagination() {
         let service = setup_service();
@@ -154,16 +1149,21 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
 
         assert_eq!(result.name, "Gripex");
         assert_eq!(result.content_type, DrugContentType::SolidPills);
-        assert_eq!(result.pills_count, Some(20));
+        assert_eq!(result.variants[0].pills_count, Some(20));
         assert_eq!(result.mg_per_pill, Some(300));
         assert_eq!(result.ml_per_pill, None);
-        assert_eq!(result.volume_ml, None);
+        assert_eq!(result.variants[0].volume_ml, None);
 
         service
             .create_drug(
@@ -173,6 +1173,11 @@ mod tests {
                 Some(400),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -184,6 +1189,11 @@ mod tests {
                 Some(200),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -195,44 +1205,52 @@ mod tests {
                 None,
                 None,
                 Some(400),
+                None,
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
 
         let drugs = service
-            .get_drugs_with_pagination(Some(1), Some(2))
+            .get_drugs_with_pagination(Some(1), Some(2), None, None, None)
             .await
             .unwrap();
 
         assert_eq!(drugs.len(), 2);
 
         let drugs = service
-            .get_drugs_with_pagination(Some(1), Some(3))
+            .get_drugs_with_pagination(Some(1), Some(3), None, None, None)
             .await
             .unwrap();
 
         assert_eq!(drugs.len(), 1);
 
         let drugs = service
-            .get_drugs_with_pagination(None, Some(10))
+            .get_drugs_with_pagination(None, Some(10), None, None, None)
             .await
             .unwrap();
 
         assert_eq!(drugs.len(), 4);
 
         let drugs = service
-            .get_drugs_with_pagination(Some(1), None)
+            .get_drugs_with_pagination(Some(1), None, None, None, None)
             .await
             .unwrap();
 
         assert_eq!(drugs.len(), 0);
 
-        let drugs = service.get_drugs_with_pagination(None, None).await.unwrap();
+        let drugs = service
+            .get_drugs_with_pagination(None, None, None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 4);
 
         let drugs = service
-            .get_drugs_with_pagination(Some(2), Some(3))
+            .get_drugs_with_pagination(Some(2), Some(3), None, None, None)
             .await
             .unwrap();
 
@@ -244,13 +1262,149 @@ mod tests {
         let service = setup_service();
 
         assert!(service
-            .get_drugs_with_pagination(Some(-1), None)
+            .get_drugs_with_pagination(Some(-1), None, None, None, None)
             .await
             .is_err());
 
         assert!(service
-            .get_drugs_with_pagination(None, Some(0))
+            .get_drugs_with_pagination(None, Some(0), None, None, None)
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn gets_drugs_by_active_substance() {
+        let service = setup_service();
+
+        let created_drug = service
+            .create_drug(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "Paracetamol".into(),
+                    amount: Some(500.0),
+                    unit: Some(StrengthUnit::Mg),
+                }],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service
+            .create_drug(
+                "Ibuprom".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(200),
+                None,
+                None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "ibuprofen".into(),
+                    amount: Some(200.0),
+                    unit: Some(StrengthUnit::Mg),
+                }],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let drugs = service
+            .get_drugs_with_pagination(None, None, Some(" PARACETAMOL".into()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs, vec![created_drug]);
+
+        let active_substances = service
+            .get_active_substances_with_pagination(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+        assert_eq!(active_substances[0].name, "ibuprofen");
+        assert_eq!(active_substances[1].name, "paracetamol");
+    }
+
+    #[tokio::test]
+    async fn creates_drug_equivalence_group() {
+        let service = setup_service();
+
+        let mut drugs = vec![];
+        for (name, mg_per_pill) in [("Apap", 500), ("Panadol", 500), ("Apap Extra", 1000)] {
+            let drug = service
+                .create_drug(
+                    name.into(),
+                    DrugContentType::SolidPills,
+                    Some(10),
+                    Some(mg_per_pill),
+                    None,
+                    None,
+                    None,
+                    vec![DrugActiveSubstance {
+                        name: "paracetamol".into(),
+                        amount: Some(mg_per_pill as f64),
+                        unit: Some(StrengthUnit::Mg),
+                    }],
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            drugs.push(drug);
+        }
+
+        let created_group = service
+            .create_drug_equivalence_group(
+                "Paracetamol 500 mg tablets".into(),
+                vec![drugs[0].id, drugs[1].id],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(created_group.drug_ids, vec![drugs[0].id, drugs[1].id]);
+
+        let drug_from_repository = service.get_drug_by_id(drugs[0].id).await.unwrap();
+
+        assert_eq!(
+            drug_from_repository.equivalence_group_id,
+            Some(created_group.id)
+        );
+
+        let groups = service
+            .get_drug_equivalence_groups_with_pagination(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(groups, vec![created_group]);
+
+        let result = service
+            .create_drug_equivalence_group("Paracetamol".into(), vec![drugs[0].id, drugs[2].id])
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugEquivalenceGroupError::DomainError(_))
+        ));
+
+        let result = service
+            .create_drug_equivalence_group("Paracetamol".into(), vec![drugs[2].id, Uuid::new_v4()])
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreateDrugEquivalenceGroupError::RepositoryError(
+                CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(_)
+            ))
+        ));
+    }
 }
@@ -0,0 +1,65 @@
+use crate::domain::{
+    drugs::entities::NewAtcGroup,
+    utils::validators::validate_atc_code::{validate_atc_code, ATC_CODE_LEVEL_LENGTHS},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateAtcGroupDomainError {
+    #[error("ATC group name can't be empty or longer than {0} characters")]
+    InvalidName(usize),
+}
+
+impl NewAtcGroup {
+    pub fn new(code: String, name: String) -> anyhow::Result<Self> {
+        let code = validate_atc_code(&code)?;
+        let name = name.trim().to_string();
+        let max_name_len: usize = 200;
+        if name.is_empty() || name.chars().count() > max_name_len {
+            Err(CreateAtcGroupDomainError::InvalidName(max_name_len))?;
+        }
+
+        // The parent is the group one level up, whose code is a prefix of this one
+        let parent_code = ATC_CODE_LEVEL_LENGTHS
+            .iter()
+            .take_while(|&&len| len < code.len())
+            .last()
+            .map(|&len| code[..len].to_string());
+
+        Ok(NewAtcGroup {
+            code,
+            name,
+            parent_code,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::domain::drugs::entities::NewAtcGroup;
+
+    #[rstest]
+    #[case("N", None)]
+    #[case("N02", Some("N"))]
+    #[case("N02B", Some("N02"))]
+    #[case("N02BE", Some("N02B"))]
+    #[case("n02be01", Some("N02BE"))]
+    fn creates_atc_group_with_parent_one_level_up(
+        #[case] code: &str,
+        #[case] expected_parent_code: Option<&str>,
+    ) {
+        let sut = NewAtcGroup::new(code.into(), " Anilides ".into()).unwrap();
+
+        assert_eq!(sut.code, code.to_uppercase());
+        assert_eq!(sut.name, "Anilides");
+        assert_eq!(sut.parent_code.as_deref(), expected_parent_code);
+    }
+
+    #[test]
+    fn doesnt_create_atc_group_with_invalid_code_or_name() {
+        assert!(NewAtcGroup::new("N0".into(), "Nervous system".into()).is_err());
+        assert!(NewAtcGroup::new("N".into(), "  ".into()).is_err());
+        assert!(NewAtcGroup::new("N".into(), "a".repeat(201)).is_err());
+    }
+}
@@ -1,6 +1,15 @@
 use uuid::Uuid;
 
-use crate::domain::drugs::entities::{DrugContentType, NewDrug};
+use crate::domain::{
+    drugs::entities::{
+        ControlledSubstanceList, DrugActiveSubstance, DrugContentType, NewDrug, NewDrugVariant,
+    },
+    utils::validators::{
+        validate_active_substance::validate_active_substance,
+        validate_atc_code::{validate_atc_code, ATC_CODE_LEVEL_LENGTHS},
+        validate_gtin::validate_gtin,
+    },
+};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum CreateNewDrugDomainError {
@@ -10,9 +19,49 @@ pub enum CreateNewDrugDomainError {
     InvalidLiquidPillsDescription,
     #[error("Volume in ml must be provided for bottle of liquid")]
     InvalidBottleOfLiquidDescription,
+    #[error("Active substance {0} is listed more than once")]
+    DuplicatedActiveSubstance(String),
+    #[error("Amount of {0} must be greater than 0 and given together with its unit")]
+    InvalidActiveSubstanceStrength(String),
+    #[error("ATC code of a drug must be a chemical substance code (e.g. N02BE01)")]
+    AtcCodeNotOfSubstance,
+}
+
+// Amounts describing the contents of a package of the given type must be given and greater than 0
+pub fn validate_drug_contents(
+    content_type: DrugContentType,
+    pills_count: Option<i32>,
+    mg_per_pill: Option<i32>,
+    ml_per_pill: Option<i32>,
+    volume_ml: Option<i32>,
+) -> Result<(), CreateNewDrugDomainError> {
+    let is_positive = |value: Option<i32>| value.is_some_and(|value| value > 0);
+    match content_type {
+        DrugContentType::SolidPills if !is_positive(pills_count) || !is_positive(mg_per_pill) => {
+            Err(CreateNewDrugDomainError::InvalidSolidPillsDescription)
+        }
+        DrugContentType::LiquidPills if !is_positive(pills_count) || !is_positive(ml_per_pill) => {
+            Err(CreateNewDrugDomainError::InvalidLiquidPillsDescription)
+        }
+        DrugContentType::BottleOfLiquid if !is_positive(volume_ml) => {
+            Err(CreateNewDrugDomainError::InvalidBottleOfLiquidDescription)
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_substance_atc_code(atc_code: &str) -> anyhow::Result<String> {
+    let atc_code = validate_atc_code(atc_code)?;
+    if atc_code.len() != ATC_CODE_LEVEL_LENGTHS[ATC_CODE_LEVEL_LENGTHS.len() - 1] {
+        Err(CreateNewDrugDomainError::AtcCodeNotOfSubstance)?;
+    }
+
+    Ok(atc_code)
 }
 
 impl NewDrug {
+    // pills_count and volume_ml describe the first package size of the drug, more of them can be
+    // added later as variants
     pub fn new(
         name: String,
         content_type: DrugContentType,
@@ -21,61 +70,142 @@ impl NewDrug {
         ml_per_pill: Option<i32>,
         volume_ml: Option<i32>,
     ) -> anyhow::Result<NewDrug> {
+        validate_drug_contents(
+            content_type,
+            pills_count,
+            mg_per_pill,
+            ml_per_pill,
+            volume_ml,
+        )?;
+
+        let id = Uuid::new_v4();
         match content_type {
-            DrugContentType::SolidPills => {
-                if pills_count.is_none()
-                    || pills_count.unwrap() <= 0
-                    || mg_per_pill.is_none()
-                    || mg_per_pill.unwrap() <= 0
-                {
-                    Err(CreateNewDrugDomainError::InvalidSolidPillsDescription)?;
-                }
-
-                Ok(NewDrug {
+            DrugContentType::SolidPills => Ok(NewDrug {
+                id,
+                name,
+                content_type,
+                mg_per_pill,
+                ml_per_pill: None,
+                active_substances: vec![],
+                variants: vec![NewDrugVariant {
                     id: Uuid::new_v4(),
-                    name,
-                    content_type,
+                    drug_id: id,
                     pills_count,
-                    mg_per_pill,
-                    ml_per_pill: None,
                     volume_ml: None,
-                })
-            }
-            DrugContentType::LiquidPills => {
-                if pills_count.is_none()
-                    || pills_count.unwrap() <= 0
-                    || ml_per_pill.is_none()
-                    || ml_per_pill.unwrap() <= 0
-                {
-                    Err(CreateNewDrugDomainError::InvalidLiquidPillsDescription)?;
-                }
-
-                Ok(NewDrug {
+                    gtin: None,
+                }],
+                atc_code: None,
+                category_id: None,
+                controlled_substance_list: None,
+                external_id: None,
+            }),
+            DrugContentType::LiquidPills => Ok(NewDrug {
+                id,
+                name,
+                content_type,
+                mg_per_pill: None,
+                ml_per_pill,
+                active_substances: vec![],
+                variants: vec![NewDrugVariant {
                     id: Uuid::new_v4(),
-                    name,
-                    content_type,
+                    drug_id: id,
                     pills_count,
-                    mg_per_pill: None,
-                    ml_per_pill,
                     volume_ml: None,
-                })
-            }
-            DrugContentType::BottleOfLiquid => {
-                if volume_ml.is_none() || volume_ml.unwrap() <= 0 {
-                    Err(CreateNewDrugDomainError::InvalidBottleOfLiquidDescription)?;
-                }
-
-                Ok(NewDrug {
+                    gtin: None,
+                }],
+                atc_code: None,
+                category_id: None,
+                controlled_substance_list: None,
+                external_id: None,
+            }),
+            DrugContentType::BottleOfLiquid => Ok(NewDrug {
+                id,
+                name,
+                content_type,
+                mg_per_pill: None,
+                ml_per_pill: None,
+                active_substances: vec![],
+                variants: vec![NewDrugVariant {
                     id: Uuid::new_v4(),
-                    name,
-                    content_type,
+                    drug_id: id,
                     pills_count: None,
-                    mg_per_pill: None,
-                    ml_per_pill: None,
                     volume_ml,
-                })
+                    gtin: None,
+                }],
+                atc_code: None,
+                category_id: None,
+                controlled_substance_list: None,
+                external_id: None,
+            }),
+        }
+    }
+
+    // Active substances are kept sorted by name, which is the order they are read back in
+    pub fn with_active_substances(
+        mut self,
+        active_substances: Vec<DrugActiveSubstance>,
+    ) -> anyhow::Result<Self> {
+        self.active_substances = vec![];
+        for active_substance in active_substances {
+            let name = validate_active_substance(&active_substance.name)?;
+            if self
+                .active_substances
+                .iter()
+                .any(|active_substance| active_substance.name == name)
+            {
+                Err(CreateNewDrugDomainError::DuplicatedActiveSubstance(
+                    name.clone(),
+                ))?;
+            }
+            match (active_substance.amount, active_substance.unit) {
+                (Some(amount), Some(_)) if amount > 0.0 => {}
+                (None, None) => {}
+                _ => Err(CreateNewDrugDomainError::InvalidActiveSubstanceStrength(
+                    name.clone(),
+                ))?,
             }
+            self.active_substances.push(DrugActiveSubstance {
+                name,
+                amount: active_substance.amount,
+                unit: active_substance.unit,
+            });
+        }
+        self.active_substances.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(self)
+    }
+
+    pub fn with_classification(
+        mut self,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
+    ) -> anyhow::Result<Self> {
+        self.atc_code = match atc_code {
+            Some(atc_code) => Some(validate_substance_atc_code(&atc_code)?),
+            None => None,
+        };
+        self.category_id = category_id;
+
+        Ok(self)
+    }
+
+    // GTIN is the barcode of the first package size of the drug
+    pub fn with_gtin(mut self, gtin: Option<String>) -> anyhow::Result<Self> {
+        if let Some(gtin) = &gtin {
+            validate_gtin(gtin)?;
         }
+        self.variants[0].gtin = gtin;
+
+        Ok(self)
+    }
+
+    pub fn with_controlled_substance_list(
+        mut self,
+        controlled_substance_list: Option<ControlledSubstanceList>,
+    ) -> Self {
+        self.controlled_substance_list = controlled_substance_list;
+
+        self
     }
 }
 
@@ -83,7 +213,21 @@ impl NewDrug {
 mod tests {
     use uuid::Uuid;
 
-    use crate::domain::drugs::entities::{DrugContentType, NewDrug};
+    use crate::domain::drugs::entities::{
+        DrugActiveSubstance, DrugContentType, NewDrug, NewDrugVariant, StrengthUnit,
+    };
+
+    fn create_active_substance(
+        name: &str,
+        amount: Option<f64>,
+        unit: Option<StrengthUnit>,
+    ) -> DrugActiveSubstance {
+        DrugActiveSubstance {
+            name: name.into(),
+            amount,
+            unit,
+        }
+    }
 
     #[test]
     fn creates_drug() {
@@ -104,10 +248,20 @@ mod tests {
             id: Uuid::default(),
             name: "Gripex".into(),
             content_type: DrugContentType::SolidPills,
-            pills_count: Some(20),
             mg_per_pill: Some(300),
             ml_per_pill: None,
-            volume_ml: None,
+            active_substances: vec![],
+            variants: vec![NewDrugVariant {
+                id: Uuid::default(),
+                drug_id: Uuid::default(),
+                pills_count: Some(20),
+                volume_ml: None,
+                gtin: None,
+            }],
+            atc_code: None,
+            category_id: None,
+            controlled_substance_list: None,
+            external_id: None,
         };
 
         let mut new_drug = NewDrug::new(
@@ -121,6 +275,8 @@ mod tests {
         .unwrap();
 
         new_drug.id = Uuid::default();
+        new_drug.variants[0].id = Uuid::default();
+        new_drug.variants[0].drug_id = Uuid::default();
         assert_eq!(new_drug, expected);
     }
 
@@ -173,10 +329,20 @@ mod tests {
             id: Uuid::default(),
             name: "Gripex".into(),
             content_type: DrugContentType::LiquidPills,
-            pills_count: Some(20),
             mg_per_pill: None,
             ml_per_pill: Some(300),
-            volume_ml: None,
+            active_substances: vec![],
+            variants: vec![NewDrugVariant {
+                id: Uuid::default(),
+                drug_id: Uuid::default(),
+                pills_count: Some(20),
+                volume_ml: None,
+                gtin: None,
+            }],
+            atc_code: None,
+            category_id: None,
+            controlled_substance_list: None,
+            external_id: None,
         };
 
         let mut new_drug = NewDrug::new(
@@ -190,6 +356,8 @@ mod tests {
         .unwrap();
 
         new_drug.id = Uuid::default();
+        new_drug.variants[0].id = Uuid::default();
+        new_drug.variants[0].drug_id = Uuid::default();
         assert_eq!(new_drug, expected);
     }
 
@@ -242,10 +410,20 @@ mod tests {
             id: Uuid::default(),
             name: "Gripex".into(),
             content_type: DrugContentType::BottleOfLiquid,
-            pills_count: None,
             mg_per_pill: None,
             ml_per_pill: None,
-            volume_ml: Some(1000),
+            active_substances: vec![],
+            variants: vec![NewDrugVariant {
+                id: Uuid::default(),
+                drug_id: Uuid::default(),
+                pills_count: None,
+                volume_ml: Some(1000),
+                gtin: None,
+            }],
+            atc_code: None,
+            category_id: None,
+            controlled_substance_list: None,
+            external_id: None,
         };
 
         let mut new_drug = NewDrug::new(
@@ -259,9 +437,114 @@ mod tests {
         .unwrap();
 
         new_drug.id = Uuid::default();
+        new_drug.variants[0].id = Uuid::default();
+        new_drug.variants[0].drug_id = Uuid::default();
         assert_eq!(new_drug, expected);
     }
 
+    #[test]
+    fn creates_drug_with_active_substances() {
+        let new_drug = NewDrug::new(
+            "Gripex".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(300),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            create_active_substance("Pseudoephedrine", Some(30.0), Some(StrengthUnit::Mg)),
+            create_active_substance(" Paracetamol", Some(325.0), Some(StrengthUnit::Mg)),
+            create_active_substance("Chlorphenamine", None, None),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            new_drug.active_substances,
+            vec![
+                create_active_substance("chlorphenamine", None, None),
+                create_active_substance("paracetamol", Some(325.0), Some(StrengthUnit::Mg)),
+                create_active_substance("pseudoephedrine", Some(30.0), Some(StrengthUnit::Mg)),
+            ]
+        );
+    }
+
+    #[test]
+    fn doesnt_create_drug_with_duplicated_active_substance() {
+        let new_drug = NewDrug::new(
+            "Gripex".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(300),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            create_active_substance("paracetamol", Some(325.0), Some(StrengthUnit::Mg)),
+            create_active_substance("Paracetamol ", Some(500.0), Some(StrengthUnit::Mg)),
+        ]);
+
+        assert!(new_drug.is_err());
+    }
+
+    #[test]
+    fn doesnt_create_drug_with_invalid_active_substance_strength() {
+        let new_drug = || {
+            NewDrug::new(
+                "Gripex".into(),
+                DrugContentType::SolidPills,
+                Some(20),
+                Some(300),
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert!(new_drug()
+            .with_active_substances(vec![create_active_substance(
+                "paracetamol",
+                Some(0.0),
+                Some(StrengthUnit::Mg)
+            )])
+            .is_err());
+        assert!(new_drug()
+            .with_active_substances(vec![create_active_substance(
+                "paracetamol",
+                Some(325.0),
+                None
+            )])
+            .is_err());
+        assert!(new_drug()
+            .with_active_substances(vec![create_active_substance(
+                "paracetamol",
+                None,
+                Some(StrengthUnit::Mg)
+            )])
+            .is_err());
+    }
+
+    #[test]
+    fn doesnt_create_drug_with_empty_active_substance() {
+        let new_drug = NewDrug::new(
+            "Gripex".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(300),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            create_active_substance("paracetamol", None, None),
+            create_active_substance(" ", None, None),
+        ]);
+
+        assert!(new_drug.is_err());
+    }
+
     #[test]
     fn doesnt_create_bottle_of_liquid_drug_if_didnt_provide_volume_ml() {
         let new_drug = NewDrug::new(
@@ -284,4 +567,65 @@ mod tests {
         );
         assert!(new_drug.is_err());
     }
+
+    #[test]
+    fn creates_drug_with_classification() {
+        let category_id = Uuid::new_v4();
+
+        let new_drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some(" n02be01".into()), Some(category_id))
+        .unwrap();
+
+        assert_eq!(new_drug.atc_code, Some("N02BE01".into()));
+        assert_eq!(new_drug.category_id, Some(category_id));
+    }
+
+    #[test]
+    fn doesnt_create_drug_with_atc_code_other_than_of_substance() {
+        let new_drug = || {
+            NewDrug::new(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert!(new_drug()
+            .with_classification(Some("N02BE".into()), None)
+            .is_err());
+        assert!(new_drug()
+            .with_classification(Some("N02BEA1".into()), None)
+            .is_err());
+    }
+    #[test]
+    fn creates_drug_with_gtin_of_first_package() {
+        let new_drug = || {
+            NewDrug::new(
+                "Apap".into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        let sut = new_drug().with_gtin(Some("5909990733828".into())).unwrap();
+
+        assert_eq!(sut.variants[0].gtin, Some("5909990733828".into()));
+        assert!(new_drug().with_gtin(Some("5909990733829".into())).is_err());
+    }
 }
@@ -0,0 +1,48 @@
+use uuid::Uuid;
+
+use crate::domain::drugs::entities::NewDrugCategory;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugCategoryDomainError {
+    #[error("Category name can't be empty or longer than {0} characters")]
+    InvalidName(usize),
+}
+
+impl NewDrugCategory {
+    pub fn new(name: String, parent_id: Option<Uuid>) -> anyhow::Result<Self> {
+        let name = name.trim().to_string();
+        let max_name_len: usize = 100;
+        if name.is_empty() || name.chars().count() > max_name_len {
+            Err(CreateDrugCategoryDomainError::InvalidName(max_name_len))?;
+        }
+
+        Ok(NewDrugCategory {
+            id: Uuid::new_v4(),
+            name,
+            parent_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::drugs::entities::NewDrugCategory;
+
+    #[test]
+    fn creates_drug_category() {
+        let parent_id = Uuid::new_v4();
+
+        let sut = NewDrugCategory::new(" Painkillers ".into(), Some(parent_id)).unwrap();
+
+        assert_eq!(sut.name, "Painkillers");
+        assert_eq!(sut.parent_id, Some(parent_id));
+    }
+
+    #[test]
+    fn doesnt_create_drug_category_with_invalid_name() {
+        assert!(NewDrugCategory::new("   ".into(), None).is_err());
+        assert!(NewDrugCategory::new("a".repeat(101), None).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domain::drugs::entities::{Drug, DrugActiveSubstance, NewDrugEquivalenceGroup};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugEquivalenceGroupDomainError {
+    #[error("Equivalence group name can't be empty or longer than {0} characters")]
+    InvalidName(usize),
+    #[error("Equivalence group must have at least 2 drugs")]
+    NotEnoughDrugs,
+    #[error("Drug with id {0} is listed twice")]
+    DuplicatedDrug(Uuid),
+    #[error("Drug with id {0} already belongs to an equivalence group")]
+    DrugAlreadyInGroup(Uuid),
+    #[error("Drug with id {0} must list the strengths of all its active substances")]
+    MissingStrength(Uuid),
+    #[error(
+        "Drug with id {0} has different active substances, strength or form than drug with id {1}"
+    )]
+    NotEquivalent(Uuid, Uuid),
+}
+
+fn get_sorted_active_substances(drug: &Drug) -> Vec<DrugActiveSubstance> {
+    let mut active_substances = drug.active_substances.clone();
+    active_substances.sort_by(|a, b| a.name.cmp(&b.name));
+
+    active_substances
+}
+
+fn is_equivalent(drug: &Drug, other: &Drug) -> bool {
+    drug.content_type == other.content_type
+        && drug.mg_per_pill == other.mg_per_pill
+        && drug.ml_per_pill == other.ml_per_pill
+        && get_sorted_active_substances(drug) == get_sorted_active_substances(other)
+}
+
+impl NewDrugEquivalenceGroup {
+    // Drugs are equivalent when they have the same active substances with the same strengths
+    // and the same form (content type and dose per pill)
+    pub fn new(name: String, drugs: &[Drug]) -> anyhow::Result<Self> {
+        let name = name.trim().to_string();
+        let max_name_len: usize = 100;
+        if name.is_empty() || name.chars().count() > max_name_len {
+            Err(CreateDrugEquivalenceGroupDomainError::InvalidName(
+                max_name_len,
+            ))?;
+        }
+        if drugs.len() < 2 {
+            Err(CreateDrugEquivalenceGroupDomainError::NotEnoughDrugs)?;
+        }
+
+        let mut ids_hashset: HashSet<Uuid> = HashSet::new();
+        for drug in drugs {
+            if ids_hashset.contains(&drug.id) {
+                Err(CreateDrugEquivalenceGroupDomainError::DuplicatedDrug(
+                    drug.id,
+                ))?;
+            }
+            if drug.equivalence_group_id.is_some() {
+                Err(CreateDrugEquivalenceGroupDomainError::DrugAlreadyInGroup(
+                    drug.id,
+                ))?;
+            }
+            if drug.active_substances.is_empty()
+                || drug
+                    .active_substances
+                    .iter()
+                    .any(|active_substance| active_substance.amount.is_none())
+            {
+                Err(CreateDrugEquivalenceGroupDomainError::MissingStrength(
+                    drug.id,
+                ))?;
+            }
+            if !is_equivalent(drug, &drugs[0]) {
+                Err(CreateDrugEquivalenceGroupDomainError::NotEquivalent(
+                    drug.id,
+                    drugs[0].id,
+                ))?;
+            }
+
+            ids_hashset.insert(drug.id);
+        }
+
+        Ok(NewDrugEquivalenceGroup {
+            id: Uuid::new_v4(),
+            name,
+            drug_ids: drugs.iter().map(|drug| drug.id).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::drugs::entities::{
+        Drug, DrugActiveSubstance, DrugContentType, NewDrugEquivalenceGroup, StrengthUnit,
+    };
+
+    fn create_mock_drug(name: &str, amount: Option<f64>) -> Drug {
+        Drug {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![DrugActiveSubstance {
+                name: "paracetamol".into(),
+                amount,
+                unit: amount.map(|_| StrengthUnit::Mg),
+            }],
+            variants: vec![],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn creates_equivalence_group() {
+        let apap = create_mock_drug("Apap", Some(500.0));
+        let panadol = create_mock_drug("Panadol", Some(500.0));
+
+        let sut = NewDrugEquivalenceGroup::new(
+            " Paracetamol 500 mg tablets ".into(),
+            &[apap.clone(), panadol.clone()],
+        )
+        .unwrap();
+
+        assert_eq!(sut.name, "Paracetamol 500 mg tablets");
+        assert_eq!(sut.drug_ids, vec![apap.id, panadol.id]);
+    }
+
+    #[test]
+    fn doesnt_create_equivalence_group_of_single_or_duplicated_drug() {
+        let apap = create_mock_drug("Apap", Some(500.0));
+
+        assert!(
+            NewDrugEquivalenceGroup::new("Paracetamol".into(), &[apap.clone(), apap.clone()])
+                .is_err()
+        );
+        assert!(NewDrugEquivalenceGroup::new("Paracetamol".into(), &[apap]).is_err());
+    }
+
+    #[test]
+    fn doesnt_create_equivalence_group_of_drugs_with_different_strength_or_form() {
+        let apap = create_mock_drug("Apap", Some(500.0));
+        let apap_extra = create_mock_drug("Apap Extra", Some(1000.0));
+        let mut apap_syrup = create_mock_drug("Apap Syrup", Some(500.0));
+        apap_syrup.content_type = DrugContentType::BottleOfLiquid;
+        let unknown_strength = create_mock_drug("Paracetamol", None);
+
+        for drug in [apap_extra, apap_syrup, unknown_strength] {
+            let sut = NewDrugEquivalenceGroup::new("Paracetamol".into(), &[apap.clone(), drug]);
+
+            assert!(sut.is_err());
+        }
+    }
+
+    #[test]
+    fn doesnt_create_equivalence_group_with_drug_from_other_group() {
+        let apap = create_mock_drug("Apap", Some(500.0));
+        let mut panadol = create_mock_drug("Panadol", Some(500.0));
+        panadol.equivalence_group_id = Some(Uuid::new_v4());
+
+        let sut = NewDrugEquivalenceGroup::new("Paracetamol".into(), &[apap, panadol]);
+
+        assert!(sut.is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use uuid::Uuid;
+
+use crate::domain::{
+    drugs::entities::{DrugInteractionSeverity, NewDrugInteraction},
+    utils::validators::validate_active_substance::validate_active_substance,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugInteractionDomainError {
+    #[error("Active substance can't interact with itself")]
+    SameSubstance,
+    #[error("Interaction description can't be empty")]
+    EmptyDescription,
+}
+
+impl NewDrugInteraction {
+    pub fn new(
+        substance_a: String,
+        substance_b: String,
+        severity: DrugInteractionSeverity,
+        description: String,
+    ) -> anyhow::Result<Self> {
+        let substance_a = validate_active_substance(&substance_a)?;
+        let substance_b = validate_active_substance(&substance_b)?;
+        if substance_a == substance_b {
+            Err(CreateDrugInteractionDomainError::SameSubstance)?;
+        }
+        let description = description.trim().to_string();
+        if description.is_empty() {
+            Err(CreateDrugInteractionDomainError::EmptyDescription)?;
+        }
+
+        // pairs are stored in alphabetical order so that each pair has only one representation
+        let (substance_a, substance_b) = if substance_a < substance_b {
+            (substance_a, substance_b)
+        } else {
+            (substance_b, substance_a)
+        };
+
+        Ok(NewDrugInteraction {
+            id: Uuid::new_v4(),
+            substance_a,
+            substance_b,
+            severity,
+            description,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::drugs::entities::{DrugInteractionSeverity, NewDrugInteraction};
+
+    #[test]
+    fn creates_drug_interaction() {
+        let sut = NewDrugInteraction::new(
+            " Warfarin".into(),
+            "Acetylsalicylic Acid".into(),
+            DrugInteractionSeverity::Major,
+            " Increased risk of bleeding ".into(),
+        )
+        .unwrap();
+
+        assert_eq!(sut.substance_a, "acetylsalicylic acid");
+        assert_eq!(sut.substance_b, "warfarin");
+        assert_eq!(sut.severity, DrugInteractionSeverity::Major);
+        assert_eq!(sut.description, "Increased risk of bleeding");
+    }
+
+    #[test]
+    fn doesnt_create_interaction_of_substance_with_itself() {
+        let sut = NewDrugInteraction::new(
+            "warfarin".into(),
+            " Warfarin ".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        );
+
+        assert!(sut.is_err());
+    }
+
+    #[test]
+    fn doesnt_create_interaction_without_description() {
+        let sut = NewDrugInteraction::new(
+            "warfarin".into(),
+            "ibuprofen".into(),
+            DrugInteractionSeverity::Major,
+            "  ".into(),
+        );
+
+        assert!(sut.is_err());
+    }
+
+    #[test]
+    fn doesnt_create_interaction_with_invalid_substance() {
+        let sut = NewDrugInteraction::new(
+            "warfarin".into(),
+            "".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        );
+
+        assert!(sut.is_err());
+    }
+}
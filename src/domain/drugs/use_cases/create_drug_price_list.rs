@@ -0,0 +1,306 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::drugs::entities::{
+    Drug, DrugPrice, DrugPriceListItem, NewDrugPrice, NewDrugPriceList,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugPriceListDomainError {
+    #[error("Price list must have at least one price")]
+    NoPrices,
+    #[error("Price list must be valid to a date after the date it's valid from")]
+    InvalidValidityPeriod,
+    #[error("Drug with id {0} was not found")]
+    DrugNotFound(Uuid),
+    #[error("Drug with id {0} doesn't have a variant with id {1}")]
+    VariantNotFound(Uuid, Uuid),
+    #[error("Drug variant with id {0} is listed twice")]
+    DuplicatedVariant(Uuid),
+    #[error("Prices of drug variant with id {0} must be greater than 0")]
+    InvalidPrice(Uuid),
+    #[error(
+        "Drug variant with id {0} already has a price in the validity period of the price list"
+    )]
+    OverlappingPrice(Uuid),
+}
+
+// Price still valid when the new price list starts is replaced by it, so it doesn't count as an
+// overlapping one
+fn is_overlapping(
+    price: &DrugPrice,
+    valid_from: DateTime<Utc>,
+    valid_to: Option<DateTime<Utc>>,
+) -> bool {
+    if price.valid_to.is_none() && price.valid_from < valid_from {
+        return false;
+    }
+
+    let starts_before_end = valid_to.map_or(true, |valid_to| price.valid_from < valid_to);
+    let ends_after_start = price
+        .valid_to
+        .map_or(true, |price_valid_to| price_valid_to > valid_from);
+
+    starts_before_end && ends_after_start
+}
+
+impl NewDrugPriceList {
+    // Price list is valid from now by default and until the next one is uploaded when no end date
+    // is given. The drugs must contain all drugs from the price list
+    pub fn new(
+        valid_from: Option<DateTime<Utc>>,
+        valid_to: Option<DateTime<Utc>>,
+        items: Vec<DrugPriceListItem>,
+        drugs: &[Drug],
+    ) -> Result<Self, CreateDrugPriceListDomainError> {
+        if items.is_empty() {
+            Err(CreateDrugPriceListDomainError::NoPrices)?;
+        }
+        let valid_from = valid_from.unwrap_or(Utc::now());
+        if valid_to.is_some_and(|valid_to| valid_to <= valid_from) {
+            Err(CreateDrugPriceListDomainError::InvalidValidityPeriod)?;
+        }
+
+        let id = Uuid::new_v4();
+        let mut ids_hashset: HashSet<Uuid> = HashSet::new();
+        let mut prices: Vec<NewDrugPrice> = vec![];
+        for item in items {
+            let drug = drugs
+                .iter()
+                .find(|drug| drug.id == item.drug_id)
+                .ok_or(CreateDrugPriceListDomainError::DrugNotFound(item.drug_id))?;
+            if !drug
+                .variants
+                .iter()
+                .any(|variant| variant.id == item.variant_id)
+            {
+                Err(CreateDrugPriceListDomainError::VariantNotFound(
+                    item.drug_id,
+                    item.variant_id,
+                ))?;
+            }
+            if ids_hashset.contains(&item.variant_id) {
+                Err(CreateDrugPriceListDomainError::DuplicatedVariant(
+                    item.variant_id,
+                ))?;
+            }
+            if item.retail_price <= 0 || item.official_price <= 0 {
+                Err(CreateDrugPriceListDomainError::InvalidPrice(
+                    item.variant_id,
+                ))?;
+            }
+            if drug.prices.iter().any(|price| {
+                price.variant_id == item.variant_id && is_overlapping(price, valid_from, valid_to)
+            }) {
+                Err(CreateDrugPriceListDomainError::OverlappingPrice(
+                    item.variant_id,
+                ))?;
+            }
+
+            ids_hashset.insert(item.variant_id);
+            prices.push(NewDrugPrice {
+                id: Uuid::new_v4(),
+                price_list_id: id,
+                drug_id: item.drug_id,
+                variant_id: item.variant_id,
+                retail_price: item.retail_price,
+                official_price: item.official_price,
+                valid_from,
+                valid_to,
+            });
+        }
+
+        Ok(Self {
+            id,
+            valid_from,
+            valid_to,
+            prices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::CreateDrugPriceListDomainError;
+    use crate::domain::drugs::entities::{
+        Drug, DrugContentType, DrugPrice, DrugPriceListItem, DrugVariant, NewDrugPriceList,
+    };
+
+    fn create_mock_drug() -> Drug {
+        let drug_id = Uuid::new_v4();
+
+        Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![DrugVariant {
+                id: Uuid::new_v4(),
+                drug_id,
+                pills_count: Some(10),
+                volume_ml: None,
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_item(drug: &Drug, retail_price: i64, official_price: i64) -> DrugPriceListItem {
+        DrugPriceListItem {
+            drug_id: drug.id,
+            variant_id: drug.variants[0].id,
+            retail_price,
+            official_price,
+        }
+    }
+
+    #[test]
+    fn creates_price_list() {
+        let drug = create_mock_drug();
+        let drugs = vec![drug.clone()];
+        let valid_from = Utc::now() + Duration::days(1);
+
+        let sut = NewDrugPriceList::new(
+            Some(valid_from),
+            None,
+            vec![create_item(&drug, 1599, 1320)],
+            &drugs,
+        )
+        .unwrap();
+
+        assert_eq!(sut.valid_from, valid_from);
+        assert_eq!(sut.prices.len(), 1);
+        assert_eq!(sut.prices[0].price_list_id, sut.id);
+        assert_eq!(sut.prices[0].variant_id, drug.variants[0].id);
+        assert_eq!(sut.prices[0].retail_price, 1599);
+        assert_eq!(sut.prices[0].official_price, 1320);
+        assert_eq!(sut.prices[0].valid_from, valid_from);
+        assert_eq!(sut.prices[0].valid_to, None);
+    }
+
+    #[test]
+    fn doesnt_create_invalid_price_list() {
+        let drug = create_mock_drug();
+        let drugs = vec![drug.clone()];
+        let valid_from = Utc::now();
+
+        assert_eq!(
+            NewDrugPriceList::new(None, None, vec![], &drugs),
+            Err(CreateDrugPriceListDomainError::NoPrices)
+        );
+        assert_eq!(
+            NewDrugPriceList::new(
+                Some(valid_from),
+                Some(valid_from),
+                vec![create_item(&drug, 1599, 1320)],
+                &drugs
+            ),
+            Err(CreateDrugPriceListDomainError::InvalidValidityPeriod)
+        );
+        assert_eq!(
+            NewDrugPriceList::new(None, None, vec![create_item(&drug, 1599, 0)], &drugs),
+            Err(CreateDrugPriceListDomainError::InvalidPrice(
+                drug.variants[0].id
+            ))
+        );
+        assert_eq!(
+            NewDrugPriceList::new(
+                None,
+                None,
+                vec![
+                    create_item(&drug, 1599, 1320),
+                    create_item(&drug, 1499, 1320)
+                ],
+                &drugs
+            ),
+            Err(CreateDrugPriceListDomainError::DuplicatedVariant(
+                drug.variants[0].id
+            ))
+        );
+
+        let mut unknown_variant = create_item(&drug, 1599, 1320);
+        unknown_variant.variant_id = Uuid::new_v4();
+
+        assert_eq!(
+            NewDrugPriceList::new(None, None, vec![unknown_variant.clone()], &drugs),
+            Err(CreateDrugPriceListDomainError::VariantNotFound(
+                drug.id,
+                unknown_variant.variant_id
+            ))
+        );
+    }
+
+    #[test]
+    fn replaces_current_price_but_doesnt_overlap_other_prices() {
+        let mut drug = create_mock_drug();
+        let now = Utc::now();
+        drug.prices = vec![
+            DrugPrice {
+                id: Uuid::new_v4(),
+                price_list_id: Uuid::new_v4(),
+                drug_id: drug.id,
+                variant_id: drug.variants[0].id,
+                retail_price: 1599,
+                official_price: 1320,
+                valid_from: now - Duration::days(30),
+                valid_to: None,
+                created_at: now,
+                updated_at: now,
+            },
+            DrugPrice {
+                id: Uuid::new_v4(),
+                price_list_id: Uuid::new_v4(),
+                drug_id: drug.id,
+                variant_id: drug.variants[0].id,
+                retail_price: 1599,
+                official_price: 1320,
+                valid_from: now - Duration::days(90),
+                valid_to: Some(now - Duration::days(60)),
+                created_at: now,
+                updated_at: now,
+            },
+        ];
+        let drugs = vec![drug.clone()];
+
+        let sut = NewDrugPriceList::new(
+            Some(now),
+            None,
+            vec![create_item(&drug, 1699, 1420)],
+            &drugs,
+        );
+
+        assert!(sut.is_ok());
+
+        let sut = NewDrugPriceList::new(
+            Some(now - Duration::days(70)),
+            Some(now - Duration::days(40)),
+            vec![create_item(&drug, 1699, 1420)],
+            &drugs,
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateDrugPriceListDomainError::OverlappingPrice(
+                drug.variants[0].id
+            ))
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use uuid::Uuid;
+
+use crate::domain::drugs::entities::{Drug, NewDrugReimbursement, ReimbursementLevel};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugReimbursementDomainError {
+    #[error("Drug doesn't have a variant with id {0}")]
+    VariantNotFound(Uuid),
+    #[error("Drug variant with id {0} already has a reimbursement scheme")]
+    DuplicatedReimbursement(Uuid),
+    #[error("Reimbursement scheme must have at least one reimbursement level")]
+    NoLevels,
+    #[error("Reimbursement limit must be greater than 0")]
+    InvalidReimbursementLimit,
+}
+
+impl Drug {
+    pub fn create_reimbursement(
+        &self,
+        variant_id: Uuid,
+        levels: Vec<ReimbursementLevel>,
+        reimbursement_limit: i64,
+    ) -> Result<NewDrugReimbursement, CreateDrugReimbursementDomainError> {
+        if !self.variants.iter().any(|variant| variant.id == variant_id) {
+            Err(CreateDrugReimbursementDomainError::VariantNotFound(
+                variant_id,
+            ))?;
+        }
+        if self
+            .reimbursements
+            .iter()
+            .any(|reimbursement| reimbursement.variant_id == variant_id)
+        {
+            Err(CreateDrugReimbursementDomainError::DuplicatedReimbursement(
+                variant_id,
+            ))?;
+        }
+        let mut unique_levels: Vec<ReimbursementLevel> = vec![];
+        for level in levels {
+            if !unique_levels.contains(&level) {
+                unique_levels.push(level);
+            }
+        }
+        if unique_levels.is_empty() {
+            Err(CreateDrugReimbursementDomainError::NoLevels)?;
+        }
+        if reimbursement_limit <= 0 {
+            Err(CreateDrugReimbursementDomainError::InvalidReimbursementLimit)?;
+        }
+
+        Ok(NewDrugReimbursement {
+            id: Uuid::new_v4(),
+            drug_id: self.id,
+            variant_id,
+            levels: unique_levels,
+            reimbursement_limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::CreateDrugReimbursementDomainError;
+    use crate::domain::drugs::entities::{
+        Drug, DrugContentType, DrugReimbursement, DrugVariant, ReimbursementLevel,
+    };
+
+    fn create_mock_drug() -> Drug {
+        let drug_id = Uuid::new_v4();
+        let variant = |pills_count| DrugVariant {
+            id: Uuid::new_v4(),
+            drug_id,
+            pills_count: Some(pills_count),
+            volume_ml: None,
+            gtin: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let variants = vec![variant(10), variant(30)];
+
+        Drug {
+            id: drug_id,
+            name: "Polprazol".into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(20),
+            ml_per_pill: None,
+            active_substances: vec![],
+            reimbursements: vec![DrugReimbursement {
+                id: Uuid::new_v4(),
+                drug_id,
+                variant_id: variants[1].id,
+                levels: vec![ReimbursementLevel::FiftyPercent],
+                reimbursement_limit: 2000,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            prices: vec![],
+            external_id: None,
+            variants,
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn creates_reimbursement_of_drug_variant() {
+        let drug = create_mock_drug();
+
+        let sut = drug
+            .create_reimbursement(
+                drug.variants[0].id,
+                vec![
+                    ReimbursementLevel::ThirtyPercent,
+                    ReimbursementLevel::FreeForSeniors,
+                    ReimbursementLevel::ThirtyPercent,
+                ],
+                1250,
+            )
+            .unwrap();
+
+        assert_eq!(sut.drug_id, drug.id);
+        assert_eq!(sut.variant_id, drug.variants[0].id);
+        assert_eq!(
+            sut.levels,
+            vec![
+                ReimbursementLevel::ThirtyPercent,
+                ReimbursementLevel::FreeForSeniors
+            ]
+        );
+        assert_eq!(sut.reimbursement_limit, 1250);
+    }
+
+    #[test]
+    fn doesnt_create_reimbursement_of_unknown_or_already_reimbursed_variant() {
+        let drug = create_mock_drug();
+        let unknown_variant_id = Uuid::new_v4();
+
+        assert_eq!(
+            drug.create_reimbursement(unknown_variant_id, vec![ReimbursementLevel::FlatFee], 1250),
+            Err(CreateDrugReimbursementDomainError::VariantNotFound(
+                unknown_variant_id
+            ))
+        );
+        assert_eq!(
+            drug.create_reimbursement(drug.variants[1].id, vec![ReimbursementLevel::FlatFee], 1250),
+            Err(CreateDrugReimbursementDomainError::DuplicatedReimbursement(
+                drug.variants[1].id
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_create_reimbursement_without_levels_or_limit() {
+        let drug = create_mock_drug();
+
+        assert_eq!(
+            drug.create_reimbursement(drug.variants[0].id, vec![], 1250),
+            Err(CreateDrugReimbursementDomainError::NoLevels)
+        );
+        assert_eq!(
+            drug.create_reimbursement(drug.variants[0].id, vec![ReimbursementLevel::Full], 0),
+            Err(CreateDrugReimbursementDomainError::InvalidReimbursementLimit)
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use uuid::Uuid;
+
+use crate::domain::{
+    drugs::entities::{Drug, DrugContentType, NewDrugVariant},
+    utils::validators::validate_gtin::validate_gtin,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreateDrugVariantDomainError {
+    #[error("Pills count must be provided for pills")]
+    InvalidPillsCount,
+    #[error("Volume in ml must be provided for bottle of liquid")]
+    InvalidVolume,
+    #[error("Drug already has a variant of this size")]
+    DuplicatedVariant,
+    #[error("{0}")]
+    InvalidGtin(String),
+}
+
+impl Drug {
+    pub fn create_variant(
+        &self,
+        pills_count: Option<i32>,
+        volume_ml: Option<i32>,
+        gtin: Option<String>,
+    ) -> Result<NewDrugVariant, CreateDrugVariantDomainError> {
+        let (pills_count, volume_ml) = match self.content_type {
+            DrugContentType::SolidPills | DrugContentType::LiquidPills => {
+                if pills_count.is_none() || pills_count.unwrap() <= 0 {
+                    Err(CreateDrugVariantDomainError::InvalidPillsCount)?;
+                }
+                (pills_count, None)
+            }
+            DrugContentType::BottleOfLiquid => {
+                if volume_ml.is_none() || volume_ml.unwrap() <= 0 {
+                    Err(CreateDrugVariantDomainError::InvalidVolume)?;
+                }
+                (None, volume_ml)
+            }
+        };
+        if self
+            .variants
+            .iter()
+            .any(|variant| variant.pills_count == pills_count && variant.volume_ml == volume_ml)
+        {
+            Err(CreateDrugVariantDomainError::DuplicatedVariant)?;
+        }
+        if let Some(gtin) = &gtin {
+            validate_gtin(gtin)
+                .map_err(|err| CreateDrugVariantDomainError::InvalidGtin(err.to_string()))?;
+        }
+
+        Ok(NewDrugVariant {
+            id: Uuid::new_v4(),
+            drug_id: self.id,
+            pills_count,
+            volume_ml,
+            gtin,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::CreateDrugVariantDomainError;
+    use crate::domain::drugs::entities::{Drug, DrugContentType, DrugVariant};
+
+    fn create_mock_drug(content_type: DrugContentType) -> Drug {
+        let drug_id = Uuid::new_v4();
+
+        Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type,
+            mg_per_pill: None,
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![DrugVariant {
+                id: Uuid::new_v4(),
+                drug_id,
+                pills_count: Some(10),
+                volume_ml: None,
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn creates_variant_of_pills() {
+        let drug = create_mock_drug(DrugContentType::SolidPills);
+
+        let sut = drug.create_variant(Some(20), Some(200), None).unwrap();
+
+        assert_eq!(sut.drug_id, drug.id);
+        assert_eq!(sut.pills_count, Some(20));
+        assert_eq!(sut.volume_ml, None);
+    }
+
+    #[test]
+    fn creates_variant_of_bottle_of_liquid() {
+        let drug = create_mock_drug(DrugContentType::BottleOfLiquid);
+
+        let sut = drug.create_variant(Some(20), Some(200), None).unwrap();
+
+        assert_eq!(sut.drug_id, drug.id);
+        assert_eq!(sut.pills_count, None);
+        assert_eq!(sut.volume_ml, Some(200));
+    }
+
+    #[test]
+    fn doesnt_create_variant_without_size() {
+        let pills = create_mock_drug(DrugContentType::LiquidPills);
+        let bottle = create_mock_drug(DrugContentType::BottleOfLiquid);
+
+        assert_eq!(
+            pills.create_variant(None, Some(200), None),
+            Err(CreateDrugVariantDomainError::InvalidPillsCount)
+        );
+        assert_eq!(
+            pills.create_variant(Some(0), None, None),
+            Err(CreateDrugVariantDomainError::InvalidPillsCount)
+        );
+        assert_eq!(
+            bottle.create_variant(Some(20), None, None),
+            Err(CreateDrugVariantDomainError::InvalidVolume)
+        );
+    }
+
+    #[test]
+    fn doesnt_create_variant_of_already_existing_size() {
+        let drug = create_mock_drug(DrugContentType::SolidPills);
+
+        let sut = drug.create_variant(Some(10), None, None);
+
+        assert_eq!(sut, Err(CreateDrugVariantDomainError::DuplicatedVariant));
+    }
+    #[test]
+    fn creates_variant_with_gtin() {
+        let drug = create_mock_drug(DrugContentType::SolidPills);
+
+        let sut = drug
+            .create_variant(Some(20), None, Some("5909990733828".into()))
+            .unwrap();
+
+        assert_eq!(sut.gtin, Some("5909990733828".into()));
+        assert!(matches!(
+            drug.create_variant(Some(30), None, Some("5909990733820".into())),
+            Err(CreateDrugVariantDomainError::InvalidGtin(_))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::drugs::entities::{Drug, DrugPrice};
+
+impl Drug {
+    // Price of the package from the price list valid on the given date, so fills recorded in the
+    // past keep the price that applied then
+    pub fn get_price(&self, variant_id: Uuid, date: DateTime<Utc>) -> Option<&DrugPrice> {
+        self.prices.iter().find(|price| {
+            price.variant_id == variant_id
+                && price.valid_from <= date
+                && price.valid_to.map_or(true, |valid_to| valid_to > date)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::domain::drugs::entities::{Drug, DrugContentType, DrugPrice};
+
+    #[test]
+    fn gets_price_valid_on_given_date() {
+        let drug_id = Uuid::new_v4();
+        let variant_id = Uuid::new_v4();
+        let now = Utc::now();
+        let create_price = |retail_price, valid_from, valid_to| DrugPrice {
+            id: Uuid::new_v4(),
+            price_list_id: Uuid::new_v4(),
+            drug_id,
+            variant_id,
+            retail_price,
+            official_price: retail_price,
+            valid_from,
+            valid_to,
+            created_at: now,
+            updated_at: now,
+        };
+        let drug = Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![
+                create_price(
+                    1599,
+                    now - Duration::days(60),
+                    Some(now - Duration::days(30)),
+                ),
+                create_price(1699, now - Duration::days(30), None),
+            ],
+            external_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let get_retail_price = |date| {
+            drug.get_price(variant_id, date)
+                .map(|price| price.retail_price)
+        };
+
+        assert_eq!(get_retail_price(now - Duration::days(90)), None);
+        assert_eq!(get_retail_price(now - Duration::days(45)), Some(1599));
+        assert_eq!(get_retail_price(now - Duration::days(30)), Some(1699));
+        assert_eq!(get_retail_price(now), Some(1699));
+        assert_eq!(drug.get_price(Uuid::new_v4(), now), None);
+    }
+}
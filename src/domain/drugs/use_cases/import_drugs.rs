@@ -0,0 +1,367 @@
+use serde::Deserialize;
+
+use crate::domain::drugs::entities::{
+    Drug, DrugContentType, DrugImportFormat, DrugImportReport, DrugImportRow, DrugImportRowResult,
+    DrugImportRowStatus, NewDrug, NewDrugVariant,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ImportDrugsDomainError {
+    #[error("Invalid CSV file: {0}")]
+    InvalidCsvFile(String),
+    #[error("Invalid XML file: {0}")]
+    InvalidXmlFile(String),
+    #[error("File doesn't contain any drugs")]
+    NoDrugs,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ImportDrugRowDomainError {
+    #[error("External id is missing")]
+    MissingExternalId,
+    #[error("Name is missing")]
+    MissingName,
+    #[error("Content type is missing")]
+    MissingContentType,
+    #[error(
+        "Content type {0} is invalid, it must be SOLID_PILLS, LIQUID_PILLS or BOTTLE_OF_LIQUID"
+    )]
+    InvalidContentType(String),
+    #[error("Value {1} of {0} is not a whole number")]
+    InvalidNumber(&'static str, String),
+    #[error("External id {0} is listed more than once")]
+    DuplicatedExternalId(String),
+    #[error("Content type of the drug can't be changed from {0:?} to {1:?}")]
+    ContentTypeChanged(DrugContentType, DrugContentType),
+}
+
+// Root element of the XML export can have any name, drugs are its <drug> elements
+#[derive(Deserialize)]
+struct DrugImportXmlFile {
+    #[serde(rename = "drug", default)]
+    drugs: Vec<DrugImportRow>,
+}
+
+// CSV export must have a header row with the names of the DrugImportRow fields, XML export has
+// them as child elements of each <drug> element. Other columns and elements are ignored
+pub fn parse_drug_import_file(
+    content: &str,
+    format: DrugImportFormat,
+) -> Result<Vec<DrugImportRow>, ImportDrugsDomainError> {
+    let rows = match format {
+        DrugImportFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes())
+            .deserialize()
+            .collect::<Result<Vec<DrugImportRow>, _>>()
+            .map_err(|err| ImportDrugsDomainError::InvalidCsvFile(err.to_string()))?,
+        DrugImportFormat::Xml => {
+            quick_xml::de::from_str::<DrugImportXmlFile>(content)
+                .map_err(|err| ImportDrugsDomainError::InvalidXmlFile(err.to_string()))?
+                .drugs
+        }
+    };
+    if rows.is_empty() {
+        Err(ImportDrugsDomainError::NoDrugs)?;
+    }
+
+    Ok(rows)
+}
+
+// Empty cells and elements are treated as missing values
+fn get_value(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_number(
+    field: &'static str,
+    value: &Option<String>,
+) -> Result<Option<i32>, ImportDrugRowDomainError> {
+    get_value(value)
+        .map(|value| {
+            value
+                .parse::<i32>()
+                .map_err(|_| ImportDrugRowDomainError::InvalidNumber(field, value.into()))
+        })
+        .transpose()
+}
+
+impl DrugImportRow {
+    pub fn get_external_id(&self) -> Option<&str> {
+        get_value(&self.external_id)
+    }
+
+    // Row is validated in the same way as a drug created with POST /drugs
+    pub fn to_new_drug(&self) -> anyhow::Result<NewDrug> {
+        let external_id = self
+            .get_external_id()
+            .ok_or(ImportDrugRowDomainError::MissingExternalId)?;
+        let name = get_value(&self.name).ok_or(ImportDrugRowDomainError::MissingName)?;
+        let content_type = match get_value(&self.content_type)
+            .ok_or(ImportDrugRowDomainError::MissingContentType)?
+        {
+            "SOLID_PILLS" => DrugContentType::SolidPills,
+            "LIQUID_PILLS" => DrugContentType::LiquidPills,
+            "BOTTLE_OF_LIQUID" => DrugContentType::BottleOfLiquid,
+            content_type => Err(ImportDrugRowDomainError::InvalidContentType(
+                content_type.into(),
+            ))?,
+        };
+
+        let new_drug = NewDrug::new(
+            name.into(),
+            content_type,
+            parse_number("pills_count", &self.pills_count)?,
+            parse_number("mg_per_pill", &self.mg_per_pill)?,
+            parse_number("ml_per_pill", &self.ml_per_pill)?,
+            parse_number("volume_ml", &self.volume_ml)?,
+        )?
+        .with_classification(get_value(&self.atc_code).map(String::from), None)?
+        .with_gtin(get_value(&self.gtin).map(String::from))?;
+
+        Ok(NewDrug {
+            external_id: Some(external_id.into()),
+            ..new_drug
+        })
+    }
+}
+
+impl Drug {
+    // Data that isn't in the registry export (active substances, category, ATC code when it's
+    // missing) is kept. Package size from the export is returned as a new variant if the drug
+    // doesn't have it yet
+    pub fn update_from_import(
+        mut self,
+        new_drug: NewDrug,
+    ) -> Result<(Self, Option<NewDrugVariant>), ImportDrugRowDomainError> {
+        if self.content_type != new_drug.content_type {
+            Err(ImportDrugRowDomainError::ContentTypeChanged(
+                self.content_type,
+                new_drug.content_type,
+            ))?;
+        }
+
+        let new_variant = new_drug
+            .variants
+            .into_iter()
+            .find(|new_variant| {
+                !self.variants.iter().any(|variant| {
+                    variant.pills_count == new_variant.pills_count
+                        && variant.volume_ml == new_variant.volume_ml
+                })
+            })
+            .map(|new_variant| NewDrugVariant {
+                drug_id: self.id,
+                ..new_variant
+            });
+
+        self.name = new_drug.name;
+        self.mg_per_pill = new_drug.mg_per_pill;
+        self.ml_per_pill = new_drug.ml_per_pill;
+        if new_drug.atc_code.is_some() {
+            self.atc_code = new_drug.atc_code;
+        }
+
+        Ok((self, new_variant))
+    }
+}
+
+impl DrugImportReport {
+    pub fn new(rows: Vec<DrugImportRowResult>) -> Self {
+        let count = |status| rows.iter().filter(|row| row.status == status).count();
+
+        Self {
+            created_count: count(DrugImportRowStatus::Created),
+            updated_count: count(DrugImportRowStatus::Updated),
+            rejected_count: count(DrugImportRowStatus::Rejected),
+            rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::{parse_drug_import_file, ImportDrugRowDomainError, ImportDrugsDomainError};
+    use crate::domain::drugs::entities::{
+        Drug, DrugContentType, DrugImportFormat, DrugImportRow, DrugVariant,
+    };
+
+    fn create_row(external_id: &str, content_type: &str, pills_count: &str) -> DrugImportRow {
+        DrugImportRow {
+            external_id: Some(external_id.into()),
+            name: Some("Apap".into()),
+            content_type: Some(content_type.into()),
+            pills_count: Some(pills_count.into()),
+            mg_per_pill: Some("500".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_csv_and_xml_files() {
+        let csv = "external_id,name,content_type,pills_count,mg_per_pill,ml_per_pill,volume_ml,atc_code,gtin\n\
+            100001, Apap ,SOLID_PILLS,10,500,,,N02BE01,5909990733828\n\
+            100002,Syrop,BOTTLE_OF_LIQUID,,,,150,,\n";
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <drugs>
+                <drug>
+                    <external_id>100001</external_id>
+                    <name>Apap</name>
+                    <content_type>SOLID_PILLS</content_type>
+                    <pills_count>10</pills_count>
+                    <mg_per_pill>500</mg_per_pill>
+                    <atc_code>N02BE01</atc_code>
+                    <gtin>5909990733828</gtin>
+                </drug>
+                <drug>
+                    <external_id>100002</external_id>
+                    <name>Syrop</name>
+                    <content_type>BOTTLE_OF_LIQUID</content_type>
+                    <volume_ml>150</volume_ml>
+                    <atc_code/>
+                </drug>
+            </drugs>"#;
+
+        for (content, format) in [(csv, DrugImportFormat::Csv), (xml, DrugImportFormat::Xml)] {
+            let sut = parse_drug_import_file(content, format).unwrap();
+
+            assert_eq!(sut.len(), 2);
+
+            let apap = sut[0].to_new_drug().unwrap();
+
+            assert_eq!(apap.external_id, Some("100001".into()));
+            assert_eq!(apap.name, "Apap");
+            assert_eq!(apap.content_type, DrugContentType::SolidPills);
+            assert_eq!(apap.mg_per_pill, Some(500));
+            assert_eq!(apap.variants[0].pills_count, Some(10));
+            assert_eq!(apap.atc_code, Some("N02BE01".into()));
+            assert_eq!(apap.variants[0].gtin, Some("5909990733828".into()));
+
+            let syrop = sut[1].to_new_drug().unwrap();
+
+            assert_eq!(syrop.variants[0].volume_ml, Some(150));
+            assert_eq!(syrop.atc_code, None);
+            assert_eq!(syrop.variants[0].gtin, None);
+        }
+    }
+
+    #[test]
+    fn returns_error_if_file_is_invalid_or_empty() {
+        assert!(matches!(
+            parse_drug_import_file(
+                "external_id,name\n1,Apap,SOLID_PILLS\n",
+                DrugImportFormat::Csv
+            ),
+            Err(ImportDrugsDomainError::InvalidCsvFile(_))
+        ));
+        assert!(matches!(
+            parse_drug_import_file("<drugs><drug></drugs>", DrugImportFormat::Xml),
+            Err(ImportDrugsDomainError::InvalidXmlFile(_))
+        ));
+        assert_eq!(
+            parse_drug_import_file("external_id,name\n", DrugImportFormat::Csv),
+            Err(ImportDrugsDomainError::NoDrugs)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rows() {
+        let mut without_external_id = create_row("", "SOLID_PILLS", "10");
+        without_external_id.external_id = None;
+
+        for (row, expected_error) in [
+            (
+                without_external_id,
+                ImportDrugRowDomainError::MissingExternalId.to_string(),
+            ),
+            (
+                create_row("100001", "PILLS", "10"),
+                ImportDrugRowDomainError::InvalidContentType("PILLS".into()).to_string(),
+            ),
+            (
+                create_row("100001", "SOLID_PILLS", "ten"),
+                ImportDrugRowDomainError::InvalidNumber("pills_count", "ten".into()).to_string(),
+            ),
+            (
+                create_row("100001", "SOLID_PILLS", ""),
+                "Pills count and mg per pill must be provided for solid pills".into(),
+            ),
+        ] {
+            assert_eq!(row.to_new_drug().unwrap_err().to_string(), expected_error);
+        }
+    }
+
+    #[test]
+    fn updates_drug_from_import_adding_new_package_size() {
+        let drug_id = Uuid::new_v4();
+        let drug = Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![DrugVariant {
+                id: Uuid::new_v4(),
+                drug_id,
+                pills_count: Some(10),
+                volume_ml: None,
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            atc_code: Some("N02BE01".into()),
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: Some("100001".into()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let mut row = create_row("100001", "SOLID_PILLS", "10");
+        row.name = Some("Apap Extra".into());
+
+        let (sut, new_variant) = drug
+            .clone()
+            .update_from_import(row.to_new_drug().unwrap())
+            .unwrap();
+
+        assert_eq!(sut.id, drug_id);
+        assert_eq!(sut.name, "Apap Extra");
+        assert_eq!(sut.atc_code, Some("N02BE01".into()));
+        assert_eq!(new_variant, None);
+
+        let (_, new_variant) = drug
+            .clone()
+            .update_from_import(
+                create_row("100001", "SOLID_PILLS", "20")
+                    .to_new_drug()
+                    .unwrap(),
+            )
+            .unwrap();
+        let new_variant = new_variant.unwrap();
+
+        assert_eq!(new_variant.drug_id, drug_id);
+        assert_eq!(new_variant.pills_count, Some(20));
+
+        let mut row = create_row("100001", "BOTTLE_OF_LIQUID", "");
+        row.volume_ml = Some("100".into());
+
+        assert_eq!(
+            drug.update_from_import(row.to_new_drug().unwrap()),
+            Err(ImportDrugRowDomainError::ContentTypeChanged(
+                DrugContentType::SolidPills,
+                DrugContentType::BottleOfLiquid
+            ))
+        );
+    }
+}
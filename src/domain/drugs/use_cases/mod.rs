@@ -1 +1,11 @@
+pub mod create_atc_group;
 pub mod create_drug;
+pub mod create_drug_category;
+pub mod create_drug_equivalence_group;
+pub mod create_drug_interaction;
+pub mod create_drug_price_list;
+pub mod create_drug_reimbursement;
+pub mod create_drug_variant;
+pub mod get_drug_price;
+pub mod import_drugs;
+pub mod update_drug;
@@ -0,0 +1,166 @@
+use uuid::Uuid;
+
+use super::create_drug::{validate_drug_contents, validate_substance_atc_code};
+use crate::domain::drugs::entities::{ControlledSubstanceList, Drug, DrugContentType};
+
+impl Drug {
+    // Fields which are not given keep their current values. Content type can't be changed, as the
+    // variants describe packages of it, and only the amount per pill matching it is kept, the same
+    // as for a new drug
+    pub fn update(
+        mut self,
+        name: Option<String>,
+        mg_per_pill: Option<i32>,
+        ml_per_pill: Option<i32>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
+        controlled_substance_list: Option<ControlledSubstanceList>,
+    ) -> anyhow::Result<Self> {
+        let mg_per_pill = mg_per_pill.or(self.mg_per_pill);
+        let ml_per_pill = ml_per_pill.or(self.ml_per_pill);
+        let first_variant = self.variants.first();
+        validate_drug_contents(
+            self.content_type,
+            first_variant.and_then(|variant| variant.pills_count),
+            mg_per_pill,
+            ml_per_pill,
+            first_variant.and_then(|variant| variant.volume_ml),
+        )?;
+
+        if let Some(name) = name {
+            self.name = name;
+        }
+        (self.mg_per_pill, self.ml_per_pill) = match self.content_type {
+            DrugContentType::SolidPills => (mg_per_pill, None),
+            DrugContentType::LiquidPills => (None, ml_per_pill),
+            DrugContentType::BottleOfLiquid => (None, None),
+        };
+        if let Some(atc_code) = atc_code {
+            self.atc_code = Some(validate_substance_atc_code(&atc_code)?);
+        }
+        if category_id.is_some() {
+            self.category_id = category_id;
+        }
+        if controlled_substance_list.is_some() {
+            self.controlled_substance_list = controlled_substance_list;
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::drugs::{
+        entities::{ControlledSubstanceList, Drug, DrugContentType, DrugVariant},
+        use_cases::create_drug::CreateNewDrugDomainError,
+    };
+
+    fn create_mock_drug(content_type: DrugContentType) -> Drug {
+        let drug_id = Uuid::new_v4();
+        let is_pills = content_type != DrugContentType::BottleOfLiquid;
+
+        Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type,
+            mg_per_pill: (content_type == DrugContentType::SolidPills).then_some(500),
+            ml_per_pill: (content_type == DrugContentType::LiquidPills).then_some(5),
+            active_substances: vec![],
+            variants: vec![DrugVariant {
+                id: Uuid::new_v4(),
+                drug_id,
+                pills_count: is_pills.then_some(10),
+                volume_ml: (!is_pills).then_some(100),
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            atc_code: Some("N02BE01".into()),
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn updates_only_given_fields_of_drug() {
+        let drug = create_mock_drug(DrugContentType::SolidPills);
+        let category_id = Uuid::new_v4();
+
+        let sut = drug
+            .clone()
+            .update(
+                Some("Apap Extra".into()),
+                Some(1000),
+                Some(5),
+                None,
+                Some(category_id),
+                Some(ControlledSubstanceList::Narcotic),
+            )
+            .unwrap();
+
+        assert_eq!(sut.id, drug.id);
+        assert_eq!(sut.name, "Apap Extra");
+        assert_eq!(sut.mg_per_pill, Some(1000));
+        assert_eq!(sut.ml_per_pill, None);
+        assert_eq!(sut.atc_code, Some("N02BE01".into()));
+        assert_eq!(sut.category_id, Some(category_id));
+        assert_eq!(
+            sut.controlled_substance_list,
+            Some(ControlledSubstanceList::Narcotic)
+        );
+        assert_eq!(sut.variants, drug.variants);
+
+        let sut = drug
+            .clone()
+            .update(None, None, None, Some("n02be05".into()), None, None)
+            .unwrap();
+
+        assert_eq!(sut.name, "Apap");
+        assert_eq!(sut.mg_per_pill, Some(500));
+        assert_eq!(sut.atc_code, Some("N02BE05".into()));
+    }
+
+    #[test]
+    fn doesnt_update_drug_with_invalid_fields() {
+        let drug = create_mock_drug(DrugContentType::SolidPills);
+
+        assert_eq!(
+            drug.clone()
+                .update(None, Some(0), None, None, None, None)
+                .unwrap_err()
+                .downcast::<CreateNewDrugDomainError>()
+                .unwrap(),
+            CreateNewDrugDomainError::InvalidSolidPillsDescription
+        );
+        assert_eq!(
+            create_mock_drug(DrugContentType::LiquidPills)
+                .update(None, None, Some(-5), None, None, None)
+                .unwrap_err()
+                .downcast::<CreateNewDrugDomainError>()
+                .unwrap(),
+            CreateNewDrugDomainError::InvalidLiquidPillsDescription
+        );
+        assert_eq!(
+            drug.clone()
+                .update(None, None, None, Some("N02BE".into()), None, None)
+                .unwrap_err()
+                .downcast::<CreateNewDrugDomainError>()
+                .unwrap(),
+            CreateNewDrugDomainError::AtcCodeNotOfSubstance
+        );
+        assert!(drug
+            .update(None, None, None, Some("X".into()), None, None)
+            .is_err());
+    }
+}
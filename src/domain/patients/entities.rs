@@ -1,27 +1,85 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::utils::validators::validate_pesel_number::Sex;
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "identity_document_type", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IdentityDocumentType {
+    Passport,
+    EuIdCard,
+}
+
+fn example_document_number() -> &'static str {
+    "C01X00T47"
+}
+fn example_issuing_country() -> &'static str {
+    "DE"
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdentityDocument {
+    pub document_type: IdentityDocumentType,
+    #[schemars(example = "example_document_number")]
+    pub number: String,
+    #[schemars(
+        example = "example_issuing_country",
+        description = "ISO 3166-1 alpha-2 code of the country that issued the document"
+    )]
+    pub issuing_country: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct NewPatient {
     pub id: Uuid,
     pub name: String,
-    pub pesel_number: String,
+    pub pesel_number: Option<String>,
+    pub identity_document: Option<IdentityDocument>,
+    pub parent_pesel_number: Option<String>,
+    pub birth_date: NaiveDate,
+    pub sex: Sex,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PatientUpdate {
+    pub id: Uuid,
+    pub name: String,
+    pub pesel_number: Option<String>,
+    pub identity_document: Option<IdentityDocument>,
+    pub parent_pesel_number: Option<String>,
+    pub birth_date: NaiveDate,
+    pub sex: Sex,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Patient {
     pub id: Uuid,
     pub name: String,
-    pub pesel_number: String,
+    pub pesel_number: Option<String>,
+    #[schemars(description = "Identity document of a patient without a PESEL number")]
+    pub identity_document: Option<IdentityDocument>,
+    #[schemars(description = "PESEL number of a parent of a newborn without a PESEL number")]
+    pub parent_pesel_number: Option<String>,
+    #[schemars(description = "Derived from the PESEL number when the patient has one")]
+    pub birth_date: NaiveDate,
+    #[schemars(description = "Derived from the PESEL number when the patient has one")]
+    pub sex: Sex,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl PartialEq<NewPatient> for Patient {
     fn eq(&self, other: &NewPatient) -> bool {
-        self.id == other.id && self.name == other.name && self.pesel_number == other.pesel_number
+        self.id == other.id
+            && self.name == other.name
+            && self.pesel_number == other.pesel_number
+            && self.identity_document == other.identity_document
+            && self.parent_pesel_number == other.parent_pesel_number
+            && self.birth_date == other.birth_date
+            && self.sex == other.sex
     }
 }
 
@@ -30,3 +88,61 @@ impl PartialEq<Patient> for NewPatient {
         other.eq(self)
     }
 }
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[sqlx(type_name = "allergy_severity", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AllergySeverity {
+    Mild,
+    Moderate,
+    Severe,
+    LifeThreatening,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewPatientAllergy {
+    pub id: Uuid,
+    pub patient_id: Uuid,
+    pub substance: String,
+    pub reaction: String,
+    pub severity: AllergySeverity,
+    pub recorded_by_doctor_id: Uuid,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PatientAllergyUpdate {
+    pub id: Uuid,
+    pub patient_id: Uuid,
+    pub reaction: String,
+    pub severity: AllergySeverity,
+    pub recorded_by_doctor_id: Uuid,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PatientAllergy {
+    pub id: Uuid,
+    pub patient_id: Uuid,
+    pub substance: String,
+    pub reaction: String,
+    pub severity: AllergySeverity,
+    pub recorded_by_doctor_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewPatientAllergy> for PatientAllergy {
+    fn eq(&self, other: &NewPatientAllergy) -> bool {
+        self.id == other.id
+            && self.patient_id == other.patient_id
+            && self.substance == other.substance
+            && self.reaction == other.reaction
+            && self.severity == other.severity
+            && self.recorded_by_doctor_id == other.recorded_by_doctor_id
+    }
+}
+
+impl PartialEq<PatientAllergy> for NewPatientAllergy {
+    fn eq(&self, other: &PatientAllergy) -> bool {
+        other.eq(self)
+    }
+}
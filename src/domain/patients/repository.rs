@@ -5,7 +5,9 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::domain::{
-    patients::entities::{NewPatient, Patient},
+    patients::entities::{
+        NewPatient, NewPatientAllergy, Patient, PatientAllergy, PatientAllergyUpdate, PatientUpdate,
+    },
     utils::pagination::get_pagination_params,
 };
 
@@ -13,6 +15,8 @@ use crate::domain::{
 pub enum CreatePatientRepositoryError {
     #[error("PESEL number already exists")]
     DuplicatedPeselNumber,
+    #[error("Patient with this identity document already exists")]
+    DuplicatedIdentityDocument,
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -33,6 +37,56 @@ pub enum GetPatientByIdRepositoryError {
     DatabaseError(String),
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum UpdatePatientRepositoryError {
+    #[error("Patient with this id not found ({0})")]
+    NotFound(Uuid),
+    #[error("PESEL number already exists")]
+    DuplicatedPeselNumber,
+    #[error("Patient with this identity document already exists")]
+    DuplicatedIdentityDocument,
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreatePatientAllergyRepositoryError {
+    #[error("Patient with id {0} not found")]
+    PatientNotFound(Uuid),
+    #[error("Doctor with id {0} not found")]
+    DoctorNotFound(Uuid),
+    #[error("Patient already has an allergy to this substance")]
+    DuplicatedAllergy,
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetPatientAllergiesRepositoryError {
+    #[error("Patient with id {0} not found")]
+    PatientNotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum UpdatePatientAllergyRepositoryError {
+    #[error("Allergy with id {0} not found")]
+    NotFound(Uuid),
+    #[error("Doctor with id {0} not found")]
+    DoctorNotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum DeletePatientAllergyRepositoryError {
+    #[error("Allergy with id {0} not found")]
+    NotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
 #[async_trait]
 pub trait PatientsRepository: Send + Sync + 'static {
     async fn create_patient(
@@ -48,10 +102,32 @@ pub trait PatientsRepository: Send + Sync + 'static {
         &self,
         patient_id: Uuid,
     ) -> Result<Patient, GetPatientByIdRepositoryError>;
+    async fn update_patient(
+        &self,
+        patient_update: PatientUpdate,
+    ) -> Result<Patient, UpdatePatientRepositoryError>;
+    async fn create_patient_allergy(
+        &self,
+        allergy: NewPatientAllergy,
+    ) -> Result<PatientAllergy, CreatePatientAllergyRepositoryError>;
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, GetPatientAllergiesRepositoryError>;
+    async fn update_patient_allergy(
+        &self,
+        allergy_update: PatientAllergyUpdate,
+    ) -> Result<PatientAllergy, UpdatePatientAllergyRepositoryError>;
+    async fn delete_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        allergy_id: Uuid,
+    ) -> Result<(), DeletePatientAllergyRepositoryError>;
 }
 
 pub struct PatientsRepositoryFake {
     patients: RwLock<Vec<Patient>>,
+    allergies: RwLock<Vec<PatientAllergy>>,
 }
 
 impl PatientsRepositoryFake {
@@ -59,6 +135,7 @@ impl PatientsRepositoryFake {
     pub fn new() -> Self {
         Self {
             patients: RwLock::new(Vec::new()),
+            allergies: RwLock::new(Vec::new()),
         }
     }
 }
@@ -69,21 +146,31 @@ impl PatientsRepository for PatientsRepositoryFake {
         &self,
         new_patient: NewPatient,
     ) -> Result<Patient, CreatePatientRepositoryError> {
-        let does_pesel_number_exist = self
-            .patients
-            .read()
-            .unwrap()
-            .iter()
-            .any(|patient| patient.pesel_number == new_patient.pesel_number);
+        let does_pesel_number_exist = self.patients.read().unwrap().iter().any(|patient| {
+            new_patient.pesel_number.is_some() && patient.pesel_number == new_patient.pesel_number
+        });
 
         if does_pesel_number_exist {
             return Err(CreatePatientRepositoryError::DuplicatedPeselNumber);
         }
 
+        let does_identity_document_exist = self.patients.read().unwrap().iter().any(|patient| {
+            new_patient.identity_document.is_some()
+                && patient.identity_document == new_patient.identity_document
+        });
+
+        if does_identity_document_exist {
+            return Err(CreatePatientRepositoryError::DuplicatedIdentityDocument);
+        }
+
         let patient = Patient {
             id: new_patient.id,
             name: new_patient.name,
             pesel_number: new_patient.pesel_number,
+            identity_document: new_patient.identity_document,
+            parent_pesel_number: new_patient.parent_pesel_number,
+            birth_date: new_patient.birth_date,
+            sex: new_patient.sex,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -129,6 +216,143 @@ impl PatientsRepository for PatientsRepositoryFake {
             None => Err(GetPatientByIdRepositoryError::NotFound(patient_id)),
         }
     }
+
+    async fn update_patient(
+        &self,
+        patient_update: PatientUpdate,
+    ) -> Result<Patient, UpdatePatientRepositoryError> {
+        let mut patients = self.patients.write().unwrap();
+        let index = patients
+            .iter()
+            .position(|patient| patient.id == patient_update.id)
+            .ok_or(UpdatePatientRepositoryError::NotFound(patient_update.id))?;
+        let other_patients = || {
+            patients
+                .iter()
+                .filter(|patient| patient.id != patient_update.id)
+        };
+
+        let does_pesel_number_exist = other_patients().any(|patient| {
+            patient_update.pesel_number.is_some()
+                && patient.pesel_number == patient_update.pesel_number
+        });
+
+        if does_pesel_number_exist {
+            return Err(UpdatePatientRepositoryError::DuplicatedPeselNumber);
+        }
+
+        let does_identity_document_exist = other_patients().any(|patient| {
+            patient_update.identity_document.is_some()
+                && patient.identity_document == patient_update.identity_document
+        });
+
+        if does_identity_document_exist {
+            return Err(UpdatePatientRepositoryError::DuplicatedIdentityDocument);
+        }
+
+        let patient = &mut patients[index];
+
+        patient.name = patient_update.name;
+        patient.pesel_number = patient_update.pesel_number;
+        patient.identity_document = patient_update.identity_document;
+        patient.parent_pesel_number = patient_update.parent_pesel_number;
+        patient.birth_date = patient_update.birth_date;
+        patient.sex = patient_update.sex;
+        patient.updated_at = Utc::now();
+
+        Ok(patient.clone())
+    }
+
+    async fn create_patient_allergy(
+        &self,
+        new_allergy: NewPatientAllergy,
+    ) -> Result<PatientAllergy, CreatePatientAllergyRepositoryError> {
+        self.get_patient_by_id(new_allergy.patient_id)
+            .await
+            .map_err(|_| {
+                CreatePatientAllergyRepositoryError::PatientNotFound(new_allergy.patient_id)
+            })?;
+
+        let is_duplicated = self.allergies.read().unwrap().iter().any(|allergy| {
+            allergy.patient_id == new_allergy.patient_id
+                && allergy.substance == new_allergy.substance
+        });
+        if is_duplicated {
+            return Err(CreatePatientAllergyRepositoryError::DuplicatedAllergy);
+        }
+
+        let allergy = PatientAllergy {
+            id: new_allergy.id,
+            patient_id: new_allergy.patient_id,
+            substance: new_allergy.substance,
+            reaction: new_allergy.reaction,
+            severity: new_allergy.severity,
+            recorded_by_doctor_id: new_allergy.recorded_by_doctor_id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        self.allergies.write().unwrap().push(allergy.clone());
+
+        Ok(allergy)
+    }
+
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, GetPatientAllergiesRepositoryError> {
+        self.get_patient_by_id(patient_id)
+            .await
+            .map_err(|_| GetPatientAllergiesRepositoryError::PatientNotFound(patient_id))?;
+
+        let allergies = self
+            .allergies
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|allergy| allergy.patient_id == patient_id)
+            .cloned()
+            .collect();
+
+        Ok(allergies)
+    }
+
+    async fn update_patient_allergy(
+        &self,
+        allergy_update: PatientAllergyUpdate,
+    ) -> Result<PatientAllergy, UpdatePatientAllergyRepositoryError> {
+        let mut allergies = self.allergies.write().unwrap();
+        let allergy = allergies
+            .iter_mut()
+            .find(|allergy| {
+                allergy.id == allergy_update.id && allergy.patient_id == allergy_update.patient_id
+            })
+            .ok_or(UpdatePatientAllergyRepositoryError::NotFound(
+                allergy_update.id,
+            ))?;
+
+        allergy.reaction = allergy_update.reaction;
+        allergy.severity = allergy_update.severity;
+        allergy.recorded_by_doctor_id = allergy_update.recorded_by_doctor_id;
+        allergy.updated_at = Utc::now();
+
+        Ok(allergy.clone())
+    }
+
+    async fn delete_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        allergy_id: Uuid,
+    ) -> Result<(), DeletePatientAllergyRepositoryError> {
+        let mut allergies = self.allergies.write().unwrap();
+        let index = allergies
+            .iter()
+            .position(|allergy| allergy.id == allergy_id && allergy.patient_id == patient_id)
+            .ok_or(DeletePatientAllergyRepositoryError::NotFound(allergy_id))?;
+        allergies.remove(index);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -137,10 +361,14 @@ mod tests {
 
     use super::PatientsRepositoryFake;
     use crate::domain::patients::{
-        entities::NewPatient,
+        entities::{
+            AllergySeverity, NewPatient, NewPatientAllergy, PatientAllergyUpdate, PatientUpdate,
+        },
         repository::{
-            CreatePatientRepositoryError, GetPatientByIdRepositoryError,
-            GetPatientsRepositoryError, PatientsRepository,
+            CreatePatientAllergyRepositoryError, CreatePatientRepositoryError,
+            DeletePatientAllergyRepositoryError, GetPatientAllergiesRepositoryError,
+            GetPatientByIdRepositoryError, GetPatientsRepositoryError, PatientsRepository,
+            UpdatePatientAllergyRepositoryError, UpdatePatientRepositoryError,
         },
     };
 
@@ -258,4 +486,187 @@ mod tests {
             Err(CreatePatientRepositoryError::DuplicatedPeselNumber)
         );
     }
+
+    #[tokio::test]
+    async fn updates_patient() {
+        let repository = setup_repository();
+
+        let new_patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        let patient = repository.create_patient(new_patient).await.unwrap();
+
+        let updated_patient = repository
+            .update_patient(PatientUpdate {
+                id: patient.id,
+                name: "John Smith".into(),
+                pesel_number: Some("96021807250".into()),
+                identity_document: None,
+                parent_pesel_number: None,
+                birth_date: patient.birth_date,
+                sex: patient.sex,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_patient.name, "John Smith");
+        assert_eq!(updated_patient.pesel_number, Some("96021807250".into()));
+        assert_eq!(updated_patient.created_at, patient.created_at);
+        assert!(updated_patient.updated_at > patient.updated_at);
+        assert_eq!(
+            repository.get_patient_by_id(patient.id).await.unwrap(),
+            updated_patient
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_update_patient_if_it_doesnt_exist_or_pesel_number_is_duplicated() {
+        let repository = setup_repository();
+
+        let patient_0 = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        let patient_1 = NewPatient::new("John Doe".into(), "99031301347".into()).unwrap();
+        repository.create_patient(patient_0.clone()).await.unwrap();
+        repository.create_patient(patient_1.clone()).await.unwrap();
+
+        let patient_update = PatientUpdate {
+            id: patient_1.id,
+            name: patient_1.name,
+            pesel_number: patient_0.pesel_number,
+            identity_document: None,
+            parent_pesel_number: None,
+            birth_date: patient_1.birth_date,
+            sex: patient_1.sex,
+        };
+
+        assert_eq!(
+            repository.update_patient(patient_update.clone()).await,
+            Err(UpdatePatientRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let patient_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_patient(PatientUpdate {
+                    id: patient_id,
+                    pesel_number: Some("92022900002".into()),
+                    ..patient_update
+                })
+                .await,
+            Err(UpdatePatientRepositoryError::NotFound(patient_id))
+        );
+    }
+
+    #[tokio::test]
+    async fn creates_updates_and_deletes_patient_allergies() {
+        let repository = setup_repository();
+        let patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        repository.create_patient(patient.clone()).await.unwrap();
+        let doctor_id = Uuid::new_v4();
+
+        let new_allergy = NewPatientAllergy::new(
+            patient.id,
+            "penicillin".into(),
+            "Rash".into(),
+            AllergySeverity::Mild,
+            doctor_id,
+        )
+        .unwrap();
+        let created_allergy = repository
+            .create_patient_allergy(new_allergy.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_allergy, new_allergy);
+        assert_eq!(
+            repository.create_patient_allergy(new_allergy.clone()).await,
+            Err(CreatePatientAllergyRepositoryError::DuplicatedAllergy)
+        );
+
+        let updated_allergy = repository
+            .update_patient_allergy(
+                PatientAllergyUpdate::new(
+                    new_allergy.id,
+                    patient.id,
+                    "Anaphylaxis".into(),
+                    AllergySeverity::LifeThreatening,
+                    doctor_id,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_allergy.reaction, "Anaphylaxis");
+        assert_eq!(updated_allergy.severity, AllergySeverity::LifeThreatening);
+
+        let allergies = repository.get_patient_allergies(patient.id).await.unwrap();
+
+        assert_eq!(allergies, vec![updated_allergy]);
+
+        repository
+            .delete_patient_allergy(patient.id, new_allergy.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .get_patient_allergies(patient.id)
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            repository
+                .delete_patient_allergy(patient.id, new_allergy.id)
+                .await,
+            Err(DeletePatientAllergyRepositoryError::NotFound(
+                new_allergy.id
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_allergy_patient_doesnt_exist() {
+        let repository = setup_repository();
+        let patient_id = Uuid::new_v4();
+
+        let new_allergy = NewPatientAllergy::new(
+            patient_id,
+            "penicillin".into(),
+            "Rash".into(),
+            AllergySeverity::Mild,
+            Uuid::new_v4(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository.create_patient_allergy(new_allergy.clone()).await,
+            Err(CreatePatientAllergyRepositoryError::PatientNotFound(
+                patient_id
+            ))
+        );
+        assert_eq!(
+            repository.get_patient_allergies(patient_id).await,
+            Err(GetPatientAllergiesRepositoryError::PatientNotFound(
+                patient_id
+            ))
+        );
+        assert_eq!(
+            repository
+                .update_patient_allergy(
+                    PatientAllergyUpdate::new(
+                        new_allergy.id,
+                        patient_id,
+                        "Rash".into(),
+                        AllergySeverity::Mild,
+                        Uuid::new_v4(),
+                    )
+                    .unwrap()
+                )
+                .await,
+            Err(UpdatePatientAllergyRepositoryError::NotFound(
+                new_allergy.id
+            ))
+        );
+    }
 }
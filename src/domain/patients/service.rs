@@ -1,11 +1,24 @@
+use chrono::NaiveDate;
 use uuid::Uuid;
 
-use super::repository::{
-    CreatePatientRepositoryError, GetPatientByIdRepositoryError, GetPatientsRepositoryError,
+use super::{
+    repository::{
+        CreatePatientAllergyRepositoryError, CreatePatientRepositoryError,
+        DeletePatientAllergyRepositoryError, GetPatientAllergiesRepositoryError,
+        GetPatientByIdRepositoryError, GetPatientsRepositoryError,
+        UpdatePatientAllergyRepositoryError, UpdatePatientRepositoryError,
+    },
+    use_cases::create_patient_allergy::PatientAllergyDomainError,
 };
-use crate::domain::patients::{
-    entities::{NewPatient, Patient},
-    repository::PatientsRepository,
+use crate::domain::{
+    patients::{
+        entities::{
+            AllergySeverity, IdentityDocument, NewPatient, NewPatientAllergy, Patient,
+            PatientAllergy, PatientAllergyUpdate, PatientUpdate,
+        },
+        repository::PatientsRepository,
+    },
+    utils::validators::validate_pesel_number::Sex,
 };
 
 #[derive(Debug)]
@@ -24,6 +37,35 @@ pub enum GetPatientsWithPaginationError {
     RepositoryError(GetPatientsRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum UpdatePatientError {
+    DomainError(String),
+    RepositoryError(UpdatePatientRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum CreatePatientAllergyError {
+    DomainError(String),
+    RepositoryError(CreatePatientAllergyRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum GetPatientAllergiesError {
+    RepositoryError(GetPatientAllergiesRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum UpdatePatientAllergyError {
+    DomainError(PatientAllergyDomainError),
+    RepositoryError(UpdatePatientAllergyRepositoryError),
+}
+
+#[derive(Debug)]
+pub enum DeletePatientAllergyError {
+    DomainError(PatientAllergyDomainError),
+    RepositoryError(DeletePatientAllergyRepositoryError),
+}
+
 pub struct PatientsService {
     repository: Box<dyn PatientsRepository>,
 }
@@ -36,10 +78,21 @@ impl PatientsService {
     pub async fn create_patient(
         &self,
         name: String,
-        pesel_number: String,
+        pesel_number: Option<String>,
+        identity_document: Option<IdentityDocument>,
+        parent_pesel_number: Option<String>,
+        birth_date: Option<NaiveDate>,
+        sex: Option<Sex>,
     ) -> Result<Patient, CreatePatientError> {
-        let new_patient = NewPatient::new(name, pesel_number)
-            .map_err(|err| CreatePatientError::DomainError(err.to_string()))?;
+        let new_patient = NewPatient::from_identifiers(
+            name,
+            pesel_number,
+            identity_document,
+            parent_pesel_number,
+            birth_date,
+            sex,
+        )
+        .map_err(|err| CreatePatientError::DomainError(err.to_string()))?;
 
         let created_patient = self
             .repository
@@ -76,14 +129,167 @@ impl PatientsService {
 
         Ok(patients)
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_patient(
+        &self,
+        patient_id: Uuid,
+        name: Option<String>,
+        pesel_number: Option<String>,
+        identity_document: Option<IdentityDocument>,
+        parent_pesel_number: Option<String>,
+        birth_date: Option<NaiveDate>,
+        sex: Option<Sex>,
+    ) -> Result<Patient, UpdatePatientError> {
+        let patient = self
+            .repository
+            .get_patient_by_id(patient_id)
+            .await
+            .map_err(|err| match err {
+                GetPatientByIdRepositoryError::NotFound(id) => {
+                    UpdatePatientError::RepositoryError(UpdatePatientRepositoryError::NotFound(id))
+                }
+                GetPatientByIdRepositoryError::DatabaseError(message) => {
+                    UpdatePatientError::RepositoryError(
+                        UpdatePatientRepositoryError::DatabaseError(message),
+                    )
+                }
+            })?;
+
+        let patient_update = PatientUpdate::new(
+            &patient,
+            name,
+            pesel_number,
+            identity_document,
+            parent_pesel_number,
+            birth_date,
+            sex,
+        )
+        .map_err(|err| UpdatePatientError::DomainError(err.to_string()))?;
+
+        let updated_patient = self
+            .repository
+            .update_patient(patient_update)
+            .await
+            .map_err(UpdatePatientError::RepositoryError)?;
+
+        Ok(updated_patient)
+    }
+
+    pub async fn create_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        substance: String,
+        reaction: String,
+        severity: AllergySeverity,
+        doctor_id: Uuid,
+    ) -> Result<PatientAllergy, CreatePatientAllergyError> {
+        let new_allergy =
+            NewPatientAllergy::new(patient_id, substance, reaction, severity, doctor_id)
+                .map_err(|err| CreatePatientAllergyError::DomainError(err.to_string()))?;
+
+        let created_allergy = self
+            .repository
+            .create_patient_allergy(new_allergy)
+            .await
+            .map_err(CreatePatientAllergyError::RepositoryError)?;
+
+        Ok(created_allergy)
+    }
+
+    pub async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, GetPatientAllergiesError> {
+        let allergies = self
+            .repository
+            .get_patient_allergies(patient_id)
+            .await
+            .map_err(GetPatientAllergiesError::RepositoryError)?;
+
+        Ok(allergies)
+    }
+
+    pub async fn update_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        allergy_id: Uuid,
+        reaction: String,
+        severity: AllergySeverity,
+        doctor_id: Uuid,
+    ) -> Result<PatientAllergy, UpdatePatientAllergyError> {
+        let allergy_update =
+            PatientAllergyUpdate::new(allergy_id, patient_id, reaction, severity, doctor_id)
+                .map_err(UpdatePatientAllergyError::DomainError)?;
+
+        let updated_allergy = self
+            .repository
+            .update_patient_allergy(allergy_update)
+            .await
+            .map_err(UpdatePatientAllergyError::RepositoryError)?;
+
+        Ok(updated_allergy)
+    }
+
+    pub async fn delete_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        allergy_id: Uuid,
+        doctor_id: Uuid,
+    ) -> Result<(), DeletePatientAllergyError> {
+        let allergy = self
+            .repository
+            .get_patient_allergies(patient_id)
+            .await
+            .map_err(|err| match err {
+                GetPatientAllergiesRepositoryError::PatientNotFound(_) => {
+                    DeletePatientAllergyError::RepositoryError(
+                        DeletePatientAllergyRepositoryError::NotFound(allergy_id),
+                    )
+                }
+                _ => DeletePatientAllergyError::RepositoryError(
+                    DeletePatientAllergyRepositoryError::DatabaseError(err.to_string()),
+                ),
+            })?
+            .into_iter()
+            .find(|allergy| allergy.id == allergy_id)
+            .ok_or(DeletePatientAllergyError::RepositoryError(
+                DeletePatientAllergyRepositoryError::NotFound(allergy_id),
+            ))?;
+
+        allergy
+            .check_deletion(doctor_id)
+            .map_err(DeletePatientAllergyError::DomainError)?;
+
+        self.repository
+            .delete_patient_allergy(patient_id, allergy_id)
+            .await
+            .map_err(DeletePatientAllergyError::RepositoryError)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{NaiveDate, Utc};
     use uuid::Uuid;
 
-    use super::PatientsService;
-    use crate::domain::patients::repository::PatientsRepositoryFake;
+    use super::{
+        CreatePatientError, DeletePatientAllergyError, PatientsService, UpdatePatientAllergyError,
+        UpdatePatientError,
+    };
+    use crate::domain::{
+        patients::{
+            entities::{AllergySeverity, IdentityDocument, IdentityDocumentType},
+            repository::{
+                CreatePatientRepositoryError, DeletePatientAllergyRepositoryError,
+                PatientsRepositoryFake, UpdatePatientRepositoryError,
+            },
+            use_cases::create_patient_allergy::PatientAllergyDomainError,
+        },
+        utils::validators::validate_pesel_number::Sex,
+    };
 
     fn setup_service() -> PatientsService {
         PatientsService::new(Box::new(PatientsRepositoryFake::new()))
@@ -94,25 +300,48 @@ mod tests {
         let service = setup_service();
 
         let created_patient = service
-            .create_patient("John Doex".into(), "96021807250".into())
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
         assert_eq!(created_patient.name, "John Doex");
-        assert_eq!(created_patient.pesel_number, "96021807250");
+        assert_eq!(created_patient.pesel_number, Some("96021807250".into()));
 
         let patient_from_repository = service.get_patient_by_id(created_patient.id).await.unwrap();
 
         assert_eq!(patient_from_repository.name, "John Doex");
-        assert_eq!(patient_from_repository.pesel_number, "96021807250");
+        assert_eq!(
+            patient_from_repository.pesel_number,
+            Some("96021807250".into())
+        );
+        assert_eq!(
+            patient_from_repository.birth_date,
+            NaiveDate::from_ymd_opt(1996, 2, 18).unwrap()
+        );
+        assert_eq!(patient_from_repository.sex, Sex::Male);
     }
 
     #[tokio::test]
     async fn create_patient_returns_error_if_body_is_incorrect() {
         let service = setup_service();
 
+        // invalid pesel
         let result = service
-            .create_patient("John Doex".into(), "96021807251".into()) // invalid pesel
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807251".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await;
 
         assert!(result.is_err());
@@ -123,17 +352,229 @@ mod tests {
         let service = setup_service();
 
         service
-            .create_patient("John Doex".into(), "96021807250".into())
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
         let duplicated_pesel_number_result = service
-            .create_patient("John Doex".into(), "96021807250".into())
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await;
 
         assert!(duplicated_pesel_number_result.is_err());
     }
 
+    #[tokio::test]
+    async fn creates_patients_without_pesel_number() {
+        let service = setup_service();
+        let identity_document = IdentityDocument {
+            document_type: IdentityDocumentType::Passport,
+            number: "C01X00T47".into(),
+            issuing_country: "US".into(),
+        };
+        let birth_date = NaiveDate::from_ymd_opt(1988, 4, 12).unwrap();
+
+        let foreign_patient = service
+            .create_patient(
+                "John Doex".into(),
+                None,
+                Some(identity_document.clone()),
+                None,
+                Some(birth_date),
+                Some(Sex::Male),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(foreign_patient.pesel_number, None);
+        assert_eq!(
+            foreign_patient.identity_document,
+            Some(identity_document.clone())
+        );
+        assert_eq!(foreign_patient.birth_date, birth_date);
+
+        service
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let newborn = service
+            .create_patient(
+                "Baby Doex".into(),
+                None,
+                None,
+                Some("96021807250".into()),
+                Some(Utc::now().date_naive()),
+                Some(Sex::Female),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(newborn.parent_pesel_number, Some("96021807250".into()));
+
+        let duplicated_identity_document_result = service
+            .create_patient(
+                "Jane Doex".into(),
+                None,
+                Some(identity_document),
+                None,
+                Some(birth_date),
+                Some(Sex::Female),
+            )
+            .await;
+
+        assert!(matches!(
+            duplicated_identity_document_result,
+            Err(CreatePatientError::RepositoryError(
+                CreatePatientRepositoryError::DuplicatedIdentityDocument
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn updates_patient() {
+        let service = setup_service();
+
+        service
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let newborn = service
+            .create_patient(
+                "Baby Doex".into(),
+                None,
+                None,
+                Some("96021807250".into()),
+                Some(NaiveDate::from_ymd_opt(2002, 7, 8).unwrap()),
+                Some(Sex::Female),
+            )
+            .await
+            .unwrap();
+
+        let updated_patient = service
+            .update_patient(
+                newborn.id,
+                Some("Jane Doex".into()),
+                Some("02270803626".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_patient.name, "Jane Doex");
+        assert_eq!(updated_patient.pesel_number, Some("02270803626".into()));
+        assert_eq!(updated_patient.parent_pesel_number, None);
+        assert_eq!(updated_patient.birth_date, newborn.birth_date);
+
+        let patient_from_repository = service.get_patient_by_id(newborn.id).await.unwrap();
+
+        assert_eq!(patient_from_repository, updated_patient);
+    }
+
+    #[tokio::test]
+    async fn update_patient_returns_error_if_patient_doesnt_exist_or_body_is_incorrect() {
+        let service = setup_service();
+
+        let patient = service
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service
+            .create_patient(
+                "John Doey".into(),
+                Some("99031301347".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .update_patient(
+                    patient.id,
+                    None,
+                    Some("96021807251".into()),
+                    None,
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdatePatientError::DomainError(_))
+        ));
+        assert!(matches!(
+            service
+                .update_patient(
+                    patient.id,
+                    None,
+                    Some("99031301347".into()),
+                    None,
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdatePatientError::RepositoryError(
+                UpdatePatientRepositoryError::DuplicatedPeselNumber
+            ))
+        ));
+        assert!(matches!(
+            service
+                .update_patient(
+                    Uuid::new_v4(),
+                    Some("John Smith".into()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None
+                )
+                .await,
+            Err(UpdatePatientError::RepositoryError(
+                UpdatePatientRepositoryError::NotFound(_)
+            ))
+        ));
+    }
+
     #[tokio::test]
     async fn get_patient_by_id_returns_error_if_such_patient_does_not_exist() {
         let service = setup_service();
@@ -148,19 +589,47 @@ mod tests {
         let service = setup_service();
 
         service
-            .create_patient("John Doex".into(), "96021817257".into())
+            .create_patient(
+                "John Doex".into(),
+                Some("96021817257".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
         service
-            .create_patient("John Doey".into(), "99031301347".into())
+            .create_patient(
+                "John Doey".into(),
+                Some("99031301347".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
         service
-            .create_patient("John Doez".into(), "92022900002".into())
+            .create_patient(
+                "John Doez".into(),
+                Some("92022900002".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
         service
-            .create_patient("John Doeq".into(), "96021807250".into())
+            .create_patient(
+                "John Doeq".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
@@ -221,4 +690,126 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn creates_updates_and_deletes_patient_allergy() {
+        let service = setup_service();
+        let patient = service
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let doctor_id = Uuid::new_v4();
+
+        let created_allergy = service
+            .create_patient_allergy(
+                patient.id,
+                " Penicillin ".into(),
+                "Rash".into(),
+                AllergySeverity::Moderate,
+                doctor_id,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(created_allergy.substance, "penicillin");
+        assert_eq!(created_allergy.recorded_by_doctor_id, doctor_id);
+
+        let updated_allergy = service
+            .update_patient_allergy(
+                patient.id,
+                created_allergy.id,
+                "Anaphylaxis".into(),
+                AllergySeverity::LifeThreatening,
+                doctor_id,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_allergy.reaction, "Anaphylaxis");
+
+        let allergies = service.get_patient_allergies(patient.id).await.unwrap();
+
+        assert_eq!(allergies, vec![updated_allergy]);
+
+        assert!(matches!(
+            service
+                .delete_patient_allergy(patient.id, created_allergy.id, Uuid::new_v4())
+                .await,
+            Err(DeletePatientAllergyError::DomainError(
+                PatientAllergyDomainError::NotRecordingDoctor
+            ))
+        ));
+
+        service
+            .delete_patient_allergy(patient.id, created_allergy.id, doctor_id)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .delete_patient_allergy(patient.id, created_allergy.id, doctor_id)
+                .await,
+            Err(DeletePatientAllergyError::RepositoryError(
+                DeletePatientAllergyRepositoryError::NotFound(_)
+            ))
+        ));
+
+        assert_eq!(
+            service
+                .get_patient_allergies(patient.id)
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn patient_allergy_returns_error_if_body_is_incorrect() {
+        let service = setup_service();
+        let patient = service
+            .create_patient(
+                "John Doex".into(),
+                Some("96021807250".into()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(service
+            .create_patient_allergy(
+                patient.id,
+                "x".into(),
+                "Rash".into(),
+                AllergySeverity::Mild,
+                Uuid::new_v4(),
+            )
+            .await
+            .is_err());
+
+        assert!(matches!(
+            service
+                .update_patient_allergy(
+                    patient.id,
+                    Uuid::new_v4(),
+                    "  ".into(),
+                    AllergySeverity::Mild,
+                    Uuid::new_v4(),
+                )
+                .await,
+            Err(UpdatePatientAllergyError::DomainError(
+                PatientAllergyDomainError::EmptyReaction
+            ))
+        ));
+    }
 }
@@ -1,35 +1,233 @@
+use chrono::{NaiveDate, Utc};
 use uuid::Uuid;
 
 use crate::domain::{
-    patients::entities::NewPatient,
+    patients::entities::{IdentityDocument, IdentityDocumentType, NewPatient},
     utils::validators::{
-        validate_name::validate_name, validate_pesel_number::validate_pesel_number,
+        validate_name::validate_name,
+        validate_pesel_number::{Pesel, Sex},
     },
 };
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CreatePatientDomainError {
+    #[error(
+        "Patient must be identified by exactly one of: PESEL number, identity document or PESEL number of a parent"
+    )]
+    InvalidIdentifiers,
+    #[error("Birth date and sex must be given for a patient without a PESEL number")]
+    MissingBirthDateOrSex,
+    #[error("Identity document number must consist of 5 to 20 letters and digits")]
+    InvalidDocumentNumber,
+    #[error("{0} is not a valid ISO 3166-1 alpha-2 country code")]
+    InvalidIssuingCountry(String),
+    #[error("EU ID card can't be issued in {0}")]
+    IdCardNotIssuedInEu(String),
+    #[error("Birth date can't be in the future")]
+    BirthDateInFuture,
+    #[error("Newborn can't be born before the parent")]
+    BornBeforeParent,
+}
+
+// EU member states, and EEA countries and Switzerland, whose ID cards are accepted as travel
+// documents in the EU
+const EU_ID_CARD_COUNTRIES: [&str; 31] = [
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU", "IE", "IT",
+    "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK", "IS", "LI", "NO", "CH",
+];
+
+// Document numbers are compared without spaces and regardless of the case they were typed in
+pub fn normalize_document_number(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+pub fn validate_document_number(number: &str) -> Result<(), CreatePatientDomainError> {
+    if !(5..=20).contains(&number.len()) || !number.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Err(CreatePatientDomainError::InvalidDocumentNumber)?;
+    }
+
+    Ok(())
+}
+
+fn validate_birth_date(birth_date: NaiveDate) -> Result<(), CreatePatientDomainError> {
+    if birth_date > Utc::now().date_naive() {
+        Err(CreatePatientDomainError::BirthDateInFuture)?;
+    }
+
+    Ok(())
+}
+
+impl IdentityDocument {
+    pub fn new(
+        document_type: IdentityDocumentType,
+        number: String,
+        issuing_country: String,
+    ) -> Result<Self, CreatePatientDomainError> {
+        let number = normalize_document_number(&number);
+        validate_document_number(&number)?;
+        let issuing_country = issuing_country.trim().to_uppercase();
+        if issuing_country.len() != 2 || !issuing_country.chars().all(|c| c.is_ascii_uppercase()) {
+            Err(CreatePatientDomainError::InvalidIssuingCountry(
+                issuing_country.clone(),
+            ))?;
+        }
+        if document_type == IdentityDocumentType::EuIdCard
+            && !EU_ID_CARD_COUNTRIES.contains(&issuing_country.as_str())
+        {
+            Err(CreatePatientDomainError::IdCardNotIssuedInEu(
+                issuing_country.clone(),
+            ))?;
+        }
+
+        Ok(IdentityDocument {
+            document_type,
+            number,
+            issuing_country,
+        })
+    }
+}
+
 impl NewPatient {
     pub fn new(name: String, pesel_number: String) -> anyhow::Result<Self> {
         validate_name(&name)?;
-        validate_pesel_number(&pesel_number)?;
+        let pesel = Pesel::new(&pesel_number)?;
+
+        Ok(NewPatient {
+            id: Uuid::new_v4(),
+            name,
+            pesel_number: Some(pesel_number),
+            identity_document: None,
+            parent_pesel_number: None,
+            birth_date: pesel.get_birth_date(),
+            sex: pesel.get_sex(),
+        })
+    }
+
+    // Foreign patient without a PESEL number, identified by a passport or an EU ID card
+    pub fn with_identity_document(
+        name: String,
+        identity_document: IdentityDocument,
+        birth_date: NaiveDate,
+        sex: Sex,
+    ) -> anyhow::Result<Self> {
+        validate_name(&name)?;
+        let identity_document = IdentityDocument::new(
+            identity_document.document_type,
+            identity_document.number,
+            identity_document.issuing_country,
+        )?;
+        validate_birth_date(birth_date)?;
+
+        Ok(NewPatient {
+            id: Uuid::new_v4(),
+            name,
+            pesel_number: None,
+            identity_document: Some(identity_document),
+            parent_pesel_number: None,
+            birth_date,
+            sex,
+        })
+    }
+
+    // Newborn who hasn't been given a PESEL number yet is identified by the PESEL number of a
+    // parent
+    pub fn newborn(
+        name: String,
+        parent_pesel_number: String,
+        birth_date: NaiveDate,
+        sex: Sex,
+    ) -> anyhow::Result<Self> {
+        validate_name(&name)?;
+        let parent_pesel = Pesel::new(&parent_pesel_number)?;
+        validate_birth_date(birth_date)?;
+        if birth_date <= parent_pesel.get_birth_date() {
+            Err(CreatePatientDomainError::BornBeforeParent)?;
+        }
 
         Ok(NewPatient {
             id: Uuid::new_v4(),
             name,
-            pesel_number,
+            pesel_number: None,
+            identity_document: None,
+            parent_pesel_number: Some(parent_pesel_number),
+            birth_date,
+            sex,
         })
     }
+
+    // Birth date and sex of a patient with a PESEL number are always decoded from it
+    pub fn from_identifiers(
+        name: String,
+        pesel_number: Option<String>,
+        identity_document: Option<IdentityDocument>,
+        parent_pesel_number: Option<String>,
+        birth_date: Option<NaiveDate>,
+        sex: Option<Sex>,
+    ) -> anyhow::Result<Self> {
+        match (pesel_number, identity_document, parent_pesel_number) {
+            (Some(pesel_number), None, None) => NewPatient::new(name, pesel_number),
+            (None, Some(identity_document), None) => {
+                let (Some(birth_date), Some(sex)) = (birth_date, sex) else {
+                    Err(CreatePatientDomainError::MissingBirthDateOrSex)?
+                };
+                NewPatient::with_identity_document(name, identity_document, birth_date, sex)
+            }
+            (None, None, Some(parent_pesel_number)) => {
+                let (Some(birth_date), Some(sex)) = (birth_date, sex) else {
+                    Err(CreatePatientDomainError::MissingBirthDateOrSex)?
+                };
+                NewPatient::newborn(name, parent_pesel_number, birth_date, sex)
+            }
+            _ => Err(CreatePatientDomainError::InvalidIdentifiers)?,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::domain::patients::entities::NewPatient;
+    use chrono::{Duration, NaiveDate, Utc};
+
+    use super::CreatePatientDomainError;
+    use crate::domain::{
+        patients::entities::{IdentityDocument, IdentityDocumentType, NewPatient},
+        utils::validators::validate_pesel_number::Sex,
+    };
+
+    fn create_identity_document(
+        document_type: IdentityDocumentType,
+        number: &str,
+        issuing_country: &str,
+    ) -> IdentityDocument {
+        IdentityDocument {
+            document_type,
+            number: number.into(),
+            issuing_country: issuing_country.into(),
+        }
+    }
 
     #[test]
     fn creates_patient() {
         let sut = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
 
         assert_eq!(sut.name, "John Doe");
-        assert_eq!(sut.pesel_number, "96021817257");
+        assert_eq!(sut.pesel_number, Some("96021817257".into()));
+        assert_eq!(
+            sut.birth_date,
+            NaiveDate::from_ymd_opt(1996, 2, 18).unwrap()
+        );
+        assert_eq!(sut.sex, Sex::Male);
+    }
+
+    #[test]
+    fn creates_patient_born_after_2000() {
+        let sut = NewPatient::new("Jane Doe".into(), "02270803626".into()).unwrap();
+
+        assert_eq!(sut.birth_date, NaiveDate::from_ymd_opt(2002, 7, 8).unwrap());
+        assert_eq!(sut.sex, Sex::Female);
     }
 
     #[test]
@@ -41,4 +239,165 @@ mod tests {
     fn doesnt_create_patient_if_pesel_number_is_invalid() {
         assert!(NewPatient::new("John Doe".into(), "92223300009".into()).is_err());
     }
+
+    #[test]
+    fn creates_patient_with_identity_document() {
+        let birth_date = NaiveDate::from_ymd_opt(1988, 4, 12).unwrap();
+
+        let sut = NewPatient::with_identity_document(
+            "Hans Muller".into(),
+            create_identity_document(IdentityDocumentType::EuIdCard, " l01x 00t47", "de"),
+            birth_date,
+            Sex::Male,
+        )
+        .unwrap();
+
+        assert_eq!(sut.pesel_number, None);
+        assert_eq!(
+            sut.identity_document,
+            Some(create_identity_document(
+                IdentityDocumentType::EuIdCard,
+                "L01X00T47",
+                "DE"
+            ))
+        );
+        assert_eq!(sut.birth_date, birth_date);
+        assert_eq!(sut.sex, Sex::Male);
+    }
+
+    #[test]
+    fn doesnt_create_patient_if_identity_document_is_invalid() {
+        let birth_date = NaiveDate::from_ymd_opt(1988, 4, 12).unwrap();
+        let create_patient = |document_type, number, issuing_country| {
+            NewPatient::with_identity_document(
+                "Hans Muller".into(),
+                create_identity_document(document_type, number, issuing_country),
+                birth_date,
+                Sex::Male,
+            )
+            .unwrap_err()
+            .downcast::<CreatePatientDomainError>()
+            .unwrap()
+        };
+
+        assert_eq!(
+            create_patient(IdentityDocumentType::Passport, "C01", "DE"),
+            CreatePatientDomainError::InvalidDocumentNumber
+        );
+        assert_eq!(
+            create_patient(IdentityDocumentType::Passport, "C01-X00-T47", "DE"),
+            CreatePatientDomainError::InvalidDocumentNumber
+        );
+        assert_eq!(
+            create_patient(IdentityDocumentType::Passport, "C01X00T47", "DEU"),
+            CreatePatientDomainError::InvalidIssuingCountry("DEU".into())
+        );
+        assert_eq!(
+            create_patient(IdentityDocumentType::EuIdCard, "C01X00T47", "US"),
+            CreatePatientDomainError::IdCardNotIssuedInEu("US".into())
+        );
+        assert!(NewPatient::with_identity_document(
+            "Hans Muller".into(),
+            create_identity_document(IdentityDocumentType::Passport, "C01X00T47", "US"),
+            Utc::now().date_naive() + Duration::days(1),
+            Sex::Male,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn creates_newborn_with_pesel_number_of_parent() {
+        let birth_date = Utc::now().date_naive() - Duration::days(3);
+
+        let sut = NewPatient::newborn(
+            "Baby Doe".into(),
+            "92022900002".into(),
+            birth_date,
+            Sex::Female,
+        )
+        .unwrap();
+
+        assert_eq!(sut.pesel_number, None);
+        assert_eq!(sut.parent_pesel_number, Some("92022900002".into()));
+        assert_eq!(sut.birth_date, birth_date);
+        assert!(NewPatient::newborn(
+            "Baby Doe".into(),
+            "92022900003".into(),
+            birth_date,
+            Sex::Female
+        )
+        .is_err());
+        assert_eq!(
+            NewPatient::newborn(
+                "Baby Doe".into(),
+                "92022900002".into(),
+                NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                Sex::Female
+            )
+            .unwrap_err()
+            .downcast::<CreatePatientDomainError>()
+            .unwrap(),
+            CreatePatientDomainError::BornBeforeParent
+        );
+    }
+
+    #[test]
+    fn creates_patient_identified_by_exactly_one_identifier() {
+        let identity_document =
+            create_identity_document(IdentityDocumentType::Passport, "C01X00T47", "US");
+        let birth_date = NaiveDate::from_ymd_opt(1988, 4, 12).unwrap();
+
+        let sut = NewPatient::from_identifiers(
+            "Jane Doe".into(),
+            Some("02270803626".into()),
+            None,
+            None,
+            Some(birth_date),
+            Some(Sex::Male),
+        )
+        .unwrap();
+
+        assert_eq!(sut.birth_date, NaiveDate::from_ymd_opt(2002, 7, 8).unwrap());
+        assert_eq!(sut.sex, Sex::Female);
+        assert!(NewPatient::from_identifiers(
+            "John Doe".into(),
+            None,
+            Some(identity_document.clone()),
+            None,
+            Some(birth_date),
+            Some(Sex::Male),
+        )
+        .is_ok());
+        assert_eq!(
+            NewPatient::from_identifiers(
+                "John Doe".into(),
+                None,
+                Some(identity_document.clone()),
+                None,
+                Some(birth_date),
+                None,
+            )
+            .unwrap_err()
+            .downcast::<CreatePatientDomainError>()
+            .unwrap(),
+            CreatePatientDomainError::MissingBirthDateOrSex
+        );
+        assert_eq!(
+            NewPatient::from_identifiers(
+                "John Doe".into(),
+                Some("02270803626".into()),
+                Some(identity_document),
+                None,
+                None,
+                None,
+            )
+            .unwrap_err()
+            .downcast::<CreatePatientDomainError>()
+            .unwrap(),
+            CreatePatientDomainError::InvalidIdentifiers
+        );
+        assert!(
+            NewPatient::from_identifiers("John Doe".into(), None, None, None, None, None).is_err()
+        );
+    }
 }
@@ -0,0 +1,97 @@
+use uuid::Uuid;
+
+use crate::domain::{
+    patients::entities::{AllergySeverity, NewPatientAllergy},
+    utils::validators::validate_active_substance::validate_active_substance,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum PatientAllergyDomainError {
+    #[error("Allergic reaction description can't be empty")]
+    EmptyReaction,
+    #[error("Only the doctor who recorded the allergy can delete it")]
+    NotRecordingDoctor,
+}
+
+pub fn validate_reaction(reaction: &str) -> Result<String, PatientAllergyDomainError> {
+    let reaction = reaction.trim().to_string();
+    if reaction.is_empty() {
+        Err(PatientAllergyDomainError::EmptyReaction)?;
+    }
+
+    Ok(reaction)
+}
+
+impl NewPatientAllergy {
+    pub fn new(
+        patient_id: Uuid,
+        substance: String,
+        reaction: String,
+        severity: AllergySeverity,
+        recorded_by_doctor_id: Uuid,
+    ) -> anyhow::Result<Self> {
+        let substance = validate_active_substance(&substance)?;
+        let reaction = validate_reaction(&reaction)?;
+
+        Ok(NewPatientAllergy {
+            id: Uuid::new_v4(),
+            patient_id,
+            substance,
+            reaction,
+            severity,
+            recorded_by_doctor_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::patients::entities::{AllergySeverity, NewPatientAllergy};
+
+    #[test]
+    fn creates_patient_allergy() {
+        let patient_id = Uuid::new_v4();
+        let doctor_id = Uuid::new_v4();
+
+        let sut = NewPatientAllergy::new(
+            patient_id,
+            " Penicillin ".into(),
+            " Anaphylaxis ".into(),
+            AllergySeverity::LifeThreatening,
+            doctor_id,
+        )
+        .unwrap();
+
+        assert_eq!(sut.patient_id, patient_id);
+        assert_eq!(sut.substance, "penicillin");
+        assert_eq!(sut.reaction, "Anaphylaxis");
+        assert_eq!(sut.severity, AllergySeverity::LifeThreatening);
+        assert_eq!(sut.recorded_by_doctor_id, doctor_id);
+    }
+
+    #[test]
+    fn doesnt_create_patient_allergy_if_substance_is_invalid() {
+        assert!(NewPatientAllergy::new(
+            Uuid::new_v4(),
+            " ".into(),
+            "Rash".into(),
+            AllergySeverity::Mild,
+            Uuid::new_v4(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn doesnt_create_patient_allergy_without_reaction() {
+        assert!(NewPatientAllergy::new(
+            Uuid::new_v4(),
+            "penicillin".into(),
+            "  ".into(),
+            AllergySeverity::Mild,
+            Uuid::new_v4(),
+        )
+        .is_err());
+    }
+}
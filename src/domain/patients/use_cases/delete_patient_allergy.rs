@@ -0,0 +1,48 @@
+use uuid::Uuid;
+
+use super::create_patient_allergy::PatientAllergyDomainError;
+use crate::domain::patients::entities::PatientAllergy;
+
+impl PatientAllergy {
+    // Deleted allergy is no longer checked when prescribing drugs to the patient, so only the
+    // doctor who recorded it can delete it
+    pub fn check_deletion(&self, doctor_id: Uuid) -> Result<(), PatientAllergyDomainError> {
+        if self.recorded_by_doctor_id != doctor_id {
+            Err(PatientAllergyDomainError::NotRecordingDoctor)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::patients::{
+        entities::{AllergySeverity, PatientAllergy},
+        use_cases::create_patient_allergy::PatientAllergyDomainError,
+    };
+
+    #[test]
+    fn allows_only_recording_doctor_to_delete_patient_allergy() {
+        let doctor_id = Uuid::new_v4();
+        let allergy = PatientAllergy {
+            id: Uuid::new_v4(),
+            patient_id: Uuid::new_v4(),
+            substance: "amoxicillin".into(),
+            reaction: "Hives".into(),
+            severity: AllergySeverity::Moderate,
+            recorded_by_doctor_id: doctor_id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        assert_eq!(allergy.check_deletion(doctor_id), Ok(()));
+        assert_eq!(
+            allergy.check_deletion(Uuid::new_v4()),
+            Err(PatientAllergyDomainError::NotRecordingDoctor)
+        );
+    }
+}
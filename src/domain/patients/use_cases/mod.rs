@@ -1 +1,5 @@
 pub mod create_patient;
+pub mod create_patient_allergy;
+pub mod delete_patient_allergy;
+pub mod update_patient;
+pub mod update_patient_allergy;
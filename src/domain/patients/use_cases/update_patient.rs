@@ -0,0 +1,181 @@
+use chrono::NaiveDate;
+
+use crate::domain::{
+    patients::entities::{IdentityDocument, NewPatient, Patient, PatientUpdate},
+    utils::validators::validate_pesel_number::Sex,
+};
+
+impl PatientUpdate {
+    // Fields which are not given keep their current values and the result is validated the same
+    // way as a new patient. Identifiers are replaced together, so a newborn identified by the
+    // PESEL number of a parent can be given its own one
+    pub fn new(
+        patient: &Patient,
+        name: Option<String>,
+        pesel_number: Option<String>,
+        identity_document: Option<IdentityDocument>,
+        parent_pesel_number: Option<String>,
+        birth_date: Option<NaiveDate>,
+        sex: Option<Sex>,
+    ) -> anyhow::Result<Self> {
+        let (pesel_number, identity_document, parent_pesel_number) = if pesel_number.is_none()
+            && identity_document.is_none()
+            && parent_pesel_number.is_none()
+        {
+            (
+                patient.pesel_number.clone(),
+                patient.identity_document.clone(),
+                patient.parent_pesel_number.clone(),
+            )
+        } else {
+            (pesel_number, identity_document, parent_pesel_number)
+        };
+
+        let new_patient = NewPatient::from_identifiers(
+            name.unwrap_or(patient.name.clone()),
+            pesel_number,
+            identity_document,
+            parent_pesel_number,
+            birth_date.or(Some(patient.birth_date)),
+            sex.or(Some(patient.sex)),
+        )?;
+
+        Ok(PatientUpdate {
+            id: patient.id,
+            name: new_patient.name,
+            pesel_number: new_patient.pesel_number,
+            identity_document: new_patient.identity_document,
+            parent_pesel_number: new_patient.parent_pesel_number,
+            birth_date: new_patient.birth_date,
+            sex: new_patient.sex,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    use crate::domain::{
+        patients::{
+            entities::{IdentityDocument, IdentityDocumentType, Patient, PatientUpdate},
+            use_cases::create_patient::CreatePatientDomainError,
+        },
+        utils::validators::validate_pesel_number::Sex,
+    };
+
+    fn create_patient(pesel_number: Option<&str>, parent_pesel_number: Option<&str>) -> Patient {
+        Patient {
+            id: Uuid::new_v4(),
+            name: "John Doe".into(),
+            pesel_number: pesel_number.map(String::from),
+            identity_document: None,
+            parent_pesel_number: parent_pesel_number.map(String::from),
+            birth_date: NaiveDate::from_ymd_opt(2002, 7, 8).unwrap(),
+            sex: Sex::Female,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn updates_only_given_fields_of_patient() {
+        let patient = create_patient(Some("02270803626"), None);
+
+        let sut = PatientUpdate::new(
+            &patient,
+            Some("Jane Doe".into()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(sut.id, patient.id);
+        assert_eq!(sut.name, "Jane Doe");
+        assert_eq!(sut.pesel_number, Some("02270803626".into()));
+        assert_eq!(sut.birth_date, patient.birth_date);
+        assert_eq!(sut.sex, Sex::Female);
+    }
+
+    #[test]
+    fn replaces_identifiers_of_patient_together() {
+        let patient = create_patient(None, Some("96021817257"));
+
+        let sut = PatientUpdate::new(
+            &patient,
+            None,
+            Some("02270803626".into()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(sut.pesel_number, Some("02270803626".into()));
+        assert_eq!(sut.parent_pesel_number, None);
+        assert_eq!(sut.birth_date, patient.birth_date);
+        assert_eq!(sut.sex, Sex::Female);
+
+        let sut = PatientUpdate::new(
+            &patient,
+            None,
+            None,
+            Some(IdentityDocument {
+                document_type: IdentityDocumentType::Passport,
+                number: "ab 1234567".into(),
+                issuing_country: "ua".into(),
+            }),
+            None,
+            None,
+            Some(Sex::Male),
+        )
+        .unwrap();
+
+        assert_eq!(sut.parent_pesel_number, None);
+        assert_eq!(sut.identity_document.unwrap().number, "AB1234567");
+        assert_eq!(sut.birth_date, patient.birth_date);
+        assert_eq!(sut.sex, Sex::Male);
+    }
+
+    #[test]
+    fn doesnt_update_patient_with_invalid_fields() {
+        let patient = create_patient(Some("02270803626"), None);
+
+        assert!(
+            PatientUpdate::new(&patient, Some("John".into()), None, None, None, None, None)
+                .is_err()
+        );
+        assert!(PatientUpdate::new(
+            &patient,
+            None,
+            Some("92223300009".into()),
+            None,
+            None,
+            None,
+            None
+        )
+        .is_err());
+
+        let sut = PatientUpdate::new(
+            &patient,
+            None,
+            Some("96021807250".into()),
+            None,
+            Some("96021817257".into()),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            sut.unwrap_err()
+                .downcast::<CreatePatientDomainError>()
+                .unwrap(),
+            CreatePatientDomainError::InvalidIdentifiers
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use uuid::Uuid;
+
+use super::create_patient_allergy::{validate_reaction, PatientAllergyDomainError};
+use crate::domain::patients::entities::{AllergySeverity, PatientAllergyUpdate};
+
+impl PatientAllergyUpdate {
+    pub fn new(
+        id: Uuid,
+        patient_id: Uuid,
+        reaction: String,
+        severity: AllergySeverity,
+        recorded_by_doctor_id: Uuid,
+    ) -> Result<Self, PatientAllergyDomainError> {
+        let reaction = validate_reaction(&reaction)?;
+
+        Ok(PatientAllergyUpdate {
+            id,
+            patient_id,
+            reaction,
+            severity,
+            recorded_by_doctor_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::patients::{
+        entities::{AllergySeverity, PatientAllergyUpdate},
+        use_cases::create_patient_allergy::PatientAllergyDomainError,
+    };
+
+    #[test]
+    fn creates_patient_allergy_update() {
+        let sut = PatientAllergyUpdate::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            " Hives ".into(),
+            AllergySeverity::Moderate,
+            Uuid::new_v4(),
+        )
+        .unwrap();
+
+        assert_eq!(sut.reaction, "Hives");
+        assert_eq!(sut.severity, AllergySeverity::Moderate);
+    }
+
+    #[test]
+    fn doesnt_create_patient_allergy_update_without_reaction() {
+        let sut = PatientAllergyUpdate::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "".into(),
+            AllergySeverity::Moderate,
+            Uuid::new_v4(),
+        );
+
+        assert_eq!(sut, Err(PatientAllergyDomainError::EmptyReaction));
+    }
+}
@@ -10,6 +10,13 @@ pub struct NewPharmacist {
     pub pesel_number: String,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct PharmacistUpdate {
+    pub id: Uuid,
+    pub name: String,
+    pub pesel_number: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct Pharmacist {
     pub id: Uuid,
@@ -5,7 +5,7 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::domain::{
-    pharmacists::entities::{NewPharmacist, Pharmacist},
+    pharmacists::entities::{NewPharmacist, Pharmacist, PharmacistUpdate},
     utils::pagination::get_pagination_params,
 };
 
@@ -33,6 +33,16 @@ pub enum GetPharmacistByIdRepositoryError {
     DatabaseError(String),
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum UpdatePharmacistRepositoryError {
+    #[error("Pharmacist with this id not found ({0})")]
+    NotFound(Uuid),
+    #[error("PESEL number already exists")]
+    DuplicatedPeselNumber,
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
 #[async_trait]
 pub trait PharmacistsRepository: Send + Sync + 'static {
     async fn create_pharmacist(
@@ -48,6 +58,10 @@ pub trait PharmacistsRepository: Send + Sync + 'static {
         &self,
         pharmacist_id: Uuid,
     ) -> Result<Pharmacist, GetPharmacistByIdRepositoryError>;
+    async fn update_pharmacist(
+        &self,
+        pharmacist_update: PharmacistUpdate,
+    ) -> Result<Pharmacist, UpdatePharmacistRepositoryError>;
 }
 
 pub struct PharmacistsRepositoryFake {
@@ -130,6 +144,35 @@ impl PharmacistsRepository for PharmacistsRepositoryFake {
             None => Err(GetPharmacistByIdRepositoryError::NotFound(pharmacist_id)),
         }
     }
+
+    async fn update_pharmacist(
+        &self,
+        pharmacist_update: PharmacistUpdate,
+    ) -> Result<Pharmacist, UpdatePharmacistRepositoryError> {
+        let mut pharmacists = self.pharmacists.write().unwrap();
+        let index = pharmacists
+            .iter()
+            .position(|pharmacist| pharmacist.id == pharmacist_update.id)
+            .ok_or(UpdatePharmacistRepositoryError::NotFound(
+                pharmacist_update.id,
+            ))?;
+        let does_pesel_number_exist = pharmacists.iter().any(|pharmacist| {
+            pharmacist.id != pharmacist_update.id
+                && pharmacist.pesel_number == pharmacist_update.pesel_number
+        });
+
+        if does_pesel_number_exist {
+            return Err(UpdatePharmacistRepositoryError::DuplicatedPeselNumber);
+        }
+
+        let pharmacist = &mut pharmacists[index];
+
+        pharmacist.name = pharmacist_update.name;
+        pharmacist.pesel_number = pharmacist_update.pesel_number;
+        pharmacist.updated_at = Utc::now();
+
+        Ok(pharmacist.clone())
+    }
 }
 
 #[cfg(test)]
@@ -139,8 +182,9 @@ mod tests {
     use super::{
         CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
         GetPharmacistsRepositoryError, PharmacistsRepository, PharmacistsRepositoryFake,
+        UpdatePharmacistRepositoryError,
     };
-    use crate::domain::pharmacists::entities::NewPharmacist;
+    use crate::domain::pharmacists::entities::{NewPharmacist, PharmacistUpdate};
 
     fn setup_repository() -> PharmacistsRepositoryFake {
         PharmacistsRepositoryFake::new()
@@ -263,4 +307,73 @@ mod tests {
             Err(CreatePharmacistRepositoryError::DuplicatedPeselNumber)
         );
     }
+
+    #[sqlx::test]
+    async fn updates_pharmacist() {
+        let repository = setup_repository();
+
+        let new_pharmacist = NewPharmacist::new("John Doe".into(), "96021817257".into()).unwrap();
+        let pharmacist = repository.create_pharmacist(new_pharmacist).await.unwrap();
+
+        let updated_pharmacist = repository
+            .update_pharmacist(PharmacistUpdate {
+                id: pharmacist.id,
+                name: "John Smith".into(),
+                pesel_number: "99031301347".into(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_pharmacist.name, "John Smith");
+        assert_eq!(updated_pharmacist.pesel_number, "99031301347");
+        assert_eq!(updated_pharmacist.created_at, pharmacist.created_at);
+        assert!(updated_pharmacist.updated_at > pharmacist.updated_at);
+        assert_eq!(
+            repository
+                .get_pharmacist_by_id(pharmacist.id)
+                .await
+                .unwrap(),
+            updated_pharmacist
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_update_pharmacist_if_it_doesnt_exist_or_pesel_number_is_duplicated() {
+        let repository = setup_repository();
+
+        let pharmacist_0 = NewPharmacist::new("John Doe".into(), "96021817257".into()).unwrap();
+        let pharmacist_1 = NewPharmacist::new("John Doe".into(), "99031301347".into()).unwrap();
+        repository
+            .create_pharmacist(pharmacist_0.clone())
+            .await
+            .unwrap();
+        repository
+            .create_pharmacist(pharmacist_1.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .update_pharmacist(PharmacistUpdate {
+                    id: pharmacist_1.id,
+                    name: pharmacist_1.name.clone(),
+                    pesel_number: pharmacist_0.pesel_number,
+                })
+                .await,
+            Err(UpdatePharmacistRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let pharmacist_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_pharmacist(PharmacistUpdate {
+                    id: pharmacist_id,
+                    name: pharmacist_1.name,
+                    pesel_number: "92022900002".into(),
+                })
+                .await,
+            Err(UpdatePharmacistRepositoryError::NotFound(pharmacist_id))
+        );
+    }
 }
@@ -2,10 +2,10 @@ use uuid::Uuid;
 
 use super::repository::{
     CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
-    GetPharmacistsRepositoryError,
+    GetPharmacistsRepositoryError, UpdatePharmacistRepositoryError,
 };
 use crate::domain::pharmacists::{
-    entities::{NewPharmacist, Pharmacist},
+    entities::{NewPharmacist, Pharmacist, PharmacistUpdate},
     repository::PharmacistsRepository,
 };
 
@@ -29,6 +29,13 @@ pub enum GetPharmacistsWithPaginationError {
     RepositoryError(GetPharmacistsRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum UpdatePharmacistError {
+    DomainError(String),
+    NotAccountOwner,
+    RepositoryError(UpdatePharmacistRepositoryError),
+}
+
 impl PharmacistsService {
     pub fn new(repository: Box<dyn PharmacistsRepository>) -> Self {
         Self { repository }
@@ -77,13 +84,54 @@ impl PharmacistsService {
 
         Ok(pharmacists)
     }
+
+    // Pharmacists can only update their own account
+    pub async fn update_pharmacist(
+        &self,
+        pharmacist_id: Uuid,
+        updating_pharmacist_id: Uuid,
+        name: Option<String>,
+        pesel_number: Option<String>,
+    ) -> Result<Pharmacist, UpdatePharmacistError> {
+        if updating_pharmacist_id != pharmacist_id {
+            return Err(UpdatePharmacistError::NotAccountOwner);
+        }
+
+        let pharmacist = self
+            .repository
+            .get_pharmacist_by_id(pharmacist_id)
+            .await
+            .map_err(|err| match err {
+                GetPharmacistByIdRepositoryError::NotFound(id) => {
+                    UpdatePharmacistError::RepositoryError(
+                        UpdatePharmacistRepositoryError::NotFound(id),
+                    )
+                }
+                GetPharmacistByIdRepositoryError::DatabaseError(message) => {
+                    UpdatePharmacistError::RepositoryError(
+                        UpdatePharmacistRepositoryError::DatabaseError(message),
+                    )
+                }
+            })?;
+
+        let pharmacist_update = PharmacistUpdate::new(&pharmacist, name, pesel_number)
+            .map_err(|err| UpdatePharmacistError::DomainError(err.to_string()))?;
+
+        let updated_pharmacist = self
+            .repository
+            .update_pharmacist(pharmacist_update)
+            .await
+            .map_err(UpdatePharmacistError::RepositoryError)?;
+
+        Ok(updated_pharmacist)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use uuid::Uuid;
 
-    use super::PharmacistsService;
+    use super::{PharmacistsService, UpdatePharmacistError};
     use crate::domain::pharmacists::repository::PharmacistsRepositoryFake;
 
     fn setup_service() -> PharmacistsService {
@@ -225,4 +273,91 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn updates_pharmacist() {
+        let service = setup_service();
+
+        let created_pharmacist = service
+            .create_pharmacist("John Doex".into(), "96021807250".into())
+            .await
+            .unwrap();
+
+        let updated_pharmacist = service
+            .update_pharmacist(
+                created_pharmacist.id,
+                created_pharmacist.id,
+                Some("John Smith".into()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_pharmacist.name, "John Smith");
+        assert_eq!(updated_pharmacist.pesel_number, "96021807250");
+
+        let pharmacist_from_repository = service
+            .get_pharmacist_by_id(created_pharmacist.id)
+            .await
+            .unwrap();
+
+        assert_eq!(pharmacist_from_repository, updated_pharmacist);
+    }
+
+    #[tokio::test]
+    async fn update_pharmacist_returns_error_if_pharmacist_doesnt_exist_or_body_is_incorrect() {
+        let service = setup_service();
+
+        let created_pharmacist = service
+            .create_pharmacist("John Doex".into(), "96021807250".into())
+            .await
+            .unwrap();
+        service
+            .create_pharmacist("John Doey".into(), "99031301347".into())
+            .await
+            .unwrap();
+
+        assert!(service
+            .update_pharmacist(
+                created_pharmacist.id,
+                created_pharmacist.id,
+                None,
+                Some("96021807251".into())
+            )
+            .await
+            .is_err());
+
+        assert!(service
+            .update_pharmacist(
+                created_pharmacist.id,
+                created_pharmacist.id,
+                None,
+                Some("99031301347".into())
+            )
+            .await
+            .is_err());
+
+        let pharmacist_id = Uuid::new_v4();
+        assert!(service
+            .update_pharmacist(
+                pharmacist_id,
+                pharmacist_id,
+                Some("John Smith".into()),
+                None
+            )
+            .await
+            .is_err());
+
+        assert!(matches!(
+            service
+                .update_pharmacist(
+                    created_pharmacist.id,
+                    Uuid::new_v4(),
+                    Some("John Smith".into()),
+                    None
+                )
+                .await,
+            Err(UpdatePharmacistError::NotAccountOwner)
+        ));
+    }
 }
@@ -2,15 +2,13 @@ use uuid::Uuid;
 
 use crate::domain::{
     pharmacists::entities::NewPharmacist,
-    utils::validators::{
-        validate_name::validate_name, validate_pesel_number::validate_pesel_number,
-    },
+    utils::validators::{validate_name::validate_name, validate_pesel_number::Pesel},
 };
 
 impl NewPharmacist {
     pub fn new(name: String, pesel_number: String) -> anyhow::Result<Self> {
         validate_name(&name)?;
-        validate_pesel_number(&pesel_number)?;
+        Pesel::new(&pesel_number)?;
 
         Ok(NewPharmacist {
             id: Uuid::new_v4(),
@@ -32,6 +30,11 @@ mod tests {
         assert_eq!(sut.pesel_number, "96021817257");
     }
 
+    #[test]
+    fn creates_pharmacist_born_after_2000() {
+        assert!(NewPharmacist::new("John Doe".into(), "02270803626".into()).is_ok());
+    }
+
     #[test]
     fn doesnt_create_pharmacist_if_name_is_invalid() {
         assert!(NewPharmacist::new("John".into(), "96021817257".into()).is_err());
@@ -1 +1,2 @@
 pub mod create_pharmacist;
+pub mod update_pharmacist;
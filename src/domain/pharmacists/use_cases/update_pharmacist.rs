@@ -0,0 +1,67 @@
+use crate::domain::{
+    pharmacists::entities::{Pharmacist, PharmacistUpdate},
+    utils::validators::{validate_name::validate_name, validate_pesel_number::Pesel},
+};
+
+impl PharmacistUpdate {
+    // Fields which are not given keep their current values, the result is validated the same way
+    // as a new pharmacist
+    pub fn new(
+        pharmacist: &Pharmacist,
+        name: Option<String>,
+        pesel_number: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let name = name.unwrap_or(pharmacist.name.clone());
+        let pesel_number = pesel_number.unwrap_or(pharmacist.pesel_number.clone());
+        validate_name(&name)?;
+        Pesel::new(&pesel_number)?;
+
+        Ok(PharmacistUpdate {
+            id: pharmacist.id,
+            name,
+            pesel_number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::pharmacists::entities::{Pharmacist, PharmacistUpdate};
+
+    fn create_pharmacist() -> Pharmacist {
+        Pharmacist {
+            id: Uuid::new_v4(),
+            name: "John Doe".into(),
+            pesel_number: "96021817257".into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn updates_only_given_fields_of_pharmacist() {
+        let pharmacist = create_pharmacist();
+
+        let sut = PharmacistUpdate::new(&pharmacist, Some("John Smith".into()), None).unwrap();
+
+        assert_eq!(sut.id, pharmacist.id);
+        assert_eq!(sut.name, "John Smith");
+        assert_eq!(sut.pesel_number, "96021817257");
+
+        let sut = PharmacistUpdate::new(&pharmacist, None, Some("02270803626".into())).unwrap();
+
+        assert_eq!(sut.name, "John Doe");
+        assert_eq!(sut.pesel_number, "02270803626");
+    }
+
+    #[test]
+    fn doesnt_update_pharmacist_with_invalid_fields() {
+        let pharmacist = create_pharmacist();
+
+        assert!(PharmacistUpdate::new(&pharmacist, Some("john doe".into()), None).is_err());
+        assert!(PharmacistUpdate::new(&pharmacist, None, Some("92223300009".into())).is_err());
+    }
+}
@@ -1,8 +1,14 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rocket::FromFormField;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::{
+    drugs::entities::{DrugInteractionSeverity, ReimbursementLevel},
+    patients::entities::IdentityDocument,
+};
+
 #[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 #[sqlx(type_name = "prescription_type", rename_all = "snake_case")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -11,12 +17,95 @@ pub enum PrescriptionType {
     ForAntibiotics,
     ForImmunologicalDrugs,
     ForChronicDiseaseDrugs,
+    ForControlledSubstances,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(
+    Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema, FromFormField,
+)]
+#[sqlx(type_name = "prescription_status", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrescriptionStatus {
+    #[field(value = "PENDING")]
+    Pending,
+    #[field(value = "ACTIVE")]
+    Active,
+    #[field(value = "PARTIALLY_FILLED")]
+    PartiallyFilled,
+    #[field(value = "FILLED")]
+    Filled,
+    #[field(value = "EXPIRED")]
+    Expired,
+    #[field(value = "CANCELLED")]
+    Cancelled,
+}
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[sqlx(type_name = "dose_unit", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DoseUnit {
+    Tablet,
+    Capsule,
+    Drop,
+    Ml,
+    Puff,
+    Sachet,
+    Application,
+    Unit,
+}
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[sqlx(type_name = "dosage_period", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DosagePeriod {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[sqlx(type_name = "administration_route", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AdministrationRoute {
+    Oral,
+    Sublingual,
+    Inhalation,
+    Nasal,
+    Ophthalmic,
+    Otic,
+    Topical,
+    Transdermal,
+    Rectal,
+    Vaginal,
+    Subcutaneous,
+    Intramuscular,
+    Intravenous,
+}
+
+// Signa, e.g. 1 tablet 2 times a day for 7 days, orally
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Dosage {
+    pub dose_amount: f64,
+    pub dose_unit: DoseUnit,
+    #[schemars(description = "Number of doses per frequency_period")]
+    pub frequency: i32,
+    pub frequency_period: DosagePeriod,
+    #[schemars(description = "When omitted, the drug is taken until further notice")]
+    pub duration_days: Option<i32>,
+    pub route: AdministrationRoute,
+    pub note: Option<String>,
+}
+
+// With a variant the quantity counts its packages, without one it's the total amount of the drug
+// (pills, or ml of liquid) and the pharmacist picks the package sizes
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct NewPrescribedDrug {
     pub drug_id: Uuid,
+    pub variant_id: Option<Uuid>,
     pub quantity: u32,
+    pub portion_quantity: Option<u32>,
+    pub dosage: Option<Dosage>,
+    pub reimbursement_level: Option<ReimbursementLevel>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -29,6 +118,7 @@ pub struct NewPrescription {
     pub code: String,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    pub interaction_override_justification: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
@@ -36,7 +126,13 @@ pub struct PrescribedDrug {
     pub id: Uuid,
     pub prescription_id: Uuid,
     pub drug_id: Uuid,
+    pub variant_id: Option<Uuid>,
     pub quantity: i32,
+    pub remaining_quantity: i32,
+    pub portion_quantity: Option<i32>,
+    pub dosage: Option<Dosage>,
+    #[schemars(description = "Reimbursement level chosen by the doctor, None when not reimbursed")]
+    pub reimbursement_level: Option<ReimbursementLevel>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -45,7 +141,10 @@ pub struct PrescribedDrug {
 pub struct PrescriptionPatient {
     pub id: Uuid,
     pub name: String,
-    pub pesel_number: String,
+    pub pesel_number: Option<String>,
+    pub identity_document: Option<IdentityDocument>,
+    pub parent_pesel_number: Option<String>,
+    pub birth_date: NaiveDate,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
@@ -64,9 +163,12 @@ pub struct Prescription {
     pub prescribed_drugs: Vec<PrescribedDrug>,
     pub prescription_type: PrescriptionType,
     pub code: String,
-    pub fill: Option<PrescriptionFill>,
+    pub fills: Vec<PrescriptionFill>,
+    pub cancellation: Option<PrescriptionCancellation>,
+    pub status: PrescriptionStatus,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    pub interaction_override_justification: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -80,6 +182,7 @@ impl PartialEq<NewPrescription> for Prescription {
             && self.code == other.code
             && self.start_date == other.start_date
             && self.end_date == other.end_date
+            && self.interaction_override_justification == other.interaction_override_justification
             && self.prescribed_drugs.len() == other.prescribed_drugs.len()
     }
 }
@@ -90,18 +193,65 @@ impl PartialEq<Prescription> for NewPrescription {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NewDispensedDrug {
+    pub prescribed_drug_id: Uuid,
+    pub quantity: u32,
+    pub substitute_drug_id: Option<Uuid>,
+    pub payable_amount: Option<i64>,
+    pub reimbursed_amount: Option<i64>,
+    pub price: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PrescriptionFillRequest {
+    pub pharmacist_id: Uuid,
+    pub prescription_code: String,
+    pub dispensed_drugs: Option<Vec<(Uuid, u32)>>,
+    pub substitutes: Option<Vec<(Uuid, Uuid)>>,
+    pub scanned_gtins: Option<Vec<String>>,
+    pub controlled_substances_confirmed: bool,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NewPrescriptionFill {
     pub id: Uuid,
     pub prescription_id: Uuid,
     pub pharmacist_id: Uuid,
+    pub portion_number: Option<i32>,
+    pub dispensed_drugs: Vec<NewDispensedDrug>,
+    pub controlled_substance_dispenses: Vec<NewControlledSubstanceDispense>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DispensedDrug {
+    pub id: Uuid,
+    pub prescription_fill_id: Uuid,
+    pub prescribed_drug_id: Uuid,
+    pub quantity: i32,
+    #[schemars(description = "Equivalent drug that was dispensed instead of the prescribed one")]
+    pub substitute_drug_id: Option<Uuid>,
+    #[schemars(description = "Amount (in grosze) paid by the patient for a reimbursed drug")]
+    pub payable_amount: Option<i64>,
+    #[schemars(
+        description = "Amount (in grosze) covered by the public payer for a reimbursed drug"
+    )]
+    pub reimbursed_amount: Option<i64>,
+    #[schemars(
+        description = "Price (in grosze) of the package from the price list valid at the time of the fill"
+    )]
+    pub price: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PrescriptionFill {
     pub id: Uuid,
     pub prescription_id: Uuid,
     pub pharmacist_id: Uuid,
+    pub portion_number: Option<i32>,
+    pub dispensed_drugs: Vec<DispensedDrug>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -111,6 +261,21 @@ impl PartialEq<NewPrescriptionFill> for PrescriptionFill {
         self.id == other.id
             && self.prescription_id == other.prescription_id
             && self.pharmacist_id == other.pharmacist_id
+            && self.portion_number == other.portion_number
+            && self.dispensed_drugs.len() == other.dispensed_drugs.len()
+            && self
+                .dispensed_drugs
+                .iter()
+                .zip(other.dispensed_drugs.iter())
+                .all(|(dispensed_drug, new_dispensed_drug)| {
+                    dispensed_drug.prescribed_drug_id == new_dispensed_drug.prescribed_drug_id
+                        && dispensed_drug.quantity == new_dispensed_drug.quantity as i32
+                        && dispensed_drug.substitute_drug_id
+                            == new_dispensed_drug.substitute_drug_id
+                        && dispensed_drug.payable_amount == new_dispensed_drug.payable_amount
+                        && dispensed_drug.reimbursed_amount == new_dispensed_drug.reimbursed_amount
+                        && dispensed_drug.price == new_dispensed_drug.price
+                })
     }
 }
 
@@ -119,3 +284,106 @@ impl PartialEq<PrescriptionFill> for NewPrescriptionFill {
         other.eq(self)
     }
 }
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewControlledSubstanceDispense {
+    pub id: Uuid,
+    pub prescription_id: Uuid,
+    pub prescription_fill_id: Uuid,
+    pub prescribed_drug_id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+    pub patient_id: Uuid,
+    pub doctor_id: Uuid,
+    pub pharmacist_id: Uuid,
+}
+
+// Entry of the register of controlled substances. drug_id is the drug that was actually dispensed,
+// and quantity counts packages of the prescribed variant
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ControlledSubstanceDispense {
+    pub id: Uuid,
+    pub prescription_id: Uuid,
+    pub prescription_fill_id: Uuid,
+    pub prescribed_drug_id: Uuid,
+    pub drug_id: Uuid,
+    pub variant_id: Option<Uuid>,
+    pub quantity: i32,
+    pub patient_id: Uuid,
+    pub doctor_id: Uuid,
+    pub pharmacist_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PartialEq<NewControlledSubstanceDispense> for ControlledSubstanceDispense {
+    fn eq(&self, other: &NewControlledSubstanceDispense) -> bool {
+        self.id == other.id
+            && self.prescription_id == other.prescription_id
+            && self.prescription_fill_id == other.prescription_fill_id
+            && self.prescribed_drug_id == other.prescribed_drug_id
+            && self.drug_id == other.drug_id
+            && self.variant_id == other.variant_id
+            && self.quantity == other.quantity
+            && self.patient_id == other.patient_id
+            && self.doctor_id == other.doctor_id
+            && self.pharmacist_id == other.pharmacist_id
+    }
+}
+
+impl PartialEq<ControlledSubstanceDispense> for NewControlledSubstanceDispense {
+    fn eq(&self, other: &ControlledSubstanceDispense) -> bool {
+        other.eq(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NewPrescriptionCancellation {
+    pub prescription_id: Uuid,
+    pub reason: String,
+    pub cancelled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PrescriptionCancellation {
+    pub reason: String,
+    pub cancelled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrescriptionLookup {
+    pub pesel_number: Option<String>,
+    pub document_number: Option<String>,
+    pub code: String,
+}
+
+// A drug taken into account when checking interactions, either one being prescribed (without
+// prescription_id) or one from the patient's other current prescriptions
+#[derive(Debug, PartialEq, Clone)]
+pub struct InteractingDrug {
+    pub drug_id: Uuid,
+    pub drug_name: String,
+    pub active_substances: Vec<String>,
+    pub prescription_id: Option<Uuid>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DrugInteractionWarning {
+    pub drug_id: Uuid,
+    pub drug_name: String,
+    pub substance: String,
+    pub interacting_drug_id: Uuid,
+    pub interacting_drug_name: String,
+    pub interacting_substance: String,
+    pub interacting_prescription_id: Option<Uuid>,
+    pub severity: DrugInteractionSeverity,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatedPrescription {
+    #[serde(flatten)]
+    pub prescription: Prescription,
+    pub interaction_warnings: Vec<DrugInteractionWarning>,
+}
@@ -1,17 +1,19 @@
 use std::sync::RwLock;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use uuid::Uuid;
 
-use super::entities::{PrescribedDrug, PrescriptionDoctor, PrescriptionPatient};
+use super::entities::{DispensedDrug, PrescribedDrug, PrescriptionDoctor, PrescriptionPatient};
 use crate::domain::{
     doctors::entities::Doctor,
-    drugs::entities::Drug,
-    patients::entities::Patient,
+    drugs::entities::{Drug, DrugInteraction},
+    patients::entities::{Patient, PatientAllergy},
     pharmacists::entities::Pharmacist,
     prescriptions::entities::{
-        NewPrescription, NewPrescriptionFill, Prescription, PrescriptionFill,
+        ControlledSubstanceDispense, InteractingDrug, NewPrescription, NewPrescriptionCancellation,
+        NewPrescriptionFill, Prescription, PrescriptionCancellation, PrescriptionFill,
+        PrescriptionLookup, PrescriptionStatus,
     },
     utils::pagination::get_pagination_params,
 };
@@ -24,6 +26,8 @@ pub enum CreatePrescriptionRepositoryError {
     PatientNotFound(Uuid),
     #[error("Drug with id {0} not found")]
     DrugNotFound(Uuid),
+    #[error("Variant with id {0} of the prescribed drug not found")]
+    DrugVariantNotFound(Uuid),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -50,6 +54,60 @@ pub enum FillPrescriptionRepositoryError {
     PharmacistNotFound(Uuid),
     #[error("Prescription with id {0} not found")]
     PrescriptionNotFound(Uuid),
+    #[error("Prescription with id {0} is cancelled")]
+    PrescriptionCancelled(Uuid),
+    #[error("Dispensed quantity of prescribed drug with id {0} exceeds its remaining quantity")]
+    RemainingQuantityExceeded(Uuid),
+    #[error("Dispensed quantity of prescribed drug with id {0} exceeds its current portion")]
+    PortionQuantityExceeded(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CancelPrescriptionRepositoryError {
+    #[error("Prescription with id {0} not found")]
+    NotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum LookupPrescriptionsRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckDrugInteractionsRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckAllergiesRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetPatientBirthDateRepositoryError {
+    #[error("Patient with id {0} not found")]
+    PatientNotFound(Uuid),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetDrugsByIdsRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum GetControlledSubstanceDispensesRepositoryError {
+    #[error("Invalid pagination parameters: {0}")]
+    InvalidPaginationParams(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -64,20 +122,60 @@ pub trait PrescriptionsRepository: Send + Sync + 'static {
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        status: Option<PrescriptionStatus>,
     ) -> Result<Vec<Prescription>, GetPrescriptionsRepositoryError>;
     async fn get_prescription_by_id(
         &self,
         prescription_id: Uuid,
     ) -> Result<Prescription, GetPrescriptionByIdRepositoryError>;
+    // Returns only prescriptions that can be filled today, i.e. active or partially filled ones
+    async fn lookup_prescriptions(
+        &self,
+        prescription_lookup: PrescriptionLookup,
+    ) -> Result<Vec<Prescription>, LookupPrescriptionsRepositoryError>;
     async fn fill_prescription(
         &self,
         prescription_fill: NewPrescriptionFill,
     ) -> Result<PrescriptionFill, FillPrescriptionRepositoryError>;
+    async fn cancel_prescription(
+        &self,
+        prescription_cancellation: NewPrescriptionCancellation,
+    ) -> Result<PrescriptionCancellation, CancelPrescriptionRepositoryError>;
+    // Returns the given drugs (without prescription_id) together with drugs from the patient's
+    // prescriptions that are pending, active or partially filled
+    async fn get_interacting_drugs(
+        &self,
+        patient_id: Uuid,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<InteractingDrug>, CheckDrugInteractionsRepositoryError>;
+    // Returns interactions in which both substances are among the given ones
+    async fn get_drug_interactions(
+        &self,
+        active_substances: Vec<String>,
+    ) -> Result<Vec<DrugInteraction>, CheckDrugInteractionsRepositoryError>;
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, CheckAllergiesRepositoryError>;
+    async fn get_patient_birth_date(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<NaiveDate, GetPatientBirthDateRepositoryError>;
+    // Drugs that don't exist are skipped
+    async fn get_drugs_by_ids(
+        &self,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<Drug>, GetDrugsByIdsRepositoryError>;
+    // Register entries are returned from the oldest one
+    async fn get_controlled_substance_dispenses(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ControlledSubstanceDispense>, GetControlledSubstanceDispensesRepositoryError>;
     // async fn get_prescriptions_by_prescription_id(&self, prescription_id: Uuid) ->
     // Result<Vec<Prescription>>; async fn get_prescriptions_by_patient_id(&self, patient_id:
     // Uuid) -> Result<Vec<Prescription>>; async fn update_prescription(&self, prescription:
-    // Prescription) -> Result<()>; async fn delete_prescription(&self, prescription_id: Uuid)
-    // -> Result<()>;
+    // Prescription) -> Result<()>;
 }
 
 pub struct PrescriptionsRepositoryFake {
@@ -86,6 +184,9 @@ pub struct PrescriptionsRepositoryFake {
     pharmacists: RwLock<Vec<Pharmacist>>,
     patients: RwLock<Vec<Patient>>,
     drugs: RwLock<Vec<Drug>>,
+    drug_interactions: RwLock<Vec<DrugInteraction>>,
+    patient_allergies: RwLock<Vec<PatientAllergy>>,
+    controlled_substance_dispenses: RwLock<Vec<ControlledSubstanceDispense>>,
 }
 
 impl PrescriptionsRepositoryFake {
@@ -96,6 +197,8 @@ impl PrescriptionsRepositoryFake {
         initial_patients: Option<Vec<Patient>>,
         initial_pharmacists: Option<Vec<Pharmacist>>,
         initial_drugs: Option<Vec<Drug>>,
+        initial_drug_interactions: Option<Vec<DrugInteraction>>,
+        initial_patient_allergies: Option<Vec<PatientAllergy>>,
     ) -> Self {
         Self {
             prescriptions: RwLock::new(initial_prescriptions.unwrap_or(Vec::new())),
@@ -103,6 +206,9 @@ impl PrescriptionsRepositoryFake {
             patients: RwLock::new(initial_patients.unwrap_or(Vec::new())),
             pharmacists: RwLock::new(initial_pharmacists.unwrap_or(Vec::new())),
             drugs: RwLock::new(initial_drugs.unwrap_or(Vec::new())),
+            drug_interactions: RwLock::new(initial_drug_interactions.unwrap_or_default()),
+            patient_allergies: RwLock::new(initial_patient_allergies.unwrap_or_default()),
+            controlled_substance_dispenses: RwLock::new(Vec::new()),
         }
     }
 }
@@ -131,12 +237,20 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
 
         let drugs = self.drugs.read().unwrap();
         for new_prescribed_drug in &new_prescription.prescribed_drugs {
-            drugs
+            let drug = drugs
                 .iter()
                 .find(|drug| drug.id == new_prescribed_drug.drug_id)
                 .ok_or(CreatePrescriptionRepositoryError::DrugNotFound(
                     new_prescribed_drug.drug_id,
                 ))?;
+            if let Some(variant_id) = new_prescribed_drug.variant_id {
+                drug.variants
+                    .iter()
+                    .find(|variant| variant.id == variant_id)
+                    .ok_or(CreatePrescriptionRepositoryError::DrugVariantNotFound(
+                        variant_id,
+                    ))?;
+            }
         }
 
         let prescription = Prescription {
@@ -150,7 +264,10 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
             patient: PrescriptionPatient {
                 id: found_patient.id.clone(),
                 name: found_patient.name.clone(),
-                pesel_number: found_patient.name.clone(),
+                pesel_number: found_patient.pesel_number.clone(),
+                identity_document: found_patient.identity_document.clone(),
+                parent_pesel_number: found_patient.parent_pesel_number.clone(),
+                birth_date: found_patient.birth_date,
             },
             prescribed_drugs: new_prescription
                 .prescribed_drugs
@@ -158,17 +275,27 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
                 .map(|new_prescibed_drug| PrescribedDrug {
                     id: Uuid::new_v4(),
                     drug_id: new_prescibed_drug.drug_id,
+                    variant_id: new_prescibed_drug.variant_id,
                     prescription_id: new_prescription.id,
                     quantity: new_prescibed_drug.quantity as i32,
+                    remaining_quantity: new_prescibed_drug.quantity as i32,
+                    portion_quantity: new_prescibed_drug
+                        .portion_quantity
+                        .map(|portion_quantity| portion_quantity as i32),
+                    dosage: new_prescibed_drug.dosage.clone(),
+                    reimbursement_level: new_prescibed_drug.reimbursement_level,
                     created_at: Utc::now(),
                     updated_at: Utc::now(),
                 })
                 .collect(),
             prescription_type: new_prescription.prescription_type,
             code: new_prescription.code,
-            fill: None,
+            fills: vec![],
+            cancellation: None,
+            status: PrescriptionStatus::Active,
             start_date: new_prescription.start_date,
             end_date: new_prescription.end_date,
+            interaction_override_justification: new_prescription.interaction_override_justification,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -178,27 +305,35 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
             .unwrap()
             .push(prescription.clone());
 
-        Ok(prescription)
+        Ok(Prescription {
+            status: prescription.get_status(Utc::now()),
+            ..prescription
+        })
     }
 
     async fn get_prescriptions(
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        status: Option<PrescriptionStatus>,
     ) -> Result<Vec<Prescription>, GetPrescriptionsRepositoryError> {
         let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
             GetPrescriptionsRepositoryError::InvalidPaginationParams(err.to_string())
         })?;
-        let a = offset;
-        let b = offset + page_size;
-
-        let mut prescriptions: Vec<Prescription> = vec![];
-        for i in a..b {
-            match self.prescriptions.read().unwrap().get(i as usize) {
-                Some(prescription) => prescriptions.push(prescription.clone()),
-                None => {}
-            }
-        }
+
+        let prescriptions = self
+            .prescriptions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|prescription| Prescription {
+                status: prescription.get_status(Utc::now()),
+                ..prescription.clone()
+            })
+            .filter(|prescription| status.map_or(true, |status| prescription.status == status))
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .collect();
 
         Ok(prescriptions)
     }
@@ -214,13 +349,41 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
             .iter()
             .find(|prescription| prescription.id == prescription_id)
         {
-            Some(prescription) => Ok(prescription.clone()),
+            Some(prescription) => Ok(Prescription {
+                status: prescription.get_status(Utc::now()),
+                ..prescription.clone()
+            }),
             None => Err(GetPrescriptionByIdRepositoryError::NotFound(
                 prescription_id,
             )),
         }
     }
 
+    async fn lookup_prescriptions(
+        &self,
+        prescription_lookup: PrescriptionLookup,
+    ) -> Result<Vec<Prescription>, LookupPrescriptionsRepositoryError> {
+        let prescriptions = self
+            .prescriptions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|prescription| Prescription {
+                status: prescription.get_status(Utc::now()),
+                ..prescription.clone()
+            })
+            .filter(|prescription| {
+                prescription_lookup.matches(prescription)
+                    && matches!(
+                        prescription.status,
+                        PrescriptionStatus::Active | PrescriptionStatus::PartiallyFilled
+                    )
+            })
+            .collect();
+
+        Ok(prescriptions)
+    }
+
     async fn fill_prescription(
         &self,
         new_prescription_fill: NewPrescriptionFill,
@@ -233,39 +396,267 @@ impl PrescriptionsRepository for PrescriptionsRepositoryFake {
                 new_prescription_fill.pharmacist_id,
             ))?;
 
+        let mut prescriptions = self.prescriptions.write().unwrap();
+        let prescription = prescriptions
+            .iter_mut()
+            .find(|prescription| prescription.id == new_prescription_fill.prescription_id)
+            .ok_or(FillPrescriptionRepositoryError::PrescriptionNotFound(
+                new_prescription_fill.prescription_id,
+            ))?;
+        if prescription.status == PrescriptionStatus::Cancelled {
+            return Err(FillPrescriptionRepositoryError::PrescriptionCancelled(
+                prescription.id,
+            ));
+        }
+        for new_dispensed_drug in &new_prescription_fill.dispensed_drugs {
+            let prescribed_drug = prescription
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.id == new_dispensed_drug.prescribed_drug_id)
+                .ok_or(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                    new_dispensed_drug.prescribed_drug_id,
+                ))?;
+            if new_dispensed_drug.quantity as i32 > prescribed_drug.remaining_quantity {
+                return Err(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                    new_dispensed_drug.prescribed_drug_id,
+                ));
+            }
+            if new_dispensed_drug.quantity as i32
+                > prescription
+                    .get_dispensable_quantity(prescribed_drug, new_prescription_fill.portion_number)
+            {
+                return Err(FillPrescriptionRepositoryError::PortionQuantityExceeded(
+                    new_dispensed_drug.prescribed_drug_id,
+                ));
+            }
+        }
+
         let prescription_fill = PrescriptionFill {
             id: new_prescription_fill.id,
             prescription_id: new_prescription_fill.prescription_id,
             pharmacist_id: new_prescription_fill.pharmacist_id,
+            portion_number: new_prescription_fill.portion_number,
+            dispensed_drugs: new_prescription_fill
+                .dispensed_drugs
+                .iter()
+                .map(|new_dispensed_drug| DispensedDrug {
+                    id: Uuid::new_v4(),
+                    prescription_fill_id: new_prescription_fill.id,
+                    prescribed_drug_id: new_dispensed_drug.prescribed_drug_id,
+                    quantity: new_dispensed_drug.quantity as i32,
+                    substitute_drug_id: new_dispensed_drug.substitute_drug_id,
+                    payable_amount: new_dispensed_drug.payable_amount,
+                    reimbursed_amount: new_dispensed_drug.reimbursed_amount,
+                    price: new_dispensed_drug.price,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                })
+                .collect(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
 
-        let prescriptions = self.prescriptions.read().unwrap().to_owned();
-        let (index, prescription) = prescriptions
-            .iter()
-            .enumerate()
-            .find(|(_, prescription)| prescription.id == new_prescription_fill.prescription_id)
-            .map(|(index, prescription)| {
-                (index, {
-                    let mut prescription = prescription.clone();
-                    prescription.fill = Some(prescription_fill.clone());
-                    prescription
+        for dispensed_drug in &prescription_fill.dispensed_drugs {
+            if let Some(prescribed_drug) = prescription
+                .prescribed_drugs
+                .iter_mut()
+                .find(|prescribed_drug| prescribed_drug.id == dispensed_drug.prescribed_drug_id)
+            {
+                prescribed_drug.remaining_quantity -= dispensed_drug.quantity;
+            }
+        }
+        prescription.fills.push(prescription_fill.clone());
+        prescription.status = match prescription.is_fully_dispensed() {
+            true => PrescriptionStatus::Filled,
+            false => PrescriptionStatus::PartiallyFilled,
+        };
+
+        self.controlled_substance_dispenses.write().unwrap().extend(
+            new_prescription_fill
+                .controlled_substance_dispenses
+                .into_iter()
+                .map(|new_dispense| ControlledSubstanceDispense {
+                    id: new_dispense.id,
+                    prescription_id: new_dispense.prescription_id,
+                    prescription_fill_id: new_dispense.prescription_fill_id,
+                    prescribed_drug_id: new_dispense.prescribed_drug_id,
+                    drug_id: new_dispense.drug_id,
+                    variant_id: new_dispense.variant_id,
+                    quantity: new_dispense.quantity,
+                    patient_id: new_dispense.patient_id,
+                    doctor_id: new_dispense.doctor_id,
+                    pharmacist_id: new_dispense.pharmacist_id,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                }),
+        );
+
+        Ok(prescription_fill)
+    }
+
+    async fn cancel_prescription(
+        &self,
+        new_prescription_cancellation: NewPrescriptionCancellation,
+    ) -> Result<PrescriptionCancellation, CancelPrescriptionRepositoryError> {
+        let mut prescriptions = self.prescriptions.write().unwrap();
+        let prescription = prescriptions
+            .iter_mut()
+            .find(|prescription| prescription.id == new_prescription_cancellation.prescription_id)
+            .ok_or(CancelPrescriptionRepositoryError::NotFound(
+                new_prescription_cancellation.prescription_id,
+            ))?;
+
+        let prescription_cancellation = PrescriptionCancellation {
+            reason: new_prescription_cancellation.reason,
+            cancelled_at: new_prescription_cancellation.cancelled_at,
+        };
+        prescription.cancellation = Some(prescription_cancellation.clone());
+        prescription.status = PrescriptionStatus::Cancelled;
+        prescription.updated_at = Utc::now();
+
+        Ok(prescription_cancellation)
+    }
+
+    async fn get_interacting_drugs(
+        &self,
+        patient_id: Uuid,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<InteractingDrug>, CheckDrugInteractionsRepositoryError> {
+        let drugs = self.drugs.read().unwrap();
+        let to_interacting_drug = |drug_id: Uuid, prescription_id: Option<Uuid>| {
+            drugs
+                .iter()
+                .find(|drug| drug.id == drug_id)
+                .map(|drug| InteractingDrug {
+                    drug_id: drug.id,
+                    drug_name: drug.name.clone(),
+                    active_substances: drug
+                        .active_substances
+                        .iter()
+                        .map(|active_substance| active_substance.name.clone())
+                        .collect(),
+                    prescription_id,
                 })
+        };
+
+        let mut interacting_drugs: Vec<InteractingDrug> = drug_ids
+            .iter()
+            .filter_map(|&drug_id| to_interacting_drug(drug_id, None))
+            .collect();
+        for prescription in self.prescriptions.read().unwrap().iter() {
+            let is_current = matches!(
+                prescription.get_status(Utc::now()),
+                PrescriptionStatus::Pending
+                    | PrescriptionStatus::Active
+                    | PrescriptionStatus::PartiallyFilled
+            );
+            if prescription.patient.id != patient_id || !is_current {
+                continue;
+            }
+            for prescribed_drug in &prescription.prescribed_drugs {
+                interacting_drugs.extend(to_interacting_drug(
+                    prescribed_drug.drug_id,
+                    Some(prescription.id),
+                ));
+            }
+        }
+
+        Ok(interacting_drugs)
+    }
+
+    async fn get_drug_interactions(
+        &self,
+        active_substances: Vec<String>,
+    ) -> Result<Vec<DrugInteraction>, CheckDrugInteractionsRepositoryError> {
+        let drug_interactions = self
+            .drug_interactions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|interaction| {
+                active_substances.contains(&interaction.substance_a)
+                    && active_substances.contains(&interaction.substance_b)
             })
-            .unwrap();
+            .cloned()
+            .collect();
 
-        self.prescriptions
-            .write()
+        Ok(drug_interactions)
+    }
+
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, CheckAllergiesRepositoryError> {
+        let patient_allergies = self
+            .patient_allergies
+            .read()
             .unwrap()
-            .insert(index, prescription);
+            .iter()
+            .filter(|allergy| allergy.patient_id == patient_id)
+            .cloned()
+            .collect();
 
-        Ok(prescription_fill)
+        Ok(patient_allergies)
+    }
+
+    async fn get_patient_birth_date(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<NaiveDate, GetPatientBirthDateRepositoryError> {
+        self.patients
+            .read()
+            .unwrap()
+            .iter()
+            .find(|patient| patient.id == patient_id)
+            .map(|patient| patient.birth_date)
+            .ok_or(GetPatientBirthDateRepositoryError::PatientNotFound(
+                patient_id,
+            ))
+    }
+
+    async fn get_drugs_by_ids(
+        &self,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<Drug>, GetDrugsByIdsRepositoryError> {
+        let drugs = self
+            .drugs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|drug| drug_ids.contains(&drug.id))
+            .cloned()
+            .collect();
+
+        Ok(drugs)
+    }
+
+    async fn get_controlled_substance_dispenses(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ControlledSubstanceDispense>, GetControlledSubstanceDispensesRepositoryError>
+    {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetControlledSubstanceDispensesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let controlled_substance_dispenses = self
+            .controlled_substance_dispenses
+            .read()
+            .unwrap()
+            .iter()
+            .skip(offset as usize)
+            .take(page_size as usize)
+            .cloned()
+            .collect();
+
+        Ok(controlled_substance_dispenses)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
     use uuid::Uuid;
 
     use crate::domain::{
@@ -274,11 +665,11 @@ mod tests {
             repository::{DoctorsRepository, DoctorsRepositoryFake},
         },
         drugs::{
-            entities::{DrugContentType, NewDrug},
+            entities::{DrugContentType, DrugInteractionSeverity, NewDrug, NewDrugInteraction},
             repository::{DrugsRepository, DrugsRepositoryFake},
         },
         patients::{
-            entities::NewPatient,
+            entities::{AllergySeverity, NewPatient, NewPatientAllergy},
             repository::{PatientsRepository, PatientsRepositoryFake},
         },
         pharmacists::{
@@ -286,11 +677,15 @@ mod tests {
             repository::{PharmacistsRepository, PharmacistsRepositoryFake},
         },
         prescriptions::{
-            entities::{NewPrescribedDrug, NewPrescription},
+            entities::{
+                NewDispensedDrug, NewPrescribedDrug, NewPrescription, NewPrescriptionCancellation,
+                PrescriptionLookup, PrescriptionStatus, PrescriptionType,
+            },
             repository::{
-                CreatePrescriptionRepositoryError, FillPrescriptionRepositoryError,
-                GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
-                PrescriptionsRepository, PrescriptionsRepositoryFake,
+                CancelPrescriptionRepositoryError, CreatePrescriptionRepositoryError,
+                FillPrescriptionRepositoryError, GetPrescriptionByIdRepositoryError,
+                GetPrescriptionsRepositoryError, PrescriptionsRepository,
+                PrescriptionsRepositoryFake,
             },
         },
     };
@@ -374,7 +769,7 @@ mod tests {
     }
 
     async fn setup_repository() -> (PrescriptionsRepositoryFake, DatabaseSeeds) {
-        let repository = PrescriptionsRepositoryFake::new(None, None, None, None, None);
+        let repository = PrescriptionsRepositoryFake::new(None, None, None, None, None, None, None);
         let seeds = seed_in_memory_database(&repository).await;
         (repository, seeds)
     }
@@ -392,10 +787,12 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[1].id,
                     quantity: 1,
+                    ..Default::default()
                 },
             ],
         )
@@ -414,6 +811,79 @@ mod tests {
         assert_eq!(prescription_from_db, new_prescription);
     }
 
+    #[tokio::test]
+    async fn creates_prescription_for_drug_variant() {
+        let (repository, seeds) = setup_repository().await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    variant_id: Some(seeds.drugs[0].variants[0].id),
+                    quantity: 2,
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[1].id,
+                    quantity: 30,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+
+        repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(new_prescription.id)
+            .await
+            .unwrap();
+        let prescribed_drug = |drug_id| {
+            prescription_from_db
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.drug_id == drug_id)
+                .unwrap()
+        };
+
+        assert_eq!(
+            prescribed_drug(seeds.drugs[0].id).variant_id,
+            Some(seeds.drugs[0].variants[0].id)
+        );
+        assert_eq!(prescribed_drug(seeds.drugs[1].id).variant_id, None);
+
+        let variant_of_other_drug_id = seeds.drugs[1].variants[0].id;
+        let new_prescription_with_variant_of_other_drug = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                variant_id: Some(variant_of_other_drug_id),
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository
+                .create_prescription(new_prescription_with_variant_of_other_drug)
+                .await,
+            Err(CreatePrescriptionRepositoryError::DrugVariantNotFound(
+                variant_of_other_drug_id
+            ))
+        );
+    }
+
     #[tokio::test]
     async fn doesnt_create_prescription_if_relations_dont_exist() {
         let (repository, seeds) = setup_repository().await;
@@ -427,6 +897,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -449,6 +920,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -471,6 +943,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: nonexistent_drug_id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -513,18 +986,22 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[1].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[2].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[3].id,
                     quantity: 1,
+                    ..Default::default()
                 },
             ],
         )
@@ -544,6 +1021,7 @@ mod tests {
                 vec![NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 }],
             )
             .unwrap();
@@ -553,16 +1031,22 @@ mod tests {
                 .unwrap();
         }
 
-        let prescriptions = repository.get_prescriptions(None, Some(7)).await.unwrap();
+        let prescriptions = repository
+            .get_prescriptions(None, Some(7), None)
+            .await
+            .unwrap();
 
         assert_eq!(prescriptions.len(), 7);
         assert_eq!(prescriptions[0], new_prescription);
 
-        let prescriptions = repository.get_prescriptions(None, Some(20)).await.unwrap();
+        let prescriptions = repository
+            .get_prescriptions(None, Some(20), None)
+            .await
+            .unwrap();
         assert_eq!(prescriptions.len(), 11);
 
         let prescriptions = repository
-            .get_prescriptions(Some(1), Some(10))
+            .get_prescriptions(Some(1), Some(10), None)
             .await
             .unwrap();
         assert_eq!(prescriptions.len(), 1);
@@ -573,16 +1057,18 @@ mod tests {
         let (repository, _) = setup_repository().await;
 
         assert!(
-            match repository.get_prescriptions(Some(-1), Some(10)).await {
+            match repository.get_prescriptions(Some(-1), Some(10), None).await {
                 Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
                 _ => false,
             },
         );
 
-        assert!(match repository.get_prescriptions(Some(0), Some(0)).await {
-            Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
-            _ => false,
-        });
+        assert!(
+            match repository.get_prescriptions(Some(0), Some(0), None).await {
+                Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
+                _ => false,
+            }
+        );
     }
 
     #[tokio::test]
@@ -597,6 +1083,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -611,11 +1098,11 @@ mod tests {
             .await
             .unwrap();
 
-        assert!(prescription_from_db.fill.is_none());
+        assert!(prescription_from_db.fills.is_empty());
 
         let code = prescription_from_db.code.clone();
         let new_prescription_fill = prescription_from_db
-            .fill(seeds.pharmacist.id, code)
+            .fill(seeds.pharmacist.id, code, None, Duration::zero())
             .unwrap();
         let created_prescription_fill = repository
             .fill_prescription(new_prescription_fill.clone())
@@ -629,44 +1116,126 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(prescription_from_db.fill.unwrap(), new_prescription_fill);
+        assert_eq!(prescription_from_db.fills, vec![created_prescription_fill]);
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            0
+        );
     }
 
     #[tokio::test]
-    async fn doesnt_fill_if_pharmacist_relation_doesnt_exist() {
+    async fn fills_prescription_in_multiple_parts() {
         let (repository, seeds) = setup_repository().await;
 
-        let nonexistent_pharmacist_id = Uuid::new_v4();
-        let new_prescription = NewPrescription::new(
+        let prescription = NewPrescription::new(
             seeds.doctor.id,
             seeds.patient.id,
             None,
             None,
-            vec![
-                NewPrescribedDrug {
-                    drug_id: seeds.drugs[0].id,
-                    quantity: 1,
-                },
-                NewPrescribedDrug {
-                    drug_id: seeds.drugs[1].id,
-                    quantity: 1,
-                },
-            ],
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 3,
+                ..Default::default()
+            }],
         )
         .unwrap();
 
         let prescription_from_db = repository
-            .create_prescription(new_prescription.clone())
+            .create_prescription(prescription.clone())
+            .await
+            .unwrap();
+        let prescribed_drug_id = prescription_from_db.prescribed_drugs[0].id;
+
+        let first_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id,
+                    quantity: 2,
+                    ..Default::default()
+                }]),
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .fill_prescription(first_prescription_fill)
             .await
             .unwrap();
 
-        let code = prescription_from_db.code.clone();
-        let new_prescription_fill_with_nonexistent_pharmacist_id = prescription_from_db
-            .fill(nonexistent_pharmacist_id, code)
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
             .unwrap();
 
         assert_eq!(
-            repository
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            1
+        );
+
+        let second_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                None,
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .fill_prescription(second_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(prescription_from_db.fills.len(), 2);
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            0
+        );
+        assert!(prescription_from_db.is_fully_dispensed());
+    }
+
+    #[tokio::test]
+    async fn doesnt_fill_if_pharmacist_relation_doesnt_exist() {
+        let (repository, seeds) = setup_repository().await;
+
+        let nonexistent_pharmacist_id = Uuid::new_v4();
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[1].id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+
+        let prescription_from_db = repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        let code = prescription_from_db.code.clone();
+        let new_prescription_fill_with_nonexistent_pharmacist_id = prescription_from_db
+            .fill(nonexistent_pharmacist_id, code, None, Duration::zero())
+            .unwrap();
+
+        assert_eq!(
+            repository
                 .fill_prescription(new_prescription_fill_with_nonexistent_pharmacist_id)
                 .await,
             Err(FillPrescriptionRepositoryError::PharmacistNotFound(
@@ -674,4 +1243,477 @@ mod tests {
             ))
         );
     }
+
+    #[tokio::test]
+    async fn doesnt_fill_if_prescription_was_filled_or_cancelled_in_the_meantime() {
+        let (repository, seeds) = setup_repository().await;
+
+        let create_prescription = || {
+            NewPrescription::new(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 2,
+                    ..Default::default()
+                }],
+            )
+            .unwrap()
+        };
+
+        let prescription_from_db = repository
+            .create_prescription(create_prescription())
+            .await
+            .unwrap();
+        let create_prescription_fill = || {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    None,
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill = create_prescription_fill();
+        let second_prescription_fill = create_prescription_fill();
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(second_prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                prescription_from_db.prescribed_drugs[0].id
+            ))
+        );
+
+        let prescription_from_db = repository
+            .create_prescription(create_prescription())
+            .await
+            .unwrap();
+        let prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                None,
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .cancel_prescription(NewPrescriptionCancellation {
+                prescription_id: prescription_from_db.id,
+                reason: "Issued by mistake".into(),
+                cancelled_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::PrescriptionCancelled(
+                prescription_from_db.id
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn marks_prescription_as_filled_if_other_drugs_were_dispensed_in_the_meantime() {
+        let (repository, seeds) = setup_repository().await;
+
+        let prescription_from_db = repository
+            .create_prescription(
+                NewPrescription::new(
+                    seeds.doctor.id,
+                    seeds.patient.id,
+                    None,
+                    None,
+                    vec![
+                        NewPrescribedDrug {
+                            drug_id: seeds.drugs[0].id,
+                            quantity: 1,
+                            ..Default::default()
+                        },
+                        NewPrescribedDrug {
+                            drug_id: seeds.drugs[1].id,
+                            quantity: 1,
+                            ..Default::default()
+                        },
+                    ],
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let create_prescription_fill = |prescribed_drug_id| {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    Some(vec![NewDispensedDrug {
+                        prescribed_drug_id,
+                        quantity: 1,
+                        ..Default::default()
+                    }]),
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill =
+            create_prescription_fill(prescription_from_db.prescribed_drugs[0].id);
+        let second_prescription_fill =
+            create_prescription_fill(prescription_from_db.prescribed_drugs[1].id);
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+        repository
+            .fill_prescription(second_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription_from_db.id)
+            .await
+            .unwrap();
+
+        assert_eq!(prescription_from_db.status, PrescriptionStatus::Filled);
+    }
+
+    #[tokio::test]
+    async fn doesnt_fill_if_portion_was_dispensed_in_the_meantime() {
+        let (repository, seeds) = setup_repository().await;
+
+        let prescription_from_db = repository
+            .create_prescription(
+                NewPrescription::new(
+                    seeds.doctor.id,
+                    seeds.patient.id,
+                    None,
+                    Some(PrescriptionType::ForChronicDiseaseDrugs),
+                    vec![NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 24,
+                        ..Default::default()
+                    }],
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let prescribed_drug_id = prescription_from_db.prescribed_drugs[0].id;
+        let create_prescription_fill = |quantity| {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    Some(vec![NewDispensedDrug {
+                        prescribed_drug_id,
+                        quantity,
+                        ..Default::default()
+                    }]),
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill = create_prescription_fill(1);
+        let second_prescription_fill = create_prescription_fill(2);
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(second_prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::PortionQuantityExceeded(
+                prescribed_drug_id
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn cancels_prescription_and_saves_to_database() {
+        let (repository, seeds) = setup_repository().await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        let prescription_from_db = repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        assert!(prescription_from_db.cancellation.is_none());
+
+        let new_prescription_cancellation = prescription_from_db
+            .cancel(seeds.doctor.id, "Issued by mistake".into())
+            .unwrap();
+        let created_prescription_cancellation = repository
+            .cancel_prescription(new_prescription_cancellation.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            created_prescription_cancellation.reason,
+            new_prescription_cancellation.reason
+        );
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(new_prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_db.cancellation,
+            Some(created_prescription_cancellation)
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_cancel_if_prescription_doesnt_exist() {
+        let (repository, _) = setup_repository().await;
+
+        let nonexistent_prescription_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .cancel_prescription(NewPrescriptionCancellation {
+                    prescription_id: nonexistent_prescription_id,
+                    reason: "Issued by mistake".into(),
+                    cancelled_at: Utc::now(),
+                })
+                .await,
+            Err(CancelPrescriptionRepositoryError::NotFound(
+                nonexistent_prescription_id
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn looks_up_prescriptions_by_pesel_number_and_code() {
+        let (repository, seeds) = setup_repository().await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        let prescription = repository
+            .create_prescription(new_prescription)
+            .await
+            .unwrap();
+
+        for code in [
+            prescription.code.clone(),
+            prescription.code[..4].to_string(),
+        ] {
+            let prescriptions_from_db = repository
+                .lookup_prescriptions(
+                    PrescriptionLookup::new(seeds.patient.pesel_number.clone(), None, code)
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(prescriptions_from_db.len(), 1);
+            assert_eq!(prescriptions_from_db[0].id, prescription.id);
+        }
+
+        let prescriptions_of_other_patient = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(
+                    Some("96021817257".into()),
+                    None,
+                    prescription.code.clone(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(prescriptions_of_other_patient.is_empty());
+
+        let prescription_cancellation = prescription
+            .cancel(seeds.doctor.id, "Issued by mistake".into())
+            .unwrap();
+        repository
+            .cancel_prescription(prescription_cancellation)
+            .await
+            .unwrap();
+
+        let cancelled_prescriptions = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(seeds.patient.pesel_number, None, prescription.code)
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(cancelled_prescriptions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn gets_interacting_drugs_from_current_prescriptions() {
+        let (repository, seeds) = setup_repository().await;
+
+        let current_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        repository
+            .create_prescription(current_prescription.clone())
+            .await
+            .unwrap();
+        let cancelled_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[1].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        repository
+            .create_prescription(cancelled_prescription.clone())
+            .await
+            .unwrap();
+        repository
+            .cancel_prescription(NewPrescriptionCancellation {
+                prescription_id: cancelled_prescription.id,
+                reason: "Issued by mistake".into(),
+                cancelled_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let interacting_drugs = repository
+            .get_interacting_drugs(seeds.patient.id, vec![seeds.drugs[2].id])
+            .await
+            .unwrap();
+
+        assert_eq!(interacting_drugs.len(), 2);
+        assert_eq!(interacting_drugs[0].drug_id, seeds.drugs[2].id);
+        assert_eq!(interacting_drugs[0].prescription_id, None);
+        assert_eq!(interacting_drugs[1].drug_id, seeds.drugs[0].id);
+        assert_eq!(
+            interacting_drugs[1].prescription_id,
+            Some(current_prescription.id)
+        );
+
+        let interacting_drugs = repository
+            .get_interacting_drugs(Uuid::new_v4(), vec![seeds.drugs[2].id])
+            .await
+            .unwrap();
+
+        assert_eq!(interacting_drugs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn gets_drug_interactions_between_given_substances() {
+        let interaction = NewDrugInteraction::new(
+            "warfarin".into(),
+            "ibuprofen".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        )
+        .unwrap();
+        let drugs_repo = DrugsRepositoryFake::new();
+        let created_interaction = drugs_repo
+            .create_drug_interaction(interaction)
+            .await
+            .unwrap();
+        let repository = PrescriptionsRepositoryFake::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(vec![created_interaction.clone()]),
+            None,
+        );
+
+        let interactions = repository
+            .get_drug_interactions(vec!["ibuprofen".into(), "warfarin".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(interactions, vec![created_interaction]);
+
+        let interactions = repository
+            .get_drug_interactions(vec!["ibuprofen".into(), "paracetamol".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(interactions.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn gets_patient_allergies() {
+        let patients_repo = PatientsRepositoryFake::new();
+        let patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        patients_repo.create_patient(patient.clone()).await.unwrap();
+        let allergy = NewPatientAllergy::new(
+            patient.id,
+            "amoxicillin".into(),
+            "Hives".into(),
+            AllergySeverity::Severe,
+            Uuid::new_v4(),
+        )
+        .unwrap();
+        let created_allergy = patients_repo.create_patient_allergy(allergy).await.unwrap();
+        let repository = PrescriptionsRepositoryFake::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(vec![created_allergy.clone()]),
+        );
+
+        let allergies = repository.get_patient_allergies(patient.id).await.unwrap();
+
+        assert_eq!(allergies, vec![created_allergy]);
+
+        let allergies = repository
+            .get_patient_allergies(Uuid::new_v4())
+            .await
+            .unwrap();
+
+        assert_eq!(allergies.len(), 0);
+    }
 }
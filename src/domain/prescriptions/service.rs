@@ -1,22 +1,41 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
 use super::{
-    entities::{NewPrescribedDrug, NewPrescription, Prescription, PrescriptionType},
+    entities::{
+        ControlledSubstanceDispense, CreatedPrescription, NewDispensedDrug, NewPrescribedDrug,
+        NewPrescription, Prescription, PrescriptionFillRequest, PrescriptionLookup,
+        PrescriptionStatus, PrescriptionType,
+    },
     repository::{
-        CreatePrescriptionRepositoryError, FillPrescriptionRepositoryError,
-        GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
+        CancelPrescriptionRepositoryError, CreatePrescriptionRepositoryError,
+        FillPrescriptionRepositoryError, GetControlledSubstanceDispensesRepositoryError,
+        GetPatientBirthDateRepositoryError, GetPrescriptionByIdRepositoryError,
+        GetPrescriptionsRepositoryError, LookupPrescriptionsRepositoryError,
         PrescriptionsRepository,
     },
+    use_cases::{
+        cancel_prescription::CancelPrescriptionDomainError,
+        check_allergies::CheckAllergiesDomainError,
+        check_drug_interactions::CheckDrugInteractionsDomainError,
+        check_prescription_type::CheckPrescriptionTypeDomainError,
+        fill_prescription::get_default_early_pickup_tolerance,
+        lookup_prescriptions::LookupPrescriptionsDomainError,
+        scan_dispensed_packages::ScanDispensedPackagesDomainError,
+    },
 };
 
 pub struct PrescriptionsService {
     repository: Box<dyn PrescriptionsRepository>,
+    early_pickup_tolerance: Duration,
 }
 
 #[derive(Debug)]
 pub enum CreatePrescriptionError {
     DomainError(String),
+    PrescriptionTypeError(CheckPrescriptionTypeDomainError),
+    DrugInteractionsError(CheckDrugInteractionsDomainError),
+    AllergiesError(CheckAllergiesDomainError),
     RepositoryError(CreatePrescriptionRepositoryError),
 }
 
@@ -30,15 +49,41 @@ pub enum GetPrescriptionsWithPaginationError {
     RepositoryError(GetPrescriptionsRepositoryError),
 }
 
+#[derive(Debug, PartialEq)]
+pub enum LookupPrescriptionsError {
+    DomainError(LookupPrescriptionsDomainError),
+    RepositoryError(LookupPrescriptionsRepositoryError),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum FillPrescriptionError {
     DomainError(String),
+    LookupError(LookupPrescriptionsError),
     RepositoryError(FillPrescriptionRepositoryError),
 }
 
+#[derive(Debug)]
+pub enum GetControlledSubstanceDispensesWithPaginationError {
+    RepositoryError(GetControlledSubstanceDispensesRepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CancelPrescriptionError {
+    DomainError(CancelPrescriptionDomainError),
+    RepositoryError(CancelPrescriptionRepositoryError),
+}
+
 impl PrescriptionsService {
     pub fn new(repository: Box<dyn PrescriptionsRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            early_pickup_tolerance: get_default_early_pickup_tolerance(),
+        }
+    }
+
+    pub fn with_early_pickup_tolerance(mut self, early_pickup_tolerance: Duration) -> Self {
+        self.early_pickup_tolerance = early_pickup_tolerance;
+        self
     }
 
     pub async fn create_prescription(
@@ -47,36 +92,128 @@ impl PrescriptionsService {
         patient_id: Uuid,
         start_date: Option<DateTime<Utc>>,
         prescription_type: Option<PrescriptionType>,
-        prescribed_drugs: Vec<(Uuid, u32)>,
-    ) -> Result<Prescription, CreatePrescriptionError> {
-        let new_prescription = NewPrescription::new(
+        prescribed_drugs: Vec<NewPrescribedDrug>,
+        interaction_override_justification: Option<String>,
+    ) -> Result<CreatedPrescription, CreatePrescriptionError> {
+        let mut new_prescription = NewPrescription::new(
             doctor_id,
             patient_id,
             start_date,
             prescription_type,
-            prescribed_drugs
-                .iter()
-                .map(|&(drug_id, quantity)| NewPrescribedDrug { drug_id, quantity })
-                .collect(),
+            prescribed_drugs,
         )
         .map_err(|err| CreatePrescriptionError::DomainError(err.to_string()))?;
 
+        let drug_ids: Vec<Uuid> = new_prescription
+            .prescribed_drugs
+            .iter()
+            .map(|prescribed_drug| prescribed_drug.drug_id)
+            .collect();
+        let drugs = self
+            .repository
+            .get_drugs_by_ids(drug_ids.clone())
+            .await
+            .map_err(|err| {
+                CreatePrescriptionError::RepositoryError(
+                    CreatePrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+        new_prescription
+            .check_prescription_type(&drugs)
+            .map_err(CreatePrescriptionError::PrescriptionTypeError)?;
+        new_prescription
+            .check_quantity_limits(&drugs)
+            .map_err(|err| CreatePrescriptionError::DomainError(err.to_string()))?;
+        let patient_birth_date = self
+            .repository
+            .get_patient_birth_date(patient_id)
+            .await
+            .map_err(|err| {
+                CreatePrescriptionError::RepositoryError(match err {
+                    GetPatientBirthDateRepositoryError::PatientNotFound(patient_id) => {
+                        CreatePrescriptionRepositoryError::PatientNotFound(patient_id)
+                    }
+                    GetPatientBirthDateRepositoryError::DatabaseError(err) => {
+                        CreatePrescriptionRepositoryError::DatabaseError(err)
+                    }
+                })
+            })?;
+        new_prescription
+            .check_reimbursement(&drugs, patient_birth_date)
+            .map_err(|err| CreatePrescriptionError::DomainError(err.to_string()))?;
+
+        let interacting_drugs = self
+            .repository
+            .get_interacting_drugs(patient_id, drug_ids)
+            .await
+            .map_err(|err| {
+                CreatePrescriptionError::RepositoryError(
+                    CreatePrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+        let mut active_substances: Vec<String> = interacting_drugs
+            .iter()
+            .flat_map(|drug| drug.active_substances.clone())
+            .collect();
+        active_substances.sort();
+        active_substances.dedup();
+        let drug_interactions = self
+            .repository
+            .get_drug_interactions(active_substances)
+            .await
+            .map_err(|err| {
+                CreatePrescriptionError::RepositoryError(
+                    CreatePrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+        let patient_allergies = self
+            .repository
+            .get_patient_allergies(patient_id)
+            .await
+            .map_err(|err| {
+                CreatePrescriptionError::RepositoryError(
+                    CreatePrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+
+        new_prescription
+            .check_allergies(&interacting_drugs, &patient_allergies)
+            .map_err(CreatePrescriptionError::AllergiesError)?;
+        let interaction_warnings = new_prescription
+            .check_drug_interactions(
+                &interacting_drugs,
+                &drug_interactions,
+                interaction_override_justification,
+            )
+            .map_err(CreatePrescriptionError::DrugInteractionsError)?;
+
         let created_prescription = self
             .repository
             .create_prescription(new_prescription)
             .await
             .map_err(|err| CreatePrescriptionError::RepositoryError(err))?;
 
-        Ok(created_prescription)
+        Ok(CreatedPrescription {
+            prescription: created_prescription,
+            interaction_warnings,
+        })
     }
 
     pub async fn fill_prescription(
         &self,
         prescription_id: Uuid,
-        pharmacist_id: Uuid,
-        prescription_code: String,
+        request: PrescriptionFillRequest,
     ) -> Result<Prescription, FillPrescriptionError> {
-        let mut prescription = self
+        let PrescriptionFillRequest {
+            pharmacist_id,
+            prescription_code,
+            dispensed_drugs,
+            substitutes,
+            scanned_gtins,
+            controlled_substances_confirmed,
+        } = request;
+
+        let prescription = self
             .repository
             .get_prescription_by_id(prescription_id)
             .await
@@ -91,18 +228,180 @@ impl PrescriptionsService {
                 ),
             })?;
 
-        let new_prescription_fill = prescription
-            .fill(pharmacist_id, prescription_code)
+        let substitutes = substitutes.unwrap_or_default();
+        let drug_ids: Vec<Uuid> = prescription
+            .prescribed_drugs
+            .iter()
+            .map(|prescribed_drug| prescribed_drug.drug_id)
+            .chain(
+                substitutes
+                    .iter()
+                    .map(|&(_, substitute_drug_id)| substitute_drug_id),
+            )
+            .collect();
+        let drugs = self
+            .repository
+            .get_drugs_by_ids(drug_ids)
+            .await
+            .map_err(|err| {
+                FillPrescriptionError::RepositoryError(
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+
+        let dispensed_drugs = match scanned_gtins {
+            Some(_) if dispensed_drugs.is_some() => Err(FillPrescriptionError::DomainError(
+                ScanDispensedPackagesDomainError::DispensedDrugsGivenWithScannedPackages
+                    .to_string(),
+            ))?,
+            Some(scanned_gtins) => Some(
+                prescription
+                    .get_scanned_dispensed_drugs(&scanned_gtins, &substitutes, &drugs)
+                    .map_err(|err| FillPrescriptionError::DomainError(err.to_string()))?,
+            ),
+            None => dispensed_drugs,
+        };
+
+        let mut new_prescription_fill = prescription
+            .fill(
+                pharmacist_id,
+                prescription_code,
+                dispensed_drugs.map(|dispensed_drugs| {
+                    dispensed_drugs
+                        .iter()
+                        .map(|&(prescribed_drug_id, quantity)| NewDispensedDrug {
+                            prescribed_drug_id,
+                            quantity,
+                            ..Default::default()
+                        })
+                        .collect()
+                }),
+                self.early_pickup_tolerance,
+            )
+            .map_err(|err| FillPrescriptionError::DomainError(err.to_string()))?;
+        if !substitutes.is_empty() {
+            new_prescription_fill = new_prescription_fill
+                .with_substitutes(&prescription, substitutes, &drugs)
+                .map_err(|err| FillPrescriptionError::DomainError(err.to_string()))?;
+        }
+        new_prescription_fill =
+            new_prescription_fill.with_payments(&prescription, &drugs, Utc::now());
+        new_prescription_fill = new_prescription_fill
+            .with_controlled_substances_confirmation(&prescription, controlled_substances_confirmed)
             .map_err(|err| FillPrescriptionError::DomainError(err.to_string()))?;
 
-        let prescription_fill = self
-            .repository
+        self.repository
             .fill_prescription(new_prescription_fill)
             .await
-            .map_err(|err| FillPrescriptionError::RepositoryError(err))?;
-        prescription.fill = Some(prescription_fill);
+            .map_err(FillPrescriptionError::RepositoryError)?;
 
-        Ok(prescription)
+        let filled_prescription = self
+            .repository
+            .get_prescription_by_id(prescription_id)
+            .await
+            .map_err(|err| {
+                FillPrescriptionError::RepositoryError(
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string()),
+                )
+            })?;
+
+        Ok(filled_prescription)
+    }
+
+    pub async fn lookup_prescriptions(
+        &self,
+        pesel_number: Option<String>,
+        document_number: Option<String>,
+        code: String,
+    ) -> Result<Vec<Prescription>, LookupPrescriptionsError> {
+        let prescription_lookup = PrescriptionLookup::new(pesel_number, document_number, code)
+            .map_err(LookupPrescriptionsError::DomainError)?;
+
+        let prescriptions = self
+            .repository
+            .lookup_prescriptions(prescription_lookup)
+            .await
+            .map_err(LookupPrescriptionsError::RepositoryError)?;
+
+        Ok(prescriptions)
+    }
+
+    pub async fn fill_prescription_by_code(
+        &self,
+        pesel_number: Option<String>,
+        document_number: Option<String>,
+        request: PrescriptionFillRequest,
+    ) -> Result<Prescription, FillPrescriptionError> {
+        let prescription_lookup = PrescriptionLookup::new(
+            pesel_number,
+            document_number,
+            request.prescription_code.clone(),
+        )
+        .map_err(|err| {
+            FillPrescriptionError::LookupError(LookupPrescriptionsError::DomainError(err))
+        })?;
+
+        let prescriptions = self
+            .repository
+            .lookup_prescriptions(prescription_lookup.clone())
+            .await
+            .map_err(|err| {
+                FillPrescriptionError::LookupError(LookupPrescriptionsError::RepositoryError(err))
+            })?;
+
+        let prescription = prescription_lookup
+            .find_single(prescriptions)
+            .map_err(|err| {
+                FillPrescriptionError::LookupError(LookupPrescriptionsError::DomainError(err))
+            })?;
+
+        // A short code is looked up, but the fill is checked against the full code
+        self.fill_prescription(
+            prescription.id,
+            PrescriptionFillRequest {
+                prescription_code: prescription.code,
+                ..request
+            },
+        )
+        .await
+    }
+
+    pub async fn cancel_prescription(
+        &self,
+        prescription_id: Uuid,
+        doctor_id: Uuid,
+        reason: String,
+    ) -> Result<Prescription, CancelPrescriptionError> {
+        let prescription = self
+            .repository
+            .get_prescription_by_id(prescription_id)
+            .await
+            .map_err(|err| match err {
+                GetPrescriptionByIdRepositoryError::NotFound(id) => {
+                    CancelPrescriptionError::RepositoryError(
+                        CancelPrescriptionRepositoryError::NotFound(id),
+                    )
+                }
+                _ => CancelPrescriptionError::RepositoryError(
+                    CancelPrescriptionRepositoryError::DatabaseError(err.to_string()),
+                ),
+            })?;
+
+        let new_prescription_cancellation = prescription
+            .cancel(doctor_id, reason)
+            .map_err(CancelPrescriptionError::DomainError)?;
+
+        let prescription_cancellation = self
+            .repository
+            .cancel_prescription(new_prescription_cancellation)
+            .await
+            .map_err(CancelPrescriptionError::RepositoryError)?;
+
+        Ok(Prescription {
+            cancellation: Some(prescription_cancellation),
+            status: PrescriptionStatus::Cancelled,
+            ..prescription
+        })
     }
 
     pub async fn get_prescription_by_id(
@@ -122,35 +421,76 @@ impl PrescriptionsService {
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        status: Option<PrescriptionStatus>,
     ) -> Result<Vec<Prescription>, GetPrescriptionsWithPaginationError> {
         let result = self
             .repository
-            .get_prescriptions(page, page_size)
+            .get_prescriptions(page, page_size, status)
             .await
             .map_err(|err| GetPrescriptionsWithPaginationError::RepositoryError(err))?;
 
         Ok(result)
     }
+
+    pub async fn get_controlled_substance_dispenses_with_pagination(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ControlledSubstanceDispense>, GetControlledSubstanceDispensesWithPaginationError>
+    {
+        let result = self
+            .repository
+            .get_controlled_substance_dispenses(page, page_size)
+            .await
+            .map_err(GetControlledSubstanceDispensesWithPaginationError::RepositoryError)?;
+
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{FillPrescriptionError, PrescriptionsService};
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use super::{
+        CancelPrescriptionError, CreatePrescriptionError, FillPrescriptionError,
+        GetControlledSubstanceDispensesWithPaginationError, LookupPrescriptionsError,
+        PrescriptionsService,
+    };
     use crate::domain::{
         doctors::{entities::Doctor, repository::DoctorsRepositoryFake, service::DoctorsService},
         drugs::{
-            entities::{Drug, DrugContentType},
+            entities::{
+                ControlledSubstanceList, Drug, DrugActiveSubstance, DrugContentType,
+                DrugInteractionSeverity, ReimbursementLevel, StrengthUnit,
+            },
             repository::DrugsRepositoryFake,
             service::DrugsService,
         },
         patients::{
-            entities::Patient, repository::PatientsRepositoryFake, service::PatientsService,
+            entities::{AllergySeverity, Patient},
+            repository::PatientsRepositoryFake,
+            service::PatientsService,
         },
         pharmacists::{
             entities::Pharmacist, repository::PharmacistsRepositoryFake,
             service::PharmacistsService,
         },
-        prescriptions::{entities::PrescriptionType, repository::PrescriptionsRepositoryFake},
+        prescriptions::{
+            entities::{
+                AdministrationRoute, Dosage, DosagePeriod, DoseUnit, NewPrescribedDrug,
+                PrescriptionFillRequest, PrescriptionStatus, PrescriptionType,
+            },
+            repository::PrescriptionsRepositoryFake,
+            use_cases::{
+                cancel_prescription::CancelPrescriptionDomainError,
+                check_allergies::CheckAllergiesDomainError,
+                check_drug_interactions::CheckDrugInteractionsDomainError,
+                check_prescription_type::CheckPrescriptionTypeDomainError,
+                lookup_prescriptions::LookupPrescriptionsDomainError,
+            },
+        },
     };
 
     struct DatabaseSeeds {
@@ -176,7 +516,14 @@ mod tests {
 
         let patients_service = PatientsService::new(Box::new(PatientsRepositoryFake::new()));
         let created_patient = patients_service
-            .create_patient("John Patient".into(), "92022900002".into())
+            .create_patient(
+                "John Patient".into(),
+                Some("92022900002".into()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
@@ -189,9 +536,30 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                None,
+                vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        drugs_service
+            .create_drug_reimbursement(
+                created_drug_0.id,
+                created_drug_0.variants[0].id,
+                vec![
+                    ReimbursementLevel::FiftyPercent,
+                    ReimbursementLevel::FlatFee,
+                ],
+                1999,
             )
             .await
             .unwrap();
+        let created_drug_0 = drugs_service
+            .get_drug_by_id(created_drug_0.id)
+            .await
+            .unwrap();
         let created_drug_1 = drugs_service
             .create_drug(
                 "Gripex".into(),
@@ -200,31 +568,225 @@ mod tests {
                 Some(300),
                 None,
                 None,
+                Some("5909990733828".into()),
+                vec![],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
         let created_drug_2 = drugs_service
             .create_drug(
-                "Gripex".into(),
+                "Warfin".into(),
                 DrugContentType::SolidPills,
                 Some(20),
                 Some(300),
                 None,
                 None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "warfarin".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
             )
             .await
             .unwrap();
         let created_drug_3 = drugs_service
             .create_drug(
-                "Gripex".into(),
+                "Ibuprom".into(),
+                DrugContentType::SolidPills,
+                Some(20),
+                Some(300),
+                None,
+                None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "ibuprofen".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let created_drug_4 = drugs_service
+            .create_drug(
+                "Viagra".into(),
+                DrugContentType::SolidPills,
+                Some(20),
+                Some(300),
+                None,
+                None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "sildenafil".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let created_drug_5 = drugs_service
+            .create_drug(
+                "Nitromint".into(),
                 DrugContentType::SolidPills,
                 Some(20),
                 Some(300),
                 None,
                 None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "nitroglycerin".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        for (code, name) in [
+            ("J", "Antiinfectives for systemic use"),
+            ("J01", "Antibacterials for systemic use"),
+            ("J01C", "Beta-lactam antibacterials, penicillins"),
+            (
+                "J01CR",
+                "Combinations of penicillins, incl. beta-lactamase inhibitors",
+            ),
+            ("J01CR02", "Amoxicillin and beta-lactamase inhibitor"),
+        ] {
+            drugs_service
+                .create_atc_group(code.into(), name.into())
+                .await
+                .unwrap();
+        }
+        let created_drug_6 = drugs_service
+            .create_drug(
+                "Augmentin".into(),
+                DrugContentType::SolidPills,
+                Some(14),
+                Some(875),
+                None,
+                None,
+                None,
+                vec![
+                    DrugActiveSubstance {
+                        name: "amoxicillin".into(),
+                        amount: None,
+                        unit: None,
+                    },
+                    DrugActiveSubstance {
+                        name: "clavulanic acid".into(),
+                        amount: None,
+                        unit: None,
+                    },
+                ],
+                Some("J01CR02".into()),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let mut equivalent_drugs = vec![];
+        for name in ["Apap", "Panadol"] {
+            let drug = drugs_service
+                .create_drug(
+                    name.into(),
+                    DrugContentType::SolidPills,
+                    Some(10),
+                    Some(500),
+                    None,
+                    None,
+                    None,
+                    vec![DrugActiveSubstance {
+                        name: "paracetamol".into(),
+                        amount: Some(500.0),
+                        unit: Some(StrengthUnit::Mg),
+                    }],
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            equivalent_drugs.push(drug);
+        }
+        drugs_service
+            .create_drug_equivalence_group(
+                "Paracetamol 500 mg tablets".into(),
+                equivalent_drugs.iter().map(|drug| drug.id).collect(),
+            )
+            .await
+            .unwrap();
+        let created_drug_7 = drugs_service
+            .get_drug_by_id(equivalent_drugs[0].id)
+            .await
+            .unwrap();
+        let created_drug_8 = drugs_service
+            .get_drug_by_id(equivalent_drugs[1].id)
+            .await
+            .unwrap();
+        let created_drug_9 = drugs_service
+            .create_drug(
+                "Morphini sulfas".into(),
+                DrugContentType::SolidPills,
+                Some(60),
+                Some(30),
+                None,
+                None,
+                None,
+                vec![DrugActiveSubstance {
+                    name: "morphine".into(),
+                    amount: None,
+                    unit: None,
+                }],
+                None,
+                None,
+                Some(ControlledSubstanceList::Narcotic),
+            )
+            .await
+            .unwrap();
+        let created_patient_allergy = patients_service
+            .create_patient_allergy(
+                created_patient.id,
+                "amoxicillin".into(),
+                "Anaphylaxis".into(),
+                AllergySeverity::LifeThreatening,
+                created_doctor.id,
             )
             .await
             .unwrap();
+        let created_drug_interactions = vec![
+            drugs_service
+                .create_drug_interaction(
+                    "warfarin".into(),
+                    "ibuprofen".into(),
+                    DrugInteractionSeverity::Major,
+                    "Increased risk of bleeding".into(),
+                )
+                .await
+                .unwrap(),
+            drugs_service
+                .create_drug_interaction(
+                    "sildenafil".into(),
+                    "nitroglycerin".into(),
+                    DrugInteractionSeverity::Contraindicated,
+                    "Severe hypotension".into(),
+                )
+                .await
+                .unwrap(),
+        ];
 
         (
             PrescriptionsService::new(Box::new(PrescriptionsRepositoryFake::new(
@@ -237,7 +799,15 @@ mod tests {
                     created_drug_1.clone(),
                     created_drug_2.clone(),
                     created_drug_3.clone(),
+                    created_drug_4.clone(),
+                    created_drug_5.clone(),
+                    created_drug_6.clone(),
+                    created_drug_7.clone(),
+                    created_drug_8.clone(),
+                    created_drug_9.clone(),
                 ]),
+                Some(created_drug_interactions),
+                Some(vec![created_patient_allergy]),
             ))),
             DatabaseSeeds {
                 doctor: created_doctor,
@@ -248,6 +818,12 @@ mod tests {
                     created_drug_1,
                     created_drug_2,
                     created_drug_3,
+                    created_drug_4,
+                    created_drug_5,
+                    created_drug_6,
+                    created_drug_7,
+                    created_drug_8,
+                    created_drug_9,
                 ],
             },
         )
@@ -263,10 +839,23 @@ mod tests {
                 seeds.patient.id,
                 None,
                 Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .prescription;
 
         assert_eq!(
             created_prescription.prescription_type,
@@ -301,85 +890,606 @@ mod tests {
                 seeds.patient.id,
                 None,
                 Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .prescription;
 
         let filled_prescription = service
             .fill_prescription(
                 seed_prescription.id,
-                seeds.pharmacist.id,
-                seed_prescription.code,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
             )
             .await
             .unwrap();
-        let fill = filled_prescription.fill.unwrap();
+        let fill = filled_prescription.fills[0].clone();
 
         assert!(fill.prescription_id == seed_prescription.id);
         assert!(fill.pharmacist_id == seeds.pharmacist.id);
     }
 
     #[tokio::test]
-    async fn doesnt_fill_if_already_filled() {
+    async fn fills_prescription_by_scanned_packages() {
         let (service, seeds) = setup_services_and_seed_database().await;
         let seed_prescription = service
             .create_prescription(
                 seeds.doctor.id,
                 seeds.patient.id,
                 None,
-                Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                None,
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
             )
             .await
-            .unwrap();
+            .unwrap()
+            .prescription;
+        let prescribed_drug_id = seed_prescription.prescribed_drugs[1].id;
 
-        let filled_prescription = service
+        let result = service
             .fill_prescription(
                 seed_prescription.id,
-                seeds.pharmacist.id,
-                seed_prescription.code,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    dispensed_drugs: Some(vec![(prescribed_drug_id, 2)]),
+                    scanned_gtins: Some(vec!["5909990733828".into()]),
+                    ..Default::default()
+                },
             )
-            .await
-            .unwrap();
-        let fill = filled_prescription.fill.unwrap();
+            .await;
 
-        assert!(fill.prescription_id == seed_prescription.id);
-        assert!(fill.pharmacist_id == seeds.pharmacist.id);
+        assert!(matches!(result, Err(FillPrescriptionError::DomainError(_))));
 
-        let code = filled_prescription.code.clone();
-        let prescription_filled_again = service
-            .fill_prescription(filled_prescription.id, seeds.pharmacist.id, code)
+        let result = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    scanned_gtins: Some(vec!["5909990733835".into()]),
+                    ..Default::default()
+                },
+            )
             .await;
 
-        assert!(match prescription_filled_again {
-            Err(FillPrescriptionError::DomainError(_)) => true,
-            _ => false,
-        });
+        assert!(matches!(result, Err(FillPrescriptionError::DomainError(_))));
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    scanned_gtins: Some(vec!["5909990733828".into(), "5909990733828".into()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let fill = filled_prescription.fills[0].clone();
+
+        assert_eq!(filled_prescription.fills.len(), 1);
+        assert_eq!(fill.dispensed_drugs.len(), 1);
+        assert_eq!(
+            fill.dispensed_drugs[0].prescribed_drug_id,
+            prescribed_drug_id
+        );
+        assert_eq!(fill.dispensed_drugs[0].quantity, 2);
     }
 
     #[tokio::test]
-    async fn gets_pharmacists_with_pagination() {
+    async fn fills_prescription_with_equivalent_substitute() {
         let (service, seeds) = setup_services_and_seed_database().await;
-
-        service
+        let seed_prescription = service
             .create_prescription(
                 seeds.doctor.id,
                 seeds.patient.id,
                 None,
-                Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                None,
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[7].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+        let prescribed_drug_id = seed_prescription.prescribed_drugs[0].id;
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    substitutes: Some(vec![(prescribed_drug_id, seeds.drugs[8].id)]),
+                    ..Default::default()
+                },
             )
             .await
             .unwrap();
-        service
+        let fill = filled_prescription.fills[0].clone();
+
+        assert_eq!(fill.dispensed_drugs.len(), 2);
+        assert_eq!(
+            fill.dispensed_drugs[0].substitute_drug_id,
+            Some(seeds.drugs[8].id)
+        );
+        assert_eq!(fill.dispensed_drugs[1].substitute_drug_id, None);
+    }
+
+    #[tokio::test]
+    async fn fill_prescription_returns_error_if_substitute_isnt_equivalent() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
             .create_prescription(
                 seeds.doctor.id,
                 seeds.patient.id,
                 None,
-                Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
-            )
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[7].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let result = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    substitutes: Some(vec![(
+                        seed_prescription.prescribed_drugs[0].id,
+                        seeds.drugs[3].id,
+                    )]),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(FillPrescriptionError::DomainError(_))));
+
+        let prescription = service
+            .get_prescription_by_id(seed_prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(prescription.fills.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_for_controlled_substances_and_registers_dispenses() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForControlledSubstances),
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[9].id,
+                    variant_id: Some(seeds.drugs[9].variants[0].id),
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let result = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(result, Err(FillPrescriptionError::DomainError(_))));
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    controlled_substances_confirmed: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let controlled_substance_dispenses = service
+            .get_controlled_substance_dispenses_with_pagination(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(controlled_substance_dispenses.len(), 1);
+        assert_eq!(
+            controlled_substance_dispenses[0].prescription_fill_id,
+            filled_prescription.fills[0].id
+        );
+        assert_eq!(controlled_substance_dispenses[0].drug_id, seeds.drugs[9].id);
+        assert_eq!(controlled_substance_dispenses[0].quantity, 1);
+        assert_eq!(
+            controlled_substance_dispenses[0].pharmacist_id,
+            seeds.pharmacist.id
+        );
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_with_reimbursed_drug_and_computes_payments() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let create_prescription = |reimbursement_level| {
+            service.create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    variant_id: Some(seeds.drugs[0].variants[0].id),
+                    quantity: 2,
+                    reimbursement_level: Some(reimbursement_level),
+                    ..Default::default()
+                }],
+                None,
+            )
+        };
+
+        let result = create_prescription(ReimbursementLevel::Full).await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::DomainError(_))
+        ));
+
+        let seed_prescription = create_prescription(ReimbursementLevel::FiftyPercent)
+            .await
+            .unwrap()
+            .prescription;
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let dispensed_drug = &filled_prescription.fills[0].dispensed_drugs[0];
+        assert_eq!(dispensed_drug.payable_amount, Some(2000));
+        assert_eq!(dispensed_drug.reimbursed_amount, Some(1998));
+    }
+
+    #[tokio::test]
+    async fn get_controlled_substance_dispenses_returns_error_if_params_are_invalid() {
+        let (service, _) = setup_services_and_seed_database().await;
+
+        let result = service
+            .get_controlled_substance_dispenses_with_pagination(Some(-1), None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(GetControlledSubstanceDispensesWithPaginationError::RepositoryError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fills_prescription_partially() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::Regular),
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+        let prescribed_drug_id = seed_prescription.prescribed_drugs[1].id;
+
+        let partially_filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    dispensed_drugs: Some(vec![(prescribed_drug_id, 1)]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(partially_filled_prescription.fills.len(), 1);
+        assert_eq!(
+            partially_filled_prescription.prescribed_drugs[0].remaining_quantity,
+            1
+        );
+        assert_eq!(
+            partially_filled_prescription.prescribed_drugs[1].remaining_quantity,
+            1
+        );
+
+        let overfilled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    dispensed_drugs: Some(vec![(prescribed_drug_id, 2)]),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            overfilled_prescription,
+            Err(FillPrescriptionError::DomainError(_))
+        ));
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(filled_prescription.fills.len(), 2);
+        assert!(filled_prescription.is_fully_dispensed());
+    }
+
+    #[tokio::test]
+    async fn fills_chronic_disease_drugs_prescription_in_portions() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 24,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(filled_prescription.fills[0].portion_number, Some(0));
+        assert_eq!(
+            filled_prescription.prescribed_drugs[0].remaining_quantity,
+            22
+        );
+
+        let filled_again_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            filled_again_prescription,
+            Err(FillPrescriptionError::DomainError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fills_next_portion_early_when_tolerance_allows_it() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let service = service.with_early_pickup_tolerance(Duration::days(30));
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 24,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(filled_prescription.fills[0].portion_number, Some(1));
+        assert_eq!(
+            filled_prescription.prescribed_drugs[0].remaining_quantity,
+            20
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_fill_if_already_filled() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let fill = filled_prescription.fills[0].clone();
+
+        assert!(fill.prescription_id == seed_prescription.id);
+        assert!(fill.pharmacist_id == seeds.pharmacist.id);
+
+        let code = filled_prescription.code.clone();
+        let prescription_filled_again = service
+            .fill_prescription(
+                filled_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: code,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(match prescription_filled_again {
+            Err(FillPrescriptionError::DomainError(_)) => true,
+            _ => false,
+        });
+    }
+
+    #[tokio::test]
+    async fn gets_pharmacists_with_pagination() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+
+        service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
             .await
             .unwrap();
         service
@@ -388,7 +1498,19 @@ mod tests {
                 seeds.patient.id,
                 None,
                 Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
             )
             .await
             .unwrap();
@@ -398,48 +1520,82 @@ mod tests {
                 seeds.patient.id,
                 None,
                 Some(PrescriptionType::ForChronicDiseaseDrugs),
-                vec![(seeds.drugs[0].id, 1), (seeds.drugs[1].id, 2)],
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+        service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[1].id,
+                        quantity: 2,
+                        ..Default::default()
+                    },
+                ],
+                None,
             )
             .await
             .unwrap();
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(Some(1), Some(2))
+            .get_prescriptions_with_pagination(Some(1), Some(2), None)
             .await
             .unwrap();
 
         assert_eq!(prescriptions.len(), 2);
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(Some(1), Some(3))
+            .get_prescriptions_with_pagination(Some(1), Some(3), None)
             .await
             .unwrap();
 
         assert_eq!(prescriptions.len(), 1);
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(None, Some(10))
+            .get_prescriptions_with_pagination(None, Some(10), None)
             .await
             .unwrap();
 
         assert_eq!(prescriptions.len(), 4);
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(Some(1), None)
+            .get_prescriptions_with_pagination(Some(1), None, None)
             .await
             .unwrap();
 
         assert_eq!(prescriptions.len(), 0);
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(None, None)
+            .get_prescriptions_with_pagination(None, None, None)
             .await
             .unwrap();
 
         assert_eq!(prescriptions.len(), 4);
 
         let prescriptions = service
-            .get_prescriptions_with_pagination(Some(2), Some(3))
+            .get_prescriptions_with_pagination(Some(2), Some(3), None)
             .await
             .unwrap();
 
@@ -451,13 +1607,499 @@ mod tests {
         let (service, _) = setup_services_and_seed_database().await;
 
         assert!(service
-            .get_prescriptions_with_pagination(Some(-1), None)
+            .get_prescriptions_with_pagination(Some(-1), None, None)
             .await
             .is_err());
 
         assert!(service
-            .get_prescriptions_with_pagination(None, Some(0))
+            .get_prescriptions_with_pagination(None, Some(0), None)
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn cancels_prescription() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+
+        let cancelled_by_other_doctor = service
+            .cancel_prescription(
+                seed_prescription.id,
+                Uuid::new_v4(),
+                "Issued by mistake".into(),
+            )
+            .await;
+
+        assert_eq!(
+            cancelled_by_other_doctor,
+            Err(CancelPrescriptionError::DomainError(
+                CancelPrescriptionDomainError::NotIssuingDoctor
+            ))
+        );
+
+        let cancelled_prescription = service
+            .cancel_prescription(
+                seed_prescription.id,
+                seeds.doctor.id,
+                "Issued by mistake".into(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cancelled_prescription.cancellation.unwrap().reason,
+            "Issued by mistake"
+        );
+
+        let filled_prescription = service
+            .fill_prescription(
+                seed_prescription.id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            filled_prescription,
+            Err(FillPrescriptionError::DomainError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn gets_prescriptions_by_status() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let mut prescriptions = vec![];
+        for _ in 0..3 {
+            let prescription = service
+                .create_prescription(
+                    seeds.doctor.id,
+                    seeds.patient.id,
+                    None,
+                    None,
+                    vec![NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 2,
+                        ..Default::default()
+                    }],
+                    None,
+                )
+                .await
+                .unwrap()
+                .prescription;
+
+            assert_eq!(prescription.status, PrescriptionStatus::Active);
+
+            prescriptions.push(prescription);
+        }
+
+        let partially_filled_prescription = service
+            .fill_prescription(
+                prescriptions[1].id,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: prescriptions[1].code.clone(),
+                    dispensed_drugs: Some(vec![(prescriptions[1].prescribed_drugs[0].id, 1)]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            partially_filled_prescription.status,
+            PrescriptionStatus::PartiallyFilled
+        );
+
+        let cancelled_prescription = service
+            .cancel_prescription(
+                prescriptions[2].id,
+                seeds.doctor.id,
+                "Issued by mistake".into(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(cancelled_prescription.status, PrescriptionStatus::Cancelled);
+
+        for (status, expected_prescription) in [
+            (PrescriptionStatus::Active, &prescriptions[0]),
+            (PrescriptionStatus::PartiallyFilled, &prescriptions[1]),
+            (PrescriptionStatus::Cancelled, &prescriptions[2]),
+        ] {
+            let prescriptions_with_status = service
+                .get_prescriptions_with_pagination(None, None, Some(status))
+                .await
+                .unwrap();
+
+            assert_eq!(prescriptions_with_status.len(), 1);
+            assert_eq!(prescriptions_with_status[0].id, expected_prescription.id);
+        }
+
+        assert!(service
+            .get_prescriptions_with_pagination(None, None, Some(PrescriptionStatus::Filled))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn looks_up_and_fills_prescription_by_code() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let seed_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 2,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap()
+            .prescription;
+        let short_code = seed_prescription.code[..4].to_string();
+
+        let prescriptions = service
+            .lookup_prescriptions(seeds.patient.pesel_number.clone(), None, short_code.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(prescriptions.len(), 1);
+        assert_eq!(prescriptions[0].id, seed_prescription.id);
+
+        let partially_filled_prescription = service
+            .fill_prescription_by_code(
+                seeds.patient.pesel_number.clone(),
+                None,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: short_code,
+                    dispensed_drugs: Some(vec![(seed_prescription.prescribed_drugs[0].id, 1)]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(partially_filled_prescription.fills.len(), 1);
+
+        let filled_prescription = service
+            .fill_prescription_by_code(
+                seeds.patient.pesel_number.clone(),
+                None,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(filled_prescription.status, PrescriptionStatus::Filled);
+
+        let refilled_prescription = service
+            .fill_prescription_by_code(
+                seeds.patient.pesel_number,
+                None,
+                PrescriptionFillRequest {
+                    pharmacist_id: seeds.pharmacist.id,
+                    prescription_code: seed_prescription.code,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert_eq!(
+            refilled_prescription,
+            Err(FillPrescriptionError::LookupError(
+                LookupPrescriptionsError::DomainError(LookupPrescriptionsDomainError::NotFound)
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn warns_about_interactions_with_current_prescriptions() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let current_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[2].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(current_prescription.interaction_warnings.is_empty());
+
+        let created_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[3].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let warnings = created_prescription.interaction_warnings;
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].drug_id, seeds.drugs[3].id);
+        assert_eq!(warnings[0].interacting_drug_id, seeds.drugs[2].id);
+        assert_eq!(
+            warnings[0].interacting_prescription_id,
+            Some(current_prescription.prescription.id)
+        );
+        assert_eq!(warnings[0].severity, DrugInteractionSeverity::Major);
+        assert_eq!(
+            created_prescription
+                .prescription
+                .interaction_override_justification,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn prescribes_contraindicated_drugs_only_with_override_justification() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+        let prescribed_drugs = vec![
+            NewPrescribedDrug {
+                drug_id: seeds.drugs[4].id,
+                quantity: 1,
+                ..Default::default()
+            },
+            NewPrescribedDrug {
+                drug_id: seeds.drugs[5].id,
+                quantity: 1,
+                ..Default::default()
+            },
+        ];
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                prescribed_drugs.clone(),
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::DrugInteractionsError(
+                CheckDrugInteractionsDomainError::ContraindicatedDrugs(_)
+            ))
+        ));
+
+        let created_prescription = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                prescribed_drugs,
+                Some("Nitroglycerin is taken only in emergencies".into()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(created_prescription.interaction_warnings.len(), 1);
+        assert_eq!(
+            created_prescription.interaction_warnings[0].severity,
+            DrugInteractionSeverity::Contraindicated
+        );
+
+        let prescription_from_repository = service
+            .get_prescription_by_id(created_prescription.prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_repository.interaction_override_justification,
+            Some("Nitroglycerin is taken only in emergencies".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_error_if_drug_isnt_eligible_for_prescription_type() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[6].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::PrescriptionTypeError(
+                CheckPrescriptionTypeDomainError::AntibioticsNotAllowed(_)
+            ))
+        ));
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForAntibiotics),
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: seeds.drugs[6].id,
+                        quantity: 1,
+                        ..Default::default()
+                    },
+                ],
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::PrescriptionTypeError(
+                CheckPrescriptionTypeDomainError::NotAntibiotics(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_error_if_quantity_exceeds_limits() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    variant_id: Some(seeds.drugs[0].variants[0].id),
+                    quantity: 13,
+                    dosage: Some(Dosage {
+                        dose_amount: 1.0,
+                        dose_unit: DoseUnit::Tablet,
+                        frequency: 2,
+                        frequency_period: DosagePeriod::Day,
+                        duration_days: None,
+                        route: AdministrationRoute::Oral,
+                        note: None,
+                    }),
+                    ..Default::default()
+                }],
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::DomainError(_))
+        ));
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForAntibiotics),
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[6].id,
+                    variant_id: Some(seeds.drugs[6].variants[0].id),
+                    quantity: 3,
+                    ..Default::default()
+                }],
+                Some("Patient needs this antibiotic".into()),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::DomainError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_prescription_returns_error_if_patient_is_allergic_to_prescribed_drug() {
+        let (service, seeds) = setup_services_and_seed_database().await;
+
+        let result = service
+            .create_prescription(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                Some(PrescriptionType::ForAntibiotics),
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[6].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+                Some("Patient needs this antibiotic".into()),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CreatePrescriptionError::AllergiesError(
+                CheckAllergiesDomainError::AllergenicDrugs(_)
+            ))
+        ));
+    }
 }
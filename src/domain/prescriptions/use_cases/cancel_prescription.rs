@@ -0,0 +1,133 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::prescriptions::entities::{
+    NewPrescriptionCancellation, Prescription, PrescriptionStatus,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CancelPrescriptionDomainError {
+    #[error("Only the doctor who issued the prescription can cancel it")]
+    NotIssuingDoctor,
+    #[error("Prescription is already cancelled")]
+    AlreadyCancelled,
+    #[error("Prescription is already fully dispensed")]
+    FullyDispensed,
+    #[error("Prescription is already expired")]
+    Expired,
+    #[error("Cancellation reason can't be empty")]
+    EmptyReason,
+}
+
+impl Prescription {
+    pub fn cancel(
+        &self,
+        doctor_id: Uuid,
+        reason: String,
+    ) -> Result<NewPrescriptionCancellation, CancelPrescriptionDomainError> {
+        if self.doctor.id != doctor_id {
+            Err(CancelPrescriptionDomainError::NotIssuingDoctor)?;
+        }
+        let now = Utc::now();
+        match self.get_status(now) {
+            PrescriptionStatus::Cancelled => Err(CancelPrescriptionDomainError::AlreadyCancelled)?,
+            PrescriptionStatus::Filled => Err(CancelPrescriptionDomainError::FullyDispensed)?,
+            PrescriptionStatus::Expired => Err(CancelPrescriptionDomainError::Expired)?,
+            PrescriptionStatus::Pending
+            | PrescriptionStatus::Active
+            | PrescriptionStatus::PartiallyFilled => {}
+        }
+        let reason = reason.trim().to_string();
+        if reason.is_empty() {
+            Err(CancelPrescriptionDomainError::EmptyReason)?;
+        }
+
+        Ok(NewPrescriptionCancellation {
+            prescription_id: self.id,
+            reason,
+            cancelled_at: now,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::CancelPrescriptionDomainError;
+    use crate::domain::prescriptions::{
+        entities::{Prescription, PrescriptionCancellation},
+        use_cases::prescription_builder::PrescriptionBuilder,
+    };
+
+    fn create_mock_prescription() -> Prescription {
+        PrescriptionBuilder::default()
+            .prescribed_drug(Uuid::new_v4(), None, 2)
+            .build()
+    }
+
+    #[test]
+    fn cancels_prescription() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription
+            .cancel(prescription.doctor.id, " Issued by mistake ".into())
+            .unwrap();
+
+        assert_eq!(sut.prescription_id, prescription.id);
+        assert_eq!(sut.reason, "Issued by mistake");
+    }
+
+    #[test]
+    fn doesnt_cancel_if_doctor_didnt_issue_the_prescription() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription.cancel(Uuid::new_v4(), "Issued by mistake".into());
+
+        assert_eq!(sut, Err(CancelPrescriptionDomainError::NotIssuingDoctor));
+    }
+
+    #[test]
+    fn doesnt_cancel_if_prescription_is_already_cancelled() {
+        let mut prescription = create_mock_prescription();
+        prescription.cancellation = Some(PrescriptionCancellation {
+            reason: "Issued by mistake".into(),
+            cancelled_at: Utc::now(),
+        });
+
+        let sut = prescription.cancel(prescription.doctor.id, "Issued by mistake".into());
+
+        assert_eq!(sut, Err(CancelPrescriptionDomainError::AlreadyCancelled));
+    }
+
+    #[test]
+    fn doesnt_cancel_if_prescription_is_fully_dispensed() {
+        let mut prescription = create_mock_prescription();
+        prescription.prescribed_drugs[0].remaining_quantity = 0;
+
+        let sut = prescription.cancel(prescription.doctor.id, "Issued by mistake".into());
+
+        assert_eq!(sut, Err(CancelPrescriptionDomainError::FullyDispensed));
+    }
+
+    #[test]
+    fn doesnt_cancel_if_prescription_is_expired() {
+        let mut prescription = create_mock_prescription();
+        prescription.start_date = Utc::now() - Duration::days(60);
+        prescription.end_date = Utc::now() - Duration::days(30);
+
+        let sut = prescription.cancel(prescription.doctor.id, "Issued by mistake".into());
+
+        assert_eq!(sut, Err(CancelPrescriptionDomainError::Expired));
+    }
+
+    #[test]
+    fn doesnt_cancel_without_reason() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription.cancel(prescription.doctor.id, "   ".into());
+
+        assert_eq!(sut, Err(CancelPrescriptionDomainError::EmptyReason));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::domain::{
+    patients::entities::PatientAllergy,
+    prescriptions::entities::{InteractingDrug, NewPrescription},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckAllergiesDomainError {
+    #[error("Patient is allergic to active substances of prescribed drugs ({0})")]
+    AllergenicDrugs(String),
+}
+
+impl NewPrescription {
+    // Only the prescribed drugs (without prescription_id) are checked, drugs from the patient's
+    // other prescriptions are ignored
+    pub fn check_allergies(
+        &self,
+        drugs: &[InteractingDrug],
+        allergies: &[PatientAllergy],
+    ) -> Result<(), CheckAllergiesDomainError> {
+        let mut allergenic_drugs = vec![];
+        for prescribed_drug in &self.prescribed_drugs {
+            let Some(drug) = drugs.iter().find(|drug| {
+                drug.prescription_id.is_none() && drug.drug_id == prescribed_drug.drug_id
+            }) else {
+                continue;
+            };
+            for allergy in allergies {
+                if drug.active_substances.contains(&allergy.substance) {
+                    allergenic_drugs.push(format!("{}: {}", drug.drug_name, allergy.substance));
+                }
+            }
+        }
+
+        if !allergenic_drugs.is_empty() {
+            Err(CheckAllergiesDomainError::AllergenicDrugs(
+                allergenic_drugs.join(", "),
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::CheckAllergiesDomainError;
+    use crate::domain::{
+        patients::entities::{AllergySeverity, PatientAllergy},
+        prescriptions::entities::{InteractingDrug, NewPrescribedDrug, NewPrescription},
+    };
+
+    fn create_drug(
+        name: &str,
+        substances: &[&str],
+        prescription_id: Option<Uuid>,
+    ) -> InteractingDrug {
+        InteractingDrug {
+            drug_id: Uuid::new_v4(),
+            drug_name: name.into(),
+            active_substances: substances
+                .iter()
+                .map(|&substance| substance.into())
+                .collect(),
+            prescription_id,
+        }
+    }
+
+    fn create_allergy(patient_id: Uuid, substance: &str) -> PatientAllergy {
+        PatientAllergy {
+            id: Uuid::new_v4(),
+            patient_id,
+            substance: substance.into(),
+            reaction: "Hives".into(),
+            severity: AllergySeverity::Severe,
+            recorded_by_doctor_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_prescription(drugs: &[&InteractingDrug]) -> NewPrescription {
+        NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            drugs
+                .iter()
+                .map(|drug| NewPrescribedDrug {
+                    drug_id: drug.drug_id,
+                    quantity: 1,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn passes_if_patient_isnt_allergic_to_prescribed_drugs() {
+        let apap = create_drug("Apap", &["paracetamol"], None);
+        let ibuprom = create_drug("Ibuprom", &["ibuprofen"], None);
+        let prescription = create_prescription(&[&apap, &ibuprom]);
+        let allergies = vec![create_allergy(prescription.patient_id, "amoxicillin")];
+
+        let sut = prescription.check_allergies(&[apap.clone(), ibuprom.clone()], &allergies);
+
+        assert_eq!(sut, Ok(()));
+    }
+
+    #[test]
+    fn fails_if_prescribed_drug_contains_allergen() {
+        let apap = create_drug("Apap", &["paracetamol"], None);
+        let augmentin = create_drug("Augmentin", &["amoxicillin", "clavulanic acid"], None);
+        let prescription = create_prescription(&[&apap, &augmentin]);
+        let allergies = vec![create_allergy(prescription.patient_id, "amoxicillin")];
+
+        let sut = prescription.check_allergies(&[apap.clone(), augmentin.clone()], &allergies);
+
+        assert_eq!(
+            sut,
+            Err(CheckAllergiesDomainError::AllergenicDrugs(
+                "Augmentin: amoxicillin".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn ignores_drugs_from_patients_other_prescriptions() {
+        let apap = create_drug("Apap", &["paracetamol"], None);
+        let augmentin = create_drug("Augmentin", &["amoxicillin"], Some(Uuid::new_v4()));
+        let prescription = create_prescription(&[&apap]);
+        let allergies = vec![create_allergy(prescription.patient_id, "amoxicillin")];
+
+        let sut = prescription.check_allergies(&[apap.clone(), augmentin.clone()], &allergies);
+
+        assert_eq!(sut, Ok(()));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::domain::{
+    drugs::entities::{DrugInteraction, DrugInteractionSeverity},
+    prescriptions::entities::{DrugInteractionWarning, InteractingDrug, NewPrescription},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckDrugInteractionsDomainError {
+    #[error("Prescribed drugs are contraindicated ({0}), prescribing them requires an override justification")]
+    ContraindicatedDrugs(String),
+    #[error("Interaction override justification can't be empty")]
+    EmptyOverrideJustification,
+}
+
+fn find_interaction<'a>(
+    interactions: &'a [DrugInteraction],
+    substance: &str,
+    interacting_substance: &str,
+) -> Option<&'a DrugInteraction> {
+    interactions.iter().find(|interaction| {
+        (interaction.substance_a == substance && interaction.substance_b == interacting_substance)
+            || (interaction.substance_a == interacting_substance
+                && interaction.substance_b == substance)
+    })
+}
+
+impl NewPrescription {
+    // Checks the prescribed drugs against each other and against the patient's other current
+    // prescriptions (drugs with prescription_id). Contraindicated pairs are only allowed when the
+    // doctor gives a justification, which is then stored with the prescription
+    pub fn check_drug_interactions(
+        &mut self,
+        drugs: &[InteractingDrug],
+        interactions: &[DrugInteraction],
+        override_justification: Option<String>,
+    ) -> Result<Vec<DrugInteractionWarning>, CheckDrugInteractionsDomainError> {
+        let prescribed_drugs: Vec<&InteractingDrug> = self
+            .prescribed_drugs
+            .iter()
+            .filter_map(|prescribed_drug| {
+                drugs.iter().find(|drug| {
+                    drug.prescription_id.is_none() && drug.drug_id == prescribed_drug.drug_id
+                })
+            })
+            .collect();
+
+        let mut warnings = vec![];
+        for (i, drug) in prescribed_drugs.iter().enumerate() {
+            let other_prescribed_drugs = prescribed_drugs.iter().skip(i + 1).copied();
+            let current_drugs = drugs.iter().filter(|drug| drug.prescription_id.is_some());
+            for interacting_drug in other_prescribed_drugs.chain(current_drugs) {
+                for substance in &drug.active_substances {
+                    for interacting_substance in &interacting_drug.active_substances {
+                        let Some(interaction) =
+                            find_interaction(interactions, substance, interacting_substance)
+                        else {
+                            continue;
+                        };
+                        warnings.push(DrugInteractionWarning {
+                            drug_id: drug.drug_id,
+                            drug_name: drug.drug_name.clone(),
+                            substance: substance.clone(),
+                            interacting_drug_id: interacting_drug.drug_id,
+                            interacting_drug_name: interacting_drug.drug_name.clone(),
+                            interacting_substance: interacting_substance.clone(),
+                            interacting_prescription_id: interacting_drug.prescription_id,
+                            severity: interaction.severity,
+                            description: interaction.description.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let contraindications: Vec<String> = warnings
+            .iter()
+            .filter(|warning| warning.severity == DrugInteractionSeverity::Contraindicated)
+            .map(|warning| {
+                format!(
+                    "{} and {}",
+                    warning.drug_name, warning.interacting_drug_name
+                )
+            })
+            .collect();
+        self.interaction_override_justification = None;
+        if !contraindications.is_empty() {
+            let Some(override_justification) = override_justification else {
+                Err(CheckDrugInteractionsDomainError::ContraindicatedDrugs(
+                    contraindications.join(", "),
+                ))?
+            };
+            let override_justification = override_justification.trim().to_string();
+            if override_justification.is_empty() {
+                Err(CheckDrugInteractionsDomainError::EmptyOverrideJustification)?;
+            }
+            self.interaction_override_justification = Some(override_justification);
+        }
+
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::CheckDrugInteractionsDomainError;
+    use crate::domain::{
+        drugs::entities::{DrugInteraction, DrugInteractionSeverity, NewDrugInteraction},
+        prescriptions::entities::{InteractingDrug, NewPrescribedDrug, NewPrescription},
+    };
+
+    fn create_interaction(
+        substance_a: &str,
+        substance_b: &str,
+        severity: DrugInteractionSeverity,
+    ) -> DrugInteraction {
+        let new_interaction = NewDrugInteraction::new(
+            substance_a.into(),
+            substance_b.into(),
+            severity,
+            "Interaction description".into(),
+        )
+        .unwrap();
+
+        DrugInteraction {
+            id: new_interaction.id,
+            substance_a: new_interaction.substance_a,
+            substance_b: new_interaction.substance_b,
+            severity: new_interaction.severity,
+            description: new_interaction.description,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn create_drug(
+        name: &str,
+        substances: &[&str],
+        prescription_id: Option<Uuid>,
+    ) -> InteractingDrug {
+        InteractingDrug {
+            drug_id: Uuid::new_v4(),
+            drug_name: name.into(),
+            active_substances: substances
+                .iter()
+                .map(|&substance| substance.into())
+                .collect(),
+            prescription_id,
+        }
+    }
+
+    fn create_prescription(drugs: &[&InteractingDrug]) -> NewPrescription {
+        NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            drugs
+                .iter()
+                .map(|drug| NewPrescribedDrug {
+                    drug_id: drug.drug_id,
+                    quantity: 1,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn get_interactions() -> Vec<DrugInteraction> {
+        vec![
+            create_interaction("warfarin", "ibuprofen", DrugInteractionSeverity::Major),
+            create_interaction(
+                "sildenafil",
+                "nitroglycerin",
+                DrugInteractionSeverity::Contraindicated,
+            ),
+        ]
+    }
+
+    #[test]
+    fn returns_no_warnings_if_drugs_dont_interact() {
+        let apap = create_drug("Apap", &["paracetamol"], None);
+        let warfin = create_drug("Warfin", &["warfarin"], None);
+        let mut prescription = create_prescription(&[&apap, &warfin]);
+
+        let sut = prescription.check_drug_interactions(
+            &[apap.clone(), warfin.clone()],
+            &get_interactions(),
+            None,
+        );
+
+        assert_eq!(sut, Ok(vec![]));
+    }
+
+    #[test]
+    fn warns_about_interaction_within_prescription() {
+        let ibuprom = create_drug("Ibuprom", &["ibuprofen"], None);
+        let warfin = create_drug("Warfin", &["warfarin"], None);
+        let mut prescription = create_prescription(&[&ibuprom, &warfin]);
+
+        let sut = prescription
+            .check_drug_interactions(
+                &[ibuprom.clone(), warfin.clone()],
+                &get_interactions(),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(sut.len(), 1);
+        assert_eq!(sut[0].drug_id, ibuprom.drug_id);
+        assert_eq!(sut[0].interacting_drug_id, warfin.drug_id);
+        assert_eq!(sut[0].interacting_prescription_id, None);
+        assert_eq!(sut[0].severity, DrugInteractionSeverity::Major);
+        assert_eq!(prescription.interaction_override_justification, None);
+    }
+
+    #[test]
+    fn warns_about_interaction_with_other_active_prescription() {
+        let other_prescription_id = Uuid::new_v4();
+        let ibuprom = create_drug("Ibuprom", &["paracetamol", "ibuprofen"], None);
+        let warfin = create_drug("Warfin", &["warfarin"], Some(other_prescription_id));
+        let mut prescription = create_prescription(&[&ibuprom]);
+
+        let sut = prescription
+            .check_drug_interactions(
+                &[ibuprom.clone(), warfin.clone()],
+                &get_interactions(),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(sut.len(), 1);
+        assert_eq!(sut[0].substance, "ibuprofen");
+        assert_eq!(sut[0].interacting_substance, "warfarin");
+        assert_eq!(
+            sut[0].interacting_prescription_id,
+            Some(other_prescription_id)
+        );
+    }
+
+    #[test]
+    fn doesnt_prescribe_contraindicated_drugs_without_override_justification() {
+        let viagra = create_drug("Viagra", &["sildenafil"], None);
+        let nitromint = create_drug("Nitromint", &["nitroglycerin"], Some(Uuid::new_v4()));
+        let mut prescription = create_prescription(&[&viagra]);
+
+        let sut = prescription.check_drug_interactions(
+            &[viagra.clone(), nitromint.clone()],
+            &get_interactions(),
+            None,
+        );
+
+        assert_eq!(
+            sut,
+            Err(CheckDrugInteractionsDomainError::ContraindicatedDrugs(
+                "Viagra and Nitromint".into()
+            ))
+        );
+
+        let sut = prescription.check_drug_interactions(
+            &[viagra.clone(), nitromint.clone()],
+            &get_interactions(),
+            Some("  ".into()),
+        );
+
+        assert_eq!(
+            sut,
+            Err(CheckDrugInteractionsDomainError::EmptyOverrideJustification)
+        );
+    }
+
+    #[test]
+    fn prescribes_contraindicated_drugs_with_override_justification() {
+        let viagra = create_drug("Viagra", &["sildenafil"], None);
+        let nitromint = create_drug("Nitromint", &["nitroglycerin"], Some(Uuid::new_v4()));
+        let mut prescription = create_prescription(&[&viagra]);
+
+        let sut = prescription
+            .check_drug_interactions(
+                &[viagra.clone(), nitromint.clone()],
+                &get_interactions(),
+                Some(" Nitroglycerin was discontinued ".into()),
+            )
+            .unwrap();
+
+        assert_eq!(sut.len(), 1);
+        assert_eq!(sut[0].severity, DrugInteractionSeverity::Contraindicated);
+        assert_eq!(
+            prescription.interaction_override_justification,
+            Some("Nitroglycerin was discontinued".into())
+        );
+    }
+
+    #[test]
+    fn doesnt_store_override_justification_without_contraindications() {
+        let apap = create_drug("Apap", &["paracetamol"], None);
+        let mut prescription = create_prescription(&[&apap]);
+
+        let sut = prescription.check_drug_interactions(
+            &[apap],
+            &get_interactions(),
+            Some("Just in case".into()),
+        );
+
+        assert_eq!(sut, Ok(vec![]));
+        assert_eq!(prescription.interaction_override_justification, None);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::domain::{
+    drugs::entities::Drug,
+    prescriptions::entities::{NewPrescription, PrescriptionType},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckPrescriptionTypeDomainError {
+    #[error("Antibiotics can only be prescribed on prescription for antibiotics ({0})")]
+    AntibioticsNotAllowed(String),
+    #[error("Prescription for antibiotics can only contain antibiotics ({0})")]
+    NotAntibiotics(String),
+    #[error("Prescription for immunological drugs can only contain immunological drugs ({0})")]
+    NotImmunologicalDrugs(String),
+    #[error("Controlled substances can only be prescribed on prescription for controlled substances ({0})")]
+    ControlledSubstancesNotAllowed(String),
+    #[error("Prescription for controlled substances can only contain controlled substances ({0})")]
+    NotControlledSubstances(String),
+}
+
+// ATC group of antibacterials for systemic use
+const ANTIBIOTICS_ATC_CODE_PREFIXES: [&str; 1] = ["J01"];
+// ATC groups of immune sera and immunoglobulins, vaccines and allergens
+const IMMUNOLOGICAL_DRUGS_ATC_CODE_PREFIXES: [&str; 3] = ["J06", "J07", "V01"];
+
+fn is_in_atc_groups(drug: &Drug, atc_code_prefixes: &[&str]) -> bool {
+    drug.atc_code.as_ref().is_some_and(|atc_code| {
+        atc_code_prefixes
+            .iter()
+            .any(|prefix| atc_code.starts_with(prefix))
+    })
+}
+
+fn describe_drugs(drugs: &[&Drug]) -> String {
+    drugs
+        .iter()
+        .map(|drug| {
+            format!(
+                "{}: {}",
+                drug.name,
+                drug.atc_code.as_deref().unwrap_or("no ATC code")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl NewPrescription {
+    // Drugs are classified by their ATC codes, so unclassified drugs can only be prescribed on
+    // regular prescriptions and prescriptions for chronic disease drugs. Controlled substances are
+    // recognized by the list they are on instead
+    pub fn check_prescription_type(
+        &self,
+        drugs: &[Drug],
+    ) -> Result<(), CheckPrescriptionTypeDomainError> {
+        let prescribed_drugs: Vec<&Drug> = self
+            .prescribed_drugs
+            .iter()
+            .filter_map(|prescribed_drug| {
+                drugs.iter().find(|drug| drug.id == prescribed_drug.drug_id)
+            })
+            .collect();
+
+        if self.prescription_type != PrescriptionType::ForControlledSubstances {
+            let controlled_substances: Vec<&Drug> = prescribed_drugs
+                .iter()
+                .copied()
+                .filter(|drug| drug.controlled_substance_list.is_some())
+                .collect();
+            if !controlled_substances.is_empty() {
+                Err(
+                    CheckPrescriptionTypeDomainError::ControlledSubstancesNotAllowed(
+                        describe_drugs(&controlled_substances),
+                    ),
+                )?;
+            }
+        }
+
+        match self.prescription_type {
+            PrescriptionType::Regular | PrescriptionType::ForChronicDiseaseDrugs => {
+                let antibiotics: Vec<&Drug> = prescribed_drugs
+                    .into_iter()
+                    .filter(|drug| is_in_atc_groups(drug, &ANTIBIOTICS_ATC_CODE_PREFIXES))
+                    .collect();
+                if !antibiotics.is_empty() {
+                    Err(CheckPrescriptionTypeDomainError::AntibioticsNotAllowed(
+                        describe_drugs(&antibiotics),
+                    ))?;
+                }
+            }
+            PrescriptionType::ForAntibiotics => {
+                let not_antibiotics: Vec<&Drug> = prescribed_drugs
+                    .into_iter()
+                    .filter(|drug| !is_in_atc_groups(drug, &ANTIBIOTICS_ATC_CODE_PREFIXES))
+                    .collect();
+                if !not_antibiotics.is_empty() {
+                    Err(CheckPrescriptionTypeDomainError::NotAntibiotics(
+                        describe_drugs(&not_antibiotics),
+                    ))?;
+                }
+            }
+            PrescriptionType::ForImmunologicalDrugs => {
+                let not_immunological_drugs: Vec<&Drug> = prescribed_drugs
+                    .into_iter()
+                    .filter(|drug| !is_in_atc_groups(drug, &IMMUNOLOGICAL_DRUGS_ATC_CODE_PREFIXES))
+                    .collect();
+                if !not_immunological_drugs.is_empty() {
+                    Err(CheckPrescriptionTypeDomainError::NotImmunologicalDrugs(
+                        describe_drugs(&not_immunological_drugs),
+                    ))?;
+                }
+            }
+            PrescriptionType::ForControlledSubstances => {
+                let not_controlled_substances: Vec<&Drug> = prescribed_drugs
+                    .into_iter()
+                    .filter(|drug| drug.controlled_substance_list.is_none())
+                    .collect();
+                if !not_controlled_substances.is_empty() {
+                    Err(CheckPrescriptionTypeDomainError::NotControlledSubstances(
+                        describe_drugs(&not_controlled_substances),
+                    ))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::CheckPrescriptionTypeDomainError;
+    use crate::domain::{
+        drugs::entities::{ControlledSubstanceList, Drug, DrugContentType, NewDrug},
+        prescriptions::entities::{NewPrescribedDrug, NewPrescription, PrescriptionType},
+    };
+
+    fn create_drug(name: &str, atc_code: Option<&str>) -> Drug {
+        let new_drug = NewDrug::new(
+            name.into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(atc_code.map(|atc_code| atc_code.into()), None)
+        .unwrap();
+
+        Drug {
+            id: new_drug.id,
+            name: new_drug.name,
+            content_type: new_drug.content_type,
+            mg_per_pill: new_drug.mg_per_pill,
+            ml_per_pill: new_drug.ml_per_pill,
+            active_substances: vec![],
+            variants: vec![],
+            atc_code: new_drug.atc_code,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_prescription(
+        prescription_type: PrescriptionType,
+        drugs: &[&Drug],
+    ) -> NewPrescription {
+        NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(prescription_type),
+            drugs
+                .iter()
+                .map(|drug| NewPrescribedDrug {
+                    drug_id: drug.id,
+                    variant_id: Some(Uuid::new_v4()),
+                    quantity: 1,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_drugs_eligible_for_prescription_type() {
+        let apap = create_drug("Apap", Some("N02BE01"));
+        let unclassified = create_drug("Gripex", None);
+        let augmentin = create_drug("Augmentin", Some("J01CR02"));
+        let vaccine = create_drug("Vaxigrip", Some("J07BB02"));
+        let drugs = vec![
+            apap.clone(),
+            unclassified.clone(),
+            augmentin.clone(),
+            vaccine.clone(),
+        ];
+
+        for (prescription_type, prescribed_drugs) in [
+            (PrescriptionType::Regular, vec![&apap, &unclassified]),
+            (PrescriptionType::ForChronicDiseaseDrugs, vec![&apap]),
+            (PrescriptionType::ForAntibiotics, vec![&augmentin]),
+            (PrescriptionType::ForImmunologicalDrugs, vec![&vaccine]),
+        ] {
+            let sut = create_prescription(prescription_type, &prescribed_drugs);
+
+            assert_eq!(sut.check_prescription_type(&drugs), Ok(()));
+        }
+    }
+
+    #[test]
+    fn returns_error_if_antibiotic_is_prescribed_on_regular_prescription() {
+        let apap = create_drug("Apap", Some("N02BE01"));
+        let augmentin = create_drug("Augmentin", Some("J01CR02"));
+        let drugs = vec![apap.clone(), augmentin.clone()];
+
+        for prescription_type in [
+            PrescriptionType::Regular,
+            PrescriptionType::ForChronicDiseaseDrugs,
+        ] {
+            let sut = create_prescription(prescription_type, &[&apap, &augmentin]);
+
+            assert_eq!(
+                sut.check_prescription_type(&drugs),
+                Err(CheckPrescriptionTypeDomainError::AntibioticsNotAllowed(
+                    "Augmentin: J01CR02".into()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn returns_error_listing_drugs_other_than_antibiotics() {
+        let apap = create_drug("Apap", Some("N02BE01"));
+        let unclassified = create_drug("Gripex", None);
+        let augmentin = create_drug("Augmentin", Some("J01CR02"));
+        let drugs = vec![apap.clone(), unclassified.clone(), augmentin.clone()];
+
+        let sut = create_prescription(
+            PrescriptionType::ForAntibiotics,
+            &[&apap, &augmentin, &unclassified],
+        );
+
+        assert_eq!(
+            sut.check_prescription_type(&drugs),
+            Err(CheckPrescriptionTypeDomainError::NotAntibiotics(
+                "Apap: N02BE01, Gripex: no ATC code".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn returns_error_listing_drugs_other_than_immunological_drugs() {
+        let augmentin = create_drug("Augmentin", Some("J01CR02"));
+        let vaccine = create_drug("Vaxigrip", Some("J07BB02"));
+        let drugs = vec![augmentin.clone(), vaccine.clone()];
+
+        let sut = create_prescription(
+            PrescriptionType::ForImmunologicalDrugs,
+            &[&vaccine, &augmentin],
+        );
+
+        assert_eq!(
+            sut.check_prescription_type(&drugs),
+            Err(CheckPrescriptionTypeDomainError::NotImmunologicalDrugs(
+                "Augmentin: J01CR02".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_controlled_substance_is_prescribed_on_other_prescription_type() {
+        let apap = create_drug("Apap", Some("N02BE01"));
+        let mut morphine = create_drug("Morphini sulfas", Some("N02AA01"));
+        morphine.controlled_substance_list = Some(ControlledSubstanceList::Narcotic);
+        let drugs = vec![apap.clone(), morphine.clone()];
+
+        let sut = create_prescription(PrescriptionType::Regular, &[&apap, &morphine]);
+
+        assert_eq!(
+            sut.check_prescription_type(&drugs),
+            Err(
+                CheckPrescriptionTypeDomainError::ControlledSubstancesNotAllowed(
+                    "Morphini sulfas: N02AA01".into()
+                )
+            )
+        );
+
+        let sut = create_prescription(PrescriptionType::ForControlledSubstances, &[&morphine]);
+
+        assert_eq!(sut.check_prescription_type(&drugs), Ok(()));
+
+        let sut = create_prescription(PrescriptionType::ForControlledSubstances, &[&apap]);
+
+        assert_eq!(
+            sut.check_prescription_type(&drugs),
+            Err(CheckPrescriptionTypeDomainError::NotControlledSubstances(
+                "Apap: N02BE01".into()
+            ))
+        );
+    }
+}
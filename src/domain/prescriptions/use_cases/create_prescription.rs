@@ -7,14 +7,31 @@
 //  - can have prescribed multiple different drugs, each with any quantity
 //  - has start date, which marks date from which it can be used
 //  - has end date, which marks date after which it can't be used anymore
-//  - each prescription can be used only once
+//  - prescription can be filled in multiple parts (fills), until quantity of each prescribed drug
+//    is fully dispensed
+//  - prescription for chronic disease drugs is valid for a year, but is dispensed in 30-day
+//    portions. Each prescribed drug has a portion quantity (by default quantity is split evenly
+//    into 12 portions), and only the portion for the current 30-day period can be dispensed
+//  - prescribed quantity can't exceed the supply for 120 days of therapy (360 days on prescription
+//    for chronic disease drugs), computed from the dosage and package size, and antibiotics can be
+//    prescribed in at most 2 packages
+//  - prescription for controlled substances (narcotic and psychotropic drugs) is valid for 14 days,
+//    has a single prescribed drug, its quantity must be given explicitly as a number of packages of
+//    a specific variant and can't exceed the supply for 90 days of therapy
+//  - doctor can choose a reimbursement level for each prescribed drug, from the levels available
+//    for its package (so the variant must be given), and the patient pays only a part of the price
 
 use std::collections::HashSet;
 
 use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
-use crate::domain::prescriptions::entities::{NewPrescribedDrug, NewPrescription, PrescriptionType};
+use crate::domain::{
+    drugs::entities::{Drug, DrugContentType},
+    prescriptions::entities::{
+        Dosage, DosagePeriod, DoseUnit, NewPrescribedDrug, NewPrescription, PrescriptionType,
+    },
+};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum CreateNewPrescriptionDomainError {
@@ -24,6 +41,98 @@ pub enum CreateNewPrescriptionDomainError {
     InvalidDrugQuantity(Uuid),
     #[error("Can't prescribe two drugs with the same id {0}")]
     DuplicateDrugId(Uuid),
+    #[error("Portion quantity of drug with id {0} must be between 1 and the prescribed quantity")]
+    InvalidPortionQuantity(Uuid),
+    #[error("Portion quantity of drug with id {0} can only be set on prescription for chronic disease drugs")]
+    PortionQuantityNotAllowed(Uuid),
+    #[error("Dose amount of drug with id {0} must be greater than 0")]
+    InvalidDoseAmount(Uuid),
+    #[error("Dosage frequency of drug with id {0} must be greater than 0")]
+    InvalidDosageFrequency(Uuid),
+    #[error("Dosage duration of drug with id {0} must be at least 1 day")]
+    InvalidDosageDuration(Uuid),
+    #[error("Dosage note of drug with id {0} can't be longer than {1} characters")]
+    InvalidDosageNote(Uuid, usize),
+    #[error("Quantity of drug with id {0} can't be greater than {1}")]
+    QuantityTooLarge(Uuid, i32),
+    #[error("Quantity of drug with id {0} is enough for {1} days of therapy, but at most {2} days can be prescribed on this prescription type")]
+    ExceededMaxSupplyDays(Uuid, i64, i64),
+    #[error("Antibiotic with id {0} can be prescribed in at most {1} packages")]
+    ExceededMaxAntibioticPackages(Uuid, u32),
+    #[error("Prescription for controlled substances can only have one prescribed drug")]
+    MultipleControlledSubstances,
+    #[error("Drug with id {0} on prescription for controlled substances must be prescribed as a number of packages of a specific variant")]
+    ControlledSubstanceQuantityNotExplicit(Uuid),
+    #[error("Drug with id {0} must be prescribed as a number of packages of a specific variant to be reimbursed")]
+    ReimbursementWithoutVariant(Uuid),
+    #[error("Quantity of drug with id {0} is a number of packages and requires variant_id, total_amount prescribes a number of pills (or ml) instead")]
+    QuantityWithoutVariant(Uuid),
+    #[error("Drug with id {0} must be prescribed either as a quantity of packages of a variant, or as a total_amount without a variant")]
+    InvalidPrescribedAmount(Uuid),
+}
+
+const CHRONIC_DISEASE_DRUGS_PORTIONS_COUNT: u32 = 12;
+const MAX_ANTIBIOTIC_PACKAGES: u32 = 2;
+
+// Blank note is treated as no note
+fn validate_dosage(
+    drug_id: Uuid,
+    mut dosage: Dosage,
+) -> Result<Dosage, CreateNewPrescriptionDomainError> {
+    if !dosage.dose_amount.is_finite() || dosage.dose_amount <= 0.0 {
+        Err(CreateNewPrescriptionDomainError::InvalidDoseAmount(drug_id))?;
+    }
+    if dosage.frequency <= 0 {
+        Err(CreateNewPrescriptionDomainError::InvalidDosageFrequency(
+            drug_id,
+        ))?;
+    }
+    if dosage
+        .duration_days
+        .is_some_and(|duration_days| duration_days <= 0)
+    {
+        Err(CreateNewPrescriptionDomainError::InvalidDosageDuration(
+            drug_id,
+        ))?;
+    }
+
+    let max_note_len: usize = 200;
+    dosage.note = dosage
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+    if dosage
+        .note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > max_note_len)
+    {
+        Err(CreateNewPrescriptionDomainError::InvalidDosageNote(
+            drug_id,
+            max_note_len,
+        ))?;
+    }
+
+    Ok(dosage)
+}
+
+// Quantity is a number of packages of the variant, and total amount is a number of pills (or ml of
+// liquid) of the product, which the pharmacist packs from any variants. A quantity without variant
+// is rejected, so that it isn't silently read as a total amount
+pub fn validate_prescribed_amount(
+    drug_id: Uuid,
+    variant_id: Option<Uuid>,
+    quantity: Option<u32>,
+    total_amount: Option<u32>,
+) -> Result<u32, CreateNewPrescriptionDomainError> {
+    match (variant_id, quantity, total_amount) {
+        (Some(_), Some(quantity), None) | (None, None, Some(quantity)) => Ok(quantity),
+        (None, Some(_), None) => Err(CreateNewPrescriptionDomainError::QuantityWithoutVariant(
+            drug_id,
+        )),
+        _ => Err(CreateNewPrescriptionDomainError::InvalidPrescribedAmount(
+            drug_id,
+        )),
+    }
 }
 
 impl PrescriptionType {
@@ -33,40 +142,175 @@ impl PrescriptionType {
             PrescriptionType::ForAntibiotics => Duration::days(7),
             PrescriptionType::ForImmunologicalDrugs => Duration::days(120),
             PrescriptionType::ForChronicDiseaseDrugs => Duration::days(365),
+            PrescriptionType::ForControlledSubstances => Duration::days(14),
+        }
+    }
+
+    pub fn get_portion_duration(&self) -> Option<Duration> {
+        match self {
+            PrescriptionType::ForChronicDiseaseDrugs => Some(Duration::days(30)),
+            _ => None,
+        }
+    }
+
+    pub fn get_max_supply_days(&self) -> i64 {
+        match self {
+            PrescriptionType::ForChronicDiseaseDrugs => 360,
+            PrescriptionType::ForControlledSubstances => 90,
+            _ => 120,
         }
     }
 }
 
+impl DosagePeriod {
+    pub fn get_days(&self) -> i32 {
+        match self {
+            DosagePeriod::Day => 1,
+            DosagePeriod::Week => 7,
+            DosagePeriod::Month => 30,
+        }
+    }
+}
+
+// Total amount of the drug in pills or ml, None when the prescribed variant is unknown
+fn get_prescribed_amount(prescribed_drug: &NewPrescribedDrug, drug: &Drug) -> Option<f64> {
+    let package_size = match prescribed_drug.variant_id {
+        Some(variant_id) => {
+            let variant = drug
+                .variants
+                .iter()
+                .find(|variant| variant.id == variant_id)?;
+            variant.pills_count.or(variant.volume_ml)?
+        }
+        None => 1,
+    };
+
+    Some(prescribed_drug.quantity as f64 * package_size as f64)
+}
+
+// Supply can only be computed when the dose is given in the unit the drug is counted in
+fn get_supply_days(prescribed_drug: &NewPrescribedDrug, drug: &Drug) -> Option<f64> {
+    let dosage = prescribed_drug.dosage.as_ref()?;
+    let is_dose_in_drug_unit = match drug.content_type {
+        DrugContentType::SolidPills | DrugContentType::LiquidPills => {
+            matches!(dosage.dose_unit, DoseUnit::Tablet | DoseUnit::Capsule)
+        }
+        DrugContentType::BottleOfLiquid => matches!(dosage.dose_unit, DoseUnit::Ml),
+    };
+    if !is_dose_in_drug_unit {
+        return None;
+    }
+
+    let daily_dose =
+        dosage.dose_amount * dosage.frequency as f64 / dosage.frequency_period.get_days() as f64;
+
+    Some(get_prescribed_amount(prescribed_drug, drug)? / daily_dose)
+}
+
+// Without a variant the packages are counted as if the pharmacist picked the biggest ones
+fn get_packages_count(prescribed_drug: &NewPrescribedDrug, drug: &Drug) -> Option<u32> {
+    if prescribed_drug.variant_id.is_some() {
+        return Some(prescribed_drug.quantity);
+    }
+
+    let biggest_package_size = drug
+        .variants
+        .iter()
+        .filter_map(|variant| variant.pills_count.or(variant.volume_ml))
+        .max()?;
+
+    Some(
+        prescribed_drug
+            .quantity
+            .div_ceil(biggest_package_size.max(1) as u32),
+    )
+}
+
 impl NewPrescription {
     pub fn new(
         doctor_id: Uuid,
         patient_id: Uuid,
         start_date: Option<DateTime<Utc>>,
         prescription_type: Option<PrescriptionType>,
-        prescribed_drugs: Vec<NewPrescribedDrug>,
+        mut prescribed_drugs: Vec<NewPrescribedDrug>,
     ) -> Result<Self, CreateNewPrescriptionDomainError> {
         if prescribed_drugs.is_empty() {
             Err(CreateNewPrescriptionDomainError::NoPrescribedDrugs)?;
         }
 
+        let prescription_type = prescription_type.unwrap_or(PrescriptionType::Regular);
+        let is_dispensed_in_portions = prescription_type.get_portion_duration().is_some();
+        let is_for_controlled_substances =
+            prescription_type == PrescriptionType::ForControlledSubstances;
+        if is_for_controlled_substances && prescribed_drugs.len() > 1 {
+            Err(CreateNewPrescriptionDomainError::MultipleControlledSubstances)?;
+        }
+
         let mut ids_hashset: HashSet<Uuid> = HashSet::new();
-        for prescribed_drug in &prescribed_drugs {
+        for prescribed_drug in prescribed_drugs.iter_mut() {
             if prescribed_drug.quantity == 0 {
                 Err(CreateNewPrescriptionDomainError::InvalidDrugQuantity(
                     prescribed_drug.drug_id,
                 ))?;
             }
+            if prescribed_drug.quantity > i32::MAX as u32 {
+                Err(CreateNewPrescriptionDomainError::QuantityTooLarge(
+                    prescribed_drug.drug_id,
+                    i32::MAX,
+                ))?;
+            }
             if ids_hashset.contains(&prescribed_drug.drug_id) {
                 Err(CreateNewPrescriptionDomainError::DuplicateDrugId(
                     prescribed_drug.drug_id,
                 ))?;
             }
+            if is_for_controlled_substances && prescribed_drug.variant_id.is_none() {
+                Err(
+                    CreateNewPrescriptionDomainError::ControlledSubstanceQuantityNotExplicit(
+                        prescribed_drug.drug_id,
+                    ),
+                )?;
+            }
+            if prescribed_drug.reimbursement_level.is_some() && prescribed_drug.variant_id.is_none()
+            {
+                Err(
+                    CreateNewPrescriptionDomainError::ReimbursementWithoutVariant(
+                        prescribed_drug.drug_id,
+                    ),
+                )?;
+            }
+
+            match (is_dispensed_in_portions, prescribed_drug.portion_quantity) {
+                (true, Some(portion_quantity)) => {
+                    if portion_quantity == 0 || portion_quantity > prescribed_drug.quantity {
+                        Err(CreateNewPrescriptionDomainError::InvalidPortionQuantity(
+                            prescribed_drug.drug_id,
+                        ))?;
+                    }
+                }
+                (true, None) => {
+                    prescribed_drug.portion_quantity = Some(
+                        prescribed_drug
+                            .quantity
+                            .div_ceil(CHRONIC_DISEASE_DRUGS_PORTIONS_COUNT),
+                    );
+                }
+                (false, Some(_)) => {
+                    Err(CreateNewPrescriptionDomainError::PortionQuantityNotAllowed(
+                        prescribed_drug.drug_id,
+                    ))?;
+                }
+                (false, None) => {}
+            }
+
+            if let Some(dosage) = prescribed_drug.dosage.take() {
+                prescribed_drug.dosage = Some(validate_dosage(prescribed_drug.drug_id, dosage)?);
+            }
 
             ids_hashset.insert(prescribed_drug.drug_id);
         }
 
         let start_date = start_date.unwrap_or(Utc::now());
-        let prescription_type = prescription_type.unwrap_or(PrescriptionType::Regular);
         let duration = prescription_type.get_duration();
         let end_date = start_date + duration;
 
@@ -81,8 +325,47 @@ impl NewPrescription {
             code,
             start_date,
             end_date,
+            interaction_override_justification: None,
         })
     }
+
+    // Prescribed drugs without dosage, or with a dose in other unit than the drug is counted in,
+    // aren't limited by the supply days
+    pub fn check_quantity_limits(
+        &self,
+        drugs: &[Drug],
+    ) -> Result<(), CreateNewPrescriptionDomainError> {
+        let max_supply_days = self.prescription_type.get_max_supply_days();
+        for prescribed_drug in &self.prescribed_drugs {
+            let drug = match drugs.iter().find(|drug| drug.id == prescribed_drug.drug_id) {
+                Some(drug) => drug,
+                None => continue,
+            };
+
+            if let Some(supply_days) = get_supply_days(prescribed_drug, drug) {
+                if supply_days > max_supply_days as f64 {
+                    Err(CreateNewPrescriptionDomainError::ExceededMaxSupplyDays(
+                        prescribed_drug.drug_id,
+                        supply_days.ceil() as i64,
+                        max_supply_days,
+                    ))?;
+                }
+            }
+            if self.prescription_type == PrescriptionType::ForAntibiotics
+                && get_packages_count(prescribed_drug, drug)
+                    .is_some_and(|packages_count| packages_count > MAX_ANTIBIOTIC_PACKAGES)
+            {
+                Err(
+                    CreateNewPrescriptionDomainError::ExceededMaxAntibioticPackages(
+                        prescribed_drug.drug_id,
+                        MAX_ANTIBIOTIC_PACKAGES,
+                    ),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -90,8 +373,64 @@ mod tests {
     use chrono::{Duration, Utc};
     use uuid::Uuid;
 
-    use super::{CreateNewPrescriptionDomainError, NewPrescription, PrescriptionType};
-    use crate::domain::prescriptions::entities::NewPrescribedDrug;
+    use super::{
+        validate_prescribed_amount, CreateNewPrescriptionDomainError, NewPrescription,
+        PrescriptionType,
+    };
+    use crate::domain::{
+        drugs::entities::{Drug, DrugContentType, DrugVariant, ReimbursementLevel},
+        prescriptions::entities::{
+            AdministrationRoute, Dosage, DosagePeriod, DoseUnit, NewPrescribedDrug,
+        },
+    };
+
+    fn create_mock_dosage() -> Dosage {
+        Dosage {
+            dose_amount: 1.0,
+            dose_unit: DoseUnit::Tablet,
+            frequency: 2,
+            frequency_period: DosagePeriod::Day,
+            duration_days: Some(7),
+            route: AdministrationRoute::Oral,
+            note: Some(" after meal ".into()),
+        }
+    }
+
+    fn create_mock_drug(content_type: DrugContentType, package_size: i32) -> Drug {
+        let drug_id = Uuid::new_v4();
+        let (pills_count, volume_ml) = match content_type {
+            DrugContentType::BottleOfLiquid => (None, Some(package_size)),
+            _ => (Some(package_size), None),
+        };
+
+        Drug {
+            id: drug_id,
+            name: "Apap".into(),
+            content_type,
+            mg_per_pill: None,
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![DrugVariant {
+                id: Uuid::new_v4(),
+                drug_id,
+                pills_count,
+                volume_ml,
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
 
     #[test]
     fn creates_prescription() {
@@ -100,6 +439,7 @@ mod tests {
         let new_prescribed_drug = NewPrescribedDrug {
             drug_id: Uuid::new_v4(),
             quantity: 1,
+            ..Default::default()
         };
 
         let sut = NewPrescription::new(
@@ -127,6 +467,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: Uuid::new_v4(),
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -147,6 +488,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: Uuid::new_v4(),
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -168,6 +510,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: Uuid::new_v4(),
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -189,6 +532,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: Uuid::new_v4(),
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -214,6 +558,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: Uuid::new_v4(),
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -232,6 +577,7 @@ mod tests {
         let new_prescribed_drug = NewPrescribedDrug {
             drug_id,
             quantity: 2,
+            ..Default::default()
         };
         let prescription = NewPrescription::new(
             Uuid::new_v4(),
@@ -258,14 +604,17 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id: Uuid::new_v4(),
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: Uuid::new_v4(),
                     quantity: 2,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: Uuid::new_v4(),
                     quantity: 3,
+                    ..Default::default()
                 },
             ],
         )
@@ -288,6 +637,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id,
                 quantity: 0,
+                ..Default::default()
             }],
         );
 
@@ -312,10 +662,12 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id,
                     quantity: 2,
+                    ..Default::default()
                 },
             ],
         );
@@ -326,6 +678,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn splits_chronic_disease_drugs_into_12_portions_by_default() {
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(PrescriptionType::ForChronicDiseaseDrugs),
+            vec![
+                NewPrescribedDrug {
+                    drug_id: Uuid::new_v4(),
+                    quantity: 24,
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: Uuid::new_v4(),
+                    quantity: 5,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(sut.prescribed_drugs[0].portion_quantity, Some(2));
+        assert_eq!(sut.prescribed_drugs[1].portion_quantity, Some(1));
+    }
+
+    #[test]
+    fn creates_prescription_with_custom_portion_quantity() {
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(PrescriptionType::ForChronicDiseaseDrugs),
+            vec![NewPrescribedDrug {
+                drug_id: Uuid::new_v4(),
+                variant_id: None,
+                quantity: 12,
+                portion_quantity: Some(3),
+                dosage: None,
+                reimbursement_level: None,
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(sut.prescribed_drugs[0].portion_quantity, Some(3));
+    }
+
+    #[test]
+    fn cant_set_portion_quantity_bigger_than_quantity_or_equal_to_zero() {
+        let drug_id = Uuid::new_v4();
+
+        for portion_quantity in [0, 13] {
+            let sut = NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                Some(PrescriptionType::ForChronicDiseaseDrugs),
+                vec![NewPrescribedDrug {
+                    drug_id,
+                    variant_id: None,
+                    quantity: 12,
+                    portion_quantity: Some(portion_quantity),
+                    dosage: None,
+                    reimbursement_level: None,
+                }],
+            );
+
+            assert_eq!(
+                sut,
+                Err(CreateNewPrescriptionDomainError::InvalidPortionQuantity(
+                    drug_id
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn cant_set_portion_quantity_on_prescription_not_for_chronic_disease_drugs() {
+        let drug_id = Uuid::new_v4();
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(PrescriptionType::Regular),
+            vec![NewPrescribedDrug {
+                drug_id,
+                variant_id: None,
+                quantity: 12,
+                portion_quantity: Some(1),
+                dosage: None,
+                reimbursement_level: None,
+            }],
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateNewPrescriptionDomainError::PortionQuantityNotAllowed(
+                drug_id
+            ))
+        );
+    }
+
     #[test]
     fn doesnt_create_prescription_when_no_drugs_are_added_to_prescription() {
         let sut = NewPrescription::new(Uuid::new_v4(), Uuid::new_v4(), None, None, vec![]);
@@ -335,4 +790,404 @@ mod tests {
             Err(CreateNewPrescriptionDomainError::NoPrescribedDrugs)
         );
     }
+
+    #[test]
+    fn creates_prescription_with_dosage_instructions() {
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: Uuid::new_v4(),
+                quantity: 1,
+                dosage: Some(create_mock_dosage()),
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(
+            sut.prescribed_drugs[0].dosage,
+            Some(Dosage {
+                note: Some("after meal".into()),
+                ..create_mock_dosage()
+            })
+        );
+    }
+
+    #[test]
+    fn cant_add_drug_with_invalid_dosage() {
+        let drug_id = Uuid::new_v4();
+        let dosages_with_errors = [
+            (
+                Dosage {
+                    dose_amount: 0.0,
+                    ..create_mock_dosage()
+                },
+                CreateNewPrescriptionDomainError::InvalidDoseAmount(drug_id),
+            ),
+            (
+                Dosage {
+                    frequency: 0,
+                    ..create_mock_dosage()
+                },
+                CreateNewPrescriptionDomainError::InvalidDosageFrequency(drug_id),
+            ),
+            (
+                Dosage {
+                    duration_days: Some(-7),
+                    ..create_mock_dosage()
+                },
+                CreateNewPrescriptionDomainError::InvalidDosageDuration(drug_id),
+            ),
+            (
+                Dosage {
+                    note: Some("a".repeat(201)),
+                    ..create_mock_dosage()
+                },
+                CreateNewPrescriptionDomainError::InvalidDosageNote(drug_id, 200),
+            ),
+        ];
+
+        for (dosage, error) in dosages_with_errors {
+            let sut = NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id,
+                    quantity: 1,
+                    dosage: Some(dosage),
+                    ..Default::default()
+                }],
+            );
+
+            assert_eq!(sut, Err(error));
+        }
+    }
+
+    #[test]
+    fn cant_prescribe_quantity_that_overflows_database_integer() {
+        let drug_id = Uuid::new_v4();
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id,
+                quantity: i32::MAX as u32 + 1,
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateNewPrescriptionDomainError::QuantityTooLarge(
+                drug_id,
+                i32::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn checks_supply_days_computed_from_dosage_and_package_size() {
+        let drug = create_mock_drug(DrugContentType::SolidPills, 30);
+        let syrup = create_mock_drug(DrugContentType::BottleOfLiquid, 100);
+        let dosage = Dosage {
+            dose_amount: 1.0,
+            frequency: 2,
+            ..create_mock_dosage()
+        };
+        let syrup_dosage = Dosage {
+            dose_amount: 5.0,
+            dose_unit: DoseUnit::Ml,
+            frequency: 3,
+            ..create_mock_dosage()
+        };
+
+        for (prescription_type, prescribed_drug, expected_result) in [
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: drug.id,
+                    variant_id: Some(drug.variants[0].id),
+                    quantity: 8,
+                    dosage: Some(dosage.clone()),
+                    ..Default::default()
+                },
+                Ok(()),
+            ),
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: drug.id,
+                    variant_id: Some(drug.variants[0].id),
+                    quantity: 9,
+                    dosage: Some(dosage.clone()),
+                    ..Default::default()
+                },
+                Err(CreateNewPrescriptionDomainError::ExceededMaxSupplyDays(
+                    drug.id, 135, 120,
+                )),
+            ),
+            (
+                PrescriptionType::ForChronicDiseaseDrugs,
+                NewPrescribedDrug {
+                    drug_id: drug.id,
+                    variant_id: Some(drug.variants[0].id),
+                    quantity: 24,
+                    dosage: Some(dosage.clone()),
+                    ..Default::default()
+                },
+                Ok(()),
+            ),
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: drug.id,
+                    quantity: 250,
+                    dosage: Some(Dosage {
+                        frequency_period: DosagePeriod::Week,
+                        ..dosage.clone()
+                    }),
+                    ..Default::default()
+                },
+                Err(CreateNewPrescriptionDomainError::ExceededMaxSupplyDays(
+                    drug.id, 875, 120,
+                )),
+            ),
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: drug.id,
+                    quantity: 1000,
+                    ..Default::default()
+                },
+                Ok(()),
+            ),
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: syrup.id,
+                    variant_id: Some(syrup.variants[0].id),
+                    quantity: 20,
+                    dosage: Some(syrup_dosage.clone()),
+                    ..Default::default()
+                },
+                Err(CreateNewPrescriptionDomainError::ExceededMaxSupplyDays(
+                    syrup.id, 134, 120,
+                )),
+            ),
+            (
+                PrescriptionType::Regular,
+                NewPrescribedDrug {
+                    drug_id: syrup.id,
+                    variant_id: Some(syrup.variants[0].id),
+                    quantity: 20,
+                    dosage: Some(Dosage {
+                        dose_unit: DoseUnit::Drop,
+                        ..syrup_dosage
+                    }),
+                    ..Default::default()
+                },
+                Ok(()),
+            ),
+        ] {
+            let sut = NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                Some(prescription_type),
+                vec![prescribed_drug],
+            )
+            .unwrap();
+
+            assert_eq!(
+                sut.check_quantity_limits(&[drug.clone(), syrup.clone()]),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn cant_prescribe_more_than_2_packages_of_antibiotic() {
+        let drugs = vec![create_mock_drug(DrugContentType::SolidPills, 14)];
+        let drug = &drugs[0];
+
+        for (variant_id, quantity, expected_result) in [
+            (Some(drug.variants[0].id), 2, Ok(())),
+            (None, 28, Ok(())),
+            (
+                Some(drug.variants[0].id),
+                3,
+                Err(CreateNewPrescriptionDomainError::ExceededMaxAntibioticPackages(drug.id, 2)),
+            ),
+            (
+                None,
+                29,
+                Err(CreateNewPrescriptionDomainError::ExceededMaxAntibioticPackages(drug.id, 2)),
+            ),
+        ] {
+            let sut = NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                Some(PrescriptionType::ForAntibiotics),
+                vec![NewPrescribedDrug {
+                    drug_id: drug.id,
+                    variant_id,
+                    quantity,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+
+            assert_eq!(sut.check_quantity_limits(&drugs), expected_result);
+        }
+    }
+
+    #[test]
+    fn validates_prescribed_amount() {
+        let drug_id = Uuid::new_v4();
+        let variant_id = Uuid::new_v4();
+
+        for (variant_id, quantity, total_amount, expected_result) in [
+            (Some(variant_id), Some(2), None, Ok(2)),
+            (None, None, Some(40), Ok(40)),
+            (
+                None,
+                Some(2),
+                None,
+                Err(CreateNewPrescriptionDomainError::QuantityWithoutVariant(
+                    drug_id,
+                )),
+            ),
+            (
+                Some(variant_id),
+                None,
+                Some(40),
+                Err(CreateNewPrescriptionDomainError::InvalidPrescribedAmount(
+                    drug_id,
+                )),
+            ),
+            (
+                None,
+                Some(2),
+                Some(40),
+                Err(CreateNewPrescriptionDomainError::InvalidPrescribedAmount(
+                    drug_id,
+                )),
+            ),
+            (
+                None,
+                None,
+                None,
+                Err(CreateNewPrescriptionDomainError::InvalidPrescribedAmount(
+                    drug_id,
+                )),
+            ),
+        ] {
+            let sut = validate_prescribed_amount(drug_id, variant_id, quantity, total_amount);
+
+            assert_eq!(sut, expected_result);
+        }
+    }
+
+    #[test]
+    fn creates_prescription_for_controlled_substances_valid_for_14_days() {
+        let now = Utc::now();
+        let drug_id = Uuid::new_v4();
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Some(now),
+            Some(PrescriptionType::ForControlledSubstances),
+            vec![NewPrescribedDrug {
+                drug_id,
+                variant_id: Some(Uuid::new_v4()),
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(sut.end_date, now + Duration::days(14));
+    }
+
+    #[test]
+    fn cant_prescribe_multiple_drugs_or_amount_without_variant_on_prescription_for_controlled_substances(
+    ) {
+        let drug_id = Uuid::new_v4();
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(PrescriptionType::ForControlledSubstances),
+            vec![
+                NewPrescribedDrug {
+                    drug_id,
+                    variant_id: Some(Uuid::new_v4()),
+                    quantity: 1,
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: Uuid::new_v4(),
+                    variant_id: Some(Uuid::new_v4()),
+                    quantity: 1,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateNewPrescriptionDomainError::MultipleControlledSubstances)
+        );
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            Some(PrescriptionType::ForControlledSubstances),
+            vec![NewPrescribedDrug {
+                drug_id,
+                quantity: 30,
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateNewPrescriptionDomainError::ControlledSubstanceQuantityNotExplicit(drug_id))
+        );
+    }
+
+    #[test]
+    fn cant_reimburse_drug_prescribed_without_variant() {
+        let drug_id = Uuid::new_v4();
+
+        let sut = NewPrescription::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id,
+                quantity: 30,
+                reimbursement_level: Some(ReimbursementLevel::FiftyPercent),
+                ..Default::default()
+            }],
+        );
+
+        assert_eq!(
+            sut,
+            Err(CreateNewPrescriptionDomainError::ReimbursementWithoutVariant(drug_id))
+        );
+    }
 }
@@ -1,108 +1,334 @@
-use chrono::Utc;
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
-use crate::domain::prescriptions::entities::{NewPrescriptionFill, Prescription};
+use crate::domain::prescriptions::entities::{
+    NewControlledSubstanceDispense, NewDispensedDrug, NewPrescriptionFill, PrescribedDrug,
+    Prescription, PrescriptionStatus, PrescriptionType,
+};
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum PrescriptionFillError {
+    #[error("Prescription is cancelled")]
+    Cancelled,
     #[error("Current date is not between prescription's start and end date")]
     InvalidDate,
-    #[error("Prescription is already filled")]
-    AlreadyFilled,
+    #[error("Prescription is already fully dispensed")]
+    FullyDispensed,
     #[error("Prescription code is invalid")]
     InvalidCode,
+    #[error("Fill must dispense at least one prescribed drug")]
+    NoDispensedDrugs,
+    #[error("Prescribed drug with id {0} is not part of this prescription")]
+    PrescribedDrugNotFound(Uuid),
+    #[error("Dispensed quantity of prescribed drug with id {0} can't be 0")]
+    InvalidDispensedQuantity(Uuid),
+    #[error("Can't dispense prescribed drug with id {0} twice in one fill")]
+    DuplicatePrescribedDrugId(Uuid),
+    #[error("Can't dispense more than {1} remaining packages of prescribed drug with id {0}")]
+    QuantityExceedsRemaining(Uuid, i32),
+    #[error("Can't dispense more than {1} packages of prescribed drug with id {0} in the current portion")]
+    QuantityExceedsPortion(Uuid, i32),
+    #[error("Portion for the current period is already dispensed, next portion can be dispensed from {0}")]
+    NextPortionNotAvailableYet(DateTime<Utc>),
+    #[error("Dispensing controlled substances must be confirmed by the pharmacist")]
+    ControlledSubstancesNotConfirmed,
+}
+
+// Number of days before the next portion's period starts, from which the portion can be picked up
+pub fn get_default_early_pickup_tolerance() -> Duration {
+    Duration::days(7)
 }
 
 impl Prescription {
+    pub fn is_fully_dispensed(&self) -> bool {
+        self.prescribed_drugs
+            .iter()
+            .all(|prescribed_drug| prescribed_drug.remaining_quantity <= 0)
+    }
+
+    pub fn get_portion_number(
+        &self,
+        date: DateTime<Utc>,
+        early_pickup_tolerance: Duration,
+    ) -> Option<i32> {
+        let portion_duration = self.prescription_type.get_portion_duration()?;
+        let elapsed = (date + early_pickup_tolerance - self.start_date).max(Duration::zero());
+
+        Some((elapsed.num_seconds() / portion_duration.num_seconds()) as i32)
+    }
+
+    pub fn get_dispensable_quantity(
+        &self,
+        prescribed_drug: &PrescribedDrug,
+        portion_number: Option<i32>,
+    ) -> i32 {
+        match (portion_number, prescribed_drug.portion_quantity) {
+            (Some(portion_number), Some(portion_quantity)) => {
+                let released_quantity = ((portion_number as i64 + 1) * portion_quantity as i64)
+                    .min(prescribed_drug.quantity as i64)
+                    as i32;
+                let dispensed_quantity =
+                    prescribed_drug.quantity - prescribed_drug.remaining_quantity;
+
+                (released_quantity - dispensed_quantity)
+                    .min(prescribed_drug.remaining_quantity)
+                    .max(0)
+            }
+            _ => prescribed_drug.remaining_quantity.max(0),
+        }
+    }
+
+    // When no dispensed drugs are given, everything that can be dispensed today is dispensed
     pub fn fill(
         &self,
         pharmacist_id: Uuid,
         code: String,
+        dispensed_drugs: Option<Vec<NewDispensedDrug>>,
+        early_pickup_tolerance: Duration,
     ) -> Result<NewPrescriptionFill, PrescriptionFillError> {
         let now = Utc::now();
-        if now < self.start_date || now > self.end_date {
-            Err(PrescriptionFillError::InvalidDate)?;
-        }
-        if self.fill.is_some() {
-            Err(PrescriptionFillError::AlreadyFilled)?;
+        match self.get_status(now) {
+            PrescriptionStatus::Cancelled => Err(PrescriptionFillError::Cancelled)?,
+            PrescriptionStatus::Filled => Err(PrescriptionFillError::FullyDispensed)?,
+            PrescriptionStatus::Pending | PrescriptionStatus::Expired => {
+                Err(PrescriptionFillError::InvalidDate)?
+            }
+            PrescriptionStatus::Active | PrescriptionStatus::PartiallyFilled => {}
         }
         if self.code != code {
             Err(PrescriptionFillError::InvalidCode)?;
         }
 
+        let portion_number = self.get_portion_number(now, early_pickup_tolerance);
+        let is_dispensing_all = dispensed_drugs.is_none();
+
+        let dispensed_drugs = match dispensed_drugs {
+            Some(dispensed_drugs) => dispensed_drugs,
+            None => self
+                .prescribed_drugs
+                .iter()
+                .map(|prescribed_drug| NewDispensedDrug {
+                    prescribed_drug_id: prescribed_drug.id,
+                    quantity: self.get_dispensable_quantity(prescribed_drug, portion_number) as u32,
+                    ..Default::default()
+                })
+                .filter(|dispensed_drug| dispensed_drug.quantity > 0)
+                .collect(),
+        };
+
+        if dispensed_drugs.is_empty() {
+            // Nothing dispensable today is only caused by the current portion being already
+            // dispensed, an explicitly empty list is always an invalid request
+            match (
+                is_dispensing_all,
+                portion_number,
+                self.prescription_type.get_portion_duration(),
+            ) {
+                (true, Some(portion_number), Some(portion_duration)) => {
+                    Err(PrescriptionFillError::NextPortionNotAvailableYet(
+                        self.start_date + portion_duration * (portion_number + 1)
+                            - early_pickup_tolerance,
+                    ))?;
+                }
+                _ => Err(PrescriptionFillError::NoDispensedDrugs)?,
+            }
+        }
+
+        let mut ids_hashset: HashSet<Uuid> = HashSet::new();
+        for dispensed_drug in &dispensed_drugs {
+            let prescribed_drug = self
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.id == dispensed_drug.prescribed_drug_id)
+                .ok_or(PrescriptionFillError::PrescribedDrugNotFound(
+                    dispensed_drug.prescribed_drug_id,
+                ))?;
+            if dispensed_drug.quantity == 0 {
+                Err(PrescriptionFillError::InvalidDispensedQuantity(
+                    prescribed_drug.id,
+                ))?;
+            }
+            if ids_hashset.contains(&prescribed_drug.id) {
+                Err(PrescriptionFillError::DuplicatePrescribedDrugId(
+                    prescribed_drug.id,
+                ))?;
+            }
+            if dispensed_drug.quantity as i64 > prescribed_drug.remaining_quantity as i64 {
+                Err(PrescriptionFillError::QuantityExceedsRemaining(
+                    prescribed_drug.id,
+                    prescribed_drug.remaining_quantity,
+                ))?;
+            }
+            let dispensable_quantity =
+                self.get_dispensable_quantity(prescribed_drug, portion_number);
+            if dispensed_drug.quantity as i64 > dispensable_quantity as i64 {
+                Err(PrescriptionFillError::QuantityExceedsPortion(
+                    prescribed_drug.id,
+                    dispensable_quantity,
+                ))?;
+            }
+
+            ids_hashset.insert(prescribed_drug.id);
+        }
+
         Ok(NewPrescriptionFill {
             id: Uuid::new_v4(),
             pharmacist_id,
             prescription_id: self.id,
+            portion_number,
+            dispensed_drugs,
+            controlled_substance_dispenses: vec![],
         })
     }
 }
 
+impl NewPrescriptionFill {
+    // Every drug dispensed on prescription for controlled substances is written to the register,
+    // so substitutes have to be set before to register the drugs that were actually dispensed
+    pub fn with_controlled_substances_confirmation(
+        mut self,
+        prescription: &Prescription,
+        is_confirmed: bool,
+    ) -> Result<Self, PrescriptionFillError> {
+        if prescription.prescription_type != PrescriptionType::ForControlledSubstances {
+            return Ok(self);
+        }
+        if !is_confirmed {
+            Err(PrescriptionFillError::ControlledSubstancesNotConfirmed)?;
+        }
+
+        self.controlled_substance_dispenses = self
+            .dispensed_drugs
+            .iter()
+            .filter_map(|dispensed_drug| {
+                let prescribed_drug =
+                    prescription
+                        .prescribed_drugs
+                        .iter()
+                        .find(|prescribed_drug| {
+                            prescribed_drug.id == dispensed_drug.prescribed_drug_id
+                        })?;
+
+                Some(NewControlledSubstanceDispense {
+                    id: Uuid::new_v4(),
+                    prescription_id: prescription.id,
+                    prescription_fill_id: self.id,
+                    prescribed_drug_id: prescribed_drug.id,
+                    drug_id: dispensed_drug
+                        .substitute_drug_id
+                        .unwrap_or(prescribed_drug.drug_id),
+                    variant_id: prescribed_drug.variant_id,
+                    quantity: dispensed_drug.quantity as i32,
+                    patient_id: prescription.patient.id,
+                    doctor_id: prescription.doctor.id,
+                    pharmacist_id: self.pharmacist_id,
+                })
+            })
+            .collect();
+
+        Ok(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{Duration, Utc};
     use uuid::Uuid;
 
+    use super::get_default_early_pickup_tolerance;
     use crate::domain::prescriptions::{
-        entities::{
-            PrescribedDrug, Prescription, PrescriptionDoctor, PrescriptionFill,
-            PrescriptionPatient, PrescriptionType,
+        entities::{NewDispensedDrug, Prescription, PrescriptionCancellation, PrescriptionType},
+        use_cases::{
+            fill_prescription::PrescriptionFillError, prescription_builder::PrescriptionBuilder,
         },
-        use_cases::fill_prescription::PrescriptionFillError,
     };
 
     fn create_mock_prescription() -> Prescription {
-        let prescription_id = Uuid::new_v4();
-        let prescription_type = PrescriptionType::Regular;
-        let start_date = Utc::now() - Duration::hours(1);
-        let end_date = start_date + prescription_type.get_duration();
-
-        Prescription {
-            id: prescription_id,
-            doctor: PrescriptionDoctor {
-                id: Uuid::new_v4(),
-                name: "John Doctor".to_string(),
-                pesel_number: "99031301347".to_string(),
-                pwz_number: "8463856".to_string(),
-            },
-            patient: PrescriptionPatient {
-                id: Uuid::new_v4(),
-                name: "John Patient".to_string(),
-                pesel_number: "92022900002".to_string(),
-            },
-            code: "12345678".to_string(),
-            prescription_type,
-            start_date,
-            end_date,
-            prescribed_drugs: vec![PrescribedDrug {
-                id: Uuid::new_v4(),
-                drug_id: Uuid::new_v4(),
-                prescription_id,
-                quantity: 1,
-                created_at: start_date,
-                updated_at: start_date,
-            }],
-            fill: None,
-            created_at: start_date,
-            updated_at: start_date,
-        }
+        PrescriptionBuilder::default()
+            .prescribed_drug(Uuid::new_v4(), None, 1)
+            .prescribed_drug(Uuid::new_v4(), None, 3)
+            .build()
     }
 
     #[test]
     fn fills_prescription() {
         let prescription = create_mock_prescription();
 
-        let sut = prescription.fill(Uuid::new_v4(), "12345678".into());
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            None,
+            get_default_early_pickup_tolerance(),
+        );
 
         assert!(sut.is_ok())
     }
 
+    #[test]
+    fn dispenses_all_remaining_drugs_if_no_quantities_are_given() {
+        let mut prescription = create_mock_prescription();
+        prescription.prescribed_drugs[1].remaining_quantity = 2;
+
+        let sut = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            sut.dispensed_drugs,
+            vec![
+                NewDispensedDrug {
+                    prescribed_drug_id: prescription.prescribed_drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+                NewDispensedDrug {
+                    prescribed_drug_id: prescription.prescribed_drugs[1].id,
+                    quantity: 2,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dispenses_only_given_quantities() {
+        let prescription = create_mock_prescription();
+        let dispensed_drugs = vec![NewDispensedDrug {
+            prescribed_drug_id: prescription.prescribed_drugs[1].id,
+            quantity: 2,
+            ..Default::default()
+        }];
+
+        let sut = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                Some(dispensed_drugs.clone()),
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        assert_eq!(sut.dispensed_drugs, dispensed_drugs);
+    }
+
     #[test]
     fn doesnt_fill_if_prescription_the_code_is_invalid() {
         let prescription = create_mock_prescription();
         let code = "12345679".into();
 
-        let sut = prescription.fill(Uuid::new_v4(), code);
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            code,
+            None,
+            get_default_early_pickup_tolerance(),
+        );
 
         assert_eq!(sut, Err(PrescriptionFillError::InvalidCode));
     }
@@ -112,7 +338,12 @@ mod tests {
         let mut prescription = create_mock_prescription();
         prescription.start_date = Utc::now() + Duration::minutes(1);
 
-        let sut = prescription.fill(Uuid::new_v4(), "12345678".into());
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            None,
+            get_default_early_pickup_tolerance(),
+        );
 
         assert_eq!(sut, Err(PrescriptionFillError::InvalidDate));
     }
@@ -122,24 +353,375 @@ mod tests {
         let mut prescription: Prescription = create_mock_prescription();
         prescription.end_date = Utc::now() - Duration::minutes(1);
 
-        let sut = prescription.fill(Uuid::new_v4(), "12345678".into());
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            None,
+            get_default_early_pickup_tolerance(),
+        );
 
         assert_eq!(sut, Err(PrescriptionFillError::InvalidDate));
     }
 
     #[test]
-    fn doesnt_fill_if_prescription_is_filled() {
+    fn doesnt_fill_if_prescription_is_fully_dispensed() {
         let mut prescription = create_mock_prescription();
-        prescription.fill = Some(PrescriptionFill {
-            id: Uuid::new_v4(),
-            pharmacist_id: Uuid::new_v4(),
-            prescription_id: prescription.id,
-            created_at: Utc::now() - Duration::hours(1),
-            updated_at: Utc::now() - Duration::hours(1),
+        for prescribed_drug in prescription.prescribed_drugs.iter_mut() {
+            prescribed_drug.remaining_quantity = 0;
+        }
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            None,
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(sut, Err(PrescriptionFillError::FullyDispensed));
+    }
+
+    #[test]
+    fn doesnt_fill_if_prescription_is_cancelled() {
+        let mut prescription = create_mock_prescription();
+        prescription.cancellation = Some(PrescriptionCancellation {
+            reason: "Issued by mistake".into(),
+            cancelled_at: Utc::now(),
         });
 
-        let sut = prescription.fill(Uuid::new_v4(), "12345678".into());
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            None,
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(sut, Err(PrescriptionFillError::Cancelled));
+    }
+
+    #[test]
+    fn doesnt_fill_if_no_drugs_are_dispensed() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(sut, Err(PrescriptionFillError::NoDispensedDrugs));
+    }
+
+    #[test]
+    fn doesnt_dispense_drug_that_is_not_on_prescription() {
+        let prescription = create_mock_prescription();
+        let prescribed_drug_id = Uuid::new_v4();
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![NewDispensedDrug {
+                prescribed_drug_id,
+                quantity: 1,
+                ..Default::default()
+            }]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::PrescribedDrugNotFound(
+                prescribed_drug_id
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_dispense_zero_packages() {
+        let prescription = create_mock_prescription();
+        let prescribed_drug_id = prescription.prescribed_drugs[0].id;
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![NewDispensedDrug {
+                prescribed_drug_id,
+                quantity: 0,
+                ..Default::default()
+            }]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::InvalidDispensedQuantity(
+                prescribed_drug_id
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_dispense_the_same_prescribed_drug_twice() {
+        let prescription = create_mock_prescription();
+        let prescribed_drug_id = prescription.prescribed_drugs[1].id;
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![
+                NewDispensedDrug {
+                    prescribed_drug_id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+                NewDispensedDrug {
+                    prescribed_drug_id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+            ]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::DuplicatePrescribedDrugId(
+                prescribed_drug_id
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_dispense_more_than_remaining_quantity() {
+        let mut prescription = create_mock_prescription();
+        prescription.prescribed_drugs[1].remaining_quantity = 1;
+        let prescribed_drug_id = prescription.prescribed_drugs[1].id;
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![NewDispensedDrug {
+                prescribed_drug_id,
+                quantity: 2,
+                ..Default::default()
+            }]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::QuantityExceedsRemaining(
+                prescribed_drug_id,
+                1
+            ))
+        );
+    }
+
+    fn create_mock_chronic_disease_drugs_prescription(days_since_start: i64) -> Prescription {
+        let mut prescription = PrescriptionBuilder::default()
+            .prescription_type(PrescriptionType::ForChronicDiseaseDrugs)
+            .start_date(Utc::now() - Duration::days(days_since_start))
+            .prescribed_drug(Uuid::new_v4(), None, 12)
+            .prescribed_drug(Uuid::new_v4(), None, 24)
+            .build();
+        prescription.prescribed_drugs[0].portion_quantity = Some(1);
+        prescription.prescribed_drugs[1].portion_quantity = Some(2);
+        prescription
+    }
+
+    #[test]
+    fn dispenses_only_current_portion_of_chronic_disease_drugs() {
+        let prescription = create_mock_chronic_disease_drugs_prescription(0);
+
+        let sut = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        assert_eq!(sut.portion_number, Some(0));
+        assert_eq!(
+            sut.dispensed_drugs,
+            vec![
+                NewDispensedDrug {
+                    prescribed_drug_id: prescription.prescribed_drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+                NewDispensedDrug {
+                    prescribed_drug_id: prescription.prescribed_drugs[1].id,
+                    quantity: 2,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn doesnt_dispense_more_than_current_portion_of_chronic_disease_drugs() {
+        let prescription = create_mock_chronic_disease_drugs_prescription(0);
+        let prescribed_drug_id = prescription.prescribed_drugs[1].id;
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![NewDispensedDrug {
+                prescribed_drug_id,
+                quantity: 3,
+                ..Default::default()
+            }]),
+            get_default_early_pickup_tolerance(),
+        );
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::QuantityExceedsPortion(
+                prescribed_drug_id,
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_dispense_next_portion_before_its_period_starts() {
+        let mut prescription = create_mock_chronic_disease_drugs_prescription(10);
+        prescription.prescribed_drugs[0].remaining_quantity = 11;
+        prescription.prescribed_drugs[1].remaining_quantity = 22;
+
+        let sut = prescription.fill(Uuid::new_v4(), "12345678".into(), None, Duration::days(7));
+
+        assert_eq!(
+            sut,
+            Err(PrescriptionFillError::NextPortionNotAvailableYet(
+                prescription.start_date + Duration::days(30) - Duration::days(7)
+            ))
+        );
+    }
+
+    #[test]
+    fn doesnt_fill_chronic_disease_drugs_if_no_drugs_are_dispensed() {
+        let mut prescription = create_mock_chronic_disease_drugs_prescription(10);
+        prescription.prescribed_drugs[0].remaining_quantity = 11;
+        prescription.prescribed_drugs[1].remaining_quantity = 22;
+
+        let sut = prescription.fill(
+            Uuid::new_v4(),
+            "12345678".into(),
+            Some(vec![]),
+            Duration::days(7),
+        );
+
+        assert_eq!(sut, Err(PrescriptionFillError::NoDispensedDrugs));
+    }
+
+    #[test]
+    fn dispenses_next_portion_within_early_pickup_tolerance() {
+        let mut prescription = create_mock_chronic_disease_drugs_prescription(25);
+        prescription.prescribed_drugs[0].remaining_quantity = 11;
+        prescription.prescribed_drugs[1].remaining_quantity = 22;
+
+        assert!(prescription
+            .fill(Uuid::new_v4(), "12345678".into(), None, Duration::days(3),)
+            .is_err());
+
+        let sut = prescription
+            .fill(Uuid::new_v4(), "12345678".into(), None, Duration::days(7))
+            .unwrap();
+
+        assert_eq!(sut.portion_number, Some(1));
+        assert_eq!(sut.dispensed_drugs[0].quantity, 1);
+        assert_eq!(sut.dispensed_drugs[1].quantity, 2);
+    }
+
+    #[test]
+    fn dispenses_portions_that_were_not_picked_up_earlier() {
+        let prescription = create_mock_chronic_disease_drugs_prescription(65);
+
+        let sut = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        assert_eq!(sut.portion_number, Some(2));
+        assert_eq!(sut.dispensed_drugs[0].quantity, 3);
+        assert_eq!(sut.dispensed_drugs[1].quantity, 6);
+    }
+
+    #[test]
+    fn registers_dispensed_controlled_substances_after_pharmacists_confirmation() {
+        let mut prescription = create_mock_prescription();
+        prescription.prescription_type = PrescriptionType::ForControlledSubstances;
+        prescription.prescribed_drugs.truncate(1);
+        prescription.prescribed_drugs[0].variant_id = Some(Uuid::new_v4());
+        let pharmacist_id = Uuid::new_v4();
+        let substitute_drug_id = Uuid::new_v4();
+        let mut fill = prescription
+            .fill(
+                pharmacist_id,
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+        fill.dispensed_drugs[0].substitute_drug_id = Some(substitute_drug_id);
+
+        assert_eq!(
+            fill.clone()
+                .with_controlled_substances_confirmation(&prescription, false),
+            Err(PrescriptionFillError::ControlledSubstancesNotConfirmed)
+        );
+
+        let sut = fill
+            .with_controlled_substances_confirmation(&prescription, true)
+            .unwrap();
+
+        assert_eq!(sut.controlled_substance_dispenses.len(), 1);
+        let controlled_substance_dispense = &sut.controlled_substance_dispenses[0];
+        assert_eq!(controlled_substance_dispense.prescription_fill_id, sut.id);
+        assert_eq!(
+            controlled_substance_dispense.prescribed_drug_id,
+            prescription.prescribed_drugs[0].id
+        );
+        assert_eq!(controlled_substance_dispense.drug_id, substitute_drug_id);
+        assert_eq!(
+            controlled_substance_dispense.variant_id,
+            prescription.prescribed_drugs[0].variant_id
+        );
+        assert_eq!(controlled_substance_dispense.quantity, 1);
+        assert_eq!(
+            controlled_substance_dispense.patient_id,
+            prescription.patient.id
+        );
+        assert_eq!(
+            controlled_substance_dispense.doctor_id,
+            prescription.doctor.id
+        );
+        assert_eq!(controlled_substance_dispense.pharmacist_id, pharmacist_id);
+    }
+
+    #[test]
+    fn doesnt_register_drugs_dispensed_on_other_prescription_types() {
+        let prescription = create_mock_prescription();
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        let sut = fill
+            .with_controlled_substances_confirmation(&prescription, false)
+            .unwrap();
 
-        assert_eq!(sut, Err(PrescriptionFillError::AlreadyFilled));
+        assert!(sut.controlled_substance_dispenses.is_empty());
     }
 }
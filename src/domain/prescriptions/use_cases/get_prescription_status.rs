@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::prescriptions::entities::{Prescription, PrescriptionStatus};
+
+impl Prescription {
+    // Cancelled and filled prescriptions keep their status after end_date, the rest of them expire
+    pub fn get_status(&self, date: DateTime<Utc>) -> PrescriptionStatus {
+        if self.cancellation.is_some() {
+            PrescriptionStatus::Cancelled
+        } else if self.is_fully_dispensed() {
+            PrescriptionStatus::Filled
+        } else if date > self.end_date {
+            PrescriptionStatus::Expired
+        } else if date < self.start_date {
+            PrescriptionStatus::Pending
+        } else if !self.fills.is_empty() {
+            PrescriptionStatus::PartiallyFilled
+        } else {
+            PrescriptionStatus::Active
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::domain::prescriptions::{
+        entities::{Prescription, PrescriptionCancellation, PrescriptionFill, PrescriptionStatus},
+        use_cases::prescription_builder::PrescriptionBuilder,
+    };
+
+    fn create_mock_prescription() -> Prescription {
+        PrescriptionBuilder::default()
+            .prescribed_drug(Uuid::new_v4(), None, 2)
+            .build()
+    }
+
+    fn create_mock_prescription_fill(prescription: &Prescription) -> PrescriptionFill {
+        PrescriptionFill {
+            id: Uuid::new_v4(),
+            prescription_id: prescription.id,
+            pharmacist_id: Uuid::new_v4(),
+            portion_number: None,
+            dispensed_drugs: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn is_active_between_start_and_end_date() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription.get_status(prescription.start_date + Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::Active);
+    }
+
+    #[test]
+    fn is_pending_before_start_date() {
+        let prescription = create_mock_prescription();
+
+        let sut = prescription.get_status(prescription.start_date - Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::Pending);
+    }
+
+    #[test]
+    fn is_expired_after_end_date() {
+        let mut prescription = create_mock_prescription();
+        prescription.fills = vec![create_mock_prescription_fill(&prescription)];
+        prescription.prescribed_drugs[0].remaining_quantity = 1;
+
+        let sut = prescription.get_status(prescription.end_date + Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::Expired);
+    }
+
+    #[test]
+    fn is_partially_filled_if_some_drugs_remain() {
+        let mut prescription = create_mock_prescription();
+        prescription.fills = vec![create_mock_prescription_fill(&prescription)];
+        prescription.prescribed_drugs[0].remaining_quantity = 1;
+
+        let sut = prescription.get_status(prescription.start_date + Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::PartiallyFilled);
+    }
+
+    #[test]
+    fn stays_filled_after_end_date() {
+        let mut prescription = create_mock_prescription();
+        prescription.fills = vec![create_mock_prescription_fill(&prescription)];
+        prescription.prescribed_drugs[0].remaining_quantity = 0;
+
+        let sut = prescription.get_status(prescription.end_date + Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::Filled);
+    }
+
+    #[test]
+    fn stays_cancelled_after_end_date() {
+        let mut prescription = create_mock_prescription();
+        prescription.cancellation = Some(PrescriptionCancellation {
+            reason: "Issued by mistake".into(),
+            cancelled_at: Utc::now(),
+        });
+
+        let sut = prescription.get_status(prescription.end_date + Duration::days(1));
+
+        assert_eq!(sut, PrescriptionStatus::Cancelled);
+    }
+}
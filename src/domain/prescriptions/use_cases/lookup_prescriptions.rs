@@ -0,0 +1,232 @@
+use crate::domain::{
+    patients::use_cases::create_patient::{normalize_document_number, validate_document_number},
+    prescriptions::entities::{Prescription, PrescriptionLookup, PrescriptionPatient},
+    utils::validators::validate_pesel_number::validate_pesel_number,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum LookupPrescriptionsDomainError {
+    #[error("Exactly one of PESEL number and identity document number must be given")]
+    InvalidPatientIdentifier,
+    #[error("{0}")]
+    InvalidPeselNumber(String),
+    #[error("{0}")]
+    InvalidDocumentNumber(String),
+    #[error("Prescription code must consist of 4 or 8 digits")]
+    InvalidCode,
+    #[error("No active prescription matches given patient identifier and code")]
+    NotFound,
+    #[error("More than one active prescription matches given code, use the full 8-digit code")]
+    AmbiguousCode,
+}
+
+impl PrescriptionPatient {
+    // Newborn without a PESEL number is identified by the PESEL number of a parent
+    pub fn is_identified_by(
+        &self,
+        pesel_number: Option<&str>,
+        document_number: Option<&str>,
+    ) -> bool {
+        match (pesel_number, document_number) {
+            (Some(pesel_number), _) => {
+                self.pesel_number.as_deref() == Some(pesel_number)
+                    || self.parent_pesel_number.as_deref() == Some(pesel_number)
+            }
+            (None, Some(document_number)) => self
+                .identity_document
+                .as_ref()
+                .is_some_and(|identity_document| identity_document.number == document_number),
+            (None, None) => false,
+        }
+    }
+}
+
+// Patients can give either the full 8-digit code or its first 4 digits, together with their PESEL
+// number (or the one of their parent) or the number of their identity document
+impl PrescriptionLookup {
+    pub fn new(
+        pesel_number: Option<String>,
+        document_number: Option<String>,
+        code: String,
+    ) -> Result<Self, LookupPrescriptionsDomainError> {
+        let document_number = document_number.map(|number| normalize_document_number(&number));
+        match (&pesel_number, &document_number) {
+            (Some(pesel_number), None) => validate_pesel_number(pesel_number).map_err(|err| {
+                LookupPrescriptionsDomainError::InvalidPeselNumber(err.to_string())
+            })?,
+            (None, Some(document_number)) => {
+                validate_document_number(document_number).map_err(|err| {
+                    LookupPrescriptionsDomainError::InvalidDocumentNumber(err.to_string())
+                })?
+            }
+            _ => Err(LookupPrescriptionsDomainError::InvalidPatientIdentifier)?,
+        }
+        if !(code.len() == 4 || code.len() == 8) || !code.chars().all(|c| c.is_ascii_digit()) {
+            Err(LookupPrescriptionsDomainError::InvalidCode)?;
+        }
+
+        Ok(PrescriptionLookup {
+            pesel_number,
+            document_number,
+            code,
+        })
+    }
+
+    pub fn matches(&self, prescription: &Prescription) -> bool {
+        prescription.patient.is_identified_by(
+            self.pesel_number.as_deref(),
+            self.document_number.as_deref(),
+        ) && prescription.code.starts_with(&self.code)
+    }
+
+    pub fn find_single(
+        &self,
+        prescriptions: Vec<Prescription>,
+    ) -> Result<Prescription, LookupPrescriptionsDomainError> {
+        let mut matching_prescriptions = prescriptions
+            .into_iter()
+            .filter(|prescription| self.matches(prescription));
+
+        let prescription = matching_prescriptions
+            .next()
+            .ok_or(LookupPrescriptionsDomainError::NotFound)?;
+        if matching_prescriptions.next().is_some() {
+            Err(LookupPrescriptionsDomainError::AmbiguousCode)?;
+        }
+
+        Ok(prescription)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LookupPrescriptionsDomainError;
+    use crate::domain::{
+        patients::entities::{IdentityDocument, IdentityDocumentType},
+        prescriptions::{
+            entities::{Prescription, PrescriptionLookup},
+            use_cases::prescription_builder::PrescriptionBuilder,
+        },
+    };
+
+    fn create_mock_prescription(pesel_number: &str, code: &str) -> Prescription {
+        PrescriptionBuilder::default()
+            .patient_pesel_number(pesel_number)
+            .code(code)
+            .build()
+    }
+
+    #[test]
+    fn creates_lookup_with_4_or_8_digit_code() {
+        assert!(PrescriptionLookup::new(Some("96021817257".into()), None, "1234".into()).is_ok());
+        assert!(
+            PrescriptionLookup::new(Some("96021817257".into()), None, "12345678".into()).is_ok()
+        );
+    }
+
+    #[test]
+    fn doesnt_create_lookup_if_code_is_invalid() {
+        for code in ["", "123", "123456", "123456789", "12a4"] {
+            assert_eq!(
+                PrescriptionLookup::new(Some("96021817257".into()), None, code.into()),
+                Err(LookupPrescriptionsDomainError::InvalidCode)
+            );
+        }
+    }
+
+    #[test]
+    fn doesnt_create_lookup_if_pesel_number_is_invalid() {
+        assert!(matches!(
+            PrescriptionLookup::new(Some("96021817258".into()), None, "1234".into()),
+            Err(LookupPrescriptionsDomainError::InvalidPeselNumber(_))
+        ));
+    }
+
+    #[test]
+    fn finds_single_prescription_by_code_prefix() {
+        let lookup =
+            PrescriptionLookup::new(Some("96021817257".into()), None, "1234".into()).unwrap();
+        let prescription = create_mock_prescription("96021817257", "12345678");
+
+        let sut = lookup.find_single(vec![
+            create_mock_prescription("96021817257", "43215678"),
+            prescription.clone(),
+            create_mock_prescription("99031301347", "12345678"),
+        ]);
+
+        assert_eq!(sut, Ok(prescription));
+    }
+
+    #[test]
+    fn doesnt_find_single_prescription_if_none_matches() {
+        let lookup =
+            PrescriptionLookup::new(Some("96021817257".into()), None, "1234".into()).unwrap();
+
+        let sut = lookup.find_single(vec![create_mock_prescription("96021817257", "43215678")]);
+
+        assert_eq!(sut, Err(LookupPrescriptionsDomainError::NotFound));
+    }
+
+    #[test]
+    fn doesnt_find_single_prescription_if_code_is_ambiguous() {
+        let lookup =
+            PrescriptionLookup::new(Some("96021817257".into()), None, "1234".into()).unwrap();
+
+        let sut = lookup.find_single(vec![
+            create_mock_prescription("96021817257", "12345678"),
+            create_mock_prescription("96021817257", "12348765"),
+        ]);
+
+        assert_eq!(sut, Err(LookupPrescriptionsDomainError::AmbiguousCode));
+    }
+
+    #[test]
+    fn doesnt_create_lookup_without_exactly_one_patient_identifier() {
+        assert_eq!(
+            PrescriptionLookup::new(None, None, "1234".into()),
+            Err(LookupPrescriptionsDomainError::InvalidPatientIdentifier)
+        );
+        assert_eq!(
+            PrescriptionLookup::new(
+                Some("96021817257".into()),
+                Some("C01X00T47".into()),
+                "1234".into()
+            ),
+            Err(LookupPrescriptionsDomainError::InvalidPatientIdentifier)
+        );
+        assert!(matches!(
+            PrescriptionLookup::new(None, Some("C01".into()), "1234".into()),
+            Err(LookupPrescriptionsDomainError::InvalidDocumentNumber(_))
+        ));
+    }
+
+    #[test]
+    fn finds_prescriptions_of_patients_without_pesel_number() {
+        let mut foreign_patient_prescription = create_mock_prescription("96021817257", "12345678");
+        foreign_patient_prescription.patient.pesel_number = None;
+        foreign_patient_prescription.patient.identity_document = Some(IdentityDocument {
+            document_type: IdentityDocumentType::Passport,
+            number: "C01X00T47".into(),
+            issuing_country: "DE".into(),
+        });
+        let mut newborn_prescription = create_mock_prescription("96021817257", "43215678");
+        newborn_prescription.patient.pesel_number = None;
+        newborn_prescription.patient.parent_pesel_number = Some("99031301347".into());
+        let prescriptions = vec![
+            foreign_patient_prescription.clone(),
+            newborn_prescription.clone(),
+        ];
+
+        let sut = PrescriptionLookup::new(None, Some("c01x 00t47".into()), "1234".into())
+            .unwrap()
+            .find_single(prescriptions.clone());
+
+        assert_eq!(sut, Ok(foreign_patient_prescription));
+
+        let sut = PrescriptionLookup::new(Some("99031301347".into()), None, "4321".into())
+            .unwrap()
+            .find_single(prescriptions);
+
+        assert_eq!(sut, Ok(newborn_prescription));
+    }
+}
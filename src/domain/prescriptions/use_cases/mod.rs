@@ -1,2 +1,13 @@
+pub mod cancel_prescription;
+pub mod check_allergies;
+pub mod check_drug_interactions;
+pub mod check_prescription_type;
 pub mod create_prescription;
 pub mod fill_prescription;
+pub mod get_prescription_status;
+pub mod lookup_prescriptions;
+#[cfg(test)]
+pub mod prescription_builder;
+pub mod reimburse_drugs;
+pub mod scan_dispensed_packages;
+pub mod substitute_drugs;
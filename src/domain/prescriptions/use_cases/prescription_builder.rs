@@ -0,0 +1,113 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::domain::{
+    prescriptions::entities::{
+        PrescribedDrug, Prescription, PrescriptionDoctor, PrescriptionPatient, PrescriptionStatus,
+        PrescriptionType,
+    },
+    utils::validators::validate_pesel_number::Pesel,
+};
+
+// Builds an active regular prescription started an hour ago, for tests of use cases that work on
+// prescriptions read from the repository. End date and dates of prescribed drugs follow the start
+// date and type given to the builder
+pub struct PrescriptionBuilder {
+    prescription: Prescription,
+}
+
+impl Default for PrescriptionBuilder {
+    fn default() -> Self {
+        let start_date = Utc::now() - Duration::hours(1);
+        let pesel_number = "92022900002";
+
+        Self {
+            prescription: Prescription {
+                id: Uuid::new_v4(),
+                doctor: PrescriptionDoctor {
+                    id: Uuid::new_v4(),
+                    name: "John Doctor".to_string(),
+                    pesel_number: "99031301347".to_string(),
+                    pwz_number: "8463856".to_string(),
+                },
+                patient: PrescriptionPatient {
+                    id: Uuid::new_v4(),
+                    name: "John Patient".to_string(),
+                    pesel_number: Some(pesel_number.to_string()),
+                    identity_document: None,
+                    parent_pesel_number: None,
+                    birth_date: Pesel::new(pesel_number).unwrap().get_birth_date(),
+                },
+                code: "12345678".to_string(),
+                prescription_type: PrescriptionType::Regular,
+                start_date,
+                end_date: start_date,
+                prescribed_drugs: vec![],
+                fills: vec![],
+                cancellation: None,
+                interaction_override_justification: None,
+                status: PrescriptionStatus::Active,
+                created_at: start_date,
+                updated_at: start_date,
+            },
+        }
+    }
+}
+
+impl PrescriptionBuilder {
+    pub fn prescription_type(mut self, prescription_type: PrescriptionType) -> Self {
+        self.prescription.prescription_type = prescription_type;
+        self
+    }
+
+    pub fn start_date(mut self, start_date: DateTime<Utc>) -> Self {
+        self.prescription.start_date = start_date;
+        self
+    }
+
+    pub fn patient_pesel_number(mut self, pesel_number: &str) -> Self {
+        self.prescription.patient.pesel_number = Some(pesel_number.to_string());
+        self.prescription.patient.birth_date = Pesel::new(pesel_number).unwrap().get_birth_date();
+        self
+    }
+
+    pub fn code(mut self, code: &str) -> Self {
+        self.prescription.code = code.to_string();
+        self
+    }
+
+    pub fn prescribed_drug(
+        mut self,
+        drug_id: Uuid,
+        variant_id: Option<Uuid>,
+        quantity: i32,
+    ) -> Self {
+        self.prescription.prescribed_drugs.push(PrescribedDrug {
+            id: Uuid::new_v4(),
+            drug_id,
+            variant_id,
+            prescription_id: self.prescription.id,
+            quantity,
+            remaining_quantity: quantity,
+            portion_quantity: None,
+            dosage: None,
+            reimbursement_level: None,
+            created_at: self.prescription.start_date,
+            updated_at: self.prescription.start_date,
+        });
+        self
+    }
+
+    pub fn build(self) -> Prescription {
+        let mut prescription = self.prescription;
+        let start_date = prescription.start_date;
+        prescription.end_date = start_date + prescription.prescription_type.get_duration();
+        prescription.created_at = start_date;
+        prescription.updated_at = start_date;
+        for prescribed_drug in &mut prescription.prescribed_drugs {
+            prescribed_drug.created_at = start_date;
+            prescribed_drug.updated_at = start_date;
+        }
+        prescription
+    }
+}
@@ -0,0 +1,631 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::domain::{
+    drugs::entities::{Drug, DrugReimbursement, ReimbursementLevel},
+    prescriptions::entities::{NewPrescription, NewPrescriptionFill, Prescription},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ReimburseDrugsDomainError {
+    #[error("Reimbursement level isn't available for the prescribed package ({0})")]
+    ReimbursementLevelNotAvailable(String),
+    #[error("Reimbursement level isn't available for the patient's age ({0})")]
+    ReimbursementLevelNotAvailableForAge(String),
+}
+
+// Amount (in grosze) paid by the patient for a package with flat fee reimbursement
+const FLAT_FEE: i64 = 320;
+
+impl ReimbursementLevel {
+    // Patient never pays more than the package price, even with the flat fee
+    pub fn get_patient_payment(&self, price: i64) -> i64 {
+        match self {
+            ReimbursementLevel::Full
+            | ReimbursementLevel::FreeForSeniors
+            | ReimbursementLevel::FreeForChildren => 0,
+            ReimbursementLevel::FiftyPercent => price - price * 50 / 100,
+            ReimbursementLevel::ThirtyPercent => price - price * 30 / 100,
+            ReimbursementLevel::FlatFee => FLAT_FEE.min(price),
+        }
+    }
+
+    // Free drugs are for patients aged 65 or more and for children under 18, so they aren't
+    // available when the age is unknown
+    pub fn is_available_for_age(&self, age: Option<u32>) -> bool {
+        match self {
+            ReimbursementLevel::FreeForSeniors => age.is_some_and(|age| age >= 65),
+            ReimbursementLevel::FreeForChildren => age.is_some_and(|age| age < 18),
+            _ => true,
+        }
+    }
+}
+
+fn find_reimbursement(
+    drugs: &[Drug],
+    drug_id: Uuid,
+    variant_id: Uuid,
+) -> Option<&DrugReimbursement> {
+    drugs
+        .iter()
+        .find(|drug| drug.id == drug_id)?
+        .reimbursements
+        .iter()
+        .find(|reimbursement| reimbursement.variant_id == variant_id)
+}
+
+impl NewPrescription {
+    // Level chosen by the doctor must be one of the levels listed in the reimbursement scheme of
+    // the prescribed package, and the patient must be eligible for it by age on the start date
+    pub fn check_reimbursement(
+        &self,
+        drugs: &[Drug],
+        patient_birth_date: NaiveDate,
+    ) -> Result<(), ReimburseDrugsDomainError> {
+        let patient_age = self.start_date.date_naive().years_since(patient_birth_date);
+        let mut not_available_levels: Vec<String> = vec![];
+        let mut not_available_for_age_levels: Vec<String> = vec![];
+        for prescribed_drug in &self.prescribed_drugs {
+            let (Some(reimbursement_level), Some(variant_id)) = (
+                prescribed_drug.reimbursement_level,
+                prescribed_drug.variant_id,
+            ) else {
+                continue;
+            };
+            let Some(drug) = drugs.iter().find(|drug| drug.id == prescribed_drug.drug_id) else {
+                continue;
+            };
+
+            let is_available = find_reimbursement(drugs, drug.id, variant_id)
+                .is_some_and(|reimbursement| reimbursement.levels.contains(&reimbursement_level));
+            if !is_available {
+                not_available_levels.push(format!("{}: {:?}", drug.name, reimbursement_level));
+            } else if !reimbursement_level.is_available_for_age(patient_age) {
+                not_available_for_age_levels
+                    .push(format!("{}: {:?}", drug.name, reimbursement_level));
+            }
+        }
+
+        if !not_available_levels.is_empty() {
+            Err(ReimburseDrugsDomainError::ReimbursementLevelNotAvailable(
+                not_available_levels.join(", "),
+            ))?;
+        }
+        if !not_available_for_age_levels.is_empty() {
+            Err(
+                ReimburseDrugsDomainError::ReimbursementLevelNotAvailableForAge(
+                    not_available_for_age_levels.join(", "),
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Package dispensed instead of the prescribed one is the variant of the substitute drug with the
+// same size
+fn find_dispensed_variant_id(
+    drugs: &[Drug],
+    drug_id: Uuid,
+    variant_id: Uuid,
+    substitute_drug_id: Option<Uuid>,
+) -> Option<Uuid> {
+    let Some(substitute_drug_id) = substitute_drug_id else {
+        return Some(variant_id);
+    };
+    let prescribed_variant = drugs
+        .iter()
+        .find(|drug| drug.id == drug_id)?
+        .variants
+        .iter()
+        .find(|variant| variant.id == variant_id)?;
+
+    drugs
+        .iter()
+        .find(|drug| drug.id == substitute_drug_id)?
+        .variants
+        .iter()
+        .find(|variant| {
+            variant.pills_count == prescribed_variant.pills_count
+                && variant.volume_ml == prescribed_variant.volume_ml
+        })
+        .map(|variant| variant.id)
+}
+
+impl NewPrescriptionFill {
+    // Payments are computed from the reimbursement scheme of the prescribed package, also when an
+    // equivalent drug is dispensed instead, and from the price of the dispensed package valid at
+    // the time of the fill. Reimbursement covers the official price up to the limit, the rest is
+    // paid by the patient. Without a price, a reimbursed package is paid as if it cost the limit,
+    // and amounts of packages without reimbursement level are left empty. Patient not eligible for
+    // the level by age on the day of the fill pays as if the package wasn't reimbursed
+    pub fn with_payments(
+        mut self,
+        prescription: &Prescription,
+        drugs: &[Drug],
+        filled_at: DateTime<Utc>,
+    ) -> Self {
+        let patient_age = filled_at
+            .date_naive()
+            .years_since(prescription.patient.birth_date);
+        for dispensed_drug in self.dispensed_drugs.iter_mut() {
+            let Some(prescribed_drug) = prescription
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.id == dispensed_drug.prescribed_drug_id)
+            else {
+                continue;
+            };
+            let Some(variant_id) = prescribed_drug.variant_id else {
+                continue;
+            };
+
+            let dispensed_drug_id = dispensed_drug
+                .substitute_drug_id
+                .unwrap_or(prescribed_drug.drug_id);
+            let price = find_dispensed_variant_id(
+                drugs,
+                prescribed_drug.drug_id,
+                variant_id,
+                dispensed_drug.substitute_drug_id,
+            )
+            .and_then(|dispensed_variant_id| {
+                drugs
+                    .iter()
+                    .find(|drug| drug.id == dispensed_drug_id)?
+                    .get_price(dispensed_variant_id, filled_at)
+            });
+            let reimbursement = prescribed_drug
+                .reimbursement_level
+                .filter(|level| level.is_available_for_age(patient_age))
+                .and_then(|level| {
+                    find_reimbursement(drugs, prescribed_drug.drug_id, variant_id)
+                        .map(|reimbursement| (level, reimbursement.reimbursement_limit))
+                });
+
+            let (patient_payment, reimbursed_amount, applied_price) = match (reimbursement, price) {
+                (Some((reimbursement_level, reimbursement_limit)), Some(price)) => {
+                    let covered_price = price.official_price.min(reimbursement_limit);
+                    let covered_payment = reimbursement_level.get_patient_payment(covered_price);
+                    (
+                        covered_payment + price.official_price - covered_price,
+                        covered_price - covered_payment,
+                        Some(price.official_price),
+                    )
+                }
+                (Some((reimbursement_level, reimbursement_limit)), None) => {
+                    let patient_payment =
+                        reimbursement_level.get_patient_payment(reimbursement_limit);
+                    (patient_payment, reimbursement_limit - patient_payment, None)
+                }
+                (None, Some(price)) => (price.retail_price, 0, Some(price.retail_price)),
+                (None, None) => continue,
+            };
+
+            let quantity = dispensed_drug.quantity as i64;
+            dispensed_drug.payable_amount = Some(patient_payment * quantity);
+            dispensed_drug.reimbursed_amount = Some(reimbursed_amount * quantity);
+            dispensed_drug.price = applied_price;
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
+    use uuid::Uuid;
+
+    use super::ReimburseDrugsDomainError;
+    use crate::domain::{
+        drugs::entities::{
+            Drug, DrugContentType, DrugPrice, DrugReimbursement, DrugVariant, ReimbursementLevel,
+        },
+        prescriptions::{
+            entities::{NewPrescribedDrug, NewPrescription, Prescription},
+            use_cases::{
+                fill_prescription::get_default_early_pickup_tolerance,
+                prescription_builder::PrescriptionBuilder,
+            },
+        },
+    };
+
+    fn create_mock_drug(name: &str, levels: Vec<ReimbursementLevel>) -> Drug {
+        let drug_id = Uuid::new_v4();
+
+        Drug {
+            id: drug_id,
+            name: name.into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![DrugReimbursement {
+                id: Uuid::new_v4(),
+                drug_id,
+                variant_id: Uuid::new_v4(),
+                levels,
+                reimbursement_limit: 1999,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_mock_prescription(drugs: &[(&Drug, Option<ReimbursementLevel>)]) -> Prescription {
+        let mut prescription = drugs
+            .iter()
+            .fold(PrescriptionBuilder::default(), |builder, (drug, _)| {
+                builder.prescribed_drug(drug.id, Some(drug.reimbursements[0].variant_id), 2)
+            })
+            .build();
+        for (prescribed_drug, (_, reimbursement_level)) in
+            prescription.prescribed_drugs.iter_mut().zip(drugs)
+        {
+            prescribed_drug.reimbursement_level = *reimbursement_level;
+        }
+        prescription
+    }
+
+    #[test]
+    fn computes_patient_payment_for_each_reimbursement_level() {
+        for (reimbursement_level, patient_payment) in [
+            (ReimbursementLevel::Full, 0),
+            (ReimbursementLevel::FiftyPercent, 1000),
+            (ReimbursementLevel::ThirtyPercent, 1400),
+            (ReimbursementLevel::FlatFee, 320),
+            (ReimbursementLevel::FreeForSeniors, 0),
+            (ReimbursementLevel::FreeForChildren, 0),
+        ] {
+            assert_eq!(
+                reimbursement_level.get_patient_payment(1999),
+                patient_payment
+            );
+        }
+
+        assert_eq!(ReimbursementLevel::FlatFee.get_patient_payment(250), 250);
+    }
+
+    #[test]
+    fn returns_error_listing_reimbursement_levels_not_available_for_prescribed_packages() {
+        let apap = create_mock_drug("Apap", vec![ReimbursementLevel::FiftyPercent]);
+        let ibuprom = create_mock_drug("Ibuprom", vec![ReimbursementLevel::ThirtyPercent]);
+        let drugs = vec![apap.clone(), ibuprom.clone()];
+        let create_prescription = |apap_level, ibuprom_level| {
+            NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                None,
+                vec![
+                    NewPrescribedDrug {
+                        drug_id: apap.id,
+                        variant_id: Some(apap.reimbursements[0].variant_id),
+                        quantity: 1,
+                        reimbursement_level: Some(apap_level),
+                        ..Default::default()
+                    },
+                    NewPrescribedDrug {
+                        drug_id: ibuprom.id,
+                        variant_id: Some(Uuid::new_v4()),
+                        quantity: 1,
+                        reimbursement_level: ibuprom_level,
+                        ..Default::default()
+                    },
+                ],
+            )
+            .unwrap()
+        };
+
+        let patient_birth_date = NaiveDate::from_ymd_opt(1992, 2, 29).unwrap();
+
+        let sut = create_prescription(ReimbursementLevel::FiftyPercent, None);
+
+        assert_eq!(sut.check_reimbursement(&drugs, patient_birth_date), Ok(()));
+
+        let sut = create_prescription(
+            ReimbursementLevel::Full,
+            Some(ReimbursementLevel::ThirtyPercent),
+        );
+
+        assert_eq!(
+            sut.check_reimbursement(&drugs, patient_birth_date),
+            Err(ReimburseDrugsDomainError::ReimbursementLevelNotAvailable(
+                "Apap: Full, Ibuprom: ThirtyPercent".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn computes_payable_and_reimbursed_amounts_of_dispensed_drugs() {
+        let group_id = Uuid::new_v4();
+        let mut apap = create_mock_drug("Apap", vec![ReimbursementLevel::FiftyPercent]);
+        apap.equivalence_group_id = Some(group_id);
+        let mut panadol = create_mock_drug("Panadol", vec![]);
+        panadol.equivalence_group_id = Some(group_id);
+        let ibuprom = create_mock_drug("Ibuprom", vec![ReimbursementLevel::FlatFee]);
+        let drugs = vec![apap.clone(), panadol.clone(), ibuprom.clone()];
+        let prescription = create_mock_prescription(&[
+            (&apap, Some(ReimbursementLevel::FiftyPercent)),
+            (&ibuprom, None),
+        ]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap()
+            .with_substitutes(
+                &prescription,
+                vec![(prescription.prescribed_drugs[0].id, panadol.id)],
+                &drugs,
+            )
+            .unwrap();
+
+        let sut = fill.with_payments(&prescription, &drugs, Utc::now());
+
+        assert_eq!(sut.dispensed_drugs[0].payable_amount, Some(2000));
+        assert_eq!(sut.dispensed_drugs[0].reimbursed_amount, Some(1998));
+        assert_eq!(sut.dispensed_drugs[1].payable_amount, None);
+        assert_eq!(sut.dispensed_drugs[1].reimbursed_amount, None);
+    }
+
+    fn add_mock_price(
+        drug: &mut Drug,
+        variant_id: Uuid,
+        retail_price: i64,
+        official_price: i64,
+        valid_from: DateTime<Utc>,
+        valid_to: Option<DateTime<Utc>>,
+    ) {
+        if !drug.variants.iter().any(|variant| variant.id == variant_id) {
+            drug.variants.push(DrugVariant {
+                id: variant_id,
+                drug_id: drug.id,
+                pills_count: Some(10),
+                volume_ml: None,
+                gtin: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            });
+        }
+        drug.prices.push(DrugPrice {
+            id: Uuid::new_v4(),
+            price_list_id: Uuid::new_v4(),
+            drug_id: drug.id,
+            variant_id,
+            retail_price,
+            official_price,
+            valid_from,
+            valid_to,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        });
+    }
+
+    #[test]
+    fn computes_payments_from_price_valid_at_the_time_of_the_fill() {
+        let now = Utc::now();
+        let group_id = Uuid::new_v4();
+        let mut apap = create_mock_drug("Apap", vec![ReimbursementLevel::FiftyPercent]);
+        apap.equivalence_group_id = Some(group_id);
+        let apap_variant_id = apap.reimbursements[0].variant_id;
+        add_mock_price(
+            &mut apap,
+            apap_variant_id,
+            2599,
+            2499,
+            now - Duration::days(30),
+            Some(now - Duration::days(10)),
+        );
+        add_mock_price(
+            &mut apap,
+            apap_variant_id,
+            1799,
+            1599,
+            now - Duration::days(10),
+            None,
+        );
+        let mut panadol = create_mock_drug("Panadol", vec![]);
+        panadol.equivalence_group_id = Some(group_id);
+        add_mock_price(
+            &mut panadol,
+            Uuid::new_v4(),
+            1299,
+            1199,
+            now - Duration::days(30),
+            None,
+        );
+        let mut ibuprom = create_mock_drug("Ibuprom", vec![ReimbursementLevel::FlatFee]);
+        let ibuprom_variant_id = ibuprom.reimbursements[0].variant_id;
+        add_mock_price(
+            &mut ibuprom,
+            ibuprom_variant_id,
+            1499,
+            1399,
+            now - Duration::days(30),
+            None,
+        );
+        let drugs = vec![apap.clone(), panadol.clone(), ibuprom.clone()];
+        let prescription = create_mock_prescription(&[
+            (&apap, Some(ReimbursementLevel::FiftyPercent)),
+            (&apap, Some(ReimbursementLevel::FiftyPercent)),
+            (&ibuprom, None),
+        ]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap()
+            .with_substitutes(
+                &prescription,
+                vec![(prescription.prescribed_drugs[1].id, panadol.id)],
+                &drugs,
+            )
+            .unwrap();
+
+        let sut = fill.clone().with_payments(&prescription, &drugs, now);
+
+        assert_eq!(sut.dispensed_drugs[0].price, Some(1599));
+        assert_eq!(sut.dispensed_drugs[0].payable_amount, Some(1600));
+        assert_eq!(sut.dispensed_drugs[0].reimbursed_amount, Some(1598));
+        assert_eq!(sut.dispensed_drugs[1].price, Some(1199));
+        assert_eq!(sut.dispensed_drugs[1].payable_amount, Some(1200));
+        assert_eq!(sut.dispensed_drugs[1].reimbursed_amount, Some(1198));
+        assert_eq!(sut.dispensed_drugs[2].price, Some(1499));
+        assert_eq!(sut.dispensed_drugs[2].payable_amount, Some(2998));
+        assert_eq!(sut.dispensed_drugs[2].reimbursed_amount, Some(0));
+
+        let sut = fill.with_payments(&prescription, &drugs, now - Duration::days(20));
+
+        assert_eq!(sut.dispensed_drugs[0].price, Some(2499));
+        assert_eq!(
+            sut.dispensed_drugs[0].payable_amount,
+            Some(2 * (1000 + 500))
+        );
+        assert_eq!(sut.dispensed_drugs[0].reimbursed_amount, Some(1998));
+    }
+
+    #[test]
+    fn reimburses_free_drugs_only_for_patients_of_eligible_age() {
+        let now = Utc::now();
+        let mut apap = create_mock_drug(
+            "Apap",
+            vec![
+                ReimbursementLevel::FreeForSeniors,
+                ReimbursementLevel::FreeForChildren,
+            ],
+        );
+        let apap_variant_id = apap.reimbursements[0].variant_id;
+        add_mock_price(
+            &mut apap,
+            apap_variant_id,
+            1499,
+            1399,
+            now - Duration::days(30),
+            None,
+        );
+        let drugs = vec![apap.clone()];
+        let get_payable_amount = |birth_year, reimbursement_level| {
+            let mut prescription = create_mock_prescription(&[(&apap, Some(reimbursement_level))]);
+            prescription.patient.birth_date = NaiveDate::from_ymd_opt(birth_year, 1, 1).unwrap();
+
+            prescription
+                .fill(
+                    Uuid::new_v4(),
+                    "12345678".into(),
+                    None,
+                    get_default_early_pickup_tolerance(),
+                )
+                .unwrap()
+                .with_payments(&prescription, &drugs, now)
+                .dispensed_drugs[0]
+                .payable_amount
+        };
+
+        assert_eq!(
+            get_payable_amount(1950, ReimbursementLevel::FreeForSeniors),
+            Some(0)
+        );
+        assert_eq!(
+            get_payable_amount(1992, ReimbursementLevel::FreeForSeniors),
+            Some(2998)
+        );
+        assert_eq!(
+            get_payable_amount(2024, ReimbursementLevel::FreeForChildren),
+            Some(0)
+        );
+        assert_eq!(
+            get_payable_amount(1992, ReimbursementLevel::FreeForChildren),
+            Some(2998)
+        );
+        assert_eq!(
+            get_payable_amount(2100, ReimbursementLevel::FreeForChildren),
+            Some(2998)
+        );
+    }
+
+    #[test]
+    fn returns_error_if_patient_isnt_eligible_for_reimbursement_level_by_age() {
+        let apap = create_mock_drug(
+            "Apap",
+            vec![
+                ReimbursementLevel::FiftyPercent,
+                ReimbursementLevel::FreeForSeniors,
+                ReimbursementLevel::FreeForChildren,
+            ],
+        );
+        let drugs = vec![apap.clone()];
+        let check_reimbursement = |birth_year, reimbursement_level| {
+            NewPrescription::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: apap.id,
+                    variant_id: Some(apap.reimbursements[0].variant_id),
+                    quantity: 1,
+                    reimbursement_level: Some(reimbursement_level),
+                    ..Default::default()
+                }],
+            )
+            .unwrap()
+            .check_reimbursement(&drugs, NaiveDate::from_ymd_opt(birth_year, 1, 1).unwrap())
+        };
+
+        assert_eq!(
+            check_reimbursement(1950, ReimbursementLevel::FreeForSeniors),
+            Ok(())
+        );
+        assert_eq!(
+            check_reimbursement(2024, ReimbursementLevel::FreeForChildren),
+            Ok(())
+        );
+        assert_eq!(
+            check_reimbursement(1992, ReimbursementLevel::FiftyPercent),
+            Ok(())
+        );
+        assert_eq!(
+            check_reimbursement(1992, ReimbursementLevel::FreeForSeniors),
+            Err(
+                ReimburseDrugsDomainError::ReimbursementLevelNotAvailableForAge(
+                    "Apap: FreeForSeniors".into()
+                )
+            )
+        );
+        assert_eq!(
+            check_reimbursement(1992, ReimbursementLevel::FreeForChildren),
+            Err(
+                ReimburseDrugsDomainError::ReimbursementLevelNotAvailableForAge(
+                    "Apap: FreeForChildren".into()
+                )
+            )
+        );
+        assert_eq!(
+            check_reimbursement(2100, ReimbursementLevel::FreeForChildren),
+            Err(
+                ReimburseDrugsDomainError::ReimbursementLevelNotAvailableForAge(
+                    "Apap: FreeForChildren".into()
+                )
+            )
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use uuid::Uuid;
+
+use crate::domain::{
+    drugs::entities::{Drug, DrugVariant},
+    prescriptions::entities::{PrescribedDrug, Prescription},
+    utils::validators::validate_gtin::validate_gtin,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ScanDispensedPackagesDomainError {
+    #[error("{0} is not a valid GTIN")]
+    InvalidGtin(String),
+    #[error("Package with GTIN {0} is not a prescribed drug")]
+    PackageNotPrescribed(String),
+    #[error("Package with GTIN {0} is of a different size than the prescribed one")]
+    PackageOfDifferentSize(String),
+    #[error("Dispensed drugs can't be given together with scanned packages")]
+    DispensedDrugsGivenWithScannedPackages,
+}
+
+fn find_variant(drugs: &[Drug], drug_id: Uuid, variant_id: Uuid) -> Option<&DrugVariant> {
+    drugs
+        .iter()
+        .find(|drug| drug.id == drug_id)?
+        .variants
+        .iter()
+        .find(|variant| variant.id == variant_id)
+}
+
+// Substitute is dispensed in the same package size as the prescribed drug, which is compared
+// instead of the variant id
+fn is_prescribed_package(
+    prescribed_drug: &PrescribedDrug,
+    is_substitute: bool,
+    scanned_variant: &DrugVariant,
+    drugs: &[Drug],
+) -> bool {
+    let Some(variant_id) = prescribed_drug.variant_id else {
+        return true;
+    };
+    if !is_substitute {
+        return scanned_variant.id == variant_id;
+    }
+
+    find_variant(drugs, prescribed_drug.drug_id, variant_id).is_some_and(|prescribed_variant| {
+        prescribed_variant.pills_count == scanned_variant.pills_count
+            && prescribed_variant.volume_ml == scanned_variant.volume_ml
+    })
+}
+
+impl Prescription {
+    // Every scanned GTIN is one dispensed package, matched with the prescribed drug by the variant
+    // it belongs to. Package of a substitute counts for the prescribed drug it's dispensed instead
+    // of. The given drugs must contain both the prescribed drugs and the substitutes
+    pub fn get_scanned_dispensed_drugs(
+        &self,
+        scanned_gtins: &[String],
+        substitutes: &[(Uuid, Uuid)],
+        drugs: &[Drug],
+    ) -> Result<Vec<(Uuid, u32)>, ScanDispensedPackagesDomainError> {
+        let get_substitute_drug_id = |prescribed_drug: &PrescribedDrug| {
+            substitutes
+                .iter()
+                .find(|&&(prescribed_drug_id, _)| prescribed_drug_id == prescribed_drug.id)
+                .map(|&(_, substitute_drug_id)| substitute_drug_id)
+        };
+        let mut dispensed_drugs: Vec<(Uuid, u32)> = vec![];
+        for gtin in scanned_gtins {
+            let gtin = gtin.trim();
+            validate_gtin(gtin)
+                .map_err(|_| ScanDispensedPackagesDomainError::InvalidGtin(gtin.into()))?;
+            let (drug, variant) = drugs
+                .iter()
+                .find_map(|drug| {
+                    drug.variants
+                        .iter()
+                        .find(|variant| variant.gtin.as_deref() == Some(gtin))
+                        .map(|variant| (drug, variant))
+                })
+                .ok_or(ScanDispensedPackagesDomainError::PackageNotPrescribed(
+                    gtin.into(),
+                ))?;
+
+            let prescribed_drugs: Vec<(&PrescribedDrug, bool)> = self
+                .prescribed_drugs
+                .iter()
+                .map(|prescribed_drug| (prescribed_drug, get_substitute_drug_id(prescribed_drug)))
+                .filter(|&(prescribed_drug, substitute_drug_id)| {
+                    substitute_drug_id.unwrap_or(prescribed_drug.drug_id) == drug.id
+                })
+                .map(|(prescribed_drug, substitute_drug_id)| {
+                    (prescribed_drug, substitute_drug_id.is_some())
+                })
+                .collect();
+            if prescribed_drugs.is_empty() {
+                Err(ScanDispensedPackagesDomainError::PackageNotPrescribed(
+                    gtin.into(),
+                ))?;
+            }
+            let prescribed_drug = prescribed_drugs
+                .into_iter()
+                .find(|&(prescribed_drug, is_substitute)| {
+                    is_prescribed_package(prescribed_drug, is_substitute, variant, drugs)
+                })
+                .map(|(prescribed_drug, _)| prescribed_drug)
+                .ok_or(ScanDispensedPackagesDomainError::PackageOfDifferentSize(
+                    gtin.into(),
+                ))?;
+
+            match dispensed_drugs
+                .iter_mut()
+                .find(|(prescribed_drug_id, _)| *prescribed_drug_id == prescribed_drug.id)
+            {
+                Some((_, quantity)) => *quantity += 1,
+                None => dispensed_drugs.push((prescribed_drug.id, 1)),
+            }
+        }
+
+        Ok(dispensed_drugs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::ScanDispensedPackagesDomainError;
+    use crate::domain::{
+        drugs::entities::{Drug, DrugContentType, DrugVariant},
+        prescriptions::{
+            entities::Prescription, use_cases::prescription_builder::PrescriptionBuilder,
+        },
+    };
+
+    fn create_mock_drug(name: &str, gtins: [&str; 2]) -> Drug {
+        let drug_id = Uuid::new_v4();
+        let create_variant = |pills_count, gtin: &str| DrugVariant {
+            id: Uuid::new_v4(),
+            drug_id,
+            pills_count: Some(pills_count),
+            volume_ml: None,
+            gtin: Some(gtin.into()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        Drug {
+            id: drug_id,
+            name: name.into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![create_variant(10, gtins[0]), create_variant(20, gtins[1])],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id: None,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_mock_prescription(drugs: &[(&Drug, Option<Uuid>)]) -> Prescription {
+        drugs
+            .iter()
+            .fold(
+                PrescriptionBuilder::default(),
+                |builder, &(drug, variant_id)| builder.prescribed_drug(drug.id, variant_id, 2),
+            )
+            .build()
+    }
+
+    #[test]
+    fn counts_scanned_packages_of_prescribed_drugs() {
+        let apap = create_mock_drug("Apap", ["5909990733828", "5909990733835"]);
+        let ibuprom = create_mock_drug("Ibuprom", ["5909990733842", "5909990733859"]);
+        let drugs = vec![apap.clone(), ibuprom.clone()];
+        let prescription =
+            create_mock_prescription(&[(&apap, Some(apap.variants[1].id)), (&ibuprom, None)]);
+
+        let sut = prescription
+            .get_scanned_dispensed_drugs(
+                &[
+                    "5909990733835".into(),
+                    "5909990733842".into(),
+                    " 5909990733835".into(),
+                ],
+                &[],
+                &drugs,
+            )
+            .unwrap();
+
+        assert_eq!(
+            sut,
+            vec![
+                (prescription.prescribed_drugs[0].id, 2),
+                (prescription.prescribed_drugs[1].id, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_scanned_packages_of_substitute_of_the_prescribed_size() {
+        let apap = create_mock_drug("Apap", ["5909990733828", "5909990733835"]);
+        let panadol = create_mock_drug("Panadol", ["5909990733842", "5909990733859"]);
+        let drugs = vec![apap.clone(), panadol.clone()];
+        let prescription = create_mock_prescription(&[(&apap, Some(apap.variants[1].id))]);
+        let substitutes = vec![(prescription.prescribed_drugs[0].id, panadol.id)];
+
+        let sut = prescription
+            .get_scanned_dispensed_drugs(&["5909990733859".into()], &substitutes, &drugs)
+            .unwrap();
+
+        assert_eq!(sut, vec![(prescription.prescribed_drugs[0].id, 1)]);
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(
+                &["5909990733842".into()],
+                &substitutes,
+                &drugs
+            ),
+            Err(ScanDispensedPackagesDomainError::PackageOfDifferentSize(
+                "5909990733842".into()
+            ))
+        );
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(
+                &["5909990733835".into()],
+                &substitutes,
+                &drugs
+            ),
+            Err(ScanDispensedPackagesDomainError::PackageNotPrescribed(
+                "5909990733835".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_scanned_package_doesnt_match_prescribed_drugs() {
+        let apap = create_mock_drug("Apap", ["5909990733828", "5909990733835"]);
+        let ibuprom = create_mock_drug("Ibuprom", ["5909990733842", "5909990733859"]);
+        let drugs = vec![apap.clone(), ibuprom.clone()];
+        let prescription = create_mock_prescription(&[(&apap, Some(apap.variants[0].id))]);
+
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(&["5909990733835".into()], &[], &drugs),
+            Err(ScanDispensedPackagesDomainError::PackageOfDifferentSize(
+                "5909990733835".into()
+            ))
+        );
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(&["5909990733842".into()], &[], &drugs),
+            Err(ScanDispensedPackagesDomainError::PackageNotPrescribed(
+                "5909990733842".into()
+            ))
+        );
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(&["5909990733866".into()], &[], &drugs),
+            Err(ScanDispensedPackagesDomainError::PackageNotPrescribed(
+                "5909990733866".into()
+            ))
+        );
+        assert_eq!(
+            prescription.get_scanned_dispensed_drugs(&["5909990733820".into()], &[], &drugs),
+            Err(ScanDispensedPackagesDomainError::InvalidGtin(
+                "5909990733820".into()
+            ))
+        );
+    }
+}
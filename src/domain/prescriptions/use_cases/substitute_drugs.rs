@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    drugs::entities::Drug,
+    prescriptions::entities::{NewPrescriptionFill, Prescription},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum SubstituteDrugsDomainError {
+    #[error("Prescribed drug with id {0} is not dispensed in this fill")]
+    PrescribedDrugNotDispensed(Uuid),
+    #[error("Can't substitute prescribed drug with id {0} twice in one fill")]
+    DuplicatedSubstitution(Uuid),
+    #[error("Substitute of prescribed drug with id {0} is the prescribed drug itself")]
+    SubstituteIsPrescribedDrug(Uuid),
+    #[error("Substitutes must belong to the equivalence group of the prescribed drugs ({0})")]
+    NotEquivalentSubstitutes(String),
+}
+
+fn describe_drug(drugs: &[Drug], drug_id: Uuid) -> String {
+    drugs
+        .iter()
+        .find(|drug| drug.id == drug_id)
+        .map_or(drug_id.to_string(), |drug| drug.name.clone())
+}
+
+fn is_in_same_equivalence_group(drugs: &[Drug], drug_id: Uuid, substitute_drug_id: Uuid) -> bool {
+    let get_equivalence_group_id = |drug_id: Uuid| {
+        drugs
+            .iter()
+            .find(|drug| drug.id == drug_id)
+            .and_then(|drug| drug.equivalence_group_id)
+    };
+
+    get_equivalence_group_id(drug_id)
+        .is_some_and(|group_id| get_equivalence_group_id(substitute_drug_id) == Some(group_id))
+}
+
+impl NewPrescriptionFill {
+    // Substitutes are pairs of prescribed drug id and id of the drug dispensed instead of the
+    // prescribed one, the given drugs must contain both the prescribed drugs and the substitutes
+    pub fn with_substitutes(
+        mut self,
+        prescription: &Prescription,
+        substitutes: Vec<(Uuid, Uuid)>,
+        drugs: &[Drug],
+    ) -> Result<Self, SubstituteDrugsDomainError> {
+        let mut ids_hashset: HashSet<Uuid> = HashSet::new();
+        let mut not_equivalent_substitutes: Vec<String> = vec![];
+        for (prescribed_drug_id, substitute_drug_id) in substitutes {
+            let prescribed_drug = prescription
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.id == prescribed_drug_id)
+                .ok_or(SubstituteDrugsDomainError::PrescribedDrugNotDispensed(
+                    prescribed_drug_id,
+                ))?;
+            let dispensed_drug = self
+                .dispensed_drugs
+                .iter_mut()
+                .find(|dispensed_drug| dispensed_drug.prescribed_drug_id == prescribed_drug_id)
+                .ok_or(SubstituteDrugsDomainError::PrescribedDrugNotDispensed(
+                    prescribed_drug_id,
+                ))?;
+            if ids_hashset.contains(&prescribed_drug_id) {
+                Err(SubstituteDrugsDomainError::DuplicatedSubstitution(
+                    prescribed_drug_id,
+                ))?;
+            }
+            if prescribed_drug.drug_id == substitute_drug_id {
+                Err(SubstituteDrugsDomainError::SubstituteIsPrescribedDrug(
+                    prescribed_drug_id,
+                ))?;
+            }
+            if !is_in_same_equivalence_group(drugs, prescribed_drug.drug_id, substitute_drug_id) {
+                not_equivalent_substitutes.push(format!(
+                    "{} -> {}",
+                    describe_drug(drugs, prescribed_drug.drug_id),
+                    describe_drug(drugs, substitute_drug_id)
+                ));
+            }
+
+            dispensed_drug.substitute_drug_id = Some(substitute_drug_id);
+            ids_hashset.insert(prescribed_drug_id);
+        }
+
+        if !not_equivalent_substitutes.is_empty() {
+            Err(SubstituteDrugsDomainError::NotEquivalentSubstitutes(
+                not_equivalent_substitutes.join(", "),
+            ))?;
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::SubstituteDrugsDomainError;
+    use crate::domain::{
+        drugs::entities::{Drug, DrugContentType},
+        prescriptions::{
+            entities::{NewDispensedDrug, Prescription},
+            use_cases::{
+                fill_prescription::get_default_early_pickup_tolerance,
+                prescription_builder::PrescriptionBuilder,
+            },
+        },
+    };
+
+    fn create_mock_drug(name: &str, equivalence_group_id: Option<Uuid>) -> Drug {
+        Drug {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            content_type: DrugContentType::SolidPills,
+            mg_per_pill: Some(500),
+            ml_per_pill: None,
+            active_substances: vec![],
+            variants: vec![],
+            atc_code: None,
+            atc_path: vec![],
+            category_id: None,
+            equivalence_group_id,
+            controlled_substance_list: None,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_mock_prescription(drugs: &[&Drug]) -> Prescription {
+        drugs
+            .iter()
+            .fold(PrescriptionBuilder::default(), |builder, drug| {
+                builder.prescribed_drug(drug.id, None, 2)
+            })
+            .build()
+    }
+
+    #[test]
+    fn records_substitute_on_dispensed_drug() {
+        let group_id = Uuid::new_v4();
+        let apap = create_mock_drug("Apap", Some(group_id));
+        let panadol = create_mock_drug("Panadol", Some(group_id));
+        let ibuprom = create_mock_drug("Ibuprom", None);
+        let drugs = vec![apap.clone(), panadol.clone(), ibuprom.clone()];
+        let prescription = create_mock_prescription(&[&apap, &ibuprom]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        let sut = fill
+            .with_substitutes(
+                &prescription,
+                vec![(prescription.prescribed_drugs[0].id, panadol.id)],
+                &drugs,
+            )
+            .unwrap();
+
+        assert_eq!(sut.dispensed_drugs[0].substitute_drug_id, Some(panadol.id));
+        assert_eq!(sut.dispensed_drugs[1].substitute_drug_id, None);
+    }
+
+    #[test]
+    fn returns_error_listing_substitutes_from_other_equivalence_groups() {
+        let group_id = Uuid::new_v4();
+        let apap = create_mock_drug("Apap", Some(group_id));
+        let ibuprom = create_mock_drug("Ibuprom", None);
+        let nurofen = create_mock_drug("Nurofen", None);
+        let apap_extra = create_mock_drug("Apap Extra", Some(Uuid::new_v4()));
+        let drugs = vec![
+            apap.clone(),
+            ibuprom.clone(),
+            nurofen.clone(),
+            apap_extra.clone(),
+        ];
+        let prescription = create_mock_prescription(&[&apap, &ibuprom]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        let sut = fill.with_substitutes(
+            &prescription,
+            vec![
+                (prescription.prescribed_drugs[0].id, apap_extra.id),
+                (prescription.prescribed_drugs[1].id, nurofen.id),
+            ],
+            &drugs,
+        );
+
+        assert_eq!(
+            sut,
+            Err(SubstituteDrugsDomainError::NotEquivalentSubstitutes(
+                "Apap -> Apap Extra, Ibuprom -> Nurofen".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_substituted_drug_isnt_dispensed() {
+        let group_id = Uuid::new_v4();
+        let apap = create_mock_drug("Apap", Some(group_id));
+        let panadol = create_mock_drug("Panadol", Some(group_id));
+        let ibuprom = create_mock_drug("Ibuprom", None);
+        let drugs = vec![apap.clone(), panadol.clone(), ibuprom.clone()];
+        let prescription = create_mock_prescription(&[&apap, &ibuprom]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id: prescription.prescribed_drugs[1].id,
+                    quantity: 1,
+                    ..Default::default()
+                }]),
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        let sut = fill.with_substitutes(
+            &prescription,
+            vec![(prescription.prescribed_drugs[0].id, panadol.id)],
+            &drugs,
+        );
+
+        assert_eq!(
+            sut,
+            Err(SubstituteDrugsDomainError::PrescribedDrugNotDispensed(
+                prescription.prescribed_drugs[0].id
+            ))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_substitute_is_prescribed_drug() {
+        let apap = create_mock_drug("Apap", Some(Uuid::new_v4()));
+        let drugs = vec![apap.clone()];
+        let prescription = create_mock_prescription(&[&apap]);
+        let fill = prescription
+            .fill(
+                Uuid::new_v4(),
+                "12345678".into(),
+                None,
+                get_default_early_pickup_tolerance(),
+            )
+            .unwrap();
+
+        let sut = fill.with_substitutes(
+            &prescription,
+            vec![(prescription.prescribed_drugs[0].id, apap.id)],
+            &drugs,
+        );
+
+        assert_eq!(
+            sut,
+            Err(SubstituteDrugsDomainError::SubstituteIsPrescribedDrug(
+                prescription.prescribed_drugs[0].id
+            ))
+        );
+    }
+}
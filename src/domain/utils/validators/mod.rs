@@ -1,3 +1,6 @@
+pub mod validate_active_substance;
+pub mod validate_atc_code;
+pub mod validate_gtin;
 pub mod validate_name;
 pub mod validate_pesel_number;
 pub mod validate_pwz_number;
@@ -0,0 +1,42 @@
+#[derive(thiserror::Error, Debug)]
+pub enum ActiveSubstanceValidationError {
+    #[error("Active substance name must be between {0} and {1} characters long")]
+    InvalidLength(usize, usize),
+}
+
+// Active substances are compared case-insensitively, so the returned name is trimmed and lowercased
+pub fn validate_active_substance(active_substance: &str) -> anyhow::Result<String> {
+    let active_substance = active_substance.trim().to_lowercase();
+
+    let min_len: usize = 2;
+    let max_len: usize = 100;
+    let len = active_substance.chars().count();
+    if len < min_len || len > max_len {
+        Err(ActiveSubstanceValidationError::InvalidLength(
+            min_len, max_len,
+        ))?;
+    }
+
+    Ok(active_substance)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::validate_active_substance;
+
+    #[rstest]
+    #[case("paracetamol", Some("paracetamol"))]
+    #[case(" Ibuprofen ", Some("ibuprofen"))]
+    #[case("Acetylsalicylic Acid", Some("acetylsalicylic acid"))]
+    #[case("i", None)]
+    #[case("   ", None)]
+    #[case("", None)]
+    fn validates_active_substance(#[case] active_substance: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            validate_active_substance(active_substance).ok().as_deref(),
+            expected
+        );
+    }
+}
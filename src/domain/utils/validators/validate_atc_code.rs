@@ -0,0 +1,55 @@
+#[derive(thiserror::Error, Debug)]
+pub enum AtcCodeValidationError {
+    #[error("ATC code must be in format: A, A01, A01B, A01BC or A01BC01")]
+    InvalidFormat,
+}
+
+// Lengths of the codes of the 5 ATC levels: anatomical main group, therapeutic subgroup,
+// pharmacological subgroup, chemical subgroup and chemical substance
+pub const ATC_CODE_LEVEL_LENGTHS: [usize; 5] = [1, 3, 4, 5, 7];
+
+// Codes of any level are accepted, the returned code is trimmed and uppercased
+pub fn validate_atc_code(atc_code: &str) -> anyhow::Result<String> {
+    let atc_code = atc_code.trim().to_uppercase();
+
+    if !ATC_CODE_LEVEL_LENGTHS.contains(&atc_code.len()) {
+        Err(AtcCodeValidationError::InvalidFormat)?;
+    }
+
+    let is_valid_format = atc_code.chars().enumerate().all(|(i, c)| match i {
+        0 | 3 | 4 => c.is_ascii_uppercase(),
+        _ => c.is_ascii_digit(),
+    });
+    if !is_valid_format {
+        Err(AtcCodeValidationError::InvalidFormat)?;
+    }
+
+    Ok(atc_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::validate_atc_code;
+
+    #[rstest]
+    #[case("N", Some("N"))]
+    #[case("N02", Some("N02"))]
+    #[case("N02B", Some("N02B"))]
+    #[case("N02BE", Some("N02BE"))]
+    #[case("N02BE01", Some("N02BE01"))]
+    #[case(" n02be01 ", Some("N02BE01"))]
+    #[case("N0", None)]
+    #[case("N02BE0", None)]
+    #[case("N02BE011", None)]
+    #[case("102BE01", None)]
+    #[case("NA2BE01", None)]
+    #[case("N021E01", None)]
+    #[case("N02B101", None)]
+    #[case("N02BEA1", None)]
+    #[case("", None)]
+    fn validates_atc_code(#[case] atc_code: &str, #[case] expected: Option<&str>) {
+        assert_eq!(validate_atc_code(atc_code).ok().as_deref(), expected);
+    }
+}
@@ -0,0 +1,51 @@
+#[derive(thiserror::Error, Debug)]
+pub enum GtinValidationError {
+    #[error("GTIN must be 13 characters long and contain only digits")]
+    InvalidFormat,
+    #[error("The checksum of GTIN is incorrect")]
+    InvalidChecksum,
+}
+
+pub fn validate_gtin(gtin: &str) -> anyhow::Result<()> {
+    let gtin_length = 13;
+    if gtin.len() != gtin_length || !gtin.chars().all(|c| c.is_ascii_digit()) {
+        Err(GtinValidationError::InvalidFormat)?;
+    }
+
+    let (checksum_components, control_digit_str) = gtin.split_at(12);
+    let mut sum = 0;
+    for (i, c) in checksum_components.chars().enumerate() {
+        let digit = c.to_digit(10).unwrap();
+        let multiplier = if i % 2 == 0 { 1 } else { 3 };
+        sum += digit * multiplier;
+    }
+    let control_digit = control_digit_str.parse::<u32>().unwrap();
+    let checksum = (10 - sum % 10) % 10;
+    if checksum != control_digit {
+        Err(GtinValidationError::InvalidChecksum)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::validate_gtin;
+
+    #[rstest]
+    #[case("5901234123457", true)]
+    #[case("4006381333931", true)]
+    #[case("5909990733828", true)]
+    #[case("5901234123458", false)]
+    #[case("4006381333930", false)]
+    #[case("590123412345a", false)]
+    #[case("+901234123457", false)]
+    #[case("59012341234570", false)]
+    #[case("590123412345", false)]
+    #[case("", false)]
+    fn validates_gtin(#[case] gtin: &str, #[case] expected: bool) {
+        assert_eq!(validate_gtin(gtin).is_ok(), expected);
+    }
+}
@@ -1,4 +1,6 @@
 use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 #[derive(thiserror::Error, Debug)]
 pub enum PeselNumberValidationError {
@@ -10,55 +12,148 @@ pub enum PeselNumberValidationError {
     InvalidChecksum,
 }
 
-pub fn validate_pesel_number(pesel_number: &str) -> anyhow::Result<()> {
-    let pesel_length = 11;
-    if pesel_number.len() != pesel_length || pesel_number.parse::<u64>().is_err() {
-        Err(PeselNumberValidationError::InvalidFormat)?;
+#[derive(Debug, PartialEq, sqlx::Type, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[sqlx(type_name = "sex", rename_all = "snake_case")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pesel {
+    birth_date: NaiveDate,
+    sex: Sex,
+}
+
+// Century of the birth date is encoded by adding an offset to the month: 80 for 1800s, 0 for
+// 1900s, 20 for 2000s, 40 for 2100s and 60 for 2200s
+fn decode_birth_date(date_part: &str) -> Option<NaiveDate> {
+    let year = date_part[0..2].parse::<i32>().ok()?;
+    let encoded_month = date_part[2..4].parse::<u32>().ok()?;
+    let day = date_part[4..6].parse::<u32>().ok()?;
+    let (century, month) = match encoded_month {
+        81..=92 => (1800, encoded_month - 80),
+        1..=12 => (1900, encoded_month),
+        21..=32 => (2000, encoded_month - 20),
+        41..=52 => (2100, encoded_month - 40),
+        61..=72 => (2200, encoded_month - 60),
+        _ => return None,
+    };
+
+    NaiveDate::from_ymd_opt(century + year, month, day)
+}
+
+impl Pesel {
+    pub fn new(pesel_number: &str) -> anyhow::Result<Self> {
+        let pesel_length = 11;
+        if pesel_number.len() != pesel_length || !pesel_number.chars().all(|c| c.is_ascii_digit()) {
+            Err(PeselNumberValidationError::InvalidFormat)?;
+        }
+
+        let (date_part, _) = pesel_number.split_at(6);
+        let birth_date =
+            decode_birth_date(date_part).ok_or(PeselNumberValidationError::InvalidDate)?;
+
+        let (checksum_components, control_digit_str) = pesel_number.split_at(10);
+        let digit_multipliters = [1, 3, 7, 9, 1, 3, 7, 9, 1, 3];
+        let mut sum = 0;
+        for (i, c) in checksum_components.chars().enumerate() {
+            let digit = c.to_digit(10).unwrap();
+            let multiplier = digit_multipliters[i];
+            sum += digit * multiplier;
+        }
+        let control_digit = control_digit_str.parse::<u32>().unwrap();
+        let checksum = sum % 10;
+        if checksum != control_digit {
+            Err(PeselNumberValidationError::InvalidChecksum)?;
+        }
+
+        let sex_digit = checksum_components[9..].parse::<u32>().unwrap();
+        let sex = if sex_digit % 2 == 1 {
+            Sex::Male
+        } else {
+            Sex::Female
+        };
+
+        Ok(Pesel { birth_date, sex })
     }
 
-    let (date_part, _) = pesel_number.split_at(6);
-    let is_valid_date = NaiveDate::parse_from_str(&format!("19{}", date_part), "%Y%m%d").is_ok();
-    if !is_valid_date {
-        Err(PeselNumberValidationError::InvalidDate)?;
+    pub fn get_birth_date(&self) -> NaiveDate {
+        self.birth_date
     }
 
-    let (checksum_components, control_digit_str) = pesel_number.split_at(10);
-    let digit_multipliters = [1, 3, 7, 9, 1, 3, 7, 9, 1, 3];
-    let mut sum = 0;
-    for (i, c) in checksum_components.chars().enumerate() {
-        let digit = c.to_digit(10).unwrap();
-        let multiplier = digit_multipliters[i];
-        sum += digit * multiplier;
+    pub fn get_sex(&self) -> Sex {
+        self.sex
     }
-    let control_digit = control_digit_str.parse::<u32>().unwrap();
-    let checksum = sum % 10;
-    if checksum != control_digit {
-        Err(PeselNumberValidationError::InvalidChecksum)?;
+
+    // Full years of age on the given date
+    pub fn get_age(&self, date: NaiveDate) -> u32 {
+        date.years_since(self.birth_date).unwrap_or(0)
     }
+}
+
+pub fn validate_pesel_number(pesel_number: &str) -> anyhow::Result<()> {
+    Pesel::new(pesel_number)?;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::NaiveDate;
     use rstest::rstest;
 
-    use super::validate_pesel_number;
+    use super::{validate_pesel_number, Pesel, Sex};
 
     #[rstest]
     #[case("96021817257", true)]
     #[case("99031301347", true)]
     #[case("92022900002", true)]
+    #[case("02270803626", true)]
+    #[case("04322900042", true)]
+    #[case("91851201230", true)]
     #[case("96221807250", false)]
     #[case("96021807251", false)]
     #[case("93022900005", false)]
     #[case("92223300009", false)]
+    #[case("06223000016", false)]
+    #[case("00130100052", false)]
     #[case("9222330000a", false)]
     #[case("aaaaaaaaaaa", false)]
+    #[case("+9602180725", false)]
     #[case("960218072500", false)]
     #[case("30", false)]
     #[case("", false)]
     fn validates_pesel_number(#[case] pesel_number: &str, #[case] expected: bool) {
         assert_eq!(validate_pesel_number(pesel_number).is_ok(), expected);
     }
+
+    #[rstest]
+    #[case("96021817257", "1996-02-18", Sex::Male)]
+    #[case("92022900002", "1992-02-29", Sex::Female)]
+    #[case("02270803626", "2002-07-08", Sex::Female)]
+    #[case("91851201230", "1891-05-12", Sex::Male)]
+    fn decodes_birth_date_and_sex(
+        #[case] pesel_number: &str,
+        #[case] birth_date: &str,
+        #[case] sex: Sex,
+    ) {
+        let sut = Pesel::new(pesel_number).unwrap();
+
+        assert_eq!(sut.get_birth_date().to_string(), birth_date);
+        assert_eq!(sut.get_sex(), sex);
+    }
+
+    #[test]
+    fn gets_age_on_given_date() {
+        let sut = Pesel::new("02270803626").unwrap();
+
+        let get_age =
+            |year, month, day| sut.get_age(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+
+        assert_eq!(get_age(2020, 7, 7), 17);
+        assert_eq!(get_age(2020, 7, 8), 18);
+        assert_eq!(get_age(2000, 1, 1), 0);
+    }
 }
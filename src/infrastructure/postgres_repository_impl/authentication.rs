@@ -103,8 +103,9 @@ impl AuthenticationRepository for PostgresAuthenticationRepository {
             .map_err(|err| CreateUserRepositoryError::DatabaseError(err.to_string()))?;
 
         sqlx::query(
-            r#"INSERT INTO users (username, password_hash, email, phone_number, role, doctor_id, pharmacist_id) VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            r#"INSERT INTO users (id, username, password_hash, email, phone_number, role, doctor_id, pharmacist_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
         )
+        .bind(new_user.id)
         .bind(new_user.username.clone())
         .bind(new_user.password_hash)
         .bind(new_user.email)
@@ -164,7 +165,10 @@ impl AuthenticationRepository for PostgresAuthenticationRepository {
         .bind(username)
         .fetch_one(&self.pool)
         .await
-        .map_err(|err| GetUserRepositoryError::DatabaseError(err.to_string()))?;
+        .map_err(|err| match err {
+            sqlx::Error::RowNotFound => GetUserRepositoryError::NotFound(username.to_string()),
+            _ => GetUserRepositoryError::DatabaseError(err.to_string()),
+        })?;
 
         let user = self
             .parse_users_row(row)
@@ -182,43 +186,82 @@ mod tests {
     use crate::{
         application::authentication::{
             entities::{NewUser, UserRole},
-            repository::AuthenticationRepository,
+            repository::{
+                AuthenticationRepository, CreateUserRepositoryError, GetUserRepositoryError,
+            },
         },
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
+        domain::doctors::{entities::NewDoctor, repository::DoctorsRepository},
+        infrastructure::postgres_repository_impl::doctors::PostgresDoctorsRepository,
     };
 
-    async fn setup_repository(pool: sqlx::PgPool) -> PostgresAuthenticationRepository {
-        create_tables(&pool, true).await.unwrap();
-        PostgresAuthenticationRepository::new(pool)
+    async fn setup_repository(pool: sqlx::PgPool) -> (PostgresAuthenticationRepository, NewDoctor) {
+        let doctor = NewDoctor::new(
+            "John Doctor".into(), //
+            "3123456".into(),
+            "96021807250".into(),
+        )
+        .unwrap();
+        PostgresDoctorsRepository::new(pool.clone())
+            .create_doctor(doctor.clone())
+            .await
+            .unwrap();
+        (PostgresAuthenticationRepository::new(pool), doctor)
     }
 
-    fn create_mock_new_user() -> NewUser {
+    fn create_mock_new_user(doctor: &NewDoctor) -> NewUser {
         NewUser::new(
             "username".to_string(), //
             "password".to_string(),
             "john.doe@gmail.com".to_string(),
             "123456789".to_string(),
             UserRole::Doctor,
-            Some(Uuid::default()),
+            Some(doctor.id),
             None,
         )
         .unwrap()
     }
 
-    // #[sqlx::test]
-    // async fn creates_new_user_and_reads_by_username(pool: sqlx::PgPool) {
-    //     let repository = setup_repository(pool).await;
-    //     let mock_new_user = create_mock_new_user();
+    #[sqlx::test]
+    async fn creates_new_user_and_reads_by_username(pool: sqlx::PgPool) {
+        let (repository, doctor) = setup_repository(pool).await;
+        let mock_new_user = create_mock_new_user(&doctor);
+
+        let created_user = repository.create_user(mock_new_user.clone()).await.unwrap();
+
+        assert_eq!(created_user, mock_new_user);
+        assert_eq!(created_user.doctor.unwrap().id, doctor.id);
+
+        let user_by_username = repository
+            .get_user_by_username(&mock_new_user.username)
+            .await
+            .unwrap();
+
+        assert_eq!(user_by_username, mock_new_user);
+    }
+
+    #[sqlx::test]
+    async fn returns_error_if_user_does_not_exist(pool: sqlx::PgPool) {
+        let (repository, _) = setup_repository(pool).await;
 
-    //     let created_user = repository.create_user(mock_new_user.clone()).await.unwrap();
+        let user_by_username = repository.get_user_by_username("username").await;
 
-    //     assert_eq!(created_user, mock_new_user);
+        assert_eq!(
+            user_by_username,
+            Err(GetUserRepositoryError::NotFound("username".into()))
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_user_for_nonexistent_doctor(pool: sqlx::PgPool) {
+        let (repository, doctor) = setup_repository(pool).await;
+        let mut mock_new_user = create_mock_new_user(&doctor);
+        mock_new_user.doctor_id = Some(Uuid::new_v4());
 
-    //     let user_by_username = repository
-    //         .get_user_by_username(&mock_new_user.username)
-    //         .await
-    //         .unwrap();
+        let created_user = repository.create_user(mock_new_user).await;
 
-    //     assert_eq!(created_user, user_by_username);
-    // }
+        assert!(matches!(
+            created_user,
+            Err(CreateUserRepositoryError::DatabaseError(_))
+        ));
+    }
 }
@@ -4,10 +4,10 @@ use uuid::Uuid;
 
 use crate::domain::{
     doctors::{
-        entities::{Doctor, NewDoctor},
+        entities::{Doctor, DoctorUpdate, NewDoctor},
         repository::{
             CreateDoctorRepositoryError, DoctorsRepository, GetDoctorByIdRepositoryError,
-            GetDoctorsRepositoryError,
+            GetDoctorsRepositoryError, UpdateDoctorRepositoryError,
         },
     },
     utils::pagination::get_pagination_params,
@@ -122,6 +122,43 @@ impl DoctorsRepository for PostgresDoctorsRepository {
 
         Ok(doctor)
     }
+
+    async fn update_doctor(
+        &self,
+        doctor_update: DoctorUpdate,
+    ) -> Result<Doctor, UpdateDoctorRepositoryError> {
+        let result = sqlx::query(
+                r#"UPDATE doctors SET name = $2, pwz_number = $3, pesel_number = $4, updated_at = NOW() WHERE id = $1 RETURNING id, name, pwz_number, pesel_number, created_at, updated_at"#
+            )
+            .bind(doctor_update.id)
+            .bind(doctor_update.name)
+            .bind(doctor_update.pwz_number)
+            .bind(doctor_update.pesel_number)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::RowNotFound => UpdateDoctorRepositoryError::NotFound(doctor_update.id),
+                    sqlx::Error::Database(err) if err.is_unique_violation() => {
+                        match err.constraint() {
+                            Some("doctors_pwz_number_key") => {
+                                UpdateDoctorRepositoryError::DuplicatedPwzNumber
+                            }
+                            Some("doctors_pesel_number_key") => {
+                                UpdateDoctorRepositoryError::DuplicatedPeselNumber
+                            }
+                            _ => UpdateDoctorRepositoryError::DatabaseError(err.to_string()),
+                        }
+                    },
+                    _ => UpdateDoctorRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        let doctor = self
+            .parse_doctors_row(result)
+            .map_err(|err| UpdateDoctorRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(doctor)
+    }
 }
 
 #[cfg(test)]
@@ -129,19 +166,15 @@ mod tests {
     use uuid::Uuid;
 
     use super::PostgresDoctorsRepository;
-    use crate::{
-        domain::doctors::{
-            entities::NewDoctor,
-            repository::{
-                CreateDoctorRepositoryError, DoctorsRepository, GetDoctorByIdRepositoryError,
-                GetDoctorsRepositoryError,
-            },
+    use crate::domain::doctors::{
+        entities::{DoctorUpdate, NewDoctor},
+        repository::{
+            CreateDoctorRepositoryError, DoctorsRepository, GetDoctorByIdRepositoryError,
+            GetDoctorsRepositoryError, UpdateDoctorRepositoryError,
         },
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
     };
 
     async fn setup_repository(pool: sqlx::PgPool) -> PostgresDoctorsRepository {
-        create_tables(&pool, true).await.unwrap();
         PostgresDoctorsRepository::new(pool)
     }
 
@@ -270,4 +303,79 @@ mod tests {
             Err(CreateDoctorRepositoryError::DuplicatedPeselNumber)
         );
     }
+
+    #[sqlx::test]
+    async fn updates_doctor(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let new_doctor =
+            NewDoctor::new("John Doe".into(), "5425740".into(), "96021817257".into()).unwrap();
+        let doctor = repository.create_doctor(new_doctor).await.unwrap();
+
+        let updated_doctor = repository
+            .update_doctor(DoctorUpdate {
+                id: doctor.id,
+                name: "John Smith".into(),
+                pwz_number: "8463856".into(),
+                pesel_number: doctor.pesel_number.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_doctor.name, "John Smith");
+        assert_eq!(updated_doctor.pwz_number, "8463856");
+        assert_eq!(updated_doctor.created_at, doctor.created_at);
+        assert!(updated_doctor.updated_at > doctor.updated_at);
+        assert_eq!(
+            repository.get_doctor_by_id(doctor.id).await.unwrap(),
+            updated_doctor
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_update_doctor_if_it_doesnt_exist_or_numbers_are_duplicated(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let doctor_0 =
+            NewDoctor::new("John Doe".into(), "5425740".into(), "96021817257".into()).unwrap();
+        let doctor_1 =
+            NewDoctor::new("John Doe".into(), "8463856".into(), "99031301347".into()).unwrap();
+        repository.create_doctor(doctor_0.clone()).await.unwrap();
+        repository.create_doctor(doctor_1.clone()).await.unwrap();
+
+        let doctor_update = DoctorUpdate {
+            id: doctor_1.id,
+            name: doctor_1.name,
+            pwz_number: doctor_0.pwz_number.clone(),
+            pesel_number: doctor_1.pesel_number.clone(),
+        };
+
+        assert_eq!(
+            repository.update_doctor(doctor_update.clone()).await,
+            Err(UpdateDoctorRepositoryError::DuplicatedPwzNumber)
+        );
+        assert_eq!(
+            repository
+                .update_doctor(DoctorUpdate {
+                    pwz_number: "3123456".into(),
+                    pesel_number: doctor_0.pesel_number,
+                    ..doctor_update.clone()
+                })
+                .await,
+            Err(UpdateDoctorRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let doctor_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_doctor(DoctorUpdate {
+                    id: doctor_id,
+                    pwz_number: "3123456".into(),
+                    ..doctor_update
+                })
+                .await,
+            Err(UpdateDoctorRepositoryError::NotFound(doctor_id))
+        );
+    }
 }
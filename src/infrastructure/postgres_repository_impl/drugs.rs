@@ -4,10 +4,22 @@ use uuid::Uuid;
 
 use crate::domain::{
     drugs::{
-        entities::{Drug, NewDrug},
+        entities::{
+            ActiveSubstance, AtcGroup, Drug, DrugActiveSubstance, DrugCategory,
+            DrugEquivalenceGroup, DrugInteraction, DrugPrice, DrugPriceList, DrugReimbursement,
+            DrugVariant, NewAtcGroup, NewDrug, NewDrugCategory, NewDrugEquivalenceGroup,
+            NewDrugInteraction, NewDrugPriceList, NewDrugReimbursement, NewDrugVariant,
+        },
         repository::{
-            CreateDrugRepositoryError, DrugsRepository, GetDrugByIdRepositoryError,
-            GetDrugsRepositoryError,
+            CreateAtcGroupRepositoryError, CreateDrugCategoryRepositoryError,
+            CreateDrugEquivalenceGroupRepositoryError, CreateDrugInteractionRepositoryError,
+            CreateDrugPriceListRepositoryError, CreateDrugReimbursementRepositoryError,
+            CreateDrugRepositoryError, CreateDrugVariantRepositoryError, DrugsRepository,
+            GetActiveSubstancesRepositoryError, GetAtcGroupsRepositoryError,
+            GetDrugByExternalIdRepositoryError, GetDrugByGtinRepositoryError,
+            GetDrugByIdRepositoryError, GetDrugCategoriesRepositoryError,
+            GetDrugEquivalenceGroupsRepositoryError, GetDrugInteractionsRepositoryError,
+            GetDrugsRepositoryError, UpdateDrugRepositoryError,
         },
     },
     utils::pagination::get_pagination_params,
@@ -27,12 +39,253 @@ impl PostgresDrugsRepository {
             id: row.try_get(0)?,
             name: row.try_get(1)?,
             content_type: row.try_get(2)?,
-            pills_count: row.try_get(3)?,
-            mg_per_pill: row.try_get(4)?,
-            ml_per_pill: row.try_get(5)?,
-            volume_ml: row.try_get(6)?,
-            created_at: row.try_get(7)?,
-            updated_at: row.try_get(8)?,
+            mg_per_pill: row.try_get(3)?,
+            ml_per_pill: row.try_get(4)?,
+            active_substances: vec![],
+            variants: vec![],
+            atc_code: row.try_get(5)?,
+            atc_path: vec![],
+            category_id: row.try_get(6)?,
+            equivalence_group_id: row.try_get(7)?,
+            controlled_substance_list: row.try_get(8)?,
+            reimbursements: vec![],
+            prices: vec![],
+            external_id: row.try_get(11)?,
+            created_at: row.try_get(9)?,
+            updated_at: row.try_get(10)?,
+        })
+    }
+
+    fn parse_drug_equivalence_groups_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugEquivalenceGroup, sqlx::Error> {
+        Ok(DrugEquivalenceGroup {
+            id: row.try_get(0)?,
+            name: row.try_get(1)?,
+            drug_ids: row.try_get(2)?,
+            created_at: row.try_get(3)?,
+            updated_at: row.try_get(4)?,
+        })
+    }
+
+    fn parse_atc_groups_row(&self, row: sqlx::postgres::PgRow) -> Result<AtcGroup, sqlx::Error> {
+        Ok(AtcGroup {
+            code: row.try_get(0)?,
+            name: row.try_get(1)?,
+            parent_code: row.try_get(2)?,
+            created_at: row.try_get(3)?,
+            updated_at: row.try_get(4)?,
+        })
+    }
+
+    fn parse_drug_categories_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugCategory, sqlx::Error> {
+        Ok(DrugCategory {
+            id: row.try_get(0)?,
+            name: row.try_get(1)?,
+            parent_id: row.try_get(2)?,
+            created_at: row.try_get(3)?,
+            updated_at: row.try_get(4)?,
+        })
+    }
+
+    fn parse_drug_variants_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugVariant, sqlx::Error> {
+        Ok(DrugVariant {
+            id: row.try_get(0)?,
+            drug_id: row.try_get(1)?,
+            pills_count: row.try_get(2)?,
+            volume_ml: row.try_get(3)?,
+            created_at: row.try_get(4)?,
+            updated_at: row.try_get(5)?,
+            gtin: row.try_get(6)?,
+        })
+    }
+
+    fn parse_drug_reimbursements_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugReimbursement, sqlx::Error> {
+        Ok(DrugReimbursement {
+            id: row.try_get(0)?,
+            drug_id: row.try_get(1)?,
+            variant_id: row.try_get(2)?,
+            levels: row.try_get(3)?,
+            reimbursement_limit: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            updated_at: row.try_get(6)?,
+        })
+    }
+
+    fn parse_drug_prices_row(&self, row: sqlx::postgres::PgRow) -> Result<DrugPrice, sqlx::Error> {
+        Ok(DrugPrice {
+            id: row.try_get(0)?,
+            price_list_id: row.try_get(1)?,
+            drug_id: row.try_get(2)?,
+            variant_id: row.try_get(3)?,
+            retail_price: row.try_get(4)?,
+            official_price: row.try_get(5)?,
+            valid_from: row.try_get(6)?,
+            valid_to: row.try_get(7)?,
+            created_at: row.try_get(8)?,
+            updated_at: row.try_get(9)?,
+        })
+    }
+
+    fn parse_active_substances_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<ActiveSubstance, sqlx::Error> {
+        Ok(ActiveSubstance {
+            id: row.try_get(0)?,
+            name: row.try_get(1)?,
+            created_at: row.try_get(2)?,
+            updated_at: row.try_get(3)?,
+        })
+    }
+
+    async fn load_active_substances(&self, mut drugs: Vec<Drug>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drug_ids: Vec<Uuid> = drugs.iter().map(|drug| drug.id).collect();
+        let rows = sqlx::query(
+                r#"SELECT drug_active_substances.drug_id, active_substances.name, drug_active_substances.amount, drug_active_substances.unit FROM drug_active_substances INNER JOIN active_substances ON drug_active_substances.active_substance_id = active_substances.id WHERE drug_active_substances.drug_id = ANY($1) ORDER BY active_substances.name"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        for row in rows {
+            let drug_id: Uuid = row.try_get(0)?;
+            let active_substance = DrugActiveSubstance {
+                name: row.try_get(1)?,
+                amount: row.try_get(2)?,
+                unit: row.try_get(3)?,
+            };
+            if let Some(drug) = drugs.iter_mut().find(|drug| drug.id == drug_id) {
+                drug.active_substances.push(active_substance);
+            }
+        }
+
+        Ok(drugs)
+    }
+
+    async fn load_variants(&self, mut drugs: Vec<Drug>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drug_ids: Vec<Uuid> = drugs.iter().map(|drug| drug.id).collect();
+        let rows = sqlx::query(
+                r#"SELECT id, drug_id, pills_count, volume_ml, created_at, updated_at, gtin FROM drug_variants WHERE drug_id = ANY($1) ORDER BY created_at, id"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        for row in rows {
+            let variant = self.parse_drug_variants_row(row)?;
+            if let Some(drug) = drugs.iter_mut().find(|drug| drug.id == variant.drug_id) {
+                drug.variants.push(variant);
+            }
+        }
+
+        Ok(drugs)
+    }
+
+    async fn load_reimbursements(&self, mut drugs: Vec<Drug>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drug_ids: Vec<Uuid> = drugs.iter().map(|drug| drug.id).collect();
+        let rows = sqlx::query(
+                r#"SELECT id, drug_id, variant_id, levels, reimbursement_limit, created_at, updated_at FROM drug_reimbursements WHERE drug_id = ANY($1) ORDER BY created_at, id"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        for row in rows {
+            let reimbursement = self.parse_drug_reimbursements_row(row)?;
+            if let Some(drug) = drugs
+                .iter_mut()
+                .find(|drug| drug.id == reimbursement.drug_id)
+            {
+                drug.reimbursements.push(reimbursement);
+            }
+        }
+
+        Ok(drugs)
+    }
+
+    async fn load_prices(&self, mut drugs: Vec<Drug>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drug_ids: Vec<Uuid> = drugs.iter().map(|drug| drug.id).collect();
+        let rows = sqlx::query(
+                r#"SELECT id, price_list_id, drug_id, variant_id, retail_price, official_price, valid_from, valid_to, created_at, updated_at FROM drug_prices WHERE drug_id = ANY($1) ORDER BY valid_from, id"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        for row in rows {
+            let price = self.parse_drug_prices_row(row)?;
+            if let Some(drug) = drugs.iter_mut().find(|drug| drug.id == price.drug_id) {
+                drug.prices.push(price);
+            }
+        }
+
+        Ok(drugs)
+    }
+
+    async fn load_atc_paths(&self, mut drugs: Vec<Drug>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drug_ids: Vec<Uuid> = drugs.iter().map(|drug| drug.id).collect();
+        let rows = sqlx::query(
+                r#"SELECT drugs.id, atc_groups.code, atc_groups.name, atc_groups.parent_code, atc_groups.created_at, atc_groups.updated_at FROM drugs INNER JOIN atc_groups ON drugs.atc_code LIKE atc_groups.code || '%' WHERE drugs.id = ANY($1) ORDER BY LENGTH(atc_groups.code)"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        for row in rows {
+            let drug_id: Uuid = row.try_get(0)?;
+            let atc_group = AtcGroup {
+                code: row.try_get(1)?,
+                name: row.try_get(2)?,
+                parent_code: row.try_get(3)?,
+                created_at: row.try_get(4)?,
+                updated_at: row.try_get(5)?,
+            };
+            if let Some(drug) = drugs.iter_mut().find(|drug| drug.id == drug_id) {
+                drug.atc_path.push(atc_group);
+            }
+        }
+
+        Ok(drugs)
+    }
+
+    // Used by other repositories that need whole drugs, drugs that don't exist are skipped
+    pub async fn get_drugs_by_ids(&self, drug_ids: Vec<Uuid>) -> Result<Vec<Drug>, sqlx::Error> {
+        let drugs_from_db = sqlx::query(
+                r#"SELECT id, name, content_type, mg_per_pill, ml_per_pill, atc_code, category_id, equivalence_group_id, controlled_substance_list, created_at, updated_at, external_id FROM drugs WHERE id = ANY($1) ORDER BY created_at"#
+            )
+            .bind(drug_ids)
+            .fetch_all(&self.pool).await?;
+
+        let mut drugs = vec![];
+        for record in drugs_from_db {
+            drugs.push(self.parse_drugs_row(record)?);
+        }
+        let drugs = self.load_active_substances(drugs).await?;
+        let drugs = self.load_variants(drugs).await?;
+        let drugs = self.load_reimbursements(drugs).await?;
+        let drugs = self.load_prices(drugs).await?;
+
+        self.load_atc_paths(drugs).await
+    }
+
+    fn parse_drug_interactions_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugInteraction, sqlx::Error> {
+        Ok(DrugInteraction {
+            id: row.try_get(0)?,
+            substance_a: row.try_get(1)?,
+            substance_b: row.try_get(2)?,
+            severity: row.try_get(3)?,
+            description: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            updated_at: row.try_get(6)?,
         })
     }
 }
@@ -40,109 +293,1175 @@ impl PostgresDrugsRepository {
 #[async_trait]
 impl DrugsRepository for PostgresDrugsRepository {
     async fn create_drug(&self, drug: NewDrug) -> Result<Drug, CreateDrugRepositoryError> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
+
         let result = sqlx::query(
-                r#"INSERT INTO drugs (id, name, content_type, pills_count, mg_per_pill, ml_per_pill, volume_ml) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id, name, content_type, pills_count, mg_per_pill, ml_per_pill, volume_ml, created_at, updated_at"#
+                r#"INSERT INTO drugs (id, name, content_type, mg_per_pill, ml_per_pill, atc_code, category_id, controlled_substance_list, external_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id, name, content_type, mg_per_pill, ml_per_pill, atc_code, category_id, equivalence_group_id, controlled_substance_list, created_at, updated_at, external_id"#
             )
             .bind(drug.id)
             .bind(drug.name)
             .bind(drug.content_type)
-            .bind(drug.pills_count)
             .bind(drug.mg_per_pill)
             .bind(drug.ml_per_pill)
-            .bind(drug.volume_ml)
-            .fetch_one(&self.pool).await
+            .bind(&drug.atc_code)
+            .bind(drug.category_id)
+            .bind(drug.controlled_substance_list)
+            .bind(&drug.external_id)
+            .fetch_one(&mut *transaction).await
+            .map_err(|err| match err {
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_atc_code_fkey") => {
+                    CreateDrugRepositoryError::AtcGroupNotFound(drug.atc_code.clone().unwrap_or_default())
+                }
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_category_id_fkey") => {
+                    CreateDrugRepositoryError::CategoryNotFound(drug.category_id.unwrap_or_default())
+                }
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_external_id_key") => {
+                    CreateDrugRepositoryError::DuplicatedExternalId(drug.external_id.clone().unwrap_or_default())
+                }
+                err => CreateDrugRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let mut variants = vec![];
+        for variant in drug.variants {
+            let result = sqlx::query(
+                    r#"INSERT INTO drug_variants (id, drug_id, pills_count, volume_ml, gtin) VALUES ($1, $2, $3, $4, $5) RETURNING id, drug_id, pills_count, volume_ml, created_at, updated_at, gtin"#
+                )
+                .bind(variant.id)
+                .bind(variant.drug_id)
+                .bind(variant.pills_count)
+                .bind(variant.volume_ml)
+                .bind(&variant.gtin)
+                .fetch_one(&mut *transaction).await
+                .map_err(|err| match err {
+                    sqlx::Error::Database(err) if err.constraint() == Some("drug_variants_gtin_key") => {
+                        CreateDrugRepositoryError::DuplicatedGtin(variant.gtin.clone().unwrap_or_default())
+                    }
+                    err => CreateDrugRepositoryError::DatabaseError(err.to_string()),
+                })?;
+            variants.push(
+                self.parse_drug_variants_row(result)
+                    .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?,
+            );
+        }
+
+        for active_substance in &drug.active_substances {
+            // The no-op update makes RETURNING work for substances that are already in the
+            // catalogue
+            let active_substance_id: Uuid = sqlx::query(
+                    r#"INSERT INTO active_substances (name) VALUES ($1) ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name RETURNING id"#
+                )
+                .bind(&active_substance.name)
+                .fetch_one(&mut *transaction).await
+                .and_then(|row| row.try_get(0))
+                .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
+
+            sqlx::query(
+                    r#"INSERT INTO drug_active_substances (drug_id, active_substance_id, amount, unit) VALUES ($1, $2, $3, $4)"#
+                )
+                .bind(drug.id)
+                .bind(active_substance_id)
+                .bind(active_substance.amount)
+                .bind(active_substance.unit)
+                .execute(&mut *transaction).await
+                .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
+        }
+
+        transaction
+            .commit()
+            .await
             .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
 
-        Ok(self
+        let mut created_drug = self
             .parse_drugs_row(result)
-            .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?)
+            .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
+        created_drug.active_substances = drug.active_substances;
+        created_drug.variants = variants;
+        let mut created_drugs = self
+            .load_atc_paths(vec![created_drug])
+            .await
+            .map_err(|err| CreateDrugRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(created_drugs.remove(0))
     }
 
     async fn get_drugs(
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        substance: Option<String>,
+        atc_code: Option<String>,
+        category_id: Option<Uuid>,
     ) -> Result<Vec<Drug>, GetDrugsRepositoryError> {
         let (page_size, offset) = get_pagination_params(page, page_size)
             .map_err(|err| GetDrugsRepositoryError::InvalidPaginationParams(err.to_string()))?;
 
         let drugs_from_db = sqlx::query(
-                r#"SELECT id, name, content_type, pills_count, mg_per_pill, ml_per_pill, volume_ml, created_at, updated_at FROM drugs LIMIT $1 OFFSET $2"#
+                r#"
+            WITH RECURSIVE category_tree AS (
+                SELECT id FROM drug_categories WHERE id = $5
+                UNION ALL
+                SELECT drug_categories.id FROM drug_categories
+                INNER JOIN category_tree ON drug_categories.parent_id = category_tree.id
+            )
+            SELECT id, name, content_type, mg_per_pill, ml_per_pill, atc_code, category_id, equivalence_group_id, controlled_substance_list, created_at, updated_at, external_id FROM drugs
+            WHERE ($3::TEXT IS NULL OR EXISTS (
+                SELECT 1 FROM drug_active_substances
+                INNER JOIN active_substances ON drug_active_substances.active_substance_id = active_substances.id
+                WHERE drug_active_substances.drug_id = drugs.id AND active_substances.name = $3
+            ))
+            AND ($4::TEXT IS NULL OR drugs.atc_code LIKE $4 || '%')
+            AND ($5::UUID IS NULL OR drugs.category_id IN (SELECT id FROM category_tree))
+            ORDER BY created_at
+            LIMIT $1 OFFSET $2
+        "#
             )
             .bind(page_size)
             .bind(offset)
+            .bind(substance)
+            .bind(atc_code)
+            .bind(category_id)
             .fetch_all(&self.pool).await
             .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
 
-        let mut drugs = vec![];
-        for record in drugs_from_db {
-            let drug = self
-                .parse_drugs_row(record)
-                .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
-            drugs.push(drug);
-        }
+        let mut drugs = vec![];
+        for record in drugs_from_db {
+            let drug = self
+                .parse_drugs_row(record)
+                .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+            drugs.push(drug);
+        }
+        let drugs = self
+            .load_active_substances(drugs)
+            .await
+            .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_variants(drugs)
+            .await
+            .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_reimbursements(drugs)
+            .await
+            .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_prices(drugs)
+            .await
+            .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_atc_paths(drugs)
+            .await
+            .map_err(|err| GetDrugsRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs)
+    }
+
+    async fn get_drug_by_id(&self, drug_id: Uuid) -> Result<Drug, GetDrugByIdRepositoryError> {
+        let drug_from_db = sqlx::query(
+                r#"SELECT id, name, content_type, mg_per_pill, ml_per_pill, atc_code, category_id, equivalence_group_id, controlled_substance_list, created_at, updated_at, external_id FROM drugs WHERE id = $1"#
+            )
+            .bind(drug_id)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::RowNotFound => GetDrugByIdRepositoryError::NotFound(drug_id),
+                    _ => GetDrugByIdRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        let drug = self
+            .parse_drugs_row(drug_from_db)
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_active_substances(vec![drug])
+            .await
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_variants(drugs)
+            .await
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_reimbursements(drugs)
+            .await
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+        let drugs = self
+            .load_prices(drugs)
+            .await
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+        let mut drugs = self
+            .load_atc_paths(drugs)
+            .await
+            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs.remove(0))
+    }
+
+    async fn get_drug_by_external_id(
+        &self,
+        external_id: &str,
+    ) -> Result<Drug, GetDrugByExternalIdRepositoryError> {
+        let drug_id: Uuid = sqlx::query(r#"SELECT id FROM drugs WHERE external_id = $1"#)
+            .bind(external_id)
+            .fetch_one(&self.pool)
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => {
+                    GetDrugByExternalIdRepositoryError::NotFound(external_id.into())
+                }
+                _ => GetDrugByExternalIdRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let mut drugs = self
+            .get_drugs_by_ids(vec![drug_id])
+            .await
+            .map_err(|err| GetDrugByExternalIdRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs.remove(0))
+    }
+
+    async fn get_drug_by_gtin(&self, gtin: &str) -> Result<Drug, GetDrugByGtinRepositoryError> {
+        let drug_id: Uuid = sqlx::query(r#"SELECT drug_id FROM drug_variants WHERE gtin = $1"#)
+            .bind(gtin)
+            .fetch_one(&self.pool)
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => GetDrugByGtinRepositoryError::NotFound(gtin.into()),
+                _ => GetDrugByGtinRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let mut drugs = self
+            .get_drugs_by_ids(vec![drug_id])
+            .await
+            .map_err(|err| GetDrugByGtinRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs.remove(0))
+    }
+
+    async fn update_drug(&self, drug: Drug) -> Result<Drug, UpdateDrugRepositoryError> {
+        sqlx::query(
+                r#"UPDATE drugs SET name = $2, content_type = $3, mg_per_pill = $4, ml_per_pill = $5, atc_code = $6, category_id = $7, controlled_substance_list = $8, external_id = $9, updated_at = NOW() WHERE id = $1 RETURNING id"#
+            )
+            .bind(drug.id)
+            .bind(drug.name)
+            .bind(drug.content_type)
+            .bind(drug.mg_per_pill)
+            .bind(drug.ml_per_pill)
+            .bind(&drug.atc_code)
+            .bind(drug.category_id)
+            .bind(drug.controlled_substance_list)
+            .bind(&drug.external_id)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => UpdateDrugRepositoryError::NotFound(drug.id),
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_atc_code_fkey") => {
+                    UpdateDrugRepositoryError::AtcGroupNotFound(drug.atc_code.clone().unwrap_or_default())
+                }
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_category_id_fkey") => {
+                    UpdateDrugRepositoryError::CategoryNotFound(drug.category_id.unwrap_or_default())
+                }
+                sqlx::Error::Database(err) if err.constraint() == Some("drugs_external_id_key") => {
+                    UpdateDrugRepositoryError::DuplicatedExternalId(drug.external_id.clone().unwrap_or_default())
+                }
+                err => UpdateDrugRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let mut drugs = self
+            .get_drugs_by_ids(vec![drug.id])
+            .await
+            .map_err(|err| UpdateDrugRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs.remove(0))
+    }
+
+    async fn create_drug_variant(
+        &self,
+        variant: NewDrugVariant,
+    ) -> Result<DrugVariant, CreateDrugVariantRepositoryError> {
+        let result = sqlx::query(
+                r#"INSERT INTO drug_variants (id, drug_id, pills_count, volume_ml, gtin) VALUES ($1, $2, $3, $4, $5) RETURNING id, drug_id, pills_count, volume_ml, created_at, updated_at, gtin"#
+            )
+            .bind(variant.id)
+            .bind(variant.drug_id)
+            .bind(variant.pills_count)
+            .bind(variant.volume_ml)
+            .bind(&variant.gtin)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                    CreateDrugVariantRepositoryError::DrugNotFound(variant.drug_id)
+                }
+                sqlx::Error::Database(err) if err.is_unique_violation() => {
+                    CreateDrugVariantRepositoryError::DuplicatedGtin(
+                        variant.gtin.clone().unwrap_or_default(),
+                    )
+                }
+                err => CreateDrugVariantRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        Ok(self
+            .parse_drug_variants_row(result)
+            .map_err(|err| CreateDrugVariantRepositoryError::DatabaseError(err.to_string()))?)
+    }
+
+    async fn create_drug_reimbursement(
+        &self,
+        reimbursement: NewDrugReimbursement,
+    ) -> Result<DrugReimbursement, CreateDrugReimbursementRepositoryError> {
+        let result = sqlx::query(
+                r#"INSERT INTO drug_reimbursements (id, drug_id, variant_id, levels, reimbursement_limit) VALUES ($1, $2, $3, $4, $5) RETURNING id, drug_id, variant_id, levels, reimbursement_limit, created_at, updated_at"#
+            )
+            .bind(reimbursement.id)
+            .bind(reimbursement.drug_id)
+            .bind(reimbursement.variant_id)
+            .bind(reimbursement.levels)
+            .bind(reimbursement.reimbursement_limit)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                    CreateDrugReimbursementRepositoryError::DrugVariantNotFound(
+                        reimbursement.variant_id,
+                    )
+                }
+                sqlx::Error::Database(err) if err.is_unique_violation() => {
+                    CreateDrugReimbursementRepositoryError::DuplicatedReimbursement(
+                        reimbursement.variant_id,
+                    )
+                }
+                err => CreateDrugReimbursementRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        Ok(self.parse_drug_reimbursements_row(result).map_err(|err| {
+            CreateDrugReimbursementRepositoryError::DatabaseError(err.to_string())
+        })?)
+    }
+
+    async fn create_drug_price_list(
+        &self,
+        price_list: NewDrugPriceList,
+    ) -> Result<DrugPriceList, CreateDrugPriceListRepositoryError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|err| {
+                CreateDrugPriceListRepositoryError::DatabaseError(err.to_string())
+            })?;
+
+        let result = sqlx::query(
+                r#"INSERT INTO drug_price_lists (id, valid_from, valid_to) VALUES ($1, $2, $3) RETURNING created_at, updated_at"#
+            )
+            .bind(price_list.id)
+            .bind(price_list.valid_from)
+            .bind(price_list.valid_to)
+            .fetch_one(&mut *transaction).await
+            .map_err(|err| CreateDrugPriceListRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut prices = vec![];
+        for price in &price_list.prices {
+            sqlx::query(
+                    r#"UPDATE drug_prices SET valid_to = $2, updated_at = CURRENT_TIMESTAMP WHERE variant_id = $1 AND valid_to IS NULL AND valid_from < $2"#
+                )
+                .bind(price.variant_id)
+                .bind(price.valid_from)
+                .execute(&mut *transaction).await
+                .map_err(|err| CreateDrugPriceListRepositoryError::DatabaseError(err.to_string()))?;
+
+            let result = sqlx::query(
+                    r#"INSERT INTO drug_prices (id, price_list_id, drug_id, variant_id, retail_price, official_price, valid_from, valid_to) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id, price_list_id, drug_id, variant_id, retail_price, official_price, valid_from, valid_to, created_at, updated_at"#
+                )
+                .bind(price.id)
+                .bind(price.price_list_id)
+                .bind(price.drug_id)
+                .bind(price.variant_id)
+                .bind(price.retail_price)
+                .bind(price.official_price)
+                .bind(price.valid_from)
+                .bind(price.valid_to)
+                .fetch_one(&mut *transaction).await
+                .map_err(|err| match err {
+                    sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                        CreateDrugPriceListRepositoryError::DrugVariantNotFound(price.variant_id)
+                    }
+                    err => CreateDrugPriceListRepositoryError::DatabaseError(err.to_string()),
+                })?;
+
+            prices.push(self.parse_drug_prices_row(result).map_err(|err| {
+                CreateDrugPriceListRepositoryError::DatabaseError(err.to_string())
+            })?);
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| CreateDrugPriceListRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(DrugPriceList {
+            id: price_list.id,
+            valid_from: price_list.valid_from,
+            valid_to: price_list.valid_to,
+            prices,
+            created_at: result.try_get(0).map_err(|err| {
+                CreateDrugPriceListRepositoryError::DatabaseError(err.to_string())
+            })?,
+            updated_at: result.try_get(1).map_err(|err| {
+                CreateDrugPriceListRepositoryError::DatabaseError(err.to_string())
+            })?,
+        })
+    }
+
+    async fn create_drug_interaction(
+        &self,
+        drug_interaction: NewDrugInteraction,
+    ) -> Result<DrugInteraction, CreateDrugInteractionRepositoryError> {
+        // Substances missing from the catalogue are added to it, the same way as in create_drug
+        let result = sqlx::query(
+                r#"WITH substances AS (
+                    INSERT INTO active_substances (name) VALUES ($2), ($3) ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name RETURNING id, name
+                ), inserted AS (
+                    INSERT INTO drug_interactions (id, substance_a_id, substance_b_id, severity, description)
+                    SELECT $1, substance_a.id, substance_b.id, $4, $5 FROM substances AS substance_a, substances AS substance_b WHERE substance_a.name = $2 AND substance_b.name = $3
+                    RETURNING id, substance_a_id, substance_b_id, severity, description, created_at, updated_at
+                )
+                SELECT inserted.id, substance_a.name, substance_b.name, inserted.severity, inserted.description, inserted.created_at, inserted.updated_at
+                FROM inserted
+                INNER JOIN substances AS substance_a ON inserted.substance_a_id = substance_a.id
+                INNER JOIN substances AS substance_b ON inserted.substance_b_id = substance_b.id"#
+            )
+            .bind(drug_interaction.id)
+            .bind(drug_interaction.substance_a)
+            .bind(drug_interaction.substance_b)
+            .bind(drug_interaction.severity)
+            .bind(drug_interaction.description)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::Database(err) if err.is_unique_violation() => {
+                        CreateDrugInteractionRepositoryError::DuplicatedInteraction
+                    }
+                    _ => CreateDrugInteractionRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        Ok(self
+            .parse_drug_interactions_row(result)
+            .map_err(|err| CreateDrugInteractionRepositoryError::DatabaseError(err.to_string()))?)
+    }
+
+    async fn get_drug_interactions(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugInteraction>, GetDrugInteractionsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugInteractionsRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let interactions_from_db = sqlx::query(
+                r#"SELECT drug_interactions.id, substance_a.name, substance_b.name, drug_interactions.severity, drug_interactions.description, drug_interactions.created_at, drug_interactions.updated_at FROM drug_interactions INNER JOIN active_substances AS substance_a ON drug_interactions.substance_a_id = substance_a.id INNER JOIN active_substances AS substance_b ON drug_interactions.substance_b_id = substance_b.id ORDER BY drug_interactions.created_at LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetDrugInteractionsRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut interactions = vec![];
+        for record in interactions_from_db {
+            let interaction = self.parse_drug_interactions_row(record).map_err(|err| {
+                GetDrugInteractionsRepositoryError::DatabaseError(err.to_string())
+            })?;
+            interactions.push(interaction);
+        }
+
+        Ok(interactions)
+    }
+
+    async fn get_active_substances(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ActiveSubstance>, GetActiveSubstancesRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetActiveSubstancesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let active_substances_from_db = sqlx::query(
+                r#"SELECT id, name, created_at, updated_at FROM active_substances ORDER BY name LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetActiveSubstancesRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut active_substances = vec![];
+        for record in active_substances_from_db {
+            let active_substance = self.parse_active_substances_row(record).map_err(|err| {
+                GetActiveSubstancesRepositoryError::DatabaseError(err.to_string())
+            })?;
+            active_substances.push(active_substance);
+        }
+
+        Ok(active_substances)
+    }
+
+    async fn create_atc_group(
+        &self,
+        atc_group: NewAtcGroup,
+    ) -> Result<AtcGroup, CreateAtcGroupRepositoryError> {
+        let result = sqlx::query(
+                r#"INSERT INTO atc_groups (code, name, parent_code) VALUES ($1, $2, $3) RETURNING code, name, parent_code, created_at, updated_at"#
+            )
+            .bind(&atc_group.code)
+            .bind(atc_group.name)
+            .bind(&atc_group.parent_code)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::Database(err) if err.constraint() == Some("atc_groups_pkey") => {
+                    CreateAtcGroupRepositoryError::DuplicatedCode
+                }
+                sqlx::Error::Database(err)
+                    if err.constraint() == Some("atc_groups_parent_code_fkey") =>
+                {
+                    CreateAtcGroupRepositoryError::ParentNotFound(
+                        atc_group.parent_code.clone().unwrap_or_default(),
+                    )
+                }
+                err => CreateAtcGroupRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        Ok(self
+            .parse_atc_groups_row(result)
+            .map_err(|err| CreateAtcGroupRepositoryError::DatabaseError(err.to_string()))?)
+    }
+
+    async fn get_atc_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<AtcGroup>, GetAtcGroupsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size)
+            .map_err(|err| GetAtcGroupsRepositoryError::InvalidPaginationParams(err.to_string()))?;
+
+        let atc_groups_from_db = sqlx::query(
+                r#"SELECT code, name, parent_code, created_at, updated_at FROM atc_groups ORDER BY code LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetAtcGroupsRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut atc_groups = vec![];
+        for record in atc_groups_from_db {
+            let atc_group = self
+                .parse_atc_groups_row(record)
+                .map_err(|err| GetAtcGroupsRepositoryError::DatabaseError(err.to_string()))?;
+            atc_groups.push(atc_group);
+        }
+
+        Ok(atc_groups)
+    }
+
+    async fn create_drug_category(
+        &self,
+        drug_category: NewDrugCategory,
+    ) -> Result<DrugCategory, CreateDrugCategoryRepositoryError> {
+        let result = sqlx::query(
+                r#"INSERT INTO drug_categories (id, name, parent_id) VALUES ($1, $2, $3) RETURNING id, name, parent_id, created_at, updated_at"#
+            )
+            .bind(drug_category.id)
+            .bind(drug_category.name)
+            .bind(drug_category.parent_id)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::Database(err)
+                    if err.constraint() == Some("drug_categories_parent_id_fkey") =>
+                {
+                    CreateDrugCategoryRepositoryError::ParentNotFound(
+                        drug_category.parent_id.unwrap_or_default(),
+                    )
+                }
+                err => CreateDrugCategoryRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        Ok(self
+            .parse_drug_categories_row(result)
+            .map_err(|err| CreateDrugCategoryRepositoryError::DatabaseError(err.to_string()))?)
+    }
+
+    async fn get_drug_categories(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugCategory>, GetDrugCategoriesRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugCategoriesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let drug_categories_from_db = sqlx::query(
+                r#"SELECT id, name, parent_id, created_at, updated_at FROM drug_categories ORDER BY created_at LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetDrugCategoriesRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut drug_categories = vec![];
+        for record in drug_categories_from_db {
+            let drug_category = self
+                .parse_drug_categories_row(record)
+                .map_err(|err| GetDrugCategoriesRepositoryError::DatabaseError(err.to_string()))?;
+            drug_categories.push(drug_category);
+        }
+
+        Ok(drug_categories)
+    }
+
+    async fn create_drug_equivalence_group(
+        &self,
+        drug_equivalence_group: NewDrugEquivalenceGroup,
+    ) -> Result<DrugEquivalenceGroup, CreateDrugEquivalenceGroupRepositoryError> {
+        let mut transaction = self.pool.begin().await.map_err(|err| {
+            CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+        })?;
+
+        let result = sqlx::query(
+                r#"INSERT INTO drug_equivalence_groups (id, name) VALUES ($1, $2) RETURNING id, name, created_at, updated_at"#
+            )
+            .bind(drug_equivalence_group.id)
+            .bind(&drug_equivalence_group.name)
+            .fetch_one(&mut *transaction).await
+            .map_err(|err| CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string()))?;
+
+        let assigned_drug_ids: Vec<Uuid> = sqlx::query(
+                r#"UPDATE drugs SET equivalence_group_id = $1 WHERE id = ANY($2) AND equivalence_group_id IS NULL RETURNING id"#
+            )
+            .bind(drug_equivalence_group.id)
+            .bind(&drug_equivalence_group.drug_ids)
+            .fetch_all(&mut *transaction).await
+            .and_then(|rows| rows.iter().map(|row| row.try_get(0)).collect())
+            .map_err(|err| CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string()))?;
+
+        // The transaction is rolled back on drop, so the group isn't saved if any drug is missing
+        // or already belongs to another group
+        if let Some(&drug_id) = drug_equivalence_group
+            .drug_ids
+            .iter()
+            .find(|drug_id| !assigned_drug_ids.contains(drug_id))
+        {
+            let drug_exists = sqlx::query(r#"SELECT id FROM drugs WHERE id = $1"#)
+                .bind(drug_id)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+                })?
+                .is_some();
+            if drug_exists {
+                return Err(CreateDrugEquivalenceGroupRepositoryError::DrugAlreadyInGroup(drug_id));
+            }
+            return Err(CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(
+                drug_id,
+            ));
+        }
+
+        transaction.commit().await.map_err(|err| {
+            CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+        })?;
+
+        Ok(DrugEquivalenceGroup {
+            id: result.try_get(0).map_err(|err| {
+                CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+            })?,
+            name: result.try_get(1).map_err(|err| {
+                CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+            })?,
+            drug_ids: drug_equivalence_group.drug_ids,
+            created_at: result.try_get(2).map_err(|err| {
+                CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+            })?,
+            updated_at: result.try_get(3).map_err(|err| {
+                CreateDrugEquivalenceGroupRepositoryError::DatabaseError(err.to_string())
+            })?,
+        })
+    }
+
+    async fn get_drug_equivalence_groups(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<DrugEquivalenceGroup>, GetDrugEquivalenceGroupsRepositoryError> {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetDrugEquivalenceGroupsRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let drug_equivalence_groups_from_db = sqlx::query(
+                r#"SELECT id, name, ARRAY(SELECT drugs.id FROM drugs WHERE drugs.equivalence_group_id = drug_equivalence_groups.id ORDER BY drugs.created_at), created_at, updated_at FROM drug_equivalence_groups ORDER BY created_at LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetDrugEquivalenceGroupsRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut drug_equivalence_groups = vec![];
+        for record in drug_equivalence_groups_from_db {
+            let drug_equivalence_group =
+                self.parse_drug_equivalence_groups_row(record)
+                    .map_err(|err| {
+                        GetDrugEquivalenceGroupsRepositoryError::DatabaseError(err.to_string())
+                    })?;
+            drug_equivalence_groups.push(drug_equivalence_group);
+        }
+
+        Ok(drug_equivalence_groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, SubsecRound, Utc};
+    use uuid::Uuid;
+
+    use super::{DrugsRepository, PostgresDrugsRepository};
+    use crate::domain::drugs::{
+        entities::{
+            DrugActiveSubstance, DrugContentType, DrugInteractionSeverity, DrugPriceListItem,
+            NewAtcGroup, NewDrug, NewDrugCategory, NewDrugEquivalenceGroup, NewDrugInteraction,
+            NewDrugPriceList, ReimbursementLevel, StrengthUnit,
+        },
+        repository::{
+            CreateAtcGroupRepositoryError, CreateDrugCategoryRepositoryError,
+            CreateDrugEquivalenceGroupRepositoryError, CreateDrugInteractionRepositoryError,
+            CreateDrugPriceListRepositoryError, CreateDrugReimbursementRepositoryError,
+            CreateDrugRepositoryError, CreateDrugVariantRepositoryError,
+            GetDrugByExternalIdRepositoryError, GetDrugByGtinRepositoryError,
+            GetDrugByIdRepositoryError, GetDrugsRepositoryError, UpdateDrugRepositoryError,
+        },
+    };
+
+    async fn setup_repository(pool: sqlx::PgPool) -> PostgresDrugsRepository {
+        PostgresDrugsRepository::new(pool)
+    }
+
+    #[sqlx::test]
+    async fn create_and_read_drug_by_id(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Gripex Max".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(300),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            DrugActiveSubstance {
+                name: "paracetamol".into(),
+                amount: Some(325.0),
+                unit: Some(StrengthUnit::Mg),
+            },
+            DrugActiveSubstance {
+                name: "pseudoephedrine".into(),
+                amount: None,
+                unit: None,
+            },
+        ])
+        .unwrap();
+
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+
+        assert_eq!(drug, created_drug);
+
+        let drug_from_repo = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug, drug_from_repo);
+    }
+
+    #[sqlx::test]
+    async fn creates_drug_variant_and_reads_it_with_drug(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let new_variant = created_drug.create_variant(Some(20), None, None).unwrap();
+
+        let created_variant = repository
+            .create_drug_variant(new_variant.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_variant, new_variant);
+
+        let drug_from_db = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug_from_db.variants.len(), 2);
+        assert_eq!(drug_from_db.variants[0], drug.variants[0]);
+        assert_eq!(drug_from_db.variants[1], new_variant);
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_variant_of_nonexistent_drug(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let mut new_variant = drug.variants[0].clone();
+        new_variant.pills_count = Some(20);
+
+        let result = repository.create_drug_variant(new_variant).await;
+
+        assert_eq!(
+            result,
+            Err(CreateDrugVariantRepositoryError::DrugNotFound(drug.id))
+        );
+    }
+
+    #[sqlx::test]
+    async fn reads_drug_by_gtin_and_doesnt_create_duplicated_gtin(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_gtin(Some("5909990733828".into()))
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let new_variant = created_drug
+            .create_variant(Some(20), None, Some("5909990733835".into()))
+            .unwrap();
+        repository
+            .create_drug_variant(new_variant.clone())
+            .await
+            .unwrap();
+
+        let drug_from_db = repository.get_drug_by_gtin("5909990733835").await.unwrap();
+
+        assert_eq!(drug_from_db.id, drug.id);
+        assert_eq!(drug_from_db.variants[0], drug.variants[0]);
+        assert_eq!(drug_from_db.variants[1], new_variant);
+        assert_eq!(
+            repository.get_drug_by_gtin("5909990733842").await,
+            Err(GetDrugByGtinRepositoryError::NotFound(
+                "5909990733842".into()
+            ))
+        );
+
+        let mut duplicated_variant = created_drug
+            .create_variant(Some(30), None, Some("5909990733835".into()))
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .create_drug_variant(duplicated_variant.clone())
+                .await,
+            Err(CreateDrugVariantRepositoryError::DuplicatedGtin(
+                "5909990733835".into()
+            ))
+        );
+
+        duplicated_variant.gtin = None;
+        let other_drug = NewDrug::new(
+            "Panadol".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_gtin(Some("5909990733828".into()))
+        .unwrap();
+
+        assert_eq!(
+            repository.create_drug(other_drug).await,
+            Err(CreateDrugRepositoryError::DuplicatedGtin(
+                "5909990733828".into()
+            ))
+        );
+        assert!(repository
+            .create_drug_variant(duplicated_variant)
+            .await
+            .is_ok());
+    }
+
+    #[sqlx::test]
+    async fn creates_drug_reimbursement_and_reads_it_with_drug(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let new_reimbursement = created_drug
+            .create_reimbursement(
+                drug.variants[0].id,
+                vec![
+                    ReimbursementLevel::FiftyPercent,
+                    ReimbursementLevel::FlatFee,
+                ],
+                1999,
+            )
+            .unwrap();
+
+        let created_reimbursement = repository
+            .create_drug_reimbursement(new_reimbursement.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_reimbursement, new_reimbursement);
+
+        let drug_from_db = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug_from_db.reimbursements.len(), 1);
+        assert_eq!(drug_from_db.reimbursements[0], new_reimbursement);
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_duplicated_reimbursement_or_reimbursement_of_nonexistent_variant(
+        pool: sqlx::PgPool,
+    ) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let new_reimbursement = created_drug
+            .create_reimbursement(drug.variants[0].id, vec![ReimbursementLevel::Full], 1999)
+            .unwrap();
+        repository
+            .create_drug_reimbursement(new_reimbursement.clone())
+            .await
+            .unwrap();
+
+        let mut duplicated_reimbursement = new_reimbursement.clone();
+        duplicated_reimbursement.id = Uuid::new_v4();
+        let result = repository
+            .create_drug_reimbursement(duplicated_reimbursement)
+            .await;
+
+        assert_eq!(
+            result,
+            Err(
+                CreateDrugReimbursementRepositoryError::DuplicatedReimbursement(
+                    drug.variants[0].id
+                )
+            )
+        );
+
+        let mut other_variant_reimbursement = new_reimbursement;
+        other_variant_reimbursement.id = Uuid::new_v4();
+        other_variant_reimbursement.variant_id = Uuid::new_v4();
+        let result = repository
+            .create_drug_reimbursement(other_variant_reimbursement.clone())
+            .await;
 
-        Ok(drugs)
+        assert_eq!(
+            result,
+            Err(CreateDrugReimbursementRepositoryError::DrugVariantNotFound(
+                other_variant_reimbursement.variant_id
+            ))
+        );
     }
 
-    async fn get_drug_by_id(&self, drug_id: Uuid) -> Result<Drug, GetDrugByIdRepositoryError> {
-        let drug_from_db = sqlx::query(
-                r#"SELECT id, name, content_type, pills_count, mg_per_pill, ml_per_pill, volume_ml, created_at, updated_at FROM drugs WHERE id = $1"#
+    #[sqlx::test]
+    async fn updates_drug_and_reads_it_by_external_id(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let create_new_drug = |name: &str, external_id: &str| {
+            let new_drug = NewDrug::new(
+                name.into(),
+                DrugContentType::SolidPills,
+                Some(10),
+                Some(500),
+                None,
+                None,
             )
-            .bind(drug_id)
-            .fetch_one(&self.pool).await
-            .map_err(|err| {
-                match err {
-                    sqlx::Error::RowNotFound => GetDrugByIdRepositoryError::NotFound(drug_id),
-                    _ => GetDrugByIdRepositoryError::DatabaseError(err.to_string()),
-                }
-            })?;
+            .unwrap();
+            NewDrug {
+                external_id: Some(external_id.into()),
+                ..new_drug
+            }
+        };
+        let apap = create_new_drug("Apap", "100001");
+        repository.create_drug(apap.clone()).await.unwrap();
+        repository
+            .create_drug(create_new_drug("Ibuprom", "100002"))
+            .await
+            .unwrap();
 
-        Ok(self
-            .parse_drugs_row(drug_from_db)
-            .map_err(|err| GetDrugByIdRepositoryError::DatabaseError(err.to_string()))?)
-    }
-}
+        let result = repository
+            .create_drug(create_new_drug("Apap Extra", "100001"))
+            .await;
 
-#[cfg(test)]
-mod tests {
-    use uuid::Uuid;
+        assert_eq!(
+            result,
+            Err(CreateDrugRepositoryError::DuplicatedExternalId(
+                "100001".into()
+            ))
+        );
 
-    use super::{DrugsRepository, PostgresDrugsRepository};
-    use crate::{
-        domain::drugs::{
-            entities::{DrugContentType, NewDrug},
-            repository::{GetDrugByIdRepositoryError, GetDrugsRepositoryError},
-        },
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
-    };
+        let mut drug = repository.get_drug_by_external_id("100001").await.unwrap();
 
-    async fn setup_repository(pool: sqlx::PgPool) -> PostgresDrugsRepository {
-        create_tables(&pool, true).await.unwrap();
-        PostgresDrugsRepository::new(pool)
+        assert_eq!(drug, apap);
+
+        drug.name = "Apap Extra".into();
+        drug.mg_per_pill = Some(1000);
+        let updated_drug = repository.update_drug(drug.clone()).await.unwrap();
+
+        assert_eq!(updated_drug.name, "Apap Extra");
+        assert_eq!(updated_drug.mg_per_pill, Some(1000));
+        assert_eq!(updated_drug.variants, drug.variants);
+        assert!(updated_drug.updated_at > drug.updated_at);
+
+        drug.external_id = Some("100002".into());
+        let result = repository.update_drug(drug).await;
+
+        assert_eq!(
+            result,
+            Err(UpdateDrugRepositoryError::DuplicatedExternalId(
+                "100002".into()
+            ))
+        );
+        assert_eq!(
+            repository.get_drug_by_external_id("100003").await,
+            Err(GetDrugByExternalIdRepositoryError::NotFound(
+                "100003".into()
+            ))
+        );
     }
 
     #[sqlx::test]
-    async fn create_and_read_drug_by_id(pool: sqlx::PgPool) {
+    async fn creates_drug_price_lists_and_reads_prices_with_drug(pool: sqlx::PgPool) {
         let repository = setup_repository(pool).await;
 
         let drug = NewDrug::new(
-            "Gripex Max".into(),
+            "Apap".into(),
             DrugContentType::SolidPills,
-            Some(20),
-            Some(300),
+            Some(10),
+            Some(500),
             None,
             None,
         )
         .unwrap();
-
         let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let now = Utc::now().trunc_subsecs(0);
+        let item = DrugPriceListItem {
+            drug_id: drug.id,
+            variant_id: drug.variants[0].id,
+            retail_price: 1599,
+            official_price: 1320,
+        };
+        let first_price_list = NewDrugPriceList::new(
+            Some(now - Duration::days(30)),
+            None,
+            vec![item.clone()],
+            &[created_drug],
+        )
+        .unwrap();
 
-        assert_eq!(drug, created_drug);
+        let created_price_list = repository
+            .create_drug_price_list(first_price_list.clone())
+            .await
+            .unwrap();
 
-        let drug_from_repo = repository.get_drug_by_id(drug.id).await.unwrap();
+        assert_eq!(created_price_list, first_price_list);
 
-        assert_eq!(drug, drug_from_repo);
+        let drug_from_db = repository.get_drug_by_id(drug.id).await.unwrap();
+        let second_price_list = NewDrugPriceList::new(
+            Some(now),
+            None,
+            vec![DrugPriceListItem {
+                retail_price: 1699,
+                ..item
+            }],
+            &[drug_from_db],
+        )
+        .unwrap();
+        repository
+            .create_drug_price_list(second_price_list.clone())
+            .await
+            .unwrap();
+
+        let drug_from_db = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug_from_db.prices.len(), 2);
+        assert_eq!(drug_from_db.prices[0].retail_price, 1599);
+        assert_eq!(drug_from_db.prices[0].valid_to, Some(now));
+        assert_eq!(drug_from_db.prices[1], second_price_list.prices[0]);
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_price_list_of_nonexistent_variant(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap();
+        let mut created_drug = repository.create_drug(drug.clone()).await.unwrap();
+        let variant_id = Uuid::new_v4();
+        created_drug.variants[0].id = variant_id;
+        let price_list = NewDrugPriceList::new(
+            None,
+            None,
+            vec![DrugPriceListItem {
+                drug_id: drug.id,
+                variant_id,
+                retail_price: 1599,
+                official_price: 1320,
+            }],
+            &[created_drug],
+        )
+        .unwrap();
+
+        let result = repository.create_drug_price_list(price_list).await;
+
+        assert_eq!(
+            result,
+            Err(CreateDrugPriceListRepositoryError::DrugVariantNotFound(
+                variant_id
+            ))
+        );
     }
 
     #[sqlx::test]
@@ -204,7 +1523,10 @@ mod tests {
         repository.create_drug(new_drug_2.clone()).await.unwrap();
         repository.create_drug(new_drug_3.clone()).await.unwrap();
 
-        let drugs = repository.get_drugs(None, Some(10)).await.unwrap();
+        let drugs = repository
+            .get_drugs(None, Some(10), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 4);
         assert_eq!(drugs[0], new_drug_0);
@@ -212,18 +1534,27 @@ mod tests {
         assert_eq!(drugs[2], new_drug_2);
         assert_eq!(drugs[3], new_drug_3);
 
-        let drugs = repository.get_drugs(None, Some(2)).await.unwrap();
+        let drugs = repository
+            .get_drugs(None, Some(2), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 2);
         assert_eq!(drugs[0], new_drug_0);
         assert_eq!(drugs[1], new_drug_1);
 
-        let drugs = repository.get_drugs(Some(1), Some(3)).await.unwrap();
+        let drugs = repository
+            .get_drugs(Some(1), Some(3), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 1);
         assert_eq!(drugs[0], new_drug_3);
 
-        let drugs = repository.get_drugs(Some(2), Some(3)).await.unwrap();
+        let drugs = repository
+            .get_drugs(Some(2), Some(3), None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(drugs.len(), 0);
     }
@@ -232,14 +1563,457 @@ mod tests {
     async fn get_drugs_returns_error_if_pagination_params_are_incorrect(pool: sqlx::PgPool) {
         let repository = setup_repository(pool).await;
 
-        assert!(match repository.get_drugs(Some(-1), Some(10)).await {
+        assert!(match repository
+            .get_drugs(Some(-1), Some(10), None, None, None)
+            .await
+        {
             Err(GetDrugsRepositoryError::InvalidPaginationParams(_)) => true,
             _ => false,
         },);
 
-        assert!(match repository.get_drugs(Some(0), Some(0)).await {
+        assert!(match repository
+            .get_drugs(Some(0), Some(0), None, None, None)
+            .await
+        {
             Err(GetDrugsRepositoryError::InvalidPaginationParams(_)) => true,
             _ => false,
         },);
     }
+
+    #[sqlx::test]
+    async fn creates_and_reads_drug_interactions(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let new_interaction_0 = NewDrugInteraction::new(
+            "warfarin".into(),
+            "ibuprofen".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        )
+        .unwrap();
+        let new_interaction_1 = NewDrugInteraction::new(
+            "sildenafil".into(),
+            "nitroglycerin".into(),
+            DrugInteractionSeverity::Contraindicated,
+            "Severe hypotension".into(),
+        )
+        .unwrap();
+
+        let created_interaction = repository
+            .create_drug_interaction(new_interaction_0.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_interaction, new_interaction_0);
+
+        repository
+            .create_drug_interaction(new_interaction_1.clone())
+            .await
+            .unwrap();
+
+        let interactions = repository.get_drug_interactions(None, None).await.unwrap();
+
+        assert_eq!(interactions.len(), 2);
+        assert_eq!(interactions[0], new_interaction_0);
+        assert_eq!(interactions[1], new_interaction_1);
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_duplicated_drug_interaction(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "warfarin".into(),
+                    "ibuprofen".into(),
+                    DrugInteractionSeverity::Major,
+                    "Increased risk of bleeding".into(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let result = repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "ibuprofen".into(),
+                    "warfarin".into(),
+                    DrugInteractionSeverity::Moderate,
+                    "Increased risk of bleeding".into(),
+                )
+                .unwrap(),
+            )
+            .await;
+
+        assert_eq!(
+            result,
+            Err(CreateDrugInteractionRepositoryError::DuplicatedInteraction)
+        );
+    }
+
+    #[sqlx::test]
+    async fn adds_drug_interaction_substances_to_catalogue(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![DrugActiveSubstance {
+            name: "paracetamol".into(),
+            amount: Some(500.0),
+            unit: Some(StrengthUnit::Mg),
+        }])
+        .unwrap();
+        repository.create_drug(apap).await.unwrap();
+
+        repository
+            .create_drug_interaction(
+                NewDrugInteraction::new(
+                    "warfarin".into(),
+                    "paracetamol".into(),
+                    DrugInteractionSeverity::Moderate,
+                    "Increased anticoagulant effect".into(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let active_substances = repository.get_active_substances(None, None).await.unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+        assert_eq!(active_substances[0].name, "paracetamol");
+        assert_eq!(active_substances[1].name, "warfarin");
+    }
+
+    #[sqlx::test]
+    async fn filters_drugs_by_active_substance_and_reads_catalogue(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![DrugActiveSubstance {
+            name: "paracetamol".into(),
+            amount: Some(500.0),
+            unit: Some(StrengthUnit::Mg),
+        }])
+        .unwrap();
+        let gripex = NewDrug::new(
+            "Gripex".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(355),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_active_substances(vec![
+            DrugActiveSubstance {
+                name: "paracetamol".into(),
+                amount: Some(325.0),
+                unit: Some(StrengthUnit::Mg),
+            },
+            DrugActiveSubstance {
+                name: "pseudoephedrine".into(),
+                amount: Some(30.0),
+                unit: Some(StrengthUnit::Mg),
+            },
+        ])
+        .unwrap();
+        repository.create_drug(apap.clone()).await.unwrap();
+        repository.create_drug(gripex.clone()).await.unwrap();
+
+        let drugs = repository
+            .get_drugs(None, None, Some("paracetamol".into()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 2);
+        assert_eq!(drugs[0], apap);
+        assert_eq!(drugs[1], gripex);
+
+        let drugs = repository
+            .get_drugs(None, None, Some("pseudoephedrine".into()), None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], gripex);
+
+        let active_substances = repository.get_active_substances(None, None).await.unwrap();
+
+        assert_eq!(active_substances.len(), 2);
+        assert_eq!(active_substances[0].name, "paracetamol");
+        assert_eq!(active_substances[1].name, "pseudoephedrine");
+    }
+
+    #[sqlx::test]
+    async fn creates_atc_groups_and_reads_them_in_drug_atc_path(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let atc_groups: Vec<NewAtcGroup> = [
+            ("N", "Nervous system"),
+            ("N02", "Analgesics"),
+            ("N02B", "Other analgesics and antipyretics"),
+            ("N02BE", "Anilides"),
+            ("N02BE01", "Paracetamol"),
+        ]
+        .into_iter()
+        .map(|(code, name)| NewAtcGroup::new(code.into(), name.into()).unwrap())
+        .collect();
+        for atc_group in &atc_groups {
+            repository
+                .create_atc_group(atc_group.clone())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            repository.create_atc_group(atc_groups[1].clone()).await,
+            Err(CreateAtcGroupRepositoryError::DuplicatedCode)
+        );
+        assert_eq!(
+            repository
+                .create_atc_group(
+                    NewAtcGroup::new("M01".into(), "Anti-inflammatory".into()).unwrap()
+                )
+                .await,
+            Err(CreateAtcGroupRepositoryError::ParentNotFound("M".into()))
+        );
+
+        let atc_groups_from_db = repository.get_atc_groups(None, None).await.unwrap();
+
+        assert_eq!(atc_groups_from_db, atc_groups);
+
+        let drug = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N02BE01".into()), None)
+        .unwrap();
+        let created_drug = repository.create_drug(drug.clone()).await.unwrap();
+
+        assert_eq!(created_drug, drug);
+        assert_eq!(created_drug.atc_path, atc_groups);
+
+        let drug_from_db = repository.get_drug_by_id(drug.id).await.unwrap();
+
+        assert_eq!(drug_from_db.atc_path, atc_groups);
+
+        let drug_with_unknown_atc_code = NewDrug::new(
+            "Ibuprom".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(200),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("M01AE01".into()), None)
+        .unwrap();
+
+        assert_eq!(
+            repository.create_drug(drug_with_unknown_atc_code).await,
+            Err(CreateDrugRepositoryError::AtcGroupNotFound(
+                "M01AE01".into()
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn filters_drugs_by_atc_code_prefix_and_category_tree(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        for (code, name) in [
+            ("N", "Nervous system"),
+            ("N02", "Analgesics"),
+            ("N02B", "Other analgesics and antipyretics"),
+            ("N02BE", "Anilides"),
+            ("N02BE01", "Paracetamol"),
+            ("N05", "Psycholeptics"),
+            ("N05C", "Hypnotics and sedatives"),
+            ("N05CF", "Benzodiazepine related drugs"),
+            ("N05CF02", "Zolpidem"),
+        ] {
+            repository
+                .create_atc_group(NewAtcGroup::new(code.into(), name.into()).unwrap())
+                .await
+                .unwrap();
+        }
+        let painkillers = NewDrugCategory::new("Painkillers".into(), None).unwrap();
+        let headache = NewDrugCategory::new("Headache".into(), Some(painkillers.id)).unwrap();
+        let sleep_aids = NewDrugCategory::new("Sleep aids".into(), None).unwrap();
+        for category in [&painkillers, &headache, &sleep_aids] {
+            repository
+                .create_drug_category(category.clone())
+                .await
+                .unwrap();
+        }
+
+        let nonexistent_category_id = Uuid::new_v4();
+        let migraine =
+            NewDrugCategory::new("Migraine".into(), Some(nonexistent_category_id)).unwrap();
+
+        assert_eq!(
+            repository.create_drug_category(migraine).await,
+            Err(CreateDrugCategoryRepositoryError::ParentNotFound(
+                nonexistent_category_id
+            ))
+        );
+
+        let drug_categories = repository.get_drug_categories(None, None).await.unwrap();
+
+        assert_eq!(drug_categories.len(), 3);
+        assert_eq!(drug_categories[1], headache);
+
+        let apap = NewDrug::new(
+            "Apap".into(),
+            DrugContentType::SolidPills,
+            Some(10),
+            Some(500),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N02BE01".into()), Some(headache.id))
+        .unwrap();
+        let stilnox = NewDrug::new(
+            "Stilnox".into(),
+            DrugContentType::SolidPills,
+            Some(20),
+            Some(10),
+            None,
+            None,
+        )
+        .unwrap()
+        .with_classification(Some("N05CF02".into()), Some(sleep_aids.id))
+        .unwrap();
+        repository.create_drug(apap.clone()).await.unwrap();
+        repository.create_drug(stilnox.clone()).await.unwrap();
+
+        let drugs = repository
+            .get_drugs(None, None, None, Some("N".into()), None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 2);
+
+        let drugs = repository
+            .get_drugs(None, None, None, Some("N05C".into()), None)
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], stilnox);
+
+        let drugs = repository
+            .get_drugs(None, None, None, None, Some(painkillers.id))
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 1);
+        assert_eq!(drugs[0], apap);
+
+        let drugs = repository
+            .get_drugs(None, None, None, Some("N05".into()), Some(painkillers.id))
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 0);
+    }
+
+    #[sqlx::test]
+    async fn creates_drug_equivalence_group_and_reads_it_with_drugs(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let mut drugs = vec![];
+        for name in ["Apap", "Panadol", "Codipar"] {
+            let drug = repository
+                .create_drug(
+                    NewDrug::new(
+                        name.into(),
+                        DrugContentType::SolidPills,
+                        Some(10),
+                        Some(500),
+                        None,
+                        None,
+                    )
+                    .unwrap()
+                    .with_active_substances(vec![DrugActiveSubstance {
+                        name: "paracetamol".into(),
+                        amount: Some(500.0),
+                        unit: Some(StrengthUnit::Mg),
+                    }])
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+            drugs.push(drug);
+        }
+
+        let group = NewDrugEquivalenceGroup::new("Paracetamol 500 mg tablets".into(), &drugs[0..2])
+            .unwrap();
+        let created_group = repository
+            .create_drug_equivalence_group(group.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_group, group);
+
+        let drug_from_db = repository.get_drug_by_id(drugs[1].id).await.unwrap();
+
+        assert_eq!(drug_from_db.equivalence_group_id, Some(group.id));
+
+        let mut other_group =
+            NewDrugEquivalenceGroup::new("Paracetamol 500 mg".into(), &drugs[1..3]).unwrap();
+
+        assert_eq!(
+            repository
+                .create_drug_equivalence_group(other_group.clone())
+                .await,
+            Err(CreateDrugEquivalenceGroupRepositoryError::DrugAlreadyInGroup(drugs[1].id))
+        );
+
+        let nonexistent_drug_id = Uuid::new_v4();
+        other_group.drug_ids = vec![drugs[2].id, nonexistent_drug_id];
+
+        assert_eq!(
+            repository.create_drug_equivalence_group(other_group).await,
+            Err(CreateDrugEquivalenceGroupRepositoryError::DrugNotFound(
+                nonexistent_drug_id
+            ))
+        );
+
+        let drug_from_db = repository.get_drug_by_id(drugs[2].id).await.unwrap();
+
+        assert_eq!(drug_from_db.equivalence_group_id, None);
+
+        let groups = repository
+            .get_drug_equivalence_groups(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0], group);
+    }
 }
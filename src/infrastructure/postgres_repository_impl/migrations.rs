@@ -0,0 +1,104 @@
+use sqlx::migrate::{Migrate, MigrateError, MigrationType, Migrator};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+pub async fn run_migrations(pool: &sqlx::PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+// Reverts every applied migration newer than target_version, target_version = 0 reverts all of them
+pub async fn revert_migrations(
+    pool: &sqlx::PgPool,
+    target_version: i64,
+) -> Result<(), MigrateError> {
+    MIGRATOR.undo(pool, target_version).await
+}
+
+pub async fn get_migrations_status(
+    pool: &sqlx::PgPool,
+) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut connection = pool.acquire().await?;
+    connection.ensure_migrations_table().await?;
+    let applied_versions: Vec<i64> = connection
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect();
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type != MigrationType::ReversibleDown)
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied_versions.contains(&migration.version),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_migrations_status, revert_migrations, run_migrations};
+
+    #[sqlx::test(migrations = false)]
+    async fn applies_every_migration_on_empty_database(pool: sqlx::PgPool) {
+        for migration in get_migrations_status(&pool).await.unwrap() {
+            assert!(!migration.applied);
+        }
+
+        run_migrations(&pool).await.unwrap();
+
+        let migrations = get_migrations_status(&pool).await.unwrap();
+
+        assert!(!migrations.is_empty());
+        for migration in migrations {
+            assert!(migration.applied);
+        }
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn running_migrations_twice_is_a_no_op(pool: sqlx::PgPool) {
+        run_migrations(&pool).await.unwrap();
+        run_migrations(&pool).await.unwrap();
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn reverts_and_reapplies_every_migration(pool: sqlx::PgPool) {
+        run_migrations(&pool).await.unwrap();
+
+        let versions: Vec<i64> = get_migrations_status(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|migration| migration.version)
+            .collect();
+        for (index, version) in versions.iter().enumerate().rev() {
+            let previous_version = if index > 0 { versions[index - 1] } else { 0 };
+            revert_migrations(&pool, previous_version).await.unwrap();
+
+            let migrations = get_migrations_status(&pool).await.unwrap();
+            for migration in migrations {
+                assert_eq!(migration.applied, migration.version < *version);
+            }
+        }
+
+        let tables_count: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = 'public' AND table_name <> '_sqlx_migrations'"#,
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(tables_count, 0);
+
+        run_migrations(&pool).await.unwrap();
+    }
+}
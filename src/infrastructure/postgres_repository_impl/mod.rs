@@ -1,4 +1,4 @@
-pub mod create_tables;
+pub mod migrations;
 pub mod doctors;
 pub mod drugs;
 pub mod patients;
@@ -4,15 +4,52 @@ use uuid::Uuid;
 
 use crate::domain::{
     patients::{
-        entities::{NewPatient, Patient},
+        entities::{
+            IdentityDocument, IdentityDocumentType, NewPatient, NewPatientAllergy, Patient,
+            PatientAllergy, PatientAllergyUpdate, PatientUpdate,
+        },
         repository::{
-            CreatePatientRepositoryError, GetPatientByIdRepositoryError,
-            GetPatientsRepositoryError, PatientsRepository,
+            CreatePatientAllergyRepositoryError, CreatePatientRepositoryError,
+            DeletePatientAllergyRepositoryError, GetPatientAllergiesRepositoryError,
+            GetPatientByIdRepositoryError, GetPatientsRepositoryError, PatientsRepository,
+            UpdatePatientAllergyRepositoryError, UpdatePatientRepositoryError,
         },
     },
     utils::pagination::get_pagination_params,
 };
 
+const SELECT_PATIENTS_COLUMNS: &str = r#"
+    id,
+    name,
+    pesel_number,
+    document_type,
+    document_number,
+    document_issuing_country,
+    parent_pesel_number,
+    birth_date,
+    sex,
+    created_at,
+    updated_at
+"#;
+
+// Identity document is stored in nullable columns of patients, which are either all set or all
+// NULL
+pub fn parse_identity_document_columns(
+    row: &sqlx::postgres::PgRow,
+    first_column: usize,
+) -> Result<Option<IdentityDocument>, sqlx::Error> {
+    let document_type: Option<IdentityDocumentType> = row.try_get(first_column)?;
+    let Some(document_type) = document_type else {
+        return Ok(None);
+    };
+
+    Ok(Some(IdentityDocument {
+        document_type,
+        number: row.try_get(first_column + 1)?,
+        issuing_country: row.try_get(first_column + 2)?,
+    }))
+}
+
 pub struct PostgresPatientsRepository {
     pool: sqlx::PgPool,
 }
@@ -27,8 +64,28 @@ impl PostgresPatientsRepository {
             id: row.try_get(0)?,
             name: row.try_get(1)?,
             pesel_number: row.try_get(2)?,
-            created_at: row.try_get(3)?,
-            updated_at: row.try_get(4)?,
+            identity_document: parse_identity_document_columns(&row, 3)?,
+            parent_pesel_number: row.try_get(6)?,
+            birth_date: row.try_get(7)?,
+            sex: row.try_get(8)?,
+            created_at: row.try_get(9)?,
+            updated_at: row.try_get(10)?,
+        })
+    }
+
+    fn parse_patient_allergies_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<PatientAllergy, sqlx::Error> {
+        Ok(PatientAllergy {
+            id: row.try_get(0)?,
+            patient_id: row.try_get(1)?,
+            substance: row.try_get(2)?,
+            reaction: row.try_get(3)?,
+            severity: row.try_get(4)?,
+            recorded_by_doctor_id: row.try_get(5)?,
+            created_at: row.try_get(6)?,
+            updated_at: row.try_get(7)?,
         })
     }
 }
@@ -39,12 +96,27 @@ impl PatientsRepository for PostgresPatientsRepository {
         &self,
         patient: NewPatient,
     ) -> Result<Patient, CreatePatientRepositoryError> {
-        let result = sqlx::query(
-                r#"INSERT INTO patients (id, name, pesel_number) VALUES ($1, $2, $3) RETURNING id, name, pesel_number, created_at, updated_at"#
-            )
+        let (document_type, document_number, document_issuing_country) =
+            match patient.identity_document {
+                Some(document) => (
+                    Some(document.document_type),
+                    Some(document.number),
+                    Some(document.issuing_country),
+                ),
+                None => (None, None, None),
+            };
+        let result = sqlx::query(&format!(
+                r#"INSERT INTO patients (id, name, pesel_number, document_type, document_number, document_issuing_country, parent_pesel_number, birth_date, sex) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {SELECT_PATIENTS_COLUMNS}"#
+            ))
             .bind(patient.id)
             .bind(patient.name)
             .bind(patient.pesel_number)
+            .bind(document_type)
+            .bind(document_number)
+            .bind(document_issuing_country)
+            .bind(patient.parent_pesel_number)
+            .bind(patient.birth_date)
+            .bind(patient.sex)
             .fetch_one(&self.pool).await
             .map_err(|err| {
                 match err {
@@ -53,6 +125,9 @@ impl PatientsRepository for PostgresPatientsRepository {
                             Some("patients_pesel_number_key") => {
                                 CreatePatientRepositoryError::DuplicatedPeselNumber
                             }
+                            Some("patients_identity_document_key") => {
+                                CreatePatientRepositoryError::DuplicatedIdentityDocument
+                            }
                             _ => CreatePatientRepositoryError::DatabaseError(err.to_string()),
                         }
                     }
@@ -74,13 +149,14 @@ impl PatientsRepository for PostgresPatientsRepository {
         let (page_size, offset) = get_pagination_params(page, page_size)
             .map_err(|err| GetPatientsRepositoryError::InvalidPaginationParams(err.to_string()))?;
 
-        let patients_from_db = sqlx::query(
-                r#"SELECT id, name, pesel_number, created_at, updated_at FROM patients LIMIT $1 OFFSET $2"#
-            )
-            .bind(page_size)
-            .bind(offset)
-            .fetch_all(&self.pool).await
-            .map_err(|err| GetPatientsRepositoryError::DatabaseError(err.to_string()))?;
+        let patients_from_db = sqlx::query(&format!(
+            r#"SELECT {SELECT_PATIENTS_COLUMNS} FROM patients LIMIT $1 OFFSET $2"#
+        ))
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| GetPatientsRepositoryError::DatabaseError(err.to_string()))?;
 
         let mut patients: Vec<Patient> = Vec::new();
         for record in patients_from_db {
@@ -97,9 +173,9 @@ impl PatientsRepository for PostgresPatientsRepository {
         &self,
         patient_id: Uuid,
     ) -> Result<Patient, GetPatientByIdRepositoryError> {
-        let patient_from_db = sqlx::query(
-            r#"SELECT id, name, pesel_number, created_at, updated_at FROM patients WHERE id = $1"#,
-        )
+        let patient_from_db = sqlx::query(&format!(
+            r#"SELECT {SELECT_PATIENTS_COLUMNS} FROM patients WHERE id = $1"#
+        ))
         .bind(patient_id)
         .fetch_one(&self.pool)
         .await
@@ -113,26 +189,224 @@ impl PatientsRepository for PostgresPatientsRepository {
             .map_err(|err| GetPatientByIdRepositoryError::DatabaseError(err.to_string()))?;
         Ok(patient)
     }
+
+    async fn update_patient(
+        &self,
+        patient_update: PatientUpdate,
+    ) -> Result<Patient, UpdatePatientRepositoryError> {
+        let (document_type, document_number, document_issuing_country) =
+            match patient_update.identity_document {
+                Some(document) => (
+                    Some(document.document_type),
+                    Some(document.number),
+                    Some(document.issuing_country),
+                ),
+                None => (None, None, None),
+            };
+        let result = sqlx::query(&format!(
+                r#"UPDATE patients SET name = $2, pesel_number = $3, document_type = $4, document_number = $5, document_issuing_country = $6, parent_pesel_number = $7, birth_date = $8, sex = $9, updated_at = NOW() WHERE id = $1 RETURNING {SELECT_PATIENTS_COLUMNS}"#
+            ))
+            .bind(patient_update.id)
+            .bind(patient_update.name)
+            .bind(patient_update.pesel_number)
+            .bind(document_type)
+            .bind(document_number)
+            .bind(document_issuing_country)
+            .bind(patient_update.parent_pesel_number)
+            .bind(patient_update.birth_date)
+            .bind(patient_update.sex)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::RowNotFound => UpdatePatientRepositoryError::NotFound(patient_update.id),
+                    sqlx::Error::Database(err) if err.is_unique_violation() => {
+                        match err.constraint() {
+                            Some("patients_pesel_number_key") => {
+                                UpdatePatientRepositoryError::DuplicatedPeselNumber
+                            }
+                            Some("patients_identity_document_key") => {
+                                UpdatePatientRepositoryError::DuplicatedIdentityDocument
+                            }
+                            _ => UpdatePatientRepositoryError::DatabaseError(err.to_string()),
+                        }
+                    }
+                    _ => UpdatePatientRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        let patient = self
+            .parse_patients_row(result)
+            .map_err(|err| UpdatePatientRepositoryError::DatabaseError(err.to_string()))?;
+        Ok(patient)
+    }
+
+    async fn create_patient_allergy(
+        &self,
+        allergy: NewPatientAllergy,
+    ) -> Result<PatientAllergy, CreatePatientAllergyRepositoryError> {
+        // Substances missing from the catalogue are added to it, the same way as in create_drug
+        let result = sqlx::query(
+                r#"WITH substance AS (
+                    INSERT INTO active_substances (name) VALUES ($3) ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name RETURNING id, name
+                ), inserted AS (
+                    INSERT INTO patient_allergies (id, patient_id, active_substance_id, reaction, severity, recorded_by_doctor_id)
+                    SELECT $1, $2, substance.id, $4, $5, $6 FROM substance
+                    RETURNING id, patient_id, reaction, severity, recorded_by_doctor_id, created_at, updated_at
+                )
+                SELECT inserted.id, inserted.patient_id, substance.name, inserted.reaction, inserted.severity, inserted.recorded_by_doctor_id, inserted.created_at, inserted.updated_at
+                FROM inserted, substance"#
+            )
+            .bind(allergy.id)
+            .bind(allergy.patient_id)
+            .bind(allergy.substance)
+            .bind(allergy.reaction)
+            .bind(allergy.severity)
+            .bind(allergy.recorded_by_doctor_id)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::Database(err) if err.is_unique_violation() => {
+                        CreatePatientAllergyRepositoryError::DuplicatedAllergy
+                    }
+                    sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                        match err.constraint() {
+                            Some("patient_allergies_patient_id_fkey") => {
+                                CreatePatientAllergyRepositoryError::PatientNotFound(allergy.patient_id)
+                            }
+                            Some("patient_allergies_recorded_by_doctor_id_fkey") => {
+                                CreatePatientAllergyRepositoryError::DoctorNotFound(
+                                    allergy.recorded_by_doctor_id
+                                )
+                            }
+                            _ => CreatePatientAllergyRepositoryError::DatabaseError(err.to_string()),
+                        }
+                    }
+                    _ => CreatePatientAllergyRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        let allergy = self
+            .parse_patient_allergies_row(result)
+            .map_err(|err| CreatePatientAllergyRepositoryError::DatabaseError(err.to_string()))?;
+        Ok(allergy)
+    }
+
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, GetPatientAllergiesRepositoryError> {
+        self.get_patient_by_id(patient_id)
+            .await
+            .map_err(|err| match err {
+                GetPatientByIdRepositoryError::NotFound(_) => {
+                    GetPatientAllergiesRepositoryError::PatientNotFound(patient_id)
+                }
+                GetPatientByIdRepositoryError::DatabaseError(err) => {
+                    GetPatientAllergiesRepositoryError::DatabaseError(err)
+                }
+            })?;
+
+        let allergies_from_db = sqlx::query(
+                r#"SELECT patient_allergies.id, patient_allergies.patient_id, active_substances.name, patient_allergies.reaction, patient_allergies.severity, patient_allergies.recorded_by_doctor_id, patient_allergies.created_at, patient_allergies.updated_at FROM patient_allergies INNER JOIN active_substances ON patient_allergies.active_substance_id = active_substances.id WHERE patient_allergies.patient_id = $1 ORDER BY patient_allergies.created_at"#
+            )
+            .bind(patient_id)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetPatientAllergiesRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut allergies: Vec<PatientAllergy> = Vec::new();
+        for record in allergies_from_db {
+            let allergy = self.parse_patient_allergies_row(record).map_err(|err| {
+                GetPatientAllergiesRepositoryError::DatabaseError(err.to_string())
+            })?;
+            allergies.push(allergy);
+        }
+
+        Ok(allergies)
+    }
+
+    async fn update_patient_allergy(
+        &self,
+        allergy_update: PatientAllergyUpdate,
+    ) -> Result<PatientAllergy, UpdatePatientAllergyRepositoryError> {
+        let result = sqlx::query(
+                r#"UPDATE patient_allergies SET reaction = $1, severity = $2, recorded_by_doctor_id = $3, updated_at = NOW() FROM active_substances WHERE patient_allergies.id = $4 AND patient_allergies.patient_id = $5 AND patient_allergies.active_substance_id = active_substances.id RETURNING patient_allergies.id, patient_allergies.patient_id, active_substances.name, patient_allergies.reaction, patient_allergies.severity, patient_allergies.recorded_by_doctor_id, patient_allergies.created_at, patient_allergies.updated_at"#
+            )
+            .bind(allergy_update.reaction)
+            .bind(allergy_update.severity)
+            .bind(allergy_update.recorded_by_doctor_id)
+            .bind(allergy_update.id)
+            .bind(allergy_update.patient_id)
+            .fetch_one(&self.pool).await
+            .map_err(|err| {
+                match err {
+                    sqlx::Error::RowNotFound => {
+                        UpdatePatientAllergyRepositoryError::NotFound(allergy_update.id)
+                    }
+                    sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
+                        UpdatePatientAllergyRepositoryError::DoctorNotFound(
+                            allergy_update.recorded_by_doctor_id
+                        )
+                    }
+                    _ => UpdatePatientAllergyRepositoryError::DatabaseError(err.to_string()),
+                }
+            })?;
+
+        let allergy = self
+            .parse_patient_allergies_row(result)
+            .map_err(|err| UpdatePatientAllergyRepositoryError::DatabaseError(err.to_string()))?;
+        Ok(allergy)
+    }
+
+    async fn delete_patient_allergy(
+        &self,
+        patient_id: Uuid,
+        allergy_id: Uuid,
+    ) -> Result<(), DeletePatientAllergyRepositoryError> {
+        let result =
+            sqlx::query(r#"DELETE FROM patient_allergies WHERE id = $1 AND patient_id = $2"#)
+                .bind(allergy_id)
+                .bind(patient_id)
+                .execute(&self.pool)
+                .await
+                .map_err(|err| {
+                    DeletePatientAllergyRepositoryError::DatabaseError(err.to_string())
+                })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeletePatientAllergyRepositoryError::NotFound(allergy_id));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{NaiveDate, Utc};
     use uuid::Uuid;
 
     use super::PostgresPatientsRepository;
     use crate::{
-        domain::patients::{
-            entities::NewPatient,
-            repository::{
-                CreatePatientRepositoryError, GetPatientByIdRepositoryError,
-                GetPatientsRepositoryError, PatientsRepository,
+        domain::{
+            doctors::{entities::NewDoctor, repository::DoctorsRepository},
+            patients::{
+                entities::{
+                    AllergySeverity, IdentityDocument, IdentityDocumentType, NewPatient,
+                    NewPatientAllergy, PatientAllergyUpdate, PatientUpdate,
+                },
+                repository::{
+                    CreatePatientAllergyRepositoryError, CreatePatientRepositoryError,
+                    DeletePatientAllergyRepositoryError, GetPatientAllergiesRepositoryError,
+                    GetPatientByIdRepositoryError, GetPatientsRepositoryError, PatientsRepository,
+                    UpdatePatientAllergyRepositoryError, UpdatePatientRepositoryError,
+                },
             },
+            utils::validators::validate_pesel_number::Sex,
         },
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
+        infrastructure::postgres_repository_impl::doctors::PostgresDoctorsRepository,
     };
 
     async fn setup_repository(pool: sqlx::PgPool) -> PostgresPatientsRepository {
-        create_tables(&pool, true).await.unwrap();
         PostgresPatientsRepository::new(pool)
     }
 
@@ -152,6 +426,57 @@ mod tests {
         assert_eq!(patient_from_repo, new_patient);
     }
 
+    #[sqlx::test]
+    async fn creates_patients_without_pesel_number(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+        let identity_document = IdentityDocument {
+            document_type: IdentityDocumentType::EuIdCard,
+            number: "L01X00T47".into(),
+            issuing_country: "DE".into(),
+        };
+        let birth_date = NaiveDate::from_ymd_opt(1988, 4, 12).unwrap();
+        let foreign_patient = NewPatient::with_identity_document(
+            "Hans Muller".into(),
+            identity_document.clone(),
+            birth_date,
+            Sex::Male,
+        )
+        .unwrap();
+        let newborn = NewPatient::newborn(
+            "Baby Doe".into(),
+            "96021817257".into(),
+            Utc::now().date_naive(),
+            Sex::Female,
+        )
+        .unwrap();
+
+        for new_patient in [&foreign_patient, &newborn] {
+            repository
+                .create_patient(new_patient.clone())
+                .await
+                .unwrap();
+
+            let patient_from_repo = repository.get_patient_by_id(new_patient.id).await.unwrap();
+
+            assert_eq!(patient_from_repo, *new_patient);
+        }
+
+        let patient_with_duplicated_identity_document = NewPatient::with_identity_document(
+            "Anna Muller".into(),
+            identity_document,
+            birth_date,
+            Sex::Female,
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository
+                .create_patient(patient_with_duplicated_identity_document)
+                .await,
+            Err(CreatePatientRepositoryError::DuplicatedIdentityDocument)
+        );
+    }
+
     #[sqlx::test]
     async fn returns_error_if_patients_with_given_id_doesnt_exist(pool: sqlx::PgPool) {
         let repository = setup_repository(pool).await;
@@ -246,4 +571,202 @@ mod tests {
             Err(CreatePatientRepositoryError::DuplicatedPeselNumber)
         )
     }
+
+    #[sqlx::test]
+    async fn updates_patient(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let new_patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        let patient = repository.create_patient(new_patient).await.unwrap();
+
+        let updated_patient = repository
+            .update_patient(PatientUpdate {
+                id: patient.id,
+                name: "John Smith".into(),
+                pesel_number: Some("96021807250".into()),
+                identity_document: None,
+                parent_pesel_number: None,
+                birth_date: patient.birth_date,
+                sex: patient.sex,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_patient.name, "John Smith");
+        assert_eq!(updated_patient.pesel_number, Some("96021807250".into()));
+        assert_eq!(updated_patient.created_at, patient.created_at);
+        assert!(updated_patient.updated_at > patient.updated_at);
+        assert_eq!(
+            repository.get_patient_by_id(patient.id).await.unwrap(),
+            updated_patient
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_update_patient_if_it_doesnt_exist_or_pesel_number_is_duplicated(
+        pool: sqlx::PgPool,
+    ) {
+        let repository = setup_repository(pool).await;
+
+        let patient_0 = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        let patient_1 = NewPatient::new("John Doe".into(), "99031301347".into()).unwrap();
+        repository.create_patient(patient_0.clone()).await.unwrap();
+        repository.create_patient(patient_1.clone()).await.unwrap();
+
+        let patient_update = PatientUpdate {
+            id: patient_1.id,
+            name: patient_1.name,
+            pesel_number: patient_0.pesel_number,
+            identity_document: None,
+            parent_pesel_number: None,
+            birth_date: patient_1.birth_date,
+            sex: patient_1.sex,
+        };
+
+        assert_eq!(
+            repository.update_patient(patient_update.clone()).await,
+            Err(UpdatePatientRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let patient_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_patient(PatientUpdate {
+                    id: patient_id,
+                    pesel_number: Some("92022900002".into()),
+                    ..patient_update
+                })
+                .await,
+            Err(UpdatePatientRepositoryError::NotFound(patient_id))
+        );
+    }
+
+    #[sqlx::test]
+    async fn creates_updates_and_deletes_patient_allergies(pool: sqlx::PgPool) {
+        let doctors_repository = PostgresDoctorsRepository::new(pool.clone());
+        let doctor =
+            NewDoctor::new("John Doctor".into(), "3123456".into(), "96021807250".into()).unwrap();
+        doctors_repository
+            .create_doctor(doctor.clone())
+            .await
+            .unwrap();
+        let repository = setup_repository(pool).await;
+        let patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        repository.create_patient(patient.clone()).await.unwrap();
+
+        let new_allergy = NewPatientAllergy::new(
+            patient.id,
+            "penicillin".into(),
+            "Rash".into(),
+            AllergySeverity::Mild,
+            doctor.id,
+        )
+        .unwrap();
+        let created_allergy = repository
+            .create_patient_allergy(new_allergy.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_allergy, new_allergy);
+        assert_eq!(
+            repository.create_patient_allergy(new_allergy.clone()).await,
+            Err(CreatePatientAllergyRepositoryError::DuplicatedAllergy)
+        );
+
+        let updated_allergy = repository
+            .update_patient_allergy(
+                PatientAllergyUpdate::new(
+                    new_allergy.id,
+                    patient.id,
+                    "Anaphylaxis".into(),
+                    AllergySeverity::LifeThreatening,
+                    doctor.id,
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_allergy.reaction, "Anaphylaxis");
+        assert_eq!(updated_allergy.severity, AllergySeverity::LifeThreatening);
+
+        let allergies = repository.get_patient_allergies(patient.id).await.unwrap();
+
+        assert_eq!(allergies.len(), 1);
+        assert_eq!(allergies[0].id, new_allergy.id);
+        assert_eq!(allergies[0].reaction, "Anaphylaxis");
+
+        repository
+            .delete_patient_allergy(patient.id, new_allergy.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .get_patient_allergies(patient.id)
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            repository
+                .delete_patient_allergy(patient.id, new_allergy.id)
+                .await,
+            Err(DeletePatientAllergyRepositoryError::NotFound(
+                new_allergy.id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn returns_error_if_allergy_patient_or_doctor_doesnt_exist(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+        let patient = NewPatient::new("John Doe".into(), "96021817257".into()).unwrap();
+        repository.create_patient(patient.clone()).await.unwrap();
+        let missing_patient_id = Uuid::new_v4();
+        let missing_doctor_id = Uuid::new_v4();
+
+        let allergy_without_doctor = NewPatientAllergy::new(
+            patient.id,
+            "penicillin".into(),
+            "Rash".into(),
+            AllergySeverity::Mild,
+            missing_doctor_id,
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository
+                .create_patient_allergy(allergy_without_doctor.clone())
+                .await,
+            Err(CreatePatientAllergyRepositoryError::DoctorNotFound(
+                missing_doctor_id
+            ))
+        );
+        assert_eq!(
+            repository.get_patient_allergies(missing_patient_id).await,
+            Err(GetPatientAllergiesRepositoryError::PatientNotFound(
+                missing_patient_id
+            ))
+        );
+        assert_eq!(
+            repository
+                .update_patient_allergy(
+                    PatientAllergyUpdate::new(
+                        allergy_without_doctor.id,
+                        patient.id,
+                        "Rash".into(),
+                        AllergySeverity::Mild,
+                        missing_doctor_id,
+                    )
+                    .unwrap()
+                )
+                .await,
+            Err(UpdatePatientAllergyRepositoryError::NotFound(
+                allergy_without_doctor.id
+            ))
+        );
+    }
 }
@@ -4,10 +4,10 @@ use uuid::Uuid;
 
 use crate::domain::{
     pharmacists::{
-        entities::{NewPharmacist, Pharmacist},
+        entities::{NewPharmacist, Pharmacist, PharmacistUpdate},
         repository::{
             CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
-            GetPharmacistsRepositoryError, PharmacistsRepository,
+            GetPharmacistsRepositoryError, PharmacistsRepository, UpdatePharmacistRepositoryError,
         },
     },
     utils::pagination::get_pagination_params,
@@ -98,6 +98,31 @@ impl PharmacistsRepository for PostgresPharmacistsRepository {
             .map_err(|err| GetPharmacistByIdRepositoryError::DatabaseError(err.to_string()))?;
         Ok(pharmacist)
     }
+
+    async fn update_pharmacist(
+        &self,
+        pharmacist_update: PharmacistUpdate,
+    ) -> Result<Pharmacist, UpdatePharmacistRepositoryError> {
+        let result = sqlx::query(
+                r#"UPDATE pharmacists SET name = $2, pesel_number = $3, updated_at = NOW() WHERE id = $1 RETURNING id, name, pesel_number, created_at, updated_at"#
+            )
+            .bind(pharmacist_update.id)
+            .bind(pharmacist_update.name)
+            .bind(pharmacist_update.pesel_number)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => UpdatePharmacistRepositoryError::NotFound(pharmacist_update.id),
+                sqlx::Error::Database(err) if err.is_unique_violation() => {
+                    UpdatePharmacistRepositoryError::DuplicatedPeselNumber
+                }
+                _ => UpdatePharmacistRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let pharmacist = self
+            .parse_pharmacists_row(result)
+            .map_err(|err| UpdatePharmacistRepositoryError::DatabaseError(err.to_string()))?;
+        Ok(pharmacist)
+    }
 }
 
 #[cfg(test)]
@@ -105,19 +130,15 @@ mod tests {
     use uuid::Uuid;
 
     use super::PostgresPharmacistsRepository;
-    use crate::{
-        domain::pharmacists::{
-            entities::NewPharmacist,
-            repository::{
-                CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
-                GetPharmacistsRepositoryError, PharmacistsRepository,
-            },
+    use crate::domain::pharmacists::{
+        entities::{NewPharmacist, PharmacistUpdate},
+        repository::{
+            CreatePharmacistRepositoryError, GetPharmacistByIdRepositoryError,
+            GetPharmacistsRepositoryError, PharmacistsRepository, UpdatePharmacistRepositoryError,
         },
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
     };
 
     async fn setup_repository(pool: sqlx::PgPool) -> PostgresPharmacistsRepository {
-        create_tables(&pool, true).await.unwrap();
         PostgresPharmacistsRepository::new(pool)
     }
 
@@ -238,4 +259,75 @@ mod tests {
             Err(CreatePharmacistRepositoryError::DuplicatedPeselNumber)
         );
     }
+
+    #[sqlx::test]
+    async fn updates_pharmacist(pool: sqlx::PgPool) {
+        let repository = setup_repository(pool).await;
+
+        let new_pharmacist = NewPharmacist::new("John Doe".into(), "96021817257".into()).unwrap();
+        let pharmacist = repository.create_pharmacist(new_pharmacist).await.unwrap();
+
+        let updated_pharmacist = repository
+            .update_pharmacist(PharmacistUpdate {
+                id: pharmacist.id,
+                name: "John Smith".into(),
+                pesel_number: "99031301347".into(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated_pharmacist.name, "John Smith");
+        assert_eq!(updated_pharmacist.pesel_number, "99031301347");
+        assert_eq!(updated_pharmacist.created_at, pharmacist.created_at);
+        assert!(updated_pharmacist.updated_at > pharmacist.updated_at);
+        assert_eq!(
+            repository
+                .get_pharmacist_by_id(pharmacist.id)
+                .await
+                .unwrap(),
+            updated_pharmacist
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_update_pharmacist_if_it_doesnt_exist_or_pesel_number_is_duplicated(
+        pool: sqlx::PgPool,
+    ) {
+        let repository = setup_repository(pool).await;
+
+        let pharmacist_0 = NewPharmacist::new("John Doe".into(), "96021817257".into()).unwrap();
+        let pharmacist_1 = NewPharmacist::new("John Doe".into(), "99031301347".into()).unwrap();
+        repository
+            .create_pharmacist(pharmacist_0.clone())
+            .await
+            .unwrap();
+        repository
+            .create_pharmacist(pharmacist_1.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .update_pharmacist(PharmacistUpdate {
+                    id: pharmacist_1.id,
+                    name: pharmacist_1.name.clone(),
+                    pesel_number: pharmacist_0.pesel_number,
+                })
+                .await,
+            Err(UpdatePharmacistRepositoryError::DuplicatedPeselNumber)
+        );
+
+        let pharmacist_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .update_pharmacist(PharmacistUpdate {
+                    id: pharmacist_id,
+                    name: pharmacist_1.name,
+                    pesel_number: "92022900002".into(),
+                })
+                .await,
+            Err(UpdatePharmacistRepositoryError::NotFound(pharmacist_id))
+        );
+    }
 }
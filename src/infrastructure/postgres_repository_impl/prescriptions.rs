@@ -1,18 +1,26 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::Row;
 use uuid::Uuid;
 
+use super::{drugs::PostgresDrugsRepository, patients::parse_identity_document_columns};
 use crate::domain::{
+    drugs::entities::{Drug, DrugInteraction, ReimbursementLevel},
+    patients::entities::{IdentityDocument, PatientAllergy},
     prescriptions::{
         entities::{
-            NewPrescription, NewPrescriptionFill, PrescribedDrug, Prescription, PrescriptionDoctor,
-            PrescriptionFill, PrescriptionPatient, PrescriptionType,
+            ControlledSubstanceDispense, DispensedDrug, Dosage, InteractingDrug, NewPrescription,
+            NewPrescriptionCancellation, NewPrescriptionFill, PrescribedDrug, Prescription,
+            PrescriptionCancellation, PrescriptionDoctor, PrescriptionFill, PrescriptionLookup,
+            PrescriptionPatient, PrescriptionStatus, PrescriptionType,
         },
         repository::{
-            CreatePrescriptionRepositoryError, FillPrescriptionRepositoryError,
+            CancelPrescriptionRepositoryError, CheckAllergiesRepositoryError,
+            CheckDrugInteractionsRepositoryError, CreatePrescriptionRepositoryError,
+            FillPrescriptionRepositoryError, GetControlledSubstanceDispensesRepositoryError,
+            GetDrugsByIdsRepositoryError, GetPatientBirthDateRepositoryError,
             GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
-            PrescriptionsRepository,
+            LookupPrescriptionsRepositoryError, PrescriptionsRepository,
         },
     },
     utils::pagination::get_pagination_params,
@@ -36,16 +44,114 @@ struct PrescriptionsRow {
     doctor_pwz_number: String,
     patient_id: Uuid,
     patient_name: String,
-    patient_pesel_number: String,
+    patient_pesel_number: Option<String>,
+    patient_identity_document: Option<IdentityDocument>,
+    patient_parent_pesel_number: Option<String>,
+    patient_birth_date: NaiveDate,
     prescribed_drug_id: Uuid,
     prescribed_drug_drug_id: Uuid,
     prescribed_drug_quantity: i32,
+    prescribed_drug_remaining_quantity: i32,
     prescribed_drug_created_at: DateTime<Utc>,
     prescribed_drug_updated_at: DateTime<Utc>,
-    prescription_fill_id: Option<Uuid>,
-    prescription_fill_pharmacist_id: Option<Uuid>,
-    prescription_fill_created_at: Option<DateTime<Utc>>,
-    prescription_fill_updated_at: Option<DateTime<Utc>>,
+    prescribed_drug_portion_quantity: Option<i32>,
+    prescribed_drug_variant_id: Option<Uuid>,
+    prescription_cancellation_reason: Option<String>,
+    prescription_cancelled_at: Option<DateTime<Utc>>,
+    prescription_status: PrescriptionStatus,
+    prescription_interaction_override_justification: Option<String>,
+    prescribed_drug_dosage: Option<Dosage>,
+    prescribed_drug_reimbursement_level: Option<ReimbursementLevel>,
+}
+
+struct PrescriptionFillsRow {
+    prescription_fill_id: Uuid,
+    prescription_fill_prescription_id: Uuid,
+    prescription_fill_pharmacist_id: Uuid,
+    prescription_fill_created_at: DateTime<Utc>,
+    prescription_fill_updated_at: DateTime<Utc>,
+    prescription_fill_portion_number: Option<i32>,
+    dispensed_drug_id: Option<Uuid>,
+    dispensed_drug_prescribed_drug_id: Option<Uuid>,
+    dispensed_drug_quantity: Option<i32>,
+    dispensed_drug_substitute_drug_id: Option<Uuid>,
+    dispensed_drug_created_at: Option<DateTime<Utc>>,
+    dispensed_drug_updated_at: Option<DateTime<Utc>>,
+    dispensed_drug_payable_amount: Option<i64>,
+    dispensed_drug_reimbursed_amount: Option<i64>,
+    dispensed_drug_price: Option<i64>,
+}
+
+const SELECT_PRESCRIPTIONS_COLUMNS: &str = r#"
+    prescriptions.id,
+    prescriptions.code,
+    prescriptions.prescription_type,
+    prescriptions.start_date,
+    prescriptions.end_date,
+    prescriptions.created_at,
+    prescriptions.updated_at,
+    doctors.id,
+    doctors.name,
+    doctors.pesel_number,
+    doctors.pwz_number,
+    patients.id,
+    patients.name,
+    patients.pesel_number,
+    prescribed_drugs.id,
+    prescribed_drugs.drug_id,
+    prescribed_drugs.quantity,
+    prescribed_drugs.quantity - COALESCE((
+        SELECT SUM(dispensed_drugs.quantity) FROM dispensed_drugs
+        WHERE dispensed_drugs.prescribed_drug_id = prescribed_drugs.id
+    ), 0)::INT,
+    prescribed_drugs.created_at,
+    prescribed_drugs.updated_at,
+    prescribed_drugs.portion_quantity,
+    prescriptions.cancellation_reason,
+    prescriptions.cancelled_at,
+    CASE
+        WHEN prescriptions.status IN ('cancelled', 'filled') THEN prescriptions.status
+        WHEN prescriptions.end_date < CURRENT_TIMESTAMP THEN 'expired'::prescription_status
+        WHEN prescriptions.start_date > CURRENT_TIMESTAMP THEN 'pending'::prescription_status
+        ELSE prescriptions.status
+    END,
+    prescriptions.interaction_override_justification,
+    prescribed_drugs.variant_id,
+    prescribed_drugs.dose_amount,
+    prescribed_drugs.dose_unit,
+    prescribed_drugs.dosage_frequency,
+    prescribed_drugs.dosage_frequency_period,
+    prescribed_drugs.dosage_duration_days,
+    prescribed_drugs.administration_route,
+    prescribed_drugs.dosage_note,
+    prescribed_drugs.reimbursement_level,
+    patients.document_type,
+    patients.document_number,
+    patients.document_issuing_country,
+    patients.parent_pesel_number,
+    patients.birth_date
+"#;
+
+// Only active, partially filled, filled and cancelled statuses are stored, pending and expired
+// ones depend on the current date. Each condition can use the (status, end_date) index
+fn get_status_filter(status: Option<PrescriptionStatus>) -> &'static str {
+    match status {
+        None => "TRUE",
+        Some(PrescriptionStatus::Pending) => {
+            "status = 'active' AND start_date > CURRENT_TIMESTAMP"
+        }
+        Some(PrescriptionStatus::Active) => {
+            "status = 'active' AND end_date >= CURRENT_TIMESTAMP AND start_date <= CURRENT_TIMESTAMP"
+        }
+        Some(PrescriptionStatus::PartiallyFilled) => {
+            "status = 'partially_filled' AND end_date >= CURRENT_TIMESTAMP"
+        }
+        Some(PrescriptionStatus::Filled) => "status = 'filled'",
+        Some(PrescriptionStatus::Expired) => {
+            "status IN ('active', 'partially_filled') AND end_date < CURRENT_TIMESTAMP"
+        }
+        Some(PrescriptionStatus::Cancelled) => "status = 'cancelled'",
+    }
 }
 
 impl PostgresPrescriptionsRepository {
@@ -75,15 +181,46 @@ impl PostgresPrescriptionsRepository {
             prescribed_drug_id: row.try_get(14)?,
             prescribed_drug_drug_id: row.try_get(15)?,
             prescribed_drug_quantity: row.try_get(16)?,
-            prescribed_drug_created_at: row.try_get(17)?,
-            prescribed_drug_updated_at: row.try_get(18)?,
-            prescription_fill_id: row.try_get(19)?,
-            prescription_fill_pharmacist_id: row.try_get(20)?,
-            prescription_fill_created_at: row.try_get(21)?,
-            prescription_fill_updated_at: row.try_get(22)?,
+            prescribed_drug_remaining_quantity: row.try_get(17)?,
+            prescribed_drug_created_at: row.try_get(18)?,
+            prescribed_drug_updated_at: row.try_get(19)?,
+            prescribed_drug_portion_quantity: row.try_get(20)?,
+            prescription_cancellation_reason: row.try_get(21)?,
+            prescription_cancelled_at: row.try_get(22)?,
+            prescription_status: row.try_get(23)?,
+            prescription_interaction_override_justification: row.try_get(24)?,
+            prescribed_drug_variant_id: row.try_get(25)?,
+            prescribed_drug_dosage: self.parse_dosage_columns(&row, 26)?,
+            prescribed_drug_reimbursement_level: row.try_get(33)?,
+            patient_identity_document: parse_identity_document_columns(&row, 34)?,
+            patient_parent_pesel_number: row.try_get(37)?,
+            patient_birth_date: row.try_get(38)?,
         })
     }
 
+    // Dosage is stored in nullable columns of prescribed_drugs, which are either all set or all
+    // NULL (except duration and note)
+    fn parse_dosage_columns(
+        &self,
+        row: &sqlx::postgres::PgRow,
+        first_column: usize,
+    ) -> Result<Option<Dosage>, sqlx::Error> {
+        let dose_amount: Option<f64> = row.try_get(first_column)?;
+        let Some(dose_amount) = dose_amount else {
+            return Ok(None);
+        };
+
+        Ok(Some(Dosage {
+            dose_amount,
+            dose_unit: row.try_get(first_column + 1)?,
+            frequency: row.try_get(first_column + 2)?,
+            frequency_period: row.try_get(first_column + 3)?,
+            duration_days: row.try_get(first_column + 4)?,
+            route: row.try_get(first_column + 5)?,
+            note: row.try_get(first_column + 6)?,
+        }))
+    }
+
     fn parse_prescription_fills_row(
         &self,
         row: sqlx::postgres::PgRow,
@@ -92,10 +229,311 @@ impl PostgresPrescriptionsRepository {
             id: row.try_get(0)?,
             prescription_id: row.try_get(1)?,
             pharmacist_id: row.try_get(2)?,
+            portion_number: row.try_get(5)?,
+            dispensed_drugs: vec![],
             created_at: row.try_get(3)?,
             updated_at: row.try_get(4)?,
         })
     }
+
+    fn parse_prescription_fills_with_dispensed_drugs_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<PrescriptionFillsRow, sqlx::Error> {
+        Ok(PrescriptionFillsRow {
+            prescription_fill_id: row.try_get(0)?,
+            prescription_fill_prescription_id: row.try_get(1)?,
+            prescription_fill_pharmacist_id: row.try_get(2)?,
+            prescription_fill_created_at: row.try_get(3)?,
+            prescription_fill_updated_at: row.try_get(4)?,
+            prescription_fill_portion_number: row.try_get(5)?,
+            dispensed_drug_id: row.try_get(6)?,
+            dispensed_drug_prescribed_drug_id: row.try_get(7)?,
+            dispensed_drug_quantity: row.try_get(8)?,
+            dispensed_drug_substitute_drug_id: row.try_get(9)?,
+            dispensed_drug_created_at: row.try_get(10)?,
+            dispensed_drug_updated_at: row.try_get(11)?,
+            dispensed_drug_payable_amount: row.try_get(12)?,
+            dispensed_drug_reimbursed_amount: row.try_get(13)?,
+            dispensed_drug_price: row.try_get(14)?,
+        })
+    }
+
+    fn parse_dispensed_drugs_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DispensedDrug, sqlx::Error> {
+        Ok(DispensedDrug {
+            id: row.try_get(0)?,
+            prescription_fill_id: row.try_get(1)?,
+            prescribed_drug_id: row.try_get(2)?,
+            quantity: row.try_get(3)?,
+            substitute_drug_id: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            payable_amount: row.try_get(7)?,
+            reimbursed_amount: row.try_get(8)?,
+            price: row.try_get(9)?,
+            updated_at: row.try_get(6)?,
+        })
+    }
+
+    fn parse_controlled_substance_dispenses_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<ControlledSubstanceDispense, sqlx::Error> {
+        Ok(ControlledSubstanceDispense {
+            id: row.try_get(0)?,
+            prescription_id: row.try_get(1)?,
+            prescription_fill_id: row.try_get(2)?,
+            prescribed_drug_id: row.try_get(3)?,
+            drug_id: row.try_get(4)?,
+            variant_id: row.try_get(5)?,
+            quantity: row.try_get(6)?,
+            patient_id: row.try_get(7)?,
+            doctor_id: row.try_get(8)?,
+            pharmacist_id: row.try_get(9)?,
+            created_at: row.try_get(10)?,
+            updated_at: row.try_get(11)?,
+        })
+    }
+
+    fn parse_interacting_drugs_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<InteractingDrug, sqlx::Error> {
+        Ok(InteractingDrug {
+            drug_id: row.try_get(0)?,
+            drug_name: row.try_get(1)?,
+            active_substances: row.try_get(2)?,
+            prescription_id: row.try_get(3)?,
+        })
+    }
+
+    fn parse_drug_interactions_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<DrugInteraction, sqlx::Error> {
+        Ok(DrugInteraction {
+            id: row.try_get(0)?,
+            substance_a: row.try_get(1)?,
+            substance_b: row.try_get(2)?,
+            severity: row.try_get(3)?,
+            description: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            updated_at: row.try_get(6)?,
+        })
+    }
+
+    fn parse_patient_allergies_row(
+        &self,
+        row: sqlx::postgres::PgRow,
+    ) -> Result<PatientAllergy, sqlx::Error> {
+        Ok(PatientAllergy {
+            id: row.try_get(0)?,
+            patient_id: row.try_get(1)?,
+            substance: row.try_get(2)?,
+            reaction: row.try_get(3)?,
+            severity: row.try_get(4)?,
+            recorded_by_doctor_id: row.try_get(5)?,
+            created_at: row.try_get(6)?,
+            updated_at: row.try_get(7)?,
+        })
+    }
+
+    async fn build_prescriptions(
+        &self,
+        rows: Vec<sqlx::postgres::PgRow>,
+    ) -> Result<Vec<Prescription>, sqlx::Error> {
+        let mut prescriptions: Vec<Prescription> = vec![];
+
+        for record in rows {
+            let PrescriptionsRow {
+                prescription_id,
+                prescription_code,
+                prescription_prescription_type,
+                prescription_start_date,
+                prescription_end_date,
+                prescription_created_at,
+                prescription_updated_at,
+                doctor_id,
+                doctor_name,
+                doctor_pesel_number,
+                doctor_pwz_number,
+                patient_id,
+                patient_name,
+                patient_pesel_number,
+                patient_identity_document,
+                patient_parent_pesel_number,
+                patient_birth_date,
+                prescribed_drug_id,
+                prescribed_drug_drug_id,
+                prescribed_drug_quantity,
+                prescribed_drug_remaining_quantity,
+                prescribed_drug_created_at,
+                prescribed_drug_updated_at,
+                prescribed_drug_portion_quantity,
+                prescribed_drug_variant_id,
+                prescription_cancellation_reason,
+                prescription_cancelled_at,
+                prescription_status,
+                prescription_interaction_override_justification,
+                prescribed_drug_dosage,
+                prescribed_drug_reimbursement_level,
+            } = self.parse_prescriptions_row(record)?;
+
+            let prescription = prescriptions.iter_mut().find(|p| p.id == prescription_id);
+
+            let prescribed_drug = PrescribedDrug {
+                id: prescribed_drug_id,
+                prescription_id,
+                drug_id: prescribed_drug_drug_id,
+                variant_id: prescribed_drug_variant_id,
+                quantity: prescribed_drug_quantity,
+                remaining_quantity: prescribed_drug_remaining_quantity,
+                portion_quantity: prescribed_drug_portion_quantity,
+                dosage: prescribed_drug_dosage,
+                reimbursement_level: prescribed_drug_reimbursement_level,
+                created_at: prescribed_drug_created_at,
+                updated_at: prescribed_drug_updated_at,
+            };
+
+            if let Some(prescription) = prescription {
+                prescription.prescribed_drugs.push(prescribed_drug);
+            } else {
+                prescriptions.push(Prescription {
+                    id: prescription_id,
+                    patient: PrescriptionPatient {
+                        id: patient_id,
+                        name: patient_name,
+                        pesel_number: patient_pesel_number,
+                        identity_document: patient_identity_document,
+                        parent_pesel_number: patient_parent_pesel_number,
+                        birth_date: patient_birth_date,
+                    },
+                    doctor: PrescriptionDoctor {
+                        id: doctor_id,
+                        name: doctor_name,
+                        pesel_number: doctor_pesel_number,
+                        pwz_number: doctor_pwz_number,
+                    },
+                    code: prescription_code,
+                    prescription_type: prescription_prescription_type,
+                    start_date: prescription_start_date,
+                    end_date: prescription_end_date,
+                    prescribed_drugs: vec![prescribed_drug],
+                    fills: vec![],
+                    cancellation: match (
+                        prescription_cancellation_reason,
+                        prescription_cancelled_at,
+                    ) {
+                        (Some(reason), Some(cancelled_at)) => Some(PrescriptionCancellation {
+                            reason,
+                            cancelled_at,
+                        }),
+                        _ => None,
+                    },
+                    status: prescription_status,
+                    interaction_override_justification:
+                        prescription_interaction_override_justification,
+                    created_at: prescription_created_at,
+                    updated_at: prescription_updated_at,
+                });
+            }
+        }
+
+        let prescription_ids: Vec<Uuid> = prescriptions.iter().map(|p| p.id).collect();
+        let prescription_fills_from_db = sqlx::query(
+            r#"
+        SELECT
+            prescription_fills.id,
+            prescription_fills.prescription_id,
+            prescription_fills.pharmacist_id,
+            prescription_fills.created_at,
+            prescription_fills.updated_at,
+            prescription_fills.portion_number,
+            dispensed_drugs.id,
+            dispensed_drugs.prescribed_drug_id,
+            dispensed_drugs.quantity,
+            dispensed_drugs.substitute_drug_id,
+            dispensed_drugs.created_at,
+            dispensed_drugs.updated_at,
+            dispensed_drugs.payable_amount,
+            dispensed_drugs.reimbursed_amount,
+            dispensed_drugs.price
+        FROM prescription_fills
+        LEFT JOIN dispensed_drugs ON prescription_fills.id = dispensed_drugs.prescription_fill_id
+        WHERE prescription_fills.prescription_id = ANY($1)
+        ORDER BY prescription_fills.created_at ASC
+    "#,
+        )
+        .bind(prescription_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for record in prescription_fills_from_db {
+            let PrescriptionFillsRow {
+                prescription_fill_id,
+                prescription_fill_prescription_id,
+                prescription_fill_pharmacist_id,
+                prescription_fill_created_at,
+                prescription_fill_updated_at,
+                prescription_fill_portion_number,
+                dispensed_drug_id,
+                dispensed_drug_prescribed_drug_id,
+                dispensed_drug_quantity,
+                dispensed_drug_substitute_drug_id,
+                dispensed_drug_created_at,
+                dispensed_drug_updated_at,
+                dispensed_drug_payable_amount,
+                dispensed_drug_reimbursed_amount,
+                dispensed_drug_price,
+            } = self.parse_prescription_fills_with_dispensed_drugs_row(record)?;
+
+            let Some(prescription) = prescriptions
+                .iter_mut()
+                .find(|p| p.id == prescription_fill_prescription_id)
+            else {
+                continue;
+            };
+
+            let prescription_fill = match prescription
+                .fills
+                .iter_mut()
+                .find(|fill| fill.id == prescription_fill_id)
+            {
+                Some(prescription_fill) => prescription_fill,
+                None => {
+                    prescription.fills.push(PrescriptionFill {
+                        id: prescription_fill_id,
+                        prescription_id: prescription_fill_prescription_id,
+                        pharmacist_id: prescription_fill_pharmacist_id,
+                        portion_number: prescription_fill_portion_number,
+                        dispensed_drugs: vec![],
+                        created_at: prescription_fill_created_at,
+                        updated_at: prescription_fill_updated_at,
+                    });
+                    prescription.fills.last_mut().unwrap()
+                }
+            };
+
+            if let Some(dispensed_drug_id) = dispensed_drug_id {
+                prescription_fill.dispensed_drugs.push(DispensedDrug {
+                    id: dispensed_drug_id,
+                    prescription_fill_id,
+                    prescribed_drug_id: dispensed_drug_prescribed_drug_id.unwrap(),
+                    quantity: dispensed_drug_quantity.unwrap(),
+                    substitute_drug_id: dispensed_drug_substitute_drug_id,
+                    payable_amount: dispensed_drug_payable_amount,
+                    reimbursed_amount: dispensed_drug_reimbursed_amount,
+                    price: dispensed_drug_price,
+                    created_at: dispensed_drug_created_at.unwrap(),
+                    updated_at: dispensed_drug_updated_at.unwrap(),
+                });
+            }
+        }
+
+        Ok(prescriptions)
+    }
 }
 
 #[async_trait]
@@ -111,7 +549,7 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
             .map_err(|err| CreatePrescriptionRepositoryError::DatabaseError(err.to_string()))?;
 
         sqlx::query(
-                r#"INSERT INTO prescriptions (id, patient_id, doctor_id, code, prescription_type, start_date, end_date) VALUES ($1, $2, $3, $4, $5, $6, $7)"#
+                r#"INSERT INTO prescriptions (id, patient_id, doctor_id, code, prescription_type, start_date, end_date, interaction_override_justification) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#
             )
             .bind(prescription.id)
             .bind(prescription.patient_id)
@@ -120,6 +558,7 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
             .bind(prescription.prescription_type)
             .bind(prescription.start_date)
             .bind(prescription.end_date)
+            .bind(prescription.interaction_override_justification)
             .execute(&self.pool).await
             .map_err(|err| {
                 match err {
@@ -145,11 +584,21 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
         for prescribed_drug in &prescription.prescribed_drugs {
             sqlx
                 ::query(
-                    r#"INSERT INTO prescribed_drugs (prescription_id, drug_id, quantity) VALUES ($1, $2, $3)"#
+                    r#"INSERT INTO prescribed_drugs (prescription_id, drug_id, variant_id, quantity, portion_quantity, dose_amount, dose_unit, dosage_frequency, dosage_frequency_period, dosage_duration_days, administration_route, dosage_note, reimbursement_level) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)"#
                 )
                 .bind(prescription.id)
                 .bind(prescribed_drug.drug_id)
+                .bind(prescribed_drug.variant_id)
                 .bind(prescribed_drug.quantity as i32)
+                .bind(prescribed_drug.portion_quantity.map(|portion_quantity| portion_quantity as i32))
+                .bind(prescribed_drug.dosage.as_ref().map(|dosage| dosage.dose_amount))
+                .bind(prescribed_drug.dosage.as_ref().map(|dosage| dosage.dose_unit))
+                .bind(prescribed_drug.dosage.as_ref().map(|dosage| dosage.frequency))
+                .bind(prescribed_drug.dosage.as_ref().map(|dosage| dosage.frequency_period))
+                .bind(prescribed_drug.dosage.as_ref().and_then(|dosage| dosage.duration_days))
+                .bind(prescribed_drug.dosage.as_ref().map(|dosage| dosage.route))
+                .bind(prescribed_drug.dosage.as_ref().and_then(|dosage| dosage.note.clone()))
+                .bind(prescribed_drug.reimbursement_level)
                 .execute(&self.pool).await
                 .map_err(|err| {
                     match err {
@@ -160,6 +609,11 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
                                         prescribed_drug.drug_id
                                     )
                                 }
+                                Some("prescribed_drugs_variant_id_fkey") => {
+                                    CreatePrescriptionRepositoryError::DrugVariantNotFound(
+                                        prescribed_drug.variant_id.unwrap_or_default()
+                                    )
+                                }
                                 _ =>
                                     CreatePrescriptionRepositoryError::DatabaseError(
                                         err.to_string()
@@ -188,135 +642,38 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
         &self,
         page: Option<i64>,
         page_size: Option<i64>,
+        status: Option<PrescriptionStatus>,
     ) -> Result<Vec<Prescription>, GetPrescriptionsRepositoryError> {
         let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
             GetPrescriptionsRepositoryError::InvalidPaginationParams(err.to_string())
         })?;
+        let status_filter = get_status_filter(status);
 
-        let prescriptions_from_db = sqlx::query(
+        let prescriptions_from_db = sqlx::query(&format!(
             r#"
-        SELECT 
-            prescriptions.id, 
-            prescriptions.code,
-            prescriptions.prescription_type, 
-            prescriptions.start_date, 
-            prescriptions.end_date, 
-            prescriptions.created_at,
-            prescriptions.updated_at,
-            doctors.id,
-            doctors.name,
-            doctors.pesel_number,
-            doctors.pwz_number,
-            patients.id,
-            patients.name,
-            patients.pesel_number,
-            prescribed_drugs.id, 
-            prescribed_drugs.drug_id, 
-            prescribed_drugs.quantity,
-            prescribed_drugs.created_at,
-            prescribed_drugs.updated_at,
-            prescription_fills.id,
-            prescription_fills.pharmacist_id,
-            prescription_fills.created_at,
-            prescription_fills.updated_at
+        SELECT {SELECT_PRESCRIPTIONS_COLUMNS}
         FROM (
             SELECT * FROM prescriptions
+            WHERE {status_filter}
             ORDER BY created_at ASC
             LIMIT $1 OFFSET $2
         ) AS prescriptions
-        LEFT JOIN prescription_fills ON prescriptions.id = prescription_fills.prescription_id
         INNER JOIN prescribed_drugs ON prescriptions.id = prescribed_drugs.prescription_id
         INNER JOIN doctors ON prescriptions.doctor_id = doctors.id
         INNER JOIN patients ON prescriptions.patient_id = patients.id
-    "#,
-        )
+        ORDER BY prescriptions.created_at ASC
+    "#
+        ))
         .bind(page_size)
         .bind(offset)
         .fetch_all(&self.pool)
         .await
         .map_err(|err| GetPrescriptionsRepositoryError::DatabaseError(err.to_string()))?;
 
-        let mut prescriptions: Vec<Prescription> = vec![];
-
-        for record in prescriptions_from_db {
-            let PrescriptionsRow {
-                prescription_id,
-                prescription_code,
-                prescription_prescription_type,
-                prescription_start_date,
-                prescription_end_date,
-                prescription_created_at,
-                prescription_updated_at,
-                doctor_id,
-                doctor_name,
-                doctor_pesel_number,
-                doctor_pwz_number,
-                patient_id,
-                patient_name,
-                patient_pesel_number,
-                prescribed_drug_id,
-                prescribed_drug_drug_id,
-                prescribed_drug_quantity,
-                prescribed_drug_created_at,
-                prescribed_drug_updated_at,
-                prescription_fill_id,
-                prescription_fill_pharmacist_id,
-                prescription_fill_created_at,
-                prescription_fill_updated_at,
-            } = self
-                .parse_prescriptions_row(record)
-                .map_err(|err| GetPrescriptionsRepositoryError::DatabaseError(err.to_string()))?;
-
-            let prescription = prescriptions.iter_mut().find(|p| p.id == prescription_id);
-
-            let prescribed_drug = PrescribedDrug {
-                id: prescribed_drug_id,
-                prescription_id,
-                drug_id: prescribed_drug_drug_id,
-                quantity: prescribed_drug_quantity,
-                created_at: prescribed_drug_created_at,
-                updated_at: prescribed_drug_updated_at,
-            };
-
-            if let Some(prescription) = prescription {
-                prescription.prescribed_drugs.push(prescribed_drug);
-            } else {
-                let fill = if let Some(prescription_fill_id) = prescription_fill_id {
-                    Some(PrescriptionFill {
-                        id: prescription_fill_id,
-                        prescription_id,
-                        pharmacist_id: prescription_fill_pharmacist_id.unwrap(),
-                        created_at: prescription_fill_created_at.unwrap(),
-                        updated_at: prescription_fill_updated_at.unwrap(),
-                    })
-                } else {
-                    None
-                };
-
-                prescriptions.push(Prescription {
-                    id: prescription_id,
-                    patient: PrescriptionPatient {
-                        id: patient_id,
-                        name: patient_name,
-                        pesel_number: patient_pesel_number,
-                    },
-                    doctor: PrescriptionDoctor {
-                        id: doctor_id,
-                        name: doctor_name,
-                        pesel_number: doctor_pesel_number,
-                        pwz_number: doctor_pwz_number,
-                    },
-                    code: prescription_code,
-                    prescription_type: prescription_prescription_type,
-                    start_date: prescription_start_date,
-                    end_date: prescription_end_date,
-                    prescribed_drugs: vec![prescribed_drug],
-                    fill,
-                    created_at: prescription_created_at,
-                    updated_at: prescription_updated_at,
-                });
-            }
-        }
+        let prescriptions = self
+            .build_prescriptions(prescriptions_from_db)
+            .await
+            .map_err(|err| GetPrescriptionsRepositoryError::DatabaseError(err.to_string()))?;
 
         Ok(prescriptions)
     }
@@ -325,42 +682,18 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
         &self,
         id: Uuid,
     ) -> Result<Prescription, GetPrescriptionByIdRepositoryError> {
-        let prescription_from_db = sqlx::query(
+        let prescription_from_db = sqlx::query(&format!(
             r#"
-        SELECT
-            prescriptions.id, 
-            prescriptions.code,
-            prescriptions.prescription_type, 
-            prescriptions.start_date, 
-            prescriptions.end_date, 
-            prescriptions.created_at,
-            prescriptions.updated_at,
-            doctors.id,
-            doctors.name,
-            doctors.pesel_number,
-            doctors.pwz_number,
-            patients.id,
-            patients.name,
-            patients.pesel_number,
-            prescribed_drugs.id, 
-            prescribed_drugs.drug_id, 
-            prescribed_drugs.quantity,
-            prescribed_drugs.created_at,
-            prescribed_drugs.updated_at,
-            prescription_fills.id,
-            prescription_fills.pharmacist_id,
-            prescription_fills.created_at,
-            prescription_fills.updated_at
+        SELECT {SELECT_PRESCRIPTIONS_COLUMNS}
         FROM (
             SELECT * FROM prescriptions
             WHERE id = $1
         ) AS prescriptions
-        LEFT JOIN prescription_fills ON prescriptions.id = prescription_fills.prescription_id
         INNER JOIN prescribed_drugs ON prescriptions.id = prescribed_drugs.prescription_id
         INNER JOIN doctors ON prescriptions.doctor_id = doctors.id
         INNER JOIN patients ON prescriptions.patient_id = patients.id
-    "#,
-        )
+    "#
+        ))
         .bind(id)
         .fetch_all(&self.pool)
         .await
@@ -369,107 +702,178 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
             _ => GetPrescriptionByIdRepositoryError::DatabaseError(err.to_string()),
         })?;
 
-        let mut prescriptions: Vec<Prescription> = vec![];
+        let prescriptions = self
+            .build_prescriptions(prescription_from_db)
+            .await
+            .map_err(|err| GetPrescriptionByIdRepositoryError::DatabaseError(err.to_string()))?;
 
-        for record in prescription_from_db {
-            let PrescriptionsRow {
-                prescription_id,
-                prescription_code,
-                prescription_prescription_type,
-                prescription_start_date,
-                prescription_end_date,
-                prescription_created_at,
-                prescription_updated_at,
-                doctor_id,
-                doctor_name,
-                doctor_pesel_number,
-                doctor_pwz_number,
-                patient_id,
-                patient_name,
-                patient_pesel_number,
-                prescribed_drug_id,
-                prescribed_drug_drug_id,
-                prescribed_drug_quantity,
-                prescribed_drug_created_at,
-                prescribed_drug_updated_at,
-                prescription_fill_id,
-                prescription_fill_pharmacist_id,
-                prescription_fill_created_at,
-                prescription_fill_updated_at,
-            } = self.parse_prescriptions_row(record).map_err(|err| {
-                GetPrescriptionByIdRepositoryError::DatabaseError(err.to_string())
-            })?;
+        let prescription = prescriptions
+            .first()
+            .ok_or(GetPrescriptionByIdRepositoryError::NotFound(id))?
+            .to_owned();
 
-            let prescription = prescriptions.iter_mut().find(|p| p.id == prescription_id);
+        Ok(prescription)
+    }
 
-            let prescribed_drug = PrescribedDrug {
-                id: prescribed_drug_id,
-                prescription_id,
-                drug_id: prescribed_drug_drug_id,
-                quantity: prescribed_drug_quantity,
-                created_at: prescribed_drug_created_at,
-                updated_at: prescribed_drug_updated_at,
-            };
-
-            if let Some(prescription) = prescription {
-                prescription.prescribed_drugs.push(prescribed_drug);
-            } else {
-                let fill = if let Some(prescription_fill_id) = prescription_fill_id {
-                    Some(PrescriptionFill {
-                        id: prescription_fill_id,
-                        prescription_id,
-                        pharmacist_id: prescription_fill_pharmacist_id.unwrap(),
-                        created_at: prescription_fill_created_at.unwrap(),
-                        updated_at: prescription_fill_updated_at.unwrap(),
-                    })
-                } else {
-                    None
-                };
+    async fn lookup_prescriptions(
+        &self,
+        prescription_lookup: PrescriptionLookup,
+    ) -> Result<Vec<Prescription>, LookupPrescriptionsRepositoryError> {
+        let active_filter = get_status_filter(Some(PrescriptionStatus::Active));
+        let partially_filled_filter = get_status_filter(Some(PrescriptionStatus::PartiallyFilled));
 
-                prescriptions.push(Prescription {
-                    id: prescription_id,
-                    patient: PrescriptionPatient {
-                        id: patient_id,
-                        name: patient_name,
-                        pesel_number: patient_pesel_number,
-                    },
-                    doctor: PrescriptionDoctor {
-                        id: doctor_id,
-                        name: doctor_name,
-                        pesel_number: doctor_pesel_number,
-                        pwz_number: doctor_pwz_number,
-                    },
-                    code: prescription_code,
-                    prescription_type: prescription_prescription_type,
-                    start_date: prescription_start_date,
-                    end_date: prescription_end_date,
-                    prescribed_drugs: vec![prescribed_drug],
-                    fill,
-                    created_at: prescription_created_at,
-                    updated_at: prescription_updated_at,
-                });
-            }
-        }
+        let prescriptions_from_db = sqlx::query(&format!(
+            r#"
+        SELECT {SELECT_PRESCRIPTIONS_COLUMNS}
+        FROM (
+            SELECT prescriptions.* FROM prescriptions
+            INNER JOIN patients ON prescriptions.patient_id = patients.id
+            WHERE (
+                patients.pesel_number = $1
+                OR patients.parent_pesel_number = $1
+                OR patients.document_number = $2
+            )
+            AND prescriptions.code LIKE $3 || '%'
+            AND (({active_filter}) OR ({partially_filled_filter}))
+        ) AS prescriptions
+        INNER JOIN prescribed_drugs ON prescriptions.id = prescribed_drugs.prescription_id
+        INNER JOIN doctors ON prescriptions.doctor_id = doctors.id
+        INNER JOIN patients ON prescriptions.patient_id = patients.id
+        ORDER BY prescriptions.created_at ASC
+    "#
+        ))
+        .bind(prescription_lookup.pesel_number)
+        .bind(prescription_lookup.document_number)
+        .bind(prescription_lookup.code)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| LookupPrescriptionsRepositoryError::DatabaseError(err.to_string()))?;
 
-        let prescription = prescriptions
-            .first()
-            .ok_or(GetPrescriptionByIdRepositoryError::NotFound(id))?
-            .to_owned();
+        let prescriptions = self
+            .build_prescriptions(prescriptions_from_db)
+            .await
+            .map_err(|err| LookupPrescriptionsRepositoryError::DatabaseError(err.to_string()))?;
 
-        Ok(prescription)
+        Ok(prescriptions)
     }
 
     async fn fill_prescription(
         &self,
         prescription_fill: NewPrescriptionFill,
     ) -> Result<PrescriptionFill, FillPrescriptionRepositoryError> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+
+        // Prescription row is locked until the end of the transaction, so remaining quantities
+        // read below can't be dispensed or the prescription cancelled by a concurrent request
+        sqlx::query(r#"SELECT id FROM prescriptions WHERE id = $1 FOR UPDATE"#)
+            .bind(prescription_fill.prescription_id)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => FillPrescriptionRepositoryError::PrescriptionNotFound(
+                    prescription_fill.prescription_id,
+                ),
+                _ => FillPrescriptionRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        let remaining_quantities_from_db = sqlx::query(
+            r#"
+        SELECT prescribed_drugs.id, prescribed_drugs.quantity - COALESCE((
+            SELECT SUM(dispensed_drugs.quantity) FROM dispensed_drugs
+            WHERE dispensed_drugs.prescribed_drug_id = prescribed_drugs.id
+        ), 0)::INT, prescribed_drugs.quantity, prescribed_drugs.portion_quantity
+        FROM prescribed_drugs
+        WHERE prescribed_drugs.prescription_id = $1
+    "#,
+        )
+        .bind(prescription_fill.prescription_id)
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut remaining_quantities: Vec<(Uuid, i32, i32, Option<i32>)> = vec![];
+        for row in remaining_quantities_from_db {
+            remaining_quantities.push((
+                row.try_get(0).map_err(|err| {
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string())
+                })?,
+                row.try_get(1).map_err(|err| {
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string())
+                })?,
+                row.try_get(2).map_err(|err| {
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string())
+                })?,
+                row.try_get(3).map_err(|err| {
+                    FillPrescriptionRepositoryError::DatabaseError(err.to_string())
+                })?,
+            ));
+        }
+        for dispensed_drug in &prescription_fill.dispensed_drugs {
+            let Some(&(_, remaining_quantity, quantity, portion_quantity)) = remaining_quantities
+                .iter()
+                .find(|(prescribed_drug_id, ..)| {
+                    *prescribed_drug_id == dispensed_drug.prescribed_drug_id
+                })
+            else {
+                return Err(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                    dispensed_drug.prescribed_drug_id,
+                ));
+            };
+            if dispensed_drug.quantity as i32 > remaining_quantity {
+                return Err(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                    dispensed_drug.prescribed_drug_id,
+                ));
+            }
+
+            // Another fill of the same portion may have been committed since the domain checked
+            // the quantity released up to this portion
+            if let (Some(portion_number), Some(portion_quantity)) =
+                (prescription_fill.portion_number, portion_quantity)
+            {
+                let released_quantity =
+                    ((portion_number as i64 + 1) * portion_quantity as i64).min(quantity as i64);
+                let dispensed_quantity = (quantity - remaining_quantity) as i64;
+                if dispensed_quantity + dispensed_drug.quantity as i64 > released_quantity {
+                    return Err(FillPrescriptionRepositoryError::PortionQuantityExceeded(
+                        dispensed_drug.prescribed_drug_id,
+                    ));
+                }
+            }
+        }
+
+        // Status follows from the quantities read under the lock, as fills of other drugs of this
+        // prescription may have been committed since the prescription was read by the domain
+        let is_fully_dispensed_after_fill =
+            remaining_quantities
+                .iter()
+                .all(|(prescribed_drug_id, remaining_quantity, ..)| {
+                    let dispensed_quantity: i32 = prescription_fill
+                        .dispensed_drugs
+                        .iter()
+                        .filter(|dispensed_drug| {
+                            dispensed_drug.prescribed_drug_id == *prescribed_drug_id
+                        })
+                        .map(|dispensed_drug| dispensed_drug.quantity as i32)
+                        .sum();
+                    remaining_quantity - dispensed_quantity <= 0
+                });
+        let prescription_status = match is_fully_dispensed_after_fill {
+            true => PrescriptionStatus::Filled,
+            false => PrescriptionStatus::PartiallyFilled,
+        };
+
         let result = sqlx::query(
-                r#"INSERT INTO prescription_fills (id, prescription_id, pharmacist_id) VALUES ($1, $2, $3) RETURNING id, prescription_id, pharmacist_id, created_at, updated_at"#
+                r#"INSERT INTO prescription_fills (id, prescription_id, pharmacist_id, portion_number) VALUES ($1, $2, $3, $4) RETURNING id, prescription_id, pharmacist_id, created_at, updated_at, portion_number"#
             )
             .bind(prescription_fill.id)
             .bind(prescription_fill.prescription_id)
             .bind(prescription_fill.pharmacist_id)
-            .fetch_one(&self.pool).await
+            .bind(prescription_fill.portion_number)
+            .fetch_one(&mut *transaction).await
             .map_err(|err| {
                 match err {
                     sqlx::Error::Database(err) if err.is_foreign_key_violation() => {
@@ -479,6 +883,11 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
                                     prescription_fill.pharmacist_id
                                 )
                             }
+                            Some("prescription_fills_prescription_id_fkey") => {
+                                FillPrescriptionRepositoryError::PrescriptionNotFound(
+                                    prescription_fill.prescription_id
+                                )
+                            }
                             _ => FillPrescriptionRepositoryError::DatabaseError(err.to_string()),
                         }
                     }
@@ -486,15 +895,251 @@ impl PrescriptionsRepository for PostgresPrescriptionsRepository {
                 }
             })?;
 
-        let prescription_fill = self
+        let updated_prescriptions = sqlx::query(
+            r#"UPDATE prescriptions SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND status <> 'cancelled'"#,
+        )
+        .bind(prescription_fill.prescription_id)
+        .bind(prescription_status)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+
+        if updated_prescriptions.rows_affected() == 0 {
+            return Err(FillPrescriptionRepositoryError::PrescriptionCancelled(
+                prescription_fill.prescription_id,
+            ));
+        }
+
+        let mut created_prescription_fill = self
             .parse_prescription_fills_row(result)
             .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
-        Ok(prescription_fill)
+
+        for dispensed_drug in &prescription_fill.dispensed_drugs {
+            let result = sqlx::query(
+                    r#"INSERT INTO dispensed_drugs (prescription_fill_id, prescribed_drug_id, quantity, substitute_drug_id, payable_amount, reimbursed_amount, price) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id, prescription_fill_id, prescribed_drug_id, quantity, substitute_drug_id, created_at, updated_at, payable_amount, reimbursed_amount, price"#
+                )
+                .bind(prescription_fill.id)
+                .bind(dispensed_drug.prescribed_drug_id)
+                .bind(dispensed_drug.quantity as i32)
+                .bind(dispensed_drug.substitute_drug_id)
+                .bind(dispensed_drug.payable_amount)
+                .bind(dispensed_drug.reimbursed_amount)
+                .bind(dispensed_drug.price)
+                .fetch_one(&mut *transaction).await
+                .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+
+            let created_dispensed_drug = self
+                .parse_dispensed_drugs_row(result)
+                .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+            created_prescription_fill
+                .dispensed_drugs
+                .push(created_dispensed_drug);
+        }
+
+        for controlled_substance_dispense in &prescription_fill.controlled_substance_dispenses {
+            sqlx::query(
+                    r#"INSERT INTO controlled_substance_dispenses (id, prescription_id, prescription_fill_id, prescribed_drug_id, drug_id, variant_id, quantity, patient_id, doctor_id, pharmacist_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#
+                )
+                .bind(controlled_substance_dispense.id)
+                .bind(controlled_substance_dispense.prescription_id)
+                .bind(controlled_substance_dispense.prescription_fill_id)
+                .bind(controlled_substance_dispense.prescribed_drug_id)
+                .bind(controlled_substance_dispense.drug_id)
+                .bind(controlled_substance_dispense.variant_id)
+                .bind(controlled_substance_dispense.quantity)
+                .bind(controlled_substance_dispense.patient_id)
+                .bind(controlled_substance_dispense.doctor_id)
+                .bind(controlled_substance_dispense.pharmacist_id)
+                .execute(&mut *transaction).await
+                .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|err| FillPrescriptionRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(created_prescription_fill)
+    }
+
+    async fn cancel_prescription(
+        &self,
+        prescription_cancellation: NewPrescriptionCancellation,
+    ) -> Result<PrescriptionCancellation, CancelPrescriptionRepositoryError> {
+        let result = sqlx::query(
+                r#"UPDATE prescriptions SET status = 'cancelled', cancellation_reason = $2, cancelled_at = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1 RETURNING cancellation_reason, cancelled_at"#
+            )
+            .bind(prescription_cancellation.prescription_id)
+            .bind(prescription_cancellation.reason)
+            .bind(prescription_cancellation.cancelled_at)
+            .fetch_one(&self.pool).await
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => CancelPrescriptionRepositoryError::NotFound(
+                    prescription_cancellation.prescription_id,
+                ),
+                _ => CancelPrescriptionRepositoryError::DatabaseError(err.to_string()),
+            })?;
+
+        Ok(PrescriptionCancellation {
+            reason: result
+                .try_get(0)
+                .map_err(|err| CancelPrescriptionRepositoryError::DatabaseError(err.to_string()))?,
+            cancelled_at: result
+                .try_get(1)
+                .map_err(|err| CancelPrescriptionRepositoryError::DatabaseError(err.to_string()))?,
+        })
+    }
+
+    async fn get_interacting_drugs(
+        &self,
+        patient_id: Uuid,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<InteractingDrug>, CheckDrugInteractionsRepositoryError> {
+        let pending_filter = get_status_filter(Some(PrescriptionStatus::Pending));
+        let active_filter = get_status_filter(Some(PrescriptionStatus::Active));
+        let partially_filled_filter = get_status_filter(Some(PrescriptionStatus::PartiallyFilled));
+
+        let drugs_from_db = sqlx::query(&format!(
+            r#"
+        SELECT drugs.id, drugs.name, ARRAY(SELECT active_substances.name FROM drug_active_substances INNER JOIN active_substances ON drug_active_substances.active_substance_id = active_substances.id WHERE drug_active_substances.drug_id = drugs.id ORDER BY active_substances.name), NULL::UUID
+        FROM drugs
+        WHERE drugs.id = ANY($2)
+        UNION ALL
+        SELECT drugs.id, drugs.name, ARRAY(SELECT active_substances.name FROM drug_active_substances INNER JOIN active_substances ON drug_active_substances.active_substance_id = active_substances.id WHERE drug_active_substances.drug_id = drugs.id ORDER BY active_substances.name), prescriptions.id
+        FROM (
+            SELECT * FROM prescriptions
+            WHERE patient_id = $1
+            AND (({pending_filter}) OR ({active_filter}) OR ({partially_filled_filter}))
+        ) AS prescriptions
+        INNER JOIN prescribed_drugs ON prescriptions.id = prescribed_drugs.prescription_id
+        INNER JOIN drugs ON prescribed_drugs.drug_id = drugs.id
+    "#
+        ))
+        .bind(patient_id)
+        .bind(drug_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| CheckDrugInteractionsRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut interacting_drugs = vec![];
+        for record in drugs_from_db {
+            let interacting_drug = self.parse_interacting_drugs_row(record).map_err(|err| {
+                CheckDrugInteractionsRepositoryError::DatabaseError(err.to_string())
+            })?;
+            interacting_drugs.push(interacting_drug);
+        }
+
+        Ok(interacting_drugs)
+    }
+
+    async fn get_drug_interactions(
+        &self,
+        active_substances: Vec<String>,
+    ) -> Result<Vec<DrugInteraction>, CheckDrugInteractionsRepositoryError> {
+        let interactions_from_db = sqlx::query(
+                r#"SELECT drug_interactions.id, substance_a.name, substance_b.name, drug_interactions.severity, drug_interactions.description, drug_interactions.created_at, drug_interactions.updated_at FROM drug_interactions INNER JOIN active_substances AS substance_a ON drug_interactions.substance_a_id = substance_a.id INNER JOIN active_substances AS substance_b ON drug_interactions.substance_b_id = substance_b.id WHERE substance_a.name = ANY($1) AND substance_b.name = ANY($1)"#
+            )
+            .bind(active_substances)
+            .fetch_all(&self.pool).await
+            .map_err(|err| CheckDrugInteractionsRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut interactions = vec![];
+        for record in interactions_from_db {
+            let interaction = self.parse_drug_interactions_row(record).map_err(|err| {
+                CheckDrugInteractionsRepositoryError::DatabaseError(err.to_string())
+            })?;
+            interactions.push(interaction);
+        }
+
+        Ok(interactions)
+    }
+
+    async fn get_patient_allergies(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<Vec<PatientAllergy>, CheckAllergiesRepositoryError> {
+        let allergies_from_db = sqlx::query(
+                r#"SELECT patient_allergies.id, patient_allergies.patient_id, active_substances.name, patient_allergies.reaction, patient_allergies.severity, patient_allergies.recorded_by_doctor_id, patient_allergies.created_at, patient_allergies.updated_at FROM patient_allergies INNER JOIN active_substances ON patient_allergies.active_substance_id = active_substances.id WHERE patient_allergies.patient_id = $1"#
+            )
+            .bind(patient_id)
+            .fetch_all(&self.pool).await
+            .map_err(|err| CheckAllergiesRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut allergies = vec![];
+        for record in allergies_from_db {
+            let allergy = self
+                .parse_patient_allergies_row(record)
+                .map_err(|err| CheckAllergiesRepositoryError::DatabaseError(err.to_string()))?;
+            allergies.push(allergy);
+        }
+
+        Ok(allergies)
+    }
+
+    async fn get_patient_birth_date(
+        &self,
+        patient_id: Uuid,
+    ) -> Result<NaiveDate, GetPatientBirthDateRepositoryError> {
+        sqlx::query(r#"SELECT birth_date FROM patients WHERE id = $1"#)
+            .bind(patient_id)
+            .fetch_one(&self.pool)
+            .await
+            .and_then(|row| row.try_get(0))
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => {
+                    GetPatientBirthDateRepositoryError::PatientNotFound(patient_id)
+                }
+                _ => GetPatientBirthDateRepositoryError::DatabaseError(err.to_string()),
+            })
+    }
+
+    async fn get_drugs_by_ids(
+        &self,
+        drug_ids: Vec<Uuid>,
+    ) -> Result<Vec<Drug>, GetDrugsByIdsRepositoryError> {
+        let drugs = PostgresDrugsRepository::new(self.pool.clone())
+            .get_drugs_by_ids(drug_ids)
+            .await
+            .map_err(|err| GetDrugsByIdsRepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(drugs)
+    }
+
+    async fn get_controlled_substance_dispenses(
+        &self,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<Vec<ControlledSubstanceDispense>, GetControlledSubstanceDispensesRepositoryError>
+    {
+        let (page_size, offset) = get_pagination_params(page, page_size).map_err(|err| {
+            GetControlledSubstanceDispensesRepositoryError::InvalidPaginationParams(err.to_string())
+        })?;
+
+        let controlled_substance_dispenses_from_db = sqlx::query(
+                r#"SELECT id, prescription_id, prescription_fill_id, prescribed_drug_id, drug_id, variant_id, quantity, patient_id, doctor_id, pharmacist_id, created_at, updated_at FROM controlled_substance_dispenses ORDER BY created_at, id LIMIT $1 OFFSET $2"#
+            )
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(&self.pool).await
+            .map_err(|err| GetControlledSubstanceDispensesRepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut controlled_substance_dispenses = vec![];
+        for record in controlled_substance_dispenses_from_db {
+            let controlled_substance_dispense = self
+                .parse_controlled_substance_dispenses_row(record)
+                .map_err(|err| {
+                    GetControlledSubstanceDispensesRepositoryError::DatabaseError(err.to_string())
+                })?;
+            controlled_substance_dispenses.push(controlled_substance_dispense);
+        }
+
+        Ok(controlled_substance_dispenses)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, NaiveDate, Utc};
     use uuid::Uuid;
 
     use super::PostgresPrescriptionsRepository;
@@ -502,24 +1147,39 @@ mod tests {
         domain::{
             doctors::{entities::NewDoctor, repository::DoctorsRepository},
             drugs::{
-                entities::{DrugContentType, NewDrug},
+                entities::{
+                    DrugContentType, DrugInteractionSeverity, NewDrug, NewDrugInteraction,
+                    ReimbursementLevel,
+                },
                 repository::DrugsRepository,
             },
-            patients::{entities::NewPatient, repository::PatientsRepository},
+            patients::{
+                entities::{
+                    AllergySeverity, IdentityDocument, IdentityDocumentType, NewPatient,
+                    NewPatientAllergy,
+                },
+                repository::PatientsRepository,
+            },
             pharmacists::{entities::NewPharmacist, repository::PharmacistsRepository},
             prescriptions::{
-                entities::{NewPrescribedDrug, NewPrescription},
+                entities::{
+                    AdministrationRoute, Dosage, DosagePeriod, DoseUnit, NewDispensedDrug,
+                    NewPrescribedDrug, NewPrescription, NewPrescriptionCancellation,
+                    PrescriptionLookup, PrescriptionStatus, PrescriptionType,
+                },
                 repository::{
-                    CreatePrescriptionRepositoryError, FillPrescriptionRepositoryError,
-                    GetPrescriptionByIdRepositoryError, GetPrescriptionsRepositoryError,
-                    PrescriptionsRepository,
+                    CancelPrescriptionRepositoryError, CreatePrescriptionRepositoryError,
+                    FillPrescriptionRepositoryError,
+                    GetControlledSubstanceDispensesRepositoryError,
+                    GetPatientBirthDateRepositoryError, GetPrescriptionByIdRepositoryError,
+                    GetPrescriptionsRepositoryError, PrescriptionsRepository,
                 },
             },
+            utils::validators::validate_pesel_number::Sex,
         },
         infrastructure::postgres_repository_impl::{
-            create_tables::create_tables, doctors::PostgresDoctorsRepository,
-            drugs::PostgresDrugsRepository, patients::PostgresPatientsRepository,
-            pharmacists::PostgresPharmacistsRepository,
+            doctors::PostgresDoctorsRepository, drugs::PostgresDrugsRepository,
+            patients::PostgresPatientsRepository, pharmacists::PostgresPharmacistsRepository,
         },
     };
 
@@ -585,7 +1245,6 @@ mod tests {
     async fn setup_repository(
         pool: sqlx::PgPool,
     ) -> (PostgresPrescriptionsRepository, DatabaseSeedData) {
-        create_tables(&pool, true).await.unwrap();
         let seeds = seed_database(pool.clone()).await;
         let repository = PostgresPrescriptionsRepository::new(pool);
         (repository, seeds)
@@ -604,10 +1263,12 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[1].id,
                     quantity: 1,
+                    ..Default::default()
                 },
             ],
         )
@@ -626,6 +1287,134 @@ mod tests {
         assert_eq!(prescription_from_db, new_prescription);
     }
 
+    #[sqlx::test]
+    async fn creates_prescription_for_drug_variant(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    variant_id: Some(seeds.drugs[0].variants[0].id),
+                    quantity: 2,
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[1].id,
+                    quantity: 30,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+
+        repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(new_prescription.id)
+            .await
+            .unwrap();
+        let prescribed_drug = |drug_id| {
+            prescription_from_db
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.drug_id == drug_id)
+                .unwrap()
+        };
+
+        assert_eq!(
+            prescribed_drug(seeds.drugs[0].id).variant_id,
+            Some(seeds.drugs[0].variants[0].id)
+        );
+        assert_eq!(prescribed_drug(seeds.drugs[1].id).variant_id, None);
+
+        let variant_of_other_drug_id = seeds.drugs[1].variants[0].id;
+        let new_prescription_with_variant_of_other_drug = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                variant_id: Some(variant_of_other_drug_id),
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(
+            repository
+                .create_prescription(new_prescription_with_variant_of_other_drug)
+                .await,
+            Err(CreatePrescriptionRepositoryError::DrugVariantNotFound(
+                variant_of_other_drug_id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn creates_prescription_with_dosage_instructions(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+        let dosage = Dosage {
+            dose_amount: 1.0,
+            dose_unit: DoseUnit::Tablet,
+            frequency: 2,
+            frequency_period: DosagePeriod::Day,
+            duration_days: Some(7),
+            route: AdministrationRoute::Oral,
+            note: Some("After meal".into()),
+        };
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 1,
+                    dosage: Some(dosage.clone()),
+                    ..Default::default()
+                },
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[1].id,
+                    quantity: 1,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+
+        repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(new_prescription.id)
+            .await
+            .unwrap();
+        let prescribed_drug = |drug_id| {
+            prescription_from_db
+                .prescribed_drugs
+                .iter()
+                .find(|prescribed_drug| prescribed_drug.drug_id == drug_id)
+                .unwrap()
+        };
+
+        assert_eq!(prescribed_drug(seeds.drugs[0].id).dosage, Some(dosage));
+        assert_eq!(prescribed_drug(seeds.drugs[1].id).dosage, None);
+    }
+
     #[sqlx::test]
     async fn doesnt_create_prescription_if_relations_dont_exist(pool: sqlx::PgPool) {
         let (repository, seeds) = setup_repository(pool).await;
@@ -639,6 +1428,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -661,6 +1451,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -683,6 +1474,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: nonexistent_drug_id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -725,18 +1517,22 @@ mod tests {
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[1].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[2].id,
                     quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[3].id,
                     quantity: 1,
+                    ..Default::default()
                 },
             ],
         )
@@ -756,6 +1552,7 @@ mod tests {
                 vec![NewPrescribedDrug {
                     drug_id: seeds.drugs[0].id,
                     quantity: 1,
+                    ..Default::default()
                 }],
             )
             .unwrap();
@@ -765,16 +1562,22 @@ mod tests {
                 .unwrap();
         }
 
-        let prescriptions = repository.get_prescriptions(None, Some(7)).await.unwrap();
+        let prescriptions = repository
+            .get_prescriptions(None, Some(7), None)
+            .await
+            .unwrap();
 
         assert_eq!(prescriptions.len(), 7);
         assert_eq!(prescriptions[0], new_prescription);
 
-        let prescriptions = repository.get_prescriptions(None, Some(20)).await.unwrap();
+        let prescriptions = repository
+            .get_prescriptions(None, Some(20), None)
+            .await
+            .unwrap();
         assert_eq!(prescriptions.len(), 11);
 
         let prescriptions = repository
-            .get_prescriptions(Some(1), Some(10))
+            .get_prescriptions(Some(1), Some(10), None)
             .await
             .unwrap();
         assert_eq!(prescriptions.len(), 1);
@@ -787,16 +1590,18 @@ mod tests {
         let (repository, _) = setup_repository(pool).await;
 
         assert!(
-            match repository.get_prescriptions(Some(-1), Some(10)).await {
+            match repository.get_prescriptions(Some(-1), Some(10), None).await {
                 Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
                 _ => false,
             }
         );
 
-        assert!(match repository.get_prescriptions(Some(0), Some(0)).await {
-            Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
-            _ => false,
-        });
+        assert!(
+            match repository.get_prescriptions(Some(0), Some(0), None).await {
+                Err(GetPrescriptionsRepositoryError::InvalidPaginationParams(_)) => true,
+                _ => false,
+            }
+        );
     }
 
     #[sqlx::test]
@@ -811,6 +1616,7 @@ mod tests {
             vec![NewPrescribedDrug {
                 drug_id: seeds.drugs[0].id,
                 quantity: 1,
+                ..Default::default()
             }],
         )
         .unwrap();
@@ -825,11 +1631,11 @@ mod tests {
             .await
             .unwrap();
 
-        assert!(prescription_from_db.fill.is_none());
+        assert!(prescription_from_db.fills.is_empty());
 
         let code = prescription_from_db.code.clone();
         let new_prescription_fill = prescription_from_db
-            .fill(seeds.pharmacist.id, code)
+            .fill(seeds.pharmacist.id, code, None, Duration::zero())
             .unwrap();
         let created_prescription_fill = repository
             .fill_prescription(new_prescription_fill.clone())
@@ -843,27 +1649,284 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(prescription_from_db.fill.unwrap(), new_prescription_fill);
+        assert_eq!(prescription_from_db.fills, vec![created_prescription_fill]);
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            0
+        );
     }
 
     #[sqlx::test]
-    async fn doesnt_fill_if_pharmacist_relation_doesnt_exist(pool: sqlx::PgPool) {
+    async fn fills_prescription_in_multiple_parts(pool: sqlx::PgPool) {
         let (repository, seeds) = setup_repository(pool).await;
 
-        let nonexistent_pharmacist_id = Uuid::new_v4();
-        let new_prescription = NewPrescription::new(
+        let prescription = NewPrescription::new(
             seeds.doctor.id,
             seeds.patient.id,
             None,
             None,
-            vec![
-                NewPrescribedDrug {
-                    drug_id: seeds.drugs[0].id,
-                    quantity: 1,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 3,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        let prescription_from_db = repository
+            .create_prescription(prescription.clone())
+            .await
+            .unwrap();
+        let prescribed_drug_id = prescription_from_db.prescribed_drugs[0].id;
+
+        let first_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id,
+                    quantity: 2,
+                    ..Default::default()
+                }]),
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            1
+        );
+
+        let second_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                None,
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .fill_prescription(second_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(prescription_from_db.fills.len(), 2);
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].remaining_quantity,
+            0
+        );
+        assert!(prescription_from_db.is_fully_dispensed());
+    }
+
+    #[sqlx::test]
+    async fn saves_substitute_of_dispensed_drug(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        let prescription_from_db = repository
+            .create_prescription(prescription.clone())
+            .await
+            .unwrap();
+
+        let new_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id: prescription_from_db.prescribed_drugs[0].id,
+                    quantity: 1,
+                    substitute_drug_id: Some(seeds.drugs[1].id),
+                    ..Default::default()
+                }]),
+                Duration::zero(),
+            )
+            .unwrap();
+        let created_prescription_fill = repository
+            .fill_prescription(new_prescription_fill.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_prescription_fill, new_prescription_fill);
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_db.fills[0].dispensed_drugs[0].substitute_drug_id,
+            Some(seeds.drugs[1].id)
+        );
+    }
+
+    #[sqlx::test]
+    async fn saves_reimbursement_level_payments_and_price_of_dispensed_drug(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                variant_id: Some(seeds.drugs[0].variants[0].id),
+                quantity: 2,
+                reimbursement_level: Some(ReimbursementLevel::ThirtyPercent),
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        let prescription_from_db = repository
+            .create_prescription(prescription.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_db.prescribed_drugs[0].reimbursement_level,
+            Some(ReimbursementLevel::ThirtyPercent)
+        );
+
+        let new_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id: prescription_from_db.prescribed_drugs[0].id,
+                    quantity: 2,
+                    payable_amount: Some(2800),
+                    reimbursed_amount: Some(1198),
+                    price: Some(1999),
+                    ..Default::default()
+                }]),
+                Duration::zero(),
+            )
+            .unwrap();
+        let created_prescription_fill = repository
+            .fill_prescription(new_prescription_fill.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(created_prescription_fill, new_prescription_fill);
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription.id)
+            .await
+            .unwrap();
+        let dispensed_drug = &prescription_from_db.fills[0].dispensed_drugs[0];
+
+        assert_eq!(dispensed_drug.payable_amount, Some(2800));
+        assert_eq!(dispensed_drug.reimbursed_amount, Some(1198));
+        assert_eq!(dispensed_drug.price, Some(1999));
+    }
+
+    #[sqlx::test]
+    async fn registers_dispenses_of_controlled_substances(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            Some(PrescriptionType::ForControlledSubstances),
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                variant_id: Some(seeds.drugs[0].variants[0].id),
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        let prescription_from_db = repository.create_prescription(prescription).await.unwrap();
+        let new_prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                None,
+                Duration::zero(),
+            )
+            .unwrap()
+            .with_controlled_substances_confirmation(&prescription_from_db, true)
+            .unwrap();
+
+        repository
+            .fill_prescription(new_prescription_fill.clone())
+            .await
+            .unwrap();
+
+        let controlled_substance_dispenses = repository
+            .get_controlled_substance_dispenses(None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            controlled_substance_dispenses,
+            new_prescription_fill.controlled_substance_dispenses
+        );
+    }
+
+    #[sqlx::test]
+    async fn get_controlled_substance_dispenses_returns_error_if_pagination_params_are_incorrect(
+        pool: sqlx::PgPool,
+    ) {
+        let (repository, _) = setup_repository(pool).await;
+
+        let result = repository
+            .get_controlled_substance_dispenses(Some(-1), None)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(GetControlledSubstanceDispensesRepositoryError::InvalidPaginationParams(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn doesnt_fill_if_pharmacist_relation_doesnt_exist(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let nonexistent_pharmacist_id = Uuid::new_v4();
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![
+                NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
                 },
                 NewPrescribedDrug {
                     drug_id: seeds.drugs[1].id,
                     quantity: 1,
+                    ..Default::default()
                 },
             ],
         )
@@ -876,7 +1939,7 @@ mod tests {
 
         let code = prescription_from_db.code.clone();
         let new_prescription_fill_with_nonexistent_pharmacist_id = prescription_from_db
-            .fill(nonexistent_pharmacist_id, code)
+            .fill(nonexistent_pharmacist_id, code, None, Duration::zero())
             .unwrap();
 
         assert_eq!(
@@ -888,4 +1951,696 @@ mod tests {
             ))
         );
     }
+
+    #[sqlx::test]
+    async fn doesnt_fill_if_prescription_was_filled_or_cancelled_in_the_meantime(
+        pool: sqlx::PgPool,
+    ) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let create_prescription = || {
+            NewPrescription::new(
+                seeds.doctor.id,
+                seeds.patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 2,
+                    ..Default::default()
+                }],
+            )
+            .unwrap()
+        };
+
+        let prescription_from_db = repository
+            .create_prescription(create_prescription())
+            .await
+            .unwrap();
+        let create_prescription_fill = || {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    None,
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill = create_prescription_fill();
+        let second_prescription_fill = create_prescription_fill();
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(second_prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::RemainingQuantityExceeded(
+                prescription_from_db.prescribed_drugs[0].id
+            ))
+        );
+
+        let prescription_from_db = repository
+            .create_prescription(create_prescription())
+            .await
+            .unwrap();
+        let prescription_fill = prescription_from_db
+            .fill(
+                seeds.pharmacist.id,
+                prescription_from_db.code.clone(),
+                None,
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .cancel_prescription(NewPrescriptionCancellation {
+                prescription_id: prescription_from_db.id,
+                reason: "Issued by mistake".into(),
+                cancelled_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::PrescriptionCancelled(
+                prescription_from_db.id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn marks_prescription_as_filled_if_other_drugs_were_dispensed_in_the_meantime(
+        pool: sqlx::PgPool,
+    ) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let prescription_from_db = repository
+            .create_prescription(
+                NewPrescription::new(
+                    seeds.doctor.id,
+                    seeds.patient.id,
+                    None,
+                    None,
+                    vec![
+                        NewPrescribedDrug {
+                            drug_id: seeds.drugs[0].id,
+                            quantity: 1,
+                            ..Default::default()
+                        },
+                        NewPrescribedDrug {
+                            drug_id: seeds.drugs[1].id,
+                            quantity: 1,
+                            ..Default::default()
+                        },
+                    ],
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let create_prescription_fill = |prescribed_drug_id| {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    Some(vec![NewDispensedDrug {
+                        prescribed_drug_id,
+                        quantity: 1,
+                        ..Default::default()
+                    }]),
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill =
+            create_prescription_fill(prescription_from_db.prescribed_drugs[0].id);
+        let second_prescription_fill =
+            create_prescription_fill(prescription_from_db.prescribed_drugs[1].id);
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+        repository
+            .fill_prescription(second_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(prescription_from_db.id)
+            .await
+            .unwrap();
+
+        assert_eq!(prescription_from_db.status, PrescriptionStatus::Filled);
+    }
+
+    #[sqlx::test]
+    async fn doesnt_fill_if_portion_was_dispensed_in_the_meantime(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let prescription_from_db = repository
+            .create_prescription(
+                NewPrescription::new(
+                    seeds.doctor.id,
+                    seeds.patient.id,
+                    None,
+                    Some(PrescriptionType::ForChronicDiseaseDrugs),
+                    vec![NewPrescribedDrug {
+                        drug_id: seeds.drugs[0].id,
+                        quantity: 24,
+                        ..Default::default()
+                    }],
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        let prescribed_drug_id = prescription_from_db.prescribed_drugs[0].id;
+        let create_prescription_fill = |quantity| {
+            prescription_from_db
+                .fill(
+                    seeds.pharmacist.id,
+                    prescription_from_db.code.clone(),
+                    Some(vec![NewDispensedDrug {
+                        prescribed_drug_id,
+                        quantity,
+                        ..Default::default()
+                    }]),
+                    Duration::zero(),
+                )
+                .unwrap()
+        };
+        let first_prescription_fill = create_prescription_fill(1);
+        let second_prescription_fill = create_prescription_fill(2);
+
+        repository
+            .fill_prescription(first_prescription_fill)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.fill_prescription(second_prescription_fill).await,
+            Err(FillPrescriptionRepositoryError::PortionQuantityExceeded(
+                prescribed_drug_id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn cancels_prescription_and_saves_to_database(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        let prescription_from_db = repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        assert!(prescription_from_db.cancellation.is_none());
+
+        let new_prescription_cancellation = prescription_from_db
+            .cancel(seeds.doctor.id, "Issued by mistake".into())
+            .unwrap();
+        let created_prescription_cancellation = repository
+            .cancel_prescription(new_prescription_cancellation.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            created_prescription_cancellation.reason,
+            new_prescription_cancellation.reason
+        );
+
+        let prescription_from_db = repository
+            .get_prescription_by_id(new_prescription.id)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription_from_db.cancellation,
+            Some(created_prescription_cancellation)
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_cancel_if_prescription_doesnt_exist(pool: sqlx::PgPool) {
+        let (repository, _) = setup_repository(pool).await;
+
+        let nonexistent_prescription_id = Uuid::new_v4();
+
+        assert_eq!(
+            repository
+                .cancel_prescription(NewPrescriptionCancellation {
+                    prescription_id: nonexistent_prescription_id,
+                    reason: "Issued by mistake".into(),
+                    cancelled_at: Utc::now(),
+                })
+                .await,
+            Err(CancelPrescriptionRepositoryError::NotFound(
+                nonexistent_prescription_id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn filters_prescriptions_by_status(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let mut prescriptions = vec![];
+        for start_date in [None, None, None, Some(Utc::now() + Duration::days(1))] {
+            let new_prescription = NewPrescription::new(
+                seeds.doctor.id,
+                seeds.patient.id,
+                start_date,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 2,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+            let prescription = repository
+                .create_prescription(new_prescription)
+                .await
+                .unwrap();
+            prescriptions.push(prescription);
+        }
+
+        let partial_prescription_fill = prescriptions[1]
+            .fill(
+                seeds.pharmacist.id,
+                prescriptions[1].code.clone(),
+                Some(vec![NewDispensedDrug {
+                    prescribed_drug_id: prescriptions[1].prescribed_drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                }]),
+                Duration::zero(),
+            )
+            .unwrap();
+        repository
+            .fill_prescription(partial_prescription_fill)
+            .await
+            .unwrap();
+
+        let prescription_cancellation = prescriptions[2]
+            .cancel(seeds.doctor.id, "Issued by mistake".into())
+            .unwrap();
+        repository
+            .cancel_prescription(prescription_cancellation)
+            .await
+            .unwrap();
+
+        for (status, expected_prescription) in [
+            (PrescriptionStatus::Active, &prescriptions[0]),
+            (PrescriptionStatus::PartiallyFilled, &prescriptions[1]),
+            (PrescriptionStatus::Cancelled, &prescriptions[2]),
+            (PrescriptionStatus::Pending, &prescriptions[3]),
+        ] {
+            let prescriptions_from_db = repository
+                .get_prescriptions(None, None, Some(status))
+                .await
+                .unwrap();
+
+            assert_eq!(prescriptions_from_db.len(), 1);
+            assert_eq!(prescriptions_from_db[0].id, expected_prescription.id);
+            assert_eq!(prescriptions_from_db[0].status, status);
+        }
+
+        for status in [PrescriptionStatus::Filled, PrescriptionStatus::Expired] {
+            assert!(repository
+                .get_prescriptions(None, None, Some(status))
+                .await
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[sqlx::test]
+    async fn looks_up_prescriptions_by_pesel_number_and_code(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        let prescription = repository
+            .create_prescription(new_prescription)
+            .await
+            .unwrap();
+
+        for code in [
+            prescription.code.clone(),
+            prescription.code[..4].to_string(),
+        ] {
+            let prescriptions_from_db = repository
+                .lookup_prescriptions(
+                    PrescriptionLookup::new(seeds.patient.pesel_number.clone(), None, code)
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(prescriptions_from_db.len(), 1);
+            assert_eq!(prescriptions_from_db[0].id, prescription.id);
+        }
+
+        let prescriptions_of_other_patient = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(
+                    Some("96021817257".into()),
+                    None,
+                    prescription.code.clone(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(prescriptions_of_other_patient.is_empty());
+
+        let prescription_cancellation = prescription
+            .cancel(seeds.doctor.id, "Issued by mistake".into())
+            .unwrap();
+        repository
+            .cancel_prescription(prescription_cancellation)
+            .await
+            .unwrap();
+
+        let cancelled_prescriptions = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(seeds.patient.pesel_number, None, prescription.code)
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(cancelled_prescriptions.is_empty());
+    }
+
+    #[sqlx::test]
+    async fn looks_up_prescriptions_of_patients_without_pesel_number(pool: sqlx::PgPool) {
+        let patients_repo = PostgresPatientsRepository::new(pool.clone());
+        let (repository, seeds) = setup_repository(pool).await;
+        let birth_date = Utc::now().date_naive() - Duration::days(3);
+        let foreign_patient = NewPatient::with_identity_document(
+            "Hans Muller".into(),
+            IdentityDocument {
+                document_type: IdentityDocumentType::Passport,
+                number: "C01X00T47".into(),
+                issuing_country: "DE".into(),
+            },
+            NaiveDate::from_ymd_opt(1988, 4, 12).unwrap(),
+            Sex::Male,
+        )
+        .unwrap();
+        let newborn = NewPatient::newborn(
+            "Baby Patient".into(),
+            seeds.patient.pesel_number.clone().unwrap(),
+            birth_date,
+            Sex::Female,
+        )
+        .unwrap();
+        let mut prescriptions = vec![];
+        for patient in [&foreign_patient, &newborn] {
+            patients_repo.create_patient(patient.clone()).await.unwrap();
+            let new_prescription = NewPrescription::new(
+                seeds.doctor.id,
+                patient.id,
+                None,
+                None,
+                vec![NewPrescribedDrug {
+                    drug_id: seeds.drugs[0].id,
+                    quantity: 1,
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+            prescriptions.push(
+                repository
+                    .create_prescription(new_prescription)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let prescriptions_of_foreign_patient = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(
+                    None,
+                    Some("c01x00t47".into()),
+                    prescriptions[0].code.clone(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(prescriptions_of_foreign_patient.len(), 1);
+        assert_eq!(
+            prescriptions_of_foreign_patient[0]
+                .patient
+                .identity_document,
+            foreign_patient.identity_document
+        );
+
+        let prescriptions_of_newborn = repository
+            .lookup_prescriptions(
+                PrescriptionLookup::new(
+                    seeds.patient.pesel_number,
+                    None,
+                    prescriptions[1].code.clone(),
+                )
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(prescriptions_of_newborn.len(), 1);
+        assert_eq!(prescriptions_of_newborn[0].patient.id, newborn.id);
+        assert_eq!(prescriptions_of_newborn[0].patient.pesel_number, None);
+        assert_eq!(prescriptions_of_newborn[0].patient.birth_date, birth_date);
+    }
+
+    #[sqlx::test]
+    async fn stores_interaction_override_justification(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let mut new_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        new_prescription.interaction_override_justification =
+            Some("Previous treatment was discontinued".into());
+
+        let prescription = repository
+            .create_prescription(new_prescription.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            prescription.interaction_override_justification,
+            Some("Previous treatment was discontinued".into())
+        );
+    }
+
+    #[sqlx::test]
+    async fn gets_interacting_drugs_from_current_prescriptions(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let current_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[0].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        repository
+            .create_prescription(current_prescription.clone())
+            .await
+            .unwrap();
+        let cancelled_prescription = NewPrescription::new(
+            seeds.doctor.id,
+            seeds.patient.id,
+            None,
+            None,
+            vec![NewPrescribedDrug {
+                drug_id: seeds.drugs[1].id,
+                quantity: 1,
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+        repository
+            .create_prescription(cancelled_prescription.clone())
+            .await
+            .unwrap();
+        repository
+            .cancel_prescription(NewPrescriptionCancellation {
+                prescription_id: cancelled_prescription.id,
+                reason: "Issued by mistake".into(),
+                cancelled_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let interacting_drugs = repository
+            .get_interacting_drugs(seeds.patient.id, vec![seeds.drugs[2].id])
+            .await
+            .unwrap();
+
+        assert_eq!(interacting_drugs.len(), 2);
+        assert_eq!(interacting_drugs[0].drug_id, seeds.drugs[2].id);
+        assert_eq!(interacting_drugs[0].prescription_id, None);
+        assert_eq!(interacting_drugs[1].drug_id, seeds.drugs[0].id);
+        assert_eq!(
+            interacting_drugs[1].prescription_id,
+            Some(current_prescription.id)
+        );
+
+        let interacting_drugs = repository
+            .get_interacting_drugs(Uuid::new_v4(), vec![seeds.drugs[2].id])
+            .await
+            .unwrap();
+
+        assert_eq!(interacting_drugs.len(), 1);
+    }
+
+    #[sqlx::test]
+    async fn gets_drug_interactions_between_given_substances(pool: sqlx::PgPool) {
+        let drugs_repository = PostgresDrugsRepository::new(pool.clone());
+        let (repository, _) = setup_repository(pool).await;
+        let interaction = NewDrugInteraction::new(
+            "warfarin".into(),
+            "ibuprofen".into(),
+            DrugInteractionSeverity::Major,
+            "Increased risk of bleeding".into(),
+        )
+        .unwrap();
+        drugs_repository
+            .create_drug_interaction(interaction.clone())
+            .await
+            .unwrap();
+
+        let interactions = repository
+            .get_drug_interactions(vec!["ibuprofen".into(), "warfarin".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0], interaction);
+
+        let interactions = repository
+            .get_drug_interactions(vec!["ibuprofen".into(), "paracetamol".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(interactions.len(), 0);
+    }
+
+    #[sqlx::test]
+    async fn gets_patient_allergies(pool: sqlx::PgPool) {
+        let patients_repository = PostgresPatientsRepository::new(pool.clone());
+        let (repository, seeds) = setup_repository(pool).await;
+        let allergy = NewPatientAllergy::new(
+            seeds.patient.id,
+            "amoxicillin".into(),
+            "Hives".into(),
+            AllergySeverity::Severe,
+            seeds.doctor.id,
+        )
+        .unwrap();
+        patients_repository
+            .create_patient_allergy(allergy.clone())
+            .await
+            .unwrap();
+
+        let allergies = repository
+            .get_patient_allergies(seeds.patient.id)
+            .await
+            .unwrap();
+
+        assert_eq!(allergies.len(), 1);
+        assert_eq!(allergies[0], allergy);
+
+        let allergies = repository
+            .get_patient_allergies(Uuid::new_v4())
+            .await
+            .unwrap();
+
+        assert_eq!(allergies.len(), 0);
+    }
+
+    #[sqlx::test]
+    async fn gets_patient_birth_date(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let birth_date = repository
+            .get_patient_birth_date(seeds.patient.id)
+            .await
+            .unwrap();
+
+        assert_eq!(birth_date, seeds.patient.birth_date);
+
+        let patient_id = Uuid::new_v4();
+        let result = repository.get_patient_birth_date(patient_id).await;
+
+        assert_eq!(
+            result,
+            Err(GetPatientBirthDateRepositoryError::PatientNotFound(
+                patient_id
+            ))
+        );
+    }
+
+    #[sqlx::test]
+    async fn gets_drugs_by_ids(pool: sqlx::PgPool) {
+        let (repository, seeds) = setup_repository(pool).await;
+
+        let drugs = repository
+            .get_drugs_by_ids(vec![seeds.drugs[0].id, seeds.drugs[2].id, Uuid::new_v4()])
+            .await
+            .unwrap();
+
+        assert_eq!(drugs.len(), 2);
+        assert_eq!(drugs[0], seeds.drugs[0]);
+        assert_eq!(drugs[1], seeds.drugs[2]);
+    }
 }
@@ -65,7 +65,10 @@ impl SessionsRepository for PostgresSessionsRepository {
             .bind(id)
             .fetch_one(&self.pool)
             .await
-            .map_err(|err| GetSessionRepositoryError::DatabaseError(err.to_string()))?;
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => GetSessionRepositoryError::NotFound(id),
+                _ => GetSessionRepositoryError::DatabaseError(err.to_string()),
+            })?;
 
         let session = self
             .parse_sessions_row(row)
@@ -85,7 +88,10 @@ impl SessionsRepository for PostgresSessionsRepository {
             .bind(session.id)
             .fetch_one(&self.pool)
             .await
-            .map_err(|err| UpdateSessionRepositoryError::DatabaseError(err.to_string()))?;
+            .map_err(|err| match err {
+                sqlx::Error::RowNotFound => UpdateSessionRepositoryError::NotFound(session.id),
+                _ => UpdateSessionRepositoryError::DatabaseError(err.to_string()),
+            })?;
 
         let session = self
             .parse_sessions_row(row)
@@ -106,19 +112,61 @@ mod tests {
 
     use super::PostgresSessionsRepository;
     use crate::{
-        application::sessions::{entities::NewSession, repository::SessionsRepository},
-        infrastructure::postgres_repository_impl::create_tables::create_tables,
+        application::{
+            authentication::{
+                entities::{NewUser, UserRole},
+                repository::AuthenticationRepository,
+            },
+            sessions::{
+                entities::NewSession,
+                repository::{GetSessionRepositoryError, SessionsRepository},
+            },
+        },
+        domain::doctors::{entities::NewDoctor, repository::DoctorsRepository},
+        infrastructure::postgres_repository_impl::{
+            authentication::PostgresAuthenticationRepository, doctors::PostgresDoctorsRepository,
+        },
     };
 
-    async fn setup_repository(pool: sqlx::PgPool) -> PostgresSessionsRepository {
-        create_tables(&pool, true).await.unwrap();
-        PostgresSessionsRepository::new(pool)
+    async fn seed_database(pool: sqlx::PgPool) -> NewUser {
+        let doctor = NewDoctor::new(
+            "John Doctor".into(), //
+            "3123456".into(),
+            "96021807250".into(),
+        )
+        .unwrap();
+        PostgresDoctorsRepository::new(pool.clone())
+            .create_doctor(doctor.clone())
+            .await
+            .unwrap();
+
+        let user = NewUser::new(
+            "username".into(),
+            "password".into(),
+            "john.doe@gmail.com".into(),
+            "123456789".into(),
+            UserRole::Doctor,
+            Some(doctor.id),
+            None,
+        )
+        .unwrap();
+        PostgresAuthenticationRepository::new(pool)
+            .create_user(user.clone())
+            .await
+            .unwrap();
+
+        user
     }
 
-    fn create_mock_new_session() -> NewSession {
+    async fn setup_repository(pool: sqlx::PgPool) -> (PostgresSessionsRepository, NewUser) {
+        let user = seed_database(pool.clone()).await;
+        (PostgresSessionsRepository::new(pool), user)
+    }
+
+    fn create_mock_new_session(user: &NewUser) -> NewSession {
         NewSession::new(
-            Uuid::new_v4(),
-            Some(Uuid::new_v4()),
+            user.id,
+            user.doctor_id,
             None,
             IpAddr::V4(Ipv4Addr::from_str("127.0.0.1").unwrap()),
             "Mozilla/5.0".to_string(),
@@ -127,8 +175,8 @@ mod tests {
 
     #[sqlx::test]
     async fn creates_new_session_and_reads_by_id(pool: sqlx::PgPool) {
-        let repository = setup_repository(pool).await;
-        let mock_new_session = create_mock_new_session();
+        let (repository, user) = setup_repository(pool).await;
+        let mock_new_session = create_mock_new_session(&user);
 
         let created_session = repository
             .create_session(mock_new_session.clone())
@@ -147,8 +195,8 @@ mod tests {
 
     #[sqlx::test]
     async fn updates_session(pool: sqlx::PgPool) {
-        let repository = setup_repository(pool).await;
-        let mock_new_session = create_mock_new_session();
+        let (repository, user) = setup_repository(pool).await;
+        let mock_new_session = create_mock_new_session(&user);
 
         let created_session = repository
             .create_session(mock_new_session.clone())
@@ -176,4 +224,31 @@ mod tests {
 
         assert!(invalidated_session.invalidated_at.is_some());
     }
+
+    #[sqlx::test]
+    async fn returns_error_if_session_does_not_exist(pool: sqlx::PgPool) {
+        let (repository, _) = setup_repository(pool).await;
+        let session_id = Uuid::new_v4();
+
+        let session_by_id = repository.get_session_by_id(session_id).await;
+
+        assert_eq!(
+            session_by_id,
+            Err(GetSessionRepositoryError::NotFound(session_id))
+        );
+    }
+
+    #[sqlx::test]
+    async fn doesnt_create_session_for_nonexistent_user(pool: sqlx::PgPool) {
+        let (repository, _) = setup_repository(pool).await;
+        let mock_new_session = NewSession::new(
+            Uuid::new_v4(),
+            None,
+            None,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            "Mozilla/5.0".to_string(),
+        );
+
+        assert!(repository.create_session(mock_new_session).await.is_err());
+    }
 }
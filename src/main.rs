@@ -2,32 +2,43 @@ pub mod application;
 pub mod domain;
 pub mod infrastructure;
 
-use std::{env, sync::Arc};
+use std::{env, error::Error, fs, path::Path, process, sync::Arc};
 
 use application::{
     api::controllers::{
         authentication_controller, doctors_controller, drugs_controller, patients_controller,
         pharmacists_controller, prescriptions_controller,
     },
-    authentication::{repository::AuthenticationRepositoryFake, service::AuthenticationService},
-    sessions::{repository::SessionsRepositoryFake, service::SessionsService},
+    authentication::service::AuthenticationService,
+    sessions::service::SessionsService,
 };
+use chrono::Duration;
 use domain::{
-    doctors::service::DoctorsService, drugs::service::DrugsService,
-    patients::service::PatientsService, pharmacists::service::PharmacistsService,
+    doctors::service::DoctorsService,
+    drugs::{
+        entities::DrugImportFormat,
+        service::{DrugsService, ImportDrugsError},
+    },
+    patients::service::PatientsService,
+    pharmacists::service::PharmacistsService,
     prescriptions::service::PrescriptionsService,
 };
 use infrastructure::postgres_repository_impl::{
-    create_tables::create_tables, doctors::PostgresDoctorsRepository,
-    drugs::PostgresDrugsRepository, patients::PostgresPatientsRepository,
-    pharmacists::PostgresPharmacistsRepository, prescriptions::PostgresPrescriptionsRepository,
+    authentication::PostgresAuthenticationRepository,
+    doctors::PostgresDoctorsRepository,
+    drugs::PostgresDrugsRepository,
+    migrations::{get_migrations_status, revert_migrations, run_migrations},
+    patients::PostgresPatientsRepository,
+    pharmacists::PostgresPharmacistsRepository,
+    prescriptions::PostgresPrescriptionsRepository,
+    sessions::PostgresSessionsRepository,
 };
-use rocket::{get, launch, routes, Build, Rocket, Route};
+use rocket::{get, routes, Build, Rocket, Route};
 use rocket_okapi::{
     openapi_get_routes,
     swagger_ui::{make_swagger_ui, SwaggerUIConfig},
 };
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{migrate::MigrateError, postgres::PgPoolOptions, PgPool};
 
 async fn setup_database_connection() -> PgPool {
     let db_connection_string =
@@ -59,7 +70,24 @@ pub struct Context {
 }
 pub type Ctx = rocket::State<Context>;
 
-fn setup_context(pool: PgPool) -> Context {
+fn read_early_pickup_tolerance() -> Result<Option<Duration>, String> {
+    let days = match env::var("EARLY_PICKUP_TOLERANCE_DAYS") {
+        Ok(days) => days,
+        Err(env::VarError::NotPresent) => return Ok(None),
+        Err(err) => return Err(format!("Invalid EARLY_PICKUP_TOLERANCE_DAYS: {}", err)),
+    };
+
+    days.parse::<u32>()
+        .map(|days| Some(Duration::days(days.into())))
+        .map_err(|_| {
+            format!(
+                "Invalid EARLY_PICKUP_TOLERANCE_DAYS: {:?}, expected a non-negative number of days",
+                days
+            )
+        })
+}
+
+fn setup_context(pool: PgPool, early_pickup_tolerance: Option<Duration>) -> Context {
     let doctors_repository = Box::new(PostgresDoctorsRepository::new(pool.clone()));
     let doctors_service = Arc::new(DoctorsService::new(doctors_repository));
 
@@ -73,12 +101,17 @@ fn setup_context(pool: PgPool) -> Context {
     let drugs_service = Arc::new(DrugsService::new(drugs_repository));
 
     let prescriptions_repository = Box::new(PostgresPrescriptionsRepository::new(pool.clone()));
-    let prescriptions_service = Arc::new(PrescriptionsService::new(prescriptions_repository));
+    let mut prescriptions_service = PrescriptionsService::new(prescriptions_repository);
+    if let Some(early_pickup_tolerance) = early_pickup_tolerance {
+        prescriptions_service =
+            prescriptions_service.with_early_pickup_tolerance(early_pickup_tolerance);
+    }
+    let prescriptions_service = Arc::new(prescriptions_service);
 
-    let authentication_repository = Box::new(AuthenticationRepositoryFake::new());
+    let authentication_repository = Box::new(PostgresAuthenticationRepository::new(pool.clone()));
     let authentication_service = Arc::new(AuthenticationService::new(authentication_repository));
 
-    let sessions_repository = Box::new(SessionsRepositoryFake::new());
+    let sessions_repository = Box::new(PostgresSessionsRepository::new(pool));
     let sessions_service = Arc::new(SessionsService::new(sessions_repository));
 
     Context {
@@ -97,19 +130,45 @@ fn get_routes() -> Vec<Route> {
         doctors_controller::create_doctor,
         doctors_controller::get_doctor_by_id,
         doctors_controller::get_doctors_with_pagination,
+        doctors_controller::update_doctor,
         patients_controller::create_patient,
         patients_controller::get_patient_by_id,
         patients_controller::get_patients_with_pagination,
+        patients_controller::update_patient,
+        patients_controller::create_patient_allergy,
+        patients_controller::get_patient_allergies,
+        patients_controller::update_patient_allergy,
+        patients_controller::delete_patient_allergy,
         pharmacists_controller::create_pharmacist,
         pharmacists_controller::get_pharmacist_by_id,
         pharmacists_controller::get_pharmacists_with_pagination,
+        pharmacists_controller::update_pharmacist,
         drugs_controller::create_drug,
         drugs_controller::get_drug_by_id,
+        drugs_controller::get_drug_by_gtin,
         drugs_controller::get_drugs_with_pagination,
+        drugs_controller::update_drug,
+        drugs_controller::create_drug_variant,
+        drugs_controller::create_drug_reimbursement,
+        drugs_controller::create_drug_price_list,
+        drugs_controller::import_drugs,
+        drugs_controller::create_drug_interaction,
+        drugs_controller::get_drug_interactions_with_pagination,
+        drugs_controller::get_active_substances_with_pagination,
+        drugs_controller::create_atc_group,
+        drugs_controller::get_atc_groups_with_pagination,
+        drugs_controller::create_drug_category,
+        drugs_controller::get_drug_categories_with_pagination,
+        drugs_controller::create_drug_equivalence_group,
+        drugs_controller::get_drug_equivalence_groups_with_pagination,
         prescriptions_controller::create_prescription,
         prescriptions_controller::get_prescription_by_id,
         prescriptions_controller::get_prescriptions_with_pagination,
         prescriptions_controller::fill_prescription,
+        prescriptions_controller::cancel_prescription,
+        prescriptions_controller::lookup_prescriptions,
+        prescriptions_controller::fill_prescription_by_code,
+        prescriptions_controller::get_controlled_substance_dispenses_with_pagination,
         authentication_controller::login_doctor,
         authentication_controller::login_pharmacist,
         authentication_controller::register_doctor,
@@ -133,7 +192,7 @@ fn redirect_to_swagger_ui() -> rocket::response::Redirect {
 // fn setup_scheduler(ctx: &Context) {
 //     let mut scheduler = Scheduler::new();
 //     scheduler.every(1.day()).at("3:00 AM").run(|| {
-        // ctx.sessions_service.remove_sessions_older_than_one_week();
+//         ctx.sessions_service.remove_sessions_older_than_one_week();
 //     });
 
 //     thread::spawn(move || loop {
@@ -142,19 +201,318 @@ fn redirect_to_swagger_ui() -> rocket::response::Redirect {
 //     });
 // }
 
-#[launch]
-async fn rocket() -> Rocket<Build> {
+fn build_rocket(context: Context) -> Rocket<Build> {
+    rocket::build()
+        .manage(context)
+        .mount("/", get_routes())
+        .mount("/", routes![redirect_to_swagger_ui])
+        .mount("/swagger-ui", setup_swagger_ui())
+}
+
+const MIGRATE_USAGE: &str = "Usage: pms_v_0 migrate [run | revert [target_version] | status]";
+
+async fn run_migrate_command(pool: &PgPool, args: &[&str]) -> Result<(), MigrateError> {
+    match args {
+        [] | ["run"] => run_migrations(pool).await?,
+        ["revert"] => {
+            // reverts only the latest applied migration
+            let applied_versions: Vec<i64> = get_migrations_status(pool)
+                .await?
+                .into_iter()
+                .filter(|migration| migration.applied)
+                .map(|migration| migration.version)
+                .collect();
+            let target_version = applied_versions.iter().rev().nth(1).copied().unwrap_or(0);
+            revert_migrations(pool, target_version).await?
+        }
+        ["revert", target_version] => match target_version.parse::<i64>() {
+            Ok(target_version) => revert_migrations(pool, target_version).await?,
+            Err(_) => {
+                eprintln!("{}", MIGRATE_USAGE);
+                process::exit(2);
+            }
+        },
+        ["status"] => {
+            for migration in get_migrations_status(pool).await? {
+                println!(
+                    "{:04} {:<8} {}",
+                    migration.version,
+                    if migration.applied {
+                        "applied"
+                    } else {
+                        "pending"
+                    },
+                    migration.description
+                );
+            }
+        }
+        _ => {
+            eprintln!("{}", MIGRATE_USAGE);
+            process::exit(2);
+        }
+    }
+
+    Ok(())
+}
+
+const IMPORT_DRUGS_USAGE: &str = "Usage: pms_v_0 import-drugs <file.csv | file.xml>";
+
+async fn run_import_drugs_command(context: &Context, args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let [file_path] = args else {
+        eprintln!("{}", IMPORT_DRUGS_USAGE);
+        process::exit(2);
+    };
+    // format of the registry export is recognized by the file extension
+    let format = match Path::new(file_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("csv") => DrugImportFormat::Csv,
+        Some("xml") => DrugImportFormat::Xml,
+        _ => {
+            eprintln!("{}", IMPORT_DRUGS_USAGE);
+            process::exit(2);
+        }
+    };
+    let content = fs::read_to_string(file_path)?;
+
+    let report = context
+        .drugs_service
+        .import_drugs(content, format)
+        .await
+        .map_err(|err| match err {
+            ImportDrugsError::DomainError(err) => err,
+        })?;
+
+    for row in &report.rows {
+        println!(
+            "{:>5} {:<8} {:<20} {}",
+            row.row_number,
+            format!("{:?}", row.status).to_lowercase(),
+            row.external_id.as_deref().unwrap_or("-"),
+            row.reason
+                .clone()
+                .or(row.drug_id.map(|drug_id| drug_id.to_string()))
+                .unwrap_or_default()
+        );
+    }
+    println!(
+        "created: {}, updated: {}, rejected: {}",
+        report.created_count, report.updated_count, report.rejected_count
+    );
+
+    Ok(())
+}
+
+#[rocket::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let early_pickup_tolerance = read_early_pickup_tolerance()?;
+
     let pool = setup_database_connection().await;
 
-    create_tables(&pool, false).await.unwrap();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if let ["migrate", migrate_args @ ..] = args.as_slice() {
+        run_migrate_command(&pool, migrate_args).await?;
+        return Ok(());
+    }
+
+    run_migrations(&pool).await?;
 
-    let context = setup_context(pool);
+    let context = setup_context(pool, early_pickup_tolerance);
+
+    if let ["import-drugs", import_args @ ..] = args.as_slice() {
+        run_import_drugs_command(&context, import_args).await?;
+        return Ok(());
+    }
 
     // setup_scheduler(&context);
 
-    rocket::build()
-        .manage(context)
-        .mount("/", get_routes())
-        .mount("/", routes![redirect_to_swagger_ui])
-        .mount("/swagger-ui", setup_swagger_ui())
+    build_rocket(context).launch().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{
+        http::{ContentType, Header, Status},
+        local::asynchronous::Client,
+        serde::json,
+    };
+
+    use super::{build_rocket, setup_context};
+    use crate::domain::{
+        drugs::entities::Drug, patients::entities::Patient, prescriptions::entities::Prescription,
+    };
+
+    async fn start_server(pool: sqlx::PgPool) -> Client {
+        Client::tracked(build_rocket(setup_context(pool, None)))
+            .await
+            .unwrap()
+    }
+
+    async fn login(client: &Client, role: &str, username: &str) -> String {
+        let response = client
+            .post(format!("/auth/login/{}", role))
+            .header(ContentType::JSON)
+            .body(format!(
+                r#"{{ "username": "{}", "password": "password123" }}"#,
+                username
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let body: json::Value = json::from_str(&response.into_string().await.unwrap()).unwrap();
+        body["token"].as_str().unwrap().to_string()
+    }
+
+    #[sqlx::test]
+    async fn keeps_users_and_sessions_after_restart(pool: sqlx::PgPool) {
+        let client = start_server(pool.clone()).await;
+
+        let register_doctor_response = client
+            .post("/auth/register/doctor")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                    "username": "doctor",
+                    "password": "password123",
+                    "email": "doctor_john_doe@gmail.com",
+                    "phone_number": "123456789",
+                    "name": "John Doe",
+                    "pesel_number": "99031301347",
+                    "pwz_number": "3123456"
+                }"#,
+            )
+            .dispatch()
+            .await;
+
+        assert_eq!(register_doctor_response.status(), Status::Ok);
+
+        let register_pharmacist_response = client
+            .post("/auth/register/pharmacist")
+            .header(ContentType::JSON)
+            .body(
+                r#"{
+                    "username": "pharmacist",
+                    "password": "password123",
+                    "email": "pharmacist_john_doe@gmail.com",
+                    "phone_number": "123456789",
+                    "name": "John Doe",
+                    "pesel_number": "92022900002"
+                }"#,
+            )
+            .dispatch()
+            .await;
+
+        assert_eq!(register_pharmacist_response.status(), Status::Ok);
+
+        let doctor_token = login(&client, "doctor", "doctor").await;
+
+        let patient: Patient = client
+            .post("/patients")
+            .header(ContentType::JSON)
+            .body(r#"{ "name": "John Patient", "pesel_number": "96021807250" }"#)
+            .dispatch()
+            .await
+            .into_json()
+            .await
+            .unwrap();
+        let drug: Drug = client
+            .post("/drugs")
+            .header(ContentType::JSON)
+            .body(r#"{ "name": "Gripex", "pills_count": 20, "mg_per_pill": 300, "content_type": "SOLID_PILLS" }"#)
+            .dispatch()
+            .await
+            .into_json()
+            .await
+            .unwrap();
+
+        let client = start_server(pool.clone()).await;
+
+        let create_prescription_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }}
+                    ]
+                }}"#,
+                patient.id, drug.id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(create_prescription_response.status(), Status::Created);
+
+        let created_prescription = json::from_str::<Prescription>(
+            &create_prescription_response.into_string().await.unwrap(),
+        )
+        .unwrap();
+
+        let pharmacist_token = login(&client, "pharmacist", "pharmacist").await;
+
+        let fill_prescription_response = client
+            .post(format!("/prescriptions/{}/fill", created_prescription.id))
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", pharmacist_token),
+            ))
+            .body(format!(
+                r#"{{ "prescription_code": "{}" }}"#,
+                created_prescription.code
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(fill_prescription_response.status(), Status::Created);
+
+        let logout_response = client
+            .post("/auth/logout")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(logout_response.status(), Status::Ok);
+
+        let client = start_server(pool).await;
+
+        let create_prescription_after_logout_response = client
+            .post("/prescriptions")
+            .header(ContentType::JSON)
+            .header(Header::new(
+                "Authorization",
+                format!("Bearer {}", doctor_token),
+            ))
+            .body(format!(
+                r#"{{
+                    "patient_id": "{}",
+                    "prescribed_drugs": [
+                        {{ "drug_id": "{}", "total_amount": 1 }}
+                    ]
+                }}"#,
+                patient.id, drug.id
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(
+            create_prescription_after_logout_response.status(),
+            Status::Forbidden
+        );
+    }
 }